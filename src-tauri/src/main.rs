@@ -13,6 +13,7 @@ mod xml_judgment_service;
 // use services::smart_element_finder_service::SmartElementFinderService; // 未直接使用类型
 use services::universal_ui_service::UniversalUIService;
 use services::page_analyzer_service::PageAnalyzerService; // 新增页面分析服务
+use services::page_analysis_history::{PageAnalysisHistory, PageAnalysisQuery, PageAnalysisRecord, HISTORY_MAX_AGE_DAYS, HISTORY_MAX_RECORDS}; // 页面分析历史
 
 // 新增页面分析类型导入
 use types::page_analysis::{
@@ -32,9 +33,29 @@ use services::auth_service::*;
 use services::contact_automation::*;
 use services::contact_service::*;
 use services::crash_debugger::*;
+use services::device_health_monitor::*;
+use services::device_preconditions::*;
+use services::logcat_collector::*;
+use services::screen_recorder::*;
+use services::page_fingerprint::*;
+use services::navigation_graph::*;
+use services::template_matcher::*;
+use services::ui_dump_cache::*;
+use services::ui_idle::*;
+use services::xml_snapshot_store::*;
+use services::screenshot_annotator::annotate_screenshot;
+use services::element_locator::generate_element_locators;
+use services::app_detection_framework::reload_detector_rules;
+use services::popup_watcher::{dismiss_popups, get_popup_watcher_stats, list_popup_watchers, reset_popup_watcher_stats};
+use services::app_package_manager::{
+    block_app_self_update_on_devices, clear_app_data_on_devices, get_app_versions_on_devices,
+    grant_app_permissions_on_devices, install_apk_on_devices, uninstall_app_on_devices,
+};
+use services::app_launch_benchmark::{benchmark_app_launch, get_launch_benchmarks};
+use services::vcard::{convert_vcf_file, parse_vcf_file};
 use services::employee_service::{Employee, EmployeeService};
 use services::log_bridge::{AdbCommandLog, LogEntry, LOG_COLLECTOR};
-use services::navigation_bar_detector::{detect_navigation_bar, click_navigation_button, get_navigation_configs};
+use services::navigation_bar_detector::{detect_navigation_bar, detect_and_click_navigation_button, click_navigation_button, get_navigation_configs};
 use services::safe_adb_manager::*;
 use services::script_executor::*;
 use services::script_manager::*;  // 新增：脚本管理服务
@@ -739,16 +760,38 @@ async fn clear_adb_keys() -> Result<(), String> {
 
 // ====== 智能页面分析相关命令 ======
 
-/// 分析当前页面，获取可操作元素
+/// 分析当前页面，获取可操作元素（结果连同 XML 和截图存入历史）
 #[tauri::command]
 async fn analyze_current_page(
     device_id: String,
     config: Option<PageAnalysisConfig>,
+    history: State<'_, Mutex<PageAnalysisHistory>>,
 ) -> Result<PageAnalysisResult, String> {
     let service = PageAnalyzerService::new();
-    service.analyze_current_page(&device_id, config)
+    let (result, snapshot) = service.capture_and_analyze(&device_id, config)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    if let Some(snapshot) = snapshot {
+        let screenshot = ScreenshotService::capture_to_dir(&device_id, std::path::Path::new("data/page_history")).await;
+        let analyzed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let history = history.lock().map_err(|e| e.to_string())?;
+        if let Err(e) = history.insert(&device_id, &snapshot, screenshot.screenshot_path.as_deref(), analyzed_at, &result) {
+            tracing::warn!("⚠️ 保存页面分析历史失败: {}", e);
+        }
+        match history.prune(analyzed_at, HISTORY_MAX_AGE_DAYS, HISTORY_MAX_RECORDS) {
+            Ok(screenshots) => {
+                for path in screenshots {
+                    let _ = std::fs::remove_file(path);
+                }
+            }
+            Err(e) => tracing::warn!("⚠️ 清理页面分析历史失败: {}", e),
+        }
+    }
+    Ok(result)
 }
 
 /// 验证选中的元素配置
@@ -788,6 +831,16 @@ async fn execute_page_element_action(
     let universal_service = UniversalUIService::new();
     
     match config.action {
+        types::page_analysis::ElementAction::Click if !config.locators.is_empty() => {
+            // 按定位器回退链查找元素并点击中心
+            let xml = XmlJudgmentService::get_ui_xml(&device_id).await?;
+            let tree = services::ui_tree::UiTree::parse(&xml).map_err(|e| e.to_string())?;
+            let (node, locator) = services::element_locator::locate(&tree, &config.locators)
+                .ok_or_else(|| format!("所有定位器均未唯一命中元素: {}", config.description))?;
+            let (x, y) = node.center().ok_or_else(|| "元素没有有效的bounds".to_string())?;
+            utils::adb_utils::run_shell(&device_id, &format!("input tap {} {}", x, y)).await?;
+            Ok(format!("点击操作执行成功: {:?} -> ({}, {})", locator.strategy, x, y))
+        }
         types::page_analysis::ElementAction::Click => {
             // 转换为 Universal UI 点击操作
             universal_service.execute_ui_click(&device_id, &config.description)
@@ -805,15 +858,81 @@ async fn execute_page_element_action(
     }
 }
 
-/// 获取页面分析历史记录
+/// 获取页面分析历史记录（按设备、应用、时间范围过滤，最新的在前）
 #[tauri::command]
 async fn get_page_analysis_history(
-    _device_id: String,
-    _limit: Option<usize>,
-) -> Result<Vec<PageAnalysisResult>, String> {
-    // 这里可以从缓存或数据库中获取历史记录
-    // 目前返回空列表
-    Ok(vec![])
+    device_id: Option<String>,
+    limit: Option<usize>,
+    app_package: Option<String>,
+    since: Option<u64>,
+    until: Option<u64>,
+    history: State<'_, Mutex<PageAnalysisHistory>>,
+) -> Result<Vec<PageAnalysisRecord>, String> {
+    let query = PageAnalysisQuery { device_id, app_package, since, until, limit };
+    let history = history.lock().map_err(|e| e.to_string())?;
+    history.query(&query).map_err(|e| e.to_string())
+}
+
+/// 获取历史记录保存的原始 XML
+#[tauri::command]
+async fn get_page_analysis_xml(
+    id: i64,
+    history: State<'_, Mutex<PageAnalysisHistory>>,
+) -> Result<String, String> {
+    let history = history.lock().map_err(|e| e.to_string())?;
+    history.get_snapshot(id)
+        .map_err(|e| e.to_string())?
+        .map(|snapshot| snapshot.xml_content)
+        .ok_or_else(|| format!("分析记录不存在: {}", id))
+}
+
+/// 用当前分析器重新分析历史快照（不写入历史）
+#[tauri::command]
+async fn reanalyze_page_analysis(
+    id: i64,
+    config: Option<PageAnalysisConfig>,
+    history: State<'_, Mutex<PageAnalysisHistory>>,
+) -> Result<PageAnalysisResult, String> {
+    let snapshot = {
+        let history = history.lock().map_err(|e| e.to_string())?;
+        history.get_snapshot(id).map_err(|e| e.to_string())?
+    }
+    .ok_or_else(|| format!("分析记录不存在: {}", id))?;
+
+    let service = PageAnalyzerService::new();
+    service.analyze_snapshot(&snapshot, &config.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 比较两条历史记录的页面结构（如应用更新前后）
+#[tauri::command]
+async fn compare_page_analyses(
+    before_id: i64,
+    after_id: i64,
+    history: State<'_, Mutex<PageAnalysisHistory>>,
+) -> Result<services::ui_tree::UiDiff, String> {
+    let history = history.lock().map_err(|e| e.to_string())?;
+    let load = |id: i64| {
+        history.get_snapshot(id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("分析记录不存在: {}", id))
+    };
+    let (before, after) = (load(before_id)?, load(after_id)?);
+    services::ui_tree::UiDiff::between(&before.xml_content, &after.xml_content).map_err(|e| e.to_string())
+}
+
+/// 删除历史记录及其截图
+#[tauri::command]
+async fn delete_page_analysis(
+    id: i64,
+    history: State<'_, Mutex<PageAnalysisHistory>>,
+) -> Result<(), String> {
+    let history = history.lock().map_err(|e| e.to_string())?;
+    if let Some(path) = history.delete(id).map_err(|e| e.to_string())? {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
 }
 
 // ====== 日志桥接相关命令 ======
@@ -890,6 +1009,12 @@ fn main() {
     info!("📊 日志级别: DEBUG (开发模式)");
 
     let employee_service = EmployeeService::new().expect("Failed to initialize employee service");
+    let page_analysis_history = PageAnalysisHistory::new().expect("Failed to initialize page analysis history");
+    let xml_snapshot_store = XmlSnapshotStore::new().expect("Failed to initialize xml snapshot store");
+    let imported = xml_snapshot_store.import_legacy_dir(&legacy_debug_xml_dir());
+    if imported > 0 {
+        info!("📥 已导入 {} 个旧debug_xml缓存文件", imported);
+    }
     let adb_service = AdbService::new();
     let xiaohongshu_service = XiaohongshuService::new();
     let xiaohongshu_long_connection_service = XiaohongshuLongConnectionService::new();
@@ -917,6 +1042,8 @@ fn main() {
             Ok(())
         })
         .manage(Mutex::new(employee_service))
+        .manage(Mutex::new(page_analysis_history))
+        .manage(Mutex::new(xml_snapshot_store))
         .manage(Mutex::new(adb_service))
         .manage(tokio::sync::Mutex::new(xiaohongshu_service))
         .manage(tokio::sync::Mutex::new(xiaohongshu_long_connection_service))
@@ -941,6 +1068,42 @@ fn main() {
             start_device_tracking,    // 启动实时设备跟踪
             stop_device_tracking,     // 停止设备跟踪  
             get_tracked_devices,      // 获取当前跟踪的设备
+            // 设备健康监控
+            start_device_health_monitor,  // 启动健康监控
+            stop_device_health_monitor,   // 停止健康监控
+            get_device_health_snapshots,  // 获取健康快照
+            check_device_health,          // 立即检查设备健康
+            update_device_health_config,  // 更新健康阈值配置
+            // 设备前置条件
+            get_device_unlock_settings,   // 获取解锁配置
+            save_device_unlock_settings,  // 保存解锁配置
+            ensure_device_ready,          // 唤醒并解锁设备
+            // 设备logcat采集
+            start_logcat_stream,          // 启动logcat采集
+            stop_logcat_stream,           // 停止logcat采集
+            get_logcat_filter,            // 获取logcat过滤条件
+            get_logcat_lines,             // 获取最近logcat
+            // 执行录屏
+            list_screen_recordings,         // 列出本地录屏
+            get_recording_retention_policy, // 获取录屏保留策略
+            set_recording_retention_policy, // 更新录屏保留策略
+            cleanup_screen_recordings,      // 按策略清理录屏
+            // 页面指纹注册表
+            save_current_page_fingerprint,  // 保存当前页面为命名页面
+            list_page_fingerprints,         // 列出已保存页面
+            delete_page_fingerprint,        // 删除页面指纹
+            recognize_page_by_fingerprint,  // 按指纹识别当前页面
+            identify_registered_page,       // 按指纹识别给定 XML 对应的页面
+            // 应用导航图
+            get_navigation_graph,           // 获取应用导航图
+            clear_navigation_graph,         // 清空应用导航图
+            explore_app_navigation,         // 探索应用构建导航图
+            navigate_to_page,               // 按导航图导航到指定页面
+            // 截图模板匹配
+            find_template_on_screen,        // 在当前截图中查找模板
+            save_template_from_screenshot,  // 从截图裁剪保存模板
+            get_ui_dump_cache_stats,        // UI dump 缓存命中统计
+            wait_for_ui_idle,               // 等待界面稳定
             start_adb_server_simple,
             kill_adb_server_simple,
             execute_adb_command_simple,
@@ -1028,6 +1191,7 @@ fn main() {
             wait_for_ui_element,     // 等待元素出现
             check_device_page_state, // 检查页面状态
             match_element_by_criteria, // 按匹配条件查找元素
+            diff_ui_hierarchies,     // 比较两份UI结构差异
             // 智能应用管理功能
             get_device_apps,         // 获取设备应用列表
             search_device_apps,      // 搜索设备应用
@@ -1036,6 +1200,7 @@ fn main() {
             get_popular_apps,        // 获取常用应用列表
             // 导航栏检测功能
             detect_navigation_bar,   // 检测导航栏
+            detect_and_click_navigation_button, // 检测并点击导航按钮（底部/顶部/侧边/悬浮）
             click_navigation_button, // 点击导航按钮
             get_navigation_configs,  // 获取预设配置
             // 智能元素查找功能
@@ -1054,6 +1219,10 @@ fn main() {
             validate_element_config,     // 验证元素配置
             execute_page_element_action, // 执行页面元素操作
             get_page_analysis_history,   // 获取页面分析历史记录
+            get_page_analysis_xml,       // 获取历史记录的原始XML
+            reanalyze_page_analysis,     // 用当前分析器重新分析历史快照
+            compare_page_analyses,       // 比较两条历史记录的页面结构
+            delete_page_analysis,        // 删除页面分析历史记录
             // 应用生命周期管理功能
             // ensure_app_running,              // 确保应用运行（独立模块）
             // detect_app_state                 // 检测应用状态（独立模块）
@@ -1062,7 +1231,35 @@ fn main() {
             read_xml_cache_file,         // 读取XML缓存文件内容
             get_xml_file_size,           // 获取XML文件大小
             delete_xml_cache_file,       // 删除XML缓存文件
-            parse_cached_xml_to_elements // 解析缓存XML为UI元素
+            parse_cached_xml_to_elements, // 解析缓存XML为UI元素
+            // XML快照存储
+            capture_xml_snapshot,              // 抓取当前界面保存为快照
+            search_xml_snapshots,              // 全文检索XML快照
+            get_xml_snapshot_content,          // 读取快照XML内容
+            delete_xml_snapshot,               // 删除XML快照
+            get_xml_snapshot_retention_policy, // 获取快照保留策略
+            set_xml_snapshot_retention_policy, // 设置快照保留策略
+            annotate_screenshot,               // 截图标注元素框
+            generate_element_locators,         // 生成选中元素的定位器回退链
+            reload_detector_rules,             // 重新加载应用检测规则文件
+            // 弹窗监视器
+            list_popup_watchers,               // 获取内置弹窗监视器
+            get_popup_watcher_stats,           // 获取弹窗监视器触发统计
+            reset_popup_watcher_stats,         // 清空弹窗监视器触发统计
+            dismiss_popups,                    // 立即检查并关闭弹窗
+            // 应用包管理
+            install_apk_on_devices,            // 批量安装/升级APK
+            uninstall_app_on_devices,          // 批量卸载应用
+            clear_app_data_on_devices,         // 批量清除应用数据
+            grant_app_permissions_on_devices,  // 批量预授予运行时权限
+            block_app_self_update_on_devices,  // 批量禁止应用自更新
+            get_app_versions_on_devices,       // 查询各设备已安装版本
+            // 启动基准测试
+            benchmark_app_launch,              // 应用冷/热启动基准测试
+            get_launch_benchmarks,             // 获取各机型的启动基准结果
+            // vCard
+            parse_vcf_file,                    // 解析VCF文件（2.1/3.0/4.0）
+            convert_vcf_file                   // 转换VCF文件版本
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1070,127 +1267,71 @@ fn main() {
 
 // ==================== XML缓存管理命令 ====================
 
-/// 列出所有XML缓存文件
+/// 列出所有XML缓存文件（兼容旧接口，数据来自XML快照存储）
 #[tauri::command]
-async fn list_xml_cache_files() -> Result<Vec<String>, String> {
-    use std::fs;
-    
-    let debug_dir = get_debug_xml_dir();
-    
-    if !debug_dir.exists() {
-        info!("📂 debug_xml目录不存在，返回空列表");
-        return Ok(vec![]);
-    }
-    
-    match fs::read_dir(&debug_dir) {
-        Ok(entries) => {
-            let mut xml_files = Vec::new();
-            
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.is_file() {
-                        if let Some(file_name) = path.file_name() {
-                            if let Some(name_str) = file_name.to_str() {
-                                if name_str.ends_with(".xml") && name_str.starts_with("ui_dump_") {
-                                    xml_files.push(name_str.to_string());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            
-            // 按文件名排序（时间戳排序）
-            xml_files.sort();
-            xml_files.reverse(); // 最新的在前面
-            
-            info!("📋 找到 {} 个XML缓存文件", xml_files.len());
-            Ok(xml_files)
-        },
-        Err(e) => {
-            let error_msg = format!("❌ 读取debug_xml目录失败: {}", e);
-            info!("{}", error_msg);
-            Err(error_msg)
-        }
-    }
+async fn list_xml_cache_files(
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<Vec<String>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    let snapshots = store.search(&XmlSnapshotQuery::default()).map_err(|e| e.to_string())?;
+
+    let mut xml_files: Vec<String> = snapshots.into_iter().map(|s| s.file_name).collect();
+    // 按文件名排序（时间戳排序），最新的在前面
+    xml_files.sort();
+    xml_files.dedup();
+    xml_files.reverse();
+
+    info!("📋 找到 {} 个XML缓存文件", xml_files.len());
+    Ok(xml_files)
+}
+
+/// 按缓存文件名查找快照
+fn find_cached_snapshot(store: &XmlSnapshotStore, file_name: &str) -> Result<XmlSnapshotMeta, String> {
+    store.find_by_file_name(file_name)
+        .map_err(|e| format!("❌ 查询XML缓存失败: {} - {}", file_name, e))?
+        .ok_or_else(|| format!("❌ XML缓存文件不存在: {}", file_name))
 }
 
 /// 读取XML缓存文件内容
 #[tauri::command]
-async fn read_xml_cache_file(file_name: String) -> Result<String, String> {
-    use std::fs;
-    
-    let debug_dir = get_debug_xml_dir();
-    let file_path = debug_dir.join(&file_name);
-    
-    if !file_path.exists() {
-        let error_msg = format!("❌ XML缓存文件不存在: {}", file_name);
-        return Err(error_msg);
-    }
-    
-    match fs::read_to_string(&file_path) {
-        Ok(content) => {
-            info!("📖 成功读取XML缓存文件: {} (大小: {})", file_name, content.len());
-            Ok(content)
-        },
-        Err(e) => {
-            let error_msg = format!("❌ 读取XML缓存文件失败: {} - {}", file_name, e);
-            info!("{}", error_msg);
-            Err(error_msg)
-        }
-    }
+async fn read_xml_cache_file(
+    file_name: String,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<String, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    let snapshot = find_cached_snapshot(&store, &file_name)?;
+    let content = store.get_xml(snapshot.id)
+        .map_err(|e| format!("❌ 读取XML缓存文件失败: {} - {}", file_name, e))?
+        .ok_or_else(|| format!("❌ XML缓存文件不存在: {}", file_name))?;
+    info!("📖 成功读取XML缓存文件: {} (大小: {})", file_name, content.len());
+    Ok(content)
 }
 
 /// 获取XML文件大小
 #[tauri::command]
-async fn get_xml_file_size(file_name: String) -> Result<u64, String> {
-    use std::fs;
-    
-    let debug_dir = get_debug_xml_dir();
-    let file_path = debug_dir.join(&file_name);
-    
-    if !file_path.exists() {
-        let error_msg = format!("❌ XML缓存文件不存在: {}", file_name);
-        return Err(error_msg);
-    }
-    
-    match fs::metadata(&file_path) {
-        Ok(metadata) => {
-            let size = metadata.len();
-            Ok(size)
-        },
-        Err(e) => {
-            let error_msg = format!("❌ 获取文件大小失败: {} - {}", file_name, e);
-            Err(error_msg)
-        }
-    }
+async fn get_xml_file_size(
+    file_name: String,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<u64, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    Ok(find_cached_snapshot(&store, &file_name)?.size_bytes)
 }
 
 /// 删除XML缓存文件
 #[tauri::command]
-async fn delete_xml_cache_file(file_name: String) -> Result<(), String> {
-    use std::fs;
-    
-    let debug_dir = get_debug_xml_dir();
-    let file_path = debug_dir.join(&file_name);
-    
-    if !file_path.exists() {
-        let error_msg = format!("❌ XML缓存文件不存在: {}", file_name);
-        return Err(error_msg);
-    }
-    
-    match fs::remove_file(&file_path) {
-        Ok(_) => {
-            info!("🗑️ 成功删除XML缓存文件: {}", file_name);
-            Ok(())
-        },
-        Err(e) => {
-            let error_msg = format!("❌ 删除XML缓存文件失败: {} - {}", file_name, e);
-            info!("{}", error_msg);
-            Err(error_msg)
-        }
+async fn delete_xml_cache_file(
+    file_name: String,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<(), String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    let snapshot = find_cached_snapshot(&store, &file_name)?;
+    if let Some(path) = store.delete(snapshot.id).map_err(|e| format!("❌ 删除XML缓存文件失败: {} - {}", file_name, e))? {
+        let _ = std::fs::remove_file(path);
     }
+    // 旧目录中的同名文件一并删除，避免下次启动重新导入
+    let _ = std::fs::remove_file(legacy_debug_xml_dir().join(&file_name));
+    info!("🗑️ 成功删除XML缓存文件: {}", file_name);
+    Ok(())
 }
 
 /// 解析缓存XML为UI元素
@@ -1220,13 +1361,3 @@ async fn parse_cached_xml_to_elements(xml_content: String) -> Result<serde_json:
         }
     }
 }
-
-/// 获取debug_xml目录路径
-fn get_debug_xml_dir() -> std::path::PathBuf {
-    // 获取项目根目录的debug_xml文件夹
-    std::env::current_dir()
-        .unwrap_or_else(|_| std::path::PathBuf::from("."))
-        .parent()
-        .unwrap_or_else(|| std::path::Path::new(".."))
-        .join("debug_xml")
-}
@@ -41,9 +41,13 @@ impl ScreenshotService {
             }
         };
 
+        Self::capture_to_dir(device_id, &app_data_dir.join("screenshots")).await
+    }
+
+    /// 捕获设备截图并保存到指定目录（无 AppHandle 的后台流程使用）
+    pub async fn capture_to_dir(device_id: &str, screenshots_dir: &std::path::Path) -> ScreenshotResult {
         // 创建screenshots目录
-        let screenshots_dir = app_data_dir.join("screenshots");
-        if let Err(e) = std::fs::create_dir_all(&screenshots_dir) {
+        if let Err(e) = std::fs::create_dir_all(screenshots_dir) {
             return ScreenshotResult {
                 success: false,
                 screenshot_path: None,
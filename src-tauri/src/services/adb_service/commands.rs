@@ -68,7 +68,11 @@ impl AdbService {
             args.push(part.to_string());
         }
 
-        self.execute_command(&adb_path, &args)
+        let cache = crate::services::ui_dump_cache::get_ui_dump_cache();
+        cache.note_adb_args(&args);
+        let result = self.execute_command(&adb_path, &args);
+        cache.note_adb_args(&args);
+        result
     }
 
     /// 启动ADB服务器
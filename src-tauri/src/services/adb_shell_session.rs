@@ -6,6 +6,8 @@ use tokio::sync::Mutex;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, error, info, warn};
 
+use crate::services::ui_dump_cache::get_ui_dump_cache;
+
 /// ADB Shell长连接会话管理器
 /// 维护到指定设备的持久shell连接，减少命令执行开销
 #[derive(Clone)]
@@ -137,12 +139,15 @@ impl AdbShellSession {
 
         debug!("📤 执行Shell命令: {}", command);
 
+        let cache = get_ui_dump_cache();
+        cache.note_shell_command(Some(&self.device_id), command);
         let result = timeout(timeout_duration, async {
             // 这里需要实现命令执行逻辑
             // 由于ADB shell是交互式的，我们需要重构为更好的方式
             // 暂时使用独立命令作为fallback
             self.execute_single_command(command).await
         }).await;
+        cache.note_shell_command(Some(&self.device_id), command);
 
         match result {
             Ok(output) => {
@@ -205,11 +210,10 @@ impl AdbShellSession {
         Ok(())
     }
 
-    /// 输入文本
+    /// 输入文本（支持中文/emoji，非ASCII文本通过ADBKeyBoard输入）
     pub async fn input_text(&self, text: &str) -> Result<()> {
-        let command = format!("input text '{}'", text);
-        self.execute_command(&command).await?;
-        info!("⌨️ 输入文本: {}", text);
+        let method = crate::services::text_input::type_text(self, text).await?;
+        info!("⌨️ 输入文本: {} ({:?})", text, method);
         Ok(())
     }
 
@@ -223,18 +227,22 @@ impl AdbShellSession {
 
     /// 获取当前界面UI层次结构
     pub async fn dump_ui(&self) -> Result<String> {
-        // 使用标准方法：dump到文件然后读取文件内容
-        let command = "uiautomator dump && cat /sdcard/window_dump.xml";
-        let result = self.execute_command_with_timeout(command, Duration::from_secs(15)).await?;
-        
-        // 检查是否包含XML内容
-        if result.contains("<?xml") {
-            debug!("📱 UI结构获取成功，长度: {} 字符", result.len());
-            Ok(result)
-        } else {
-            debug!("⚠️  UI dump 失败，输出: {}", result);
-            Err(anyhow::anyhow!("UI dump 未返回有效的XML内容: {}", result))
-        }
+        get_ui_dump_cache()
+            .get_or_dump(&self.device_id, || async {
+                // 使用标准方法：dump到文件然后读取文件内容
+                let command = "uiautomator dump && cat /sdcard/window_dump.xml";
+                let result = self.execute_command_with_timeout(command, Duration::from_secs(15)).await?;
+
+                // 检查是否包含XML内容
+                if result.contains("<?xml") {
+                    debug!("📱 UI结构获取成功，长度: {} 字符", result.len());
+                    Ok(result)
+                } else {
+                    debug!("⚠️  UI dump 失败，输出: {}", result);
+                    Err(anyhow::anyhow!("UI dump 未返回有效的XML内容: {}", result))
+                }
+            })
+            .await
     }
 
     /// 获取屏幕分辨率
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::detection_rules::AppDetectionRules;
 
 /// 检测配置结构体
 /// 定义应用状态检测的各项参数
@@ -131,16 +135,42 @@ impl Default for DetectionKeywords {
     }
 }
 
+/// 规则文件的加载记录（用于热重载时判断文件是否变化）
+struct RuleSource {
+    modified: Option<SystemTime>,
+    package: Option<String>,
+}
+
+/// 默认的规则文件目录
+pub fn default_rules_dir() -> PathBuf {
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    current_dir.join("data").join("detector_rules")
+}
+
+fn is_rule_file(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("toml") | Some("json"))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
 /// 应用配置管理器
-/// 管理不同应用的检测配置
+/// 管理不同应用的检测配置，以及从规则文件加载的声明式检测规则
 pub struct AppConfigManager {
     configs: HashMap<String, DetectionConfig>,
+    rules: HashMap<String, AppDetectionRules>,
+    rule_sources: HashMap<PathBuf, RuleSource>,
+    rule_dirs: Vec<PathBuf>,
 }
 
 impl AppConfigManager {
     pub fn new() -> Self {
         let mut manager = Self {
             configs: HashMap::new(),
+            rules: HashMap::new(),
+            rule_sources: HashMap::new(),
+            rule_dirs: Vec::new(),
         };
         
         // 预置一些热门应用的配置
@@ -278,11 +308,130 @@ impl AppConfigManager {
         self.configs.keys().cloned().collect()
     }
     
-    /// 从文件加载配置
+    /// 从文件加载检测规则（.toml 或 .json，格式见 `AppDetectionRules`）
+    ///
+    /// 同一应用的规则会被后加载的文件覆盖；加载记录用于 `reload_changed_rules` 热重载。
     pub fn load_from_file(&mut self, file_path: &str) -> anyhow::Result<()> {
-        // TODO: 实现从JSON或TOML文件加载配置
+        let path = PathBuf::from(file_path);
+        let modified = modified_time(&path);
+        let result = AppDetectionRules::from_file(&path);
+
+        // 解析失败也记录修改时间，避免文件未改动时反复报错
+        let source = self.rule_sources.entry(path.clone()).or_insert(RuleSource { modified, package: None });
+        source.modified = modified;
+        let rules = result?;
+
+        let old_package = source.package.replace(rules.package.clone());
+        info!("📜 已加载检测规则: {} ({} 条) <- {}", rules.package, rules.rules.len(), path.display());
+        let package = rules.package.clone();
+        self.rules.insert(package.clone(), rules);
+        if let Some(old_package) = old_package.filter(|old| *old != package) {
+            self.resolve_package_rules(&old_package);
+        }
         Ok(())
     }
+
+    /// 某个规则文件不再声明该应用后，从其余声明该应用的文件中重新选取规则（按文件名排序取最后一个，
+    /// 与目录加载时的覆盖顺序一致）；没有其他来源时移除规则
+    fn resolve_package_rules(&mut self, package: &str) {
+        let mut candidates: Vec<PathBuf> = self
+            .rule_sources
+            .iter()
+            .filter(|(_, source)| source.package.as_deref() == Some(package))
+            .map(|(path, _)| path.clone())
+            .collect();
+        candidates.sort();
+        for path in candidates.into_iter().rev() {
+            match AppDetectionRules::from_file(&path) {
+                Ok(rules) if rules.package == package => {
+                    info!("📜 检测规则改由其他文件提供: {} <- {}", package, path.display());
+                    self.rules.insert(package.to_string(), rules);
+                    return;
+                }
+                Ok(_) => {}
+                Err(e) => warn!("⚠️ 检测规则加载失败 {}: {:#}", path.display(), e),
+            }
+        }
+        self.rules.remove(package);
+    }
+
+    /// 加载目录下的所有规则文件，并记住该目录以便热重载时发现新文件
+    ///
+    /// 单个文件出错只记录警告，返回成功加载的文件数。
+    pub fn load_rules_dir(&mut self, dir: &Path) -> usize {
+        if !self.rule_dirs.iter().any(|d| d == dir) {
+            self.rule_dirs.push(dir.to_path_buf());
+        }
+        let mut loaded = 0;
+        for path in Self::rule_files_in(dir) {
+            match self.load_from_file(&path.to_string_lossy()) {
+                Ok(()) => loaded += 1,
+                Err(e) => warn!("⚠️ 检测规则加载失败 {}: {:#}", path.display(), e),
+            }
+        }
+        loaded
+    }
+
+    fn rule_files_in(dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| is_rule_file(p)).collect())
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+
+    /// 热重载：重新加载有改动的规则文件，移除已删除文件的规则，并加载规则目录中新增的文件
+    ///
+    /// 返回规则有变化的应用包名。重载失败时保留旧规则。
+    pub fn reload_changed_rules(&mut self) -> Vec<String> {
+        let mut changed = Vec::new();
+
+        let mut paths: Vec<PathBuf> = self.rule_sources.keys().cloned().collect();
+        for dir in &self.rule_dirs {
+            for path in Self::rule_files_in(dir) {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+
+        for path in paths {
+            if !path.exists() {
+                if let Some(package) = self.rule_sources.remove(&path).and_then(|s| s.package) {
+                    info!("🗑️ 规则文件已删除: {} ({})", package, path.display());
+                    self.resolve_package_rules(&package);
+                    changed.push(package);
+                }
+                continue;
+            }
+
+            let known = self.rule_sources.get(&path).map(|s| s.modified);
+            if known.is_some() && known.flatten() == modified_time(&path) {
+                continue;
+            }
+            match self.load_from_file(&path.to_string_lossy()) {
+                Ok(()) => {
+                    if let Some(package) = self.rule_sources.get(&path).and_then(|s| s.package.clone()) {
+                        changed.push(package);
+                    }
+                }
+                Err(e) => warn!("⚠️ 检测规则重载失败，保留旧规则 {}: {:#}", path.display(), e),
+            }
+        }
+        changed
+    }
+
+    /// 获取应用的声明式检测规则
+    pub fn get_rules(&self, package_name: &str) -> Option<&AppDetectionRules> {
+        self.rules.get(package_name)
+    }
+
+    /// 获取所有已加载规则的应用列表
+    pub fn get_rule_apps(&self) -> Vec<String> {
+        let mut apps: Vec<String> = self.rules.keys().cloned().collect();
+        apps.sort();
+        apps
+    }
     
     /// 保存配置到文件
     pub fn save_to_file(&self, file_path: &str) -> anyhow::Result<()> {
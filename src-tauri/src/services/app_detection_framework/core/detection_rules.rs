@@ -0,0 +1,327 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+use super::detection_config::DetectionConfig;
+use super::detection_result::AppLaunchState;
+use crate::services::ui_tree::UiTree;
+
+/// 规则描述的应用状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleState {
+    /// 启动画面
+    Splash,
+    /// 广告页
+    Advertisement,
+    /// 权限弹窗
+    Permission,
+    /// 登录页
+    Login,
+    /// 首页（应用就绪）
+    Homepage,
+    /// 网络错误
+    NetworkError,
+    /// 加载中
+    Loading,
+    /// 更新提示
+    Update,
+}
+
+impl RuleState {
+    pub fn launch_state(self) -> AppLaunchState {
+        match self {
+            RuleState::Splash => AppLaunchState::SplashScreen,
+            RuleState::Advertisement => AppLaunchState::Advertisement,
+            RuleState::Permission => AppLaunchState::PermissionDialog,
+            RuleState::Login => AppLaunchState::LoginRequired,
+            RuleState::Homepage => AppLaunchState::Ready,
+            RuleState::NetworkError => AppLaunchState::NetworkCheck,
+            RuleState::Loading => AppLaunchState::Loading,
+            RuleState::Update => AppLaunchState::UpdateCheck,
+        }
+    }
+}
+
+/// 单条状态规则
+///
+/// 各条件之间是“与”关系，列表内部是“或”关系；未填写的条件不参与判断。
+/// 文本条件同时匹配 text 和 content-desc，均为子串匹配。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateRule {
+    pub state: RuleState,
+    /// 任意一个文本出现即满足
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub text_any: Vec<String>,
+    /// 所有文本都出现才满足
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub text_all: Vec<String>,
+    /// 出现任意一个文本则不满足
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub text_none: Vec<String>,
+    /// 任意一个 resource-id 出现即满足
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resource_id_any: Vec<String>,
+    /// 当前 Activity 包含任意一个即满足
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub activity_any: Vec<String>,
+    /// 界面中须有属于该包的节点（如系统权限弹窗 com.android.permissioncontroller）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+/// 一个应用的声明式检测规则（一个规则文件对应一个应用）
+///
+/// ```toml
+/// package = "com.ss.android.ugc.aweme"
+/// app_name = "抖音"
+/// max_wait_secs = 40
+///
+/// [[rules]]
+/// state = "advertisement"
+/// text_any = ["跳过广告", "跳过"]
+///
+/// [[rules]]
+/// state = "homepage"
+/// resource_id_any = ["com.ss.android.ugc.aweme:id/tab_home"]
+/// ```
+///
+/// 规则按文件中的顺序匹配，第一条满足的规则决定状态；都不满足时视为加载中。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppDetectionRules {
+    pub package: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wait_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_interval_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_handle_permissions: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_advertisements: Option<bool>,
+    pub rules: Vec<StateRule>,
+}
+
+/// 从UI dump中提取的匹配素材
+struct UiFacts {
+    texts: Vec<String>,
+    resource_ids: Vec<String>,
+    packages: Vec<String>,
+}
+
+impl UiFacts {
+    fn collect(ui_content: &str) -> Self {
+        let mut facts = UiFacts { texts: Vec::new(), resource_ids: Vec::new(), packages: Vec::new() };
+        match UiTree::parse(ui_content) {
+            Ok(tree) => {
+                for node in tree.elements() {
+                    for text in [node.text(), node.content_desc()] {
+                        if !text.is_empty() {
+                            facts.texts.push(text.to_string());
+                        }
+                    }
+                    if !node.resource_id().is_empty() {
+                        facts.resource_ids.push(node.resource_id().to_string());
+                    }
+                    if !node.package().is_empty() && !facts.packages.iter().any(|p| p == node.package()) {
+                        facts.packages.push(node.package().to_string());
+                    }
+                }
+            }
+            // 解析失败时退化为整段文本匹配
+            Err(_) => facts.texts.push(ui_content.to_string()),
+        }
+        facts
+    }
+
+    fn has_text(&self, pattern: &str) -> bool {
+        self.texts.iter().any(|t| t.contains(pattern))
+    }
+}
+
+impl StateRule {
+    fn matches(&self, facts: &UiFacts, activity: Option<&str>) -> bool {
+        if self.text_any.is_empty()
+            && self.text_all.is_empty()
+            && self.resource_id_any.is_empty()
+            && self.activity_any.is_empty()
+            && self.package.is_none()
+        {
+            return false;
+        }
+        if !self.text_any.is_empty() && !self.text_any.iter().any(|t| facts.has_text(t)) {
+            return false;
+        }
+        if !self.text_all.iter().all(|t| facts.has_text(t)) {
+            return false;
+        }
+        if self.text_none.iter().any(|t| facts.has_text(t)) {
+            return false;
+        }
+        if !self.resource_id_any.is_empty()
+            && !self.resource_id_any.iter().any(|id| facts.resource_ids.iter().any(|r| r.contains(id.as_str())))
+        {
+            return false;
+        }
+        if !self.activity_any.is_empty()
+            && !activity.map_or(false, |a| self.activity_any.iter().any(|p| a.contains(p.as_str())))
+        {
+            return false;
+        }
+        if let Some(package) = &self.package {
+            if !facts.packages.iter().any(|p| p == package) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl AppDetectionRules {
+    /// 按扩展名解析规则文件内容（.toml / .json）
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
+        let rules: Self = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(content).with_context(|| format!("TOML规则解析失败: {}", path.display()))?,
+            Some("json") => serde_json::from_str(content).with_context(|| format!("JSON规则解析失败: {}", path.display()))?,
+            _ => return Err(anyhow!("不支持的规则文件格式: {}", path.display())),
+        };
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("读取规则文件失败: {}", path.display()))?;
+        Self::parse(&content, path)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.package.trim().is_empty() {
+            return Err(anyhow!("规则缺少package"));
+        }
+        if self.rules.is_empty() {
+            return Err(anyhow!("{} 没有定义任何规则", self.package));
+        }
+        if !self.rules.iter().any(|r| r.state == RuleState::Homepage) {
+            return Err(anyhow!("{} 缺少homepage规则，无法判断应用就绪", self.package));
+        }
+        Ok(())
+    }
+
+    /// 返回第一条满足的规则对应的状态
+    pub fn evaluate(&self, ui_content: &str, current_activity: Option<&str>) -> Option<RuleState> {
+        let facts = UiFacts::collect(ui_content);
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(&facts, current_activity))
+            .map(|rule| rule.state)
+    }
+
+    /// 是否有指定状态的规则满足（不考虑规则顺序）
+    pub fn matches_state(&self, state: RuleState, ui_content: &str, current_activity: Option<&str>) -> bool {
+        let facts = UiFacts::collect(ui_content);
+        self.rules
+            .iter()
+            .any(|rule| rule.state == state && rule.matches(&facts, current_activity))
+    }
+
+    /// 用规则文件中的时间参数覆盖基础配置
+    pub fn apply_to(&self, mut config: DetectionConfig) -> DetectionConfig {
+        if let Some(secs) = self.max_wait_secs {
+            config.max_wait_time = Duration::from_secs(secs);
+        }
+        if let Some(ms) = self.check_interval_ms {
+            config.check_interval = Duration::from_millis(ms);
+        }
+        if let Some(auto) = self.auto_handle_permissions {
+            config.auto_handle_permissions = auto;
+        }
+        if let Some(skip) = self.skip_advertisements {
+            config.skip_advertisements = skip;
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOUYIN_RULES: &str = r#"
+package = "com.ss.android.ugc.aweme"
+app_name = "抖音"
+max_wait_secs = 40
+
+[[rules]]
+state = "permission"
+package = "com.android.permissioncontroller"
+text_any = ["允许", "始终允许"]
+
+[[rules]]
+state = "advertisement"
+text_any = ["跳过广告", "跳过"]
+
+[[rules]]
+state = "network_error"
+text_any = ["网络不给力", "无网络连接"]
+
+[[rules]]
+state = "homepage"
+resource_id_any = ["com.ss.android.ugc.aweme:id/tab_home"]
+text_none = ["加载中"]
+
+[[rules]]
+state = "splash"
+activity_any = ["SplashActivity"]
+"#;
+
+    fn dump(nodes: &[(&str, &str, &str)]) -> String {
+        let body: String = nodes
+            .iter()
+            .map(|(package, id, text)| format!(r#"<node package="{}" resource-id="{}" text="{}" bounds="[0,0][10,10]" />"#, package, id, text))
+            .collect();
+        format!("<hierarchy>{}</hierarchy>", body)
+    }
+
+    #[test]
+    fn test_parse_toml_and_evaluate_in_order() {
+        let rules = AppDetectionRules::parse(DOUYIN_RULES, Path::new("douyin.toml")).unwrap();
+        let app = "com.ss.android.ugc.aweme";
+
+        let home = dump(&[(app, "com.ss.android.ugc.aweme:id/tab_home", "首页")]);
+        assert_eq!(rules.evaluate(&home, None), Some(RuleState::Homepage));
+        assert_eq!(RuleState::Homepage.launch_state(), AppLaunchState::Ready);
+
+        // 广告规则排在首页前面，先命中
+        let ad = dump(&[(app, "com.ss.android.ugc.aweme:id/tab_home", ""), (app, "", "跳过 3")]);
+        assert_eq!(rules.evaluate(&ad, None), Some(RuleState::Advertisement));
+
+        // 权限规则要求系统弹窗的包名，应用内的“允许”文字不算
+        let in_app_allow = dump(&[(app, "", "允许陌生人私信")]);
+        assert_eq!(rules.evaluate(&in_app_allow, None), None);
+        let permission = dump(&[(app, "", ""), ("com.android.permissioncontroller", "", "允许")]);
+        assert_eq!(rules.evaluate(&permission, None), Some(RuleState::Permission));
+
+        let loading_home = dump(&[(app, "com.ss.android.ugc.aweme:id/tab_home", "加载中")]);
+        assert_eq!(rules.evaluate(&loading_home, Some("com.ss.android.ugc.aweme/.splash.SplashActivity")), Some(RuleState::Splash));
+        assert!(rules.matches_state(RuleState::Splash, &loading_home, Some("SplashActivity")));
+
+        let config = rules.apply_to(DetectionConfig::default());
+        assert_eq!(config.max_wait_time, Duration::from_secs(40));
+    }
+
+    #[test]
+    fn test_json_rules_and_validation() {
+        let json = r#"{"package": "com.smile.gifmaker", "rules": [{"state": "homepage", "text_all": ["精选", "关注"]}]}"#;
+        let rules = AppDetectionRules::parse(json, Path::new("kuaishou.json")).unwrap();
+        let page = dump(&[("com.smile.gifmaker", "", "精选"), ("com.smile.gifmaker", "", "关注")]);
+        assert_eq!(rules.evaluate(&page, None), Some(RuleState::Homepage));
+        assert_eq!(rules.evaluate(&dump(&[("com.smile.gifmaker", "", "精选")]), None), None);
+
+        let no_homepage = r#"{"package": "com.smile.gifmaker", "rules": [{"state": "splash", "text_any": ["快手"]}]}"#;
+        assert!(AppDetectionRules::parse(no_homepage, Path::new("kuaishou.json")).is_err());
+        assert!(AppDetectionRules::parse(json, Path::new("kuaishou.yaml")).is_err());
+    }
+}
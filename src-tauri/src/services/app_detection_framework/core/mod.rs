@@ -4,12 +4,15 @@
 //! - `detector_trait`: 定义应用检测器的通用接口
 //! - `detection_result`: 检测结果和状态的类型定义
 //! - `detection_config`: 检测配置管理
+//! - `detection_rules`: 声明式检测规则（TOML/JSON）
 
 pub mod detector_trait;
 pub mod detection_result;
 pub mod detection_config;
+pub mod detection_rules;
 
 // 重新导出核心类型，便于外部使用
 pub use detector_trait::{AppDetector, BaseAppDetector};
 pub use detection_result::{AppLaunchState, DetectionResult, DetectionStats};
-pub use detection_config::{DetectionConfig, DetectionKeywords, AppConfigManager};
\ No newline at end of file
+pub use detection_config::{DetectionConfig, DetectionKeywords, AppConfigManager, default_rules_dir};
+pub use detection_rules::{AppDetectionRules, RuleState, StateRule};
\ No newline at end of file
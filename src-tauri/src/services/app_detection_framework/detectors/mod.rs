@@ -4,12 +4,14 @@
 //! - `xiaohongshu_detector`: 小红书专用检测器
 //! - `wechat_detector`: 微信专用检测器  
 //! - `generic_detector`: 通用检测器
+//! - `rule_based_detector`: 规则检测器（由规则文件描述的应用）
 //! 
 //! 每个检测器都实现了 `AppDetector` trait，提供应用特定的状态检测逻辑
 
 pub mod xiaohongshu_detector;
 pub mod wechat_detector;
 pub mod generic_detector;
+pub mod rule_based_detector;
 
 // TODO: 添加更多应用的专用检测器
 // pub mod qq_detector;
@@ -20,4 +22,5 @@ pub mod generic_detector;
 // 重新导出检测器类型
 pub use xiaohongshu_detector::XiaohongshuDetector;
 pub use wechat_detector::WechatDetector;
-pub use generic_detector::GenericDetector;
\ No newline at end of file
+pub use generic_detector::GenericDetector;
+pub use rule_based_detector::RuleBasedDetector;
\ No newline at end of file
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use tracing::{info, debug};
+
+use crate::services::adb_shell_session::AdbShellSession;
+use super::super::core::{
+    AppDetector, BaseAppDetector, DetectionResult, AppLaunchState, DetectionConfig,
+    AppDetectionRules, RuleState
+};
+
+/// 规则驱动的检测器
+/// 应用状态完全由规则文件描述，新增应用不需要修改代码
+pub struct RuleBasedDetector {
+    base: BaseAppDetector,
+    rules: AppDetectionRules,
+}
+
+impl RuleBasedDetector {
+    pub fn new(rules: AppDetectionRules, app_name: String, shell_session: AdbShellSession) -> Self {
+        let app_name = rules.app_name.clone().unwrap_or(app_name);
+        let base = BaseAppDetector::new(rules.package.clone(), app_name, shell_session);
+        Self { base, rules }
+    }
+
+    /// 以基础配置为底，叠加规则文件中的时间参数
+    pub fn with_config(mut self, config: DetectionConfig) -> Self {
+        self.base = self.base.with_config(self.rules.apply_to(config));
+        self
+    }
+}
+
+#[async_trait]
+impl AppDetector for RuleBasedDetector {
+    fn package_name(&self) -> &str {
+        &self.base.package_name
+    }
+
+    fn app_name(&self) -> &str {
+        &self.base.app_name
+    }
+
+    fn detection_config(&self) -> DetectionConfig {
+        self.base.config.clone()
+    }
+
+    fn get_shell_session(&self) -> &AdbShellSession {
+        &self.base.shell_session
+    }
+
+    async fn wait_for_app_ready(&self) -> Result<DetectionResult> {
+        info!("🚀 开始等待应用就绪(规则检测): {} ({})", self.app_name(), self.package_name());
+        self.base.generic_wait_for_ready(self).await
+    }
+
+    async fn analyze_app_state(&self, ui_content: &str, current_activity: &Option<String>) -> AppLaunchState {
+        debug!("🔍 规则分析应用状态: {} (UI内容长度: {}字符)", self.app_name(), ui_content.len());
+
+        if ui_content.is_empty() {
+            return AppLaunchState::Error("UI内容为空".to_string());
+        }
+
+        match self.rules.evaluate(ui_content, current_activity.as_deref()) {
+            Some(state) => {
+                info!("📜 {} 命中规则: {:?}", self.app_name(), state);
+                state.launch_state()
+            }
+            None => {
+                info!("⏳ {} 未命中任何规则，默认为加载中", self.app_name());
+                AppLaunchState::Loading
+            }
+        }
+    }
+
+    async fn is_homepage_ready(&self, ui_content: &str) -> bool {
+        self.rules.matches_state(RuleState::Homepage, ui_content, None)
+    }
+
+    async fn is_splash_screen(&self, ui_content: &str) -> bool {
+        self.rules.matches_state(RuleState::Splash, ui_content, None)
+    }
+
+    async fn has_permission_dialog(&self, ui_content: &str) -> bool {
+        self.rules.matches_state(RuleState::Permission, ui_content, None)
+    }
+
+    async fn needs_login(&self, ui_content: &str) -> bool {
+        self.rules.matches_state(RuleState::Login, ui_content, None)
+    }
+
+    async fn check_network_status(&self, ui_content: &str) -> bool {
+        !self.rules.matches_state(RuleState::NetworkError, ui_content, None)
+    }
+}
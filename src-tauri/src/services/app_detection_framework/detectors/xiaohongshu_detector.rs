@@ -3,6 +3,7 @@ use anyhow::Result;
 use tracing::{info, debug, warn};
 
 use crate::services::adb_shell_session::AdbShellSession;
+use crate::services::popup_watcher::get_popup_watchers;
 use super::super::core::{
     AppDetector, BaseAppDetector, DetectionResult, AppLaunchState, 
     DetectionConfig, DetectionKeywords
@@ -140,6 +141,17 @@ impl XiaohongshuDetector {
     /// 自动处理小红书权限弹窗
     async fn handle_xiaohongshu_permissions(&self) -> Result<bool> {
         info!("🔐 尝试自动处理小红书权限弹窗");
+
+        // 优先交给弹窗监视器按界面定位按钮
+        let device_id = self.base.shell_session.get_device_id();
+        let watchers = get_popup_watchers();
+        let fired = watchers
+            .check_and_dismiss(device_id, &watchers.settings_for(device_id), None)
+            .await;
+        if !fired.is_empty() {
+            info!("✅ 弹窗监视器已处理 {} 个弹窗", fired.len());
+            return Ok(true);
+        }
         
         // 获取当前UI
         let ui_content = self.base.shell_session
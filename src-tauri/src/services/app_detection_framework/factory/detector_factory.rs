@@ -3,8 +3,8 @@ use anyhow::Result;
 use tracing::{info, debug};
 
 use crate::services::adb_shell_session::AdbShellSession;
-use super::super::core::{AppDetector, AppConfigManager, DetectionConfig};
-use super::super::detectors::{XiaohongshuDetector, WechatDetector, GenericDetector};
+use super::super::core::{AppDetector, AppConfigManager, DetectionConfig, default_rules_dir};
+use super::super::detectors::{XiaohongshuDetector, WechatDetector, GenericDetector, RuleBasedDetector};
 
 /// 应用检测器工厂
 /// 根据应用包名创建对应的检测器实例
@@ -26,7 +26,7 @@ impl DetectorFactory {
     }
     
     /// 创建应用检测器
-    /// 根据包名选择合适的检测器实现
+    /// 根据包名选择合适的检测器实现，规则文件中定义的应用优先使用规则检测器
     pub fn create_detector(
         &self, 
         package_name: &str, 
@@ -35,6 +35,16 @@ impl DetectorFactory {
     ) -> Arc<dyn AppDetector> {
         info!("🏭 创建应用检测器: {} ({})", app_name, package_name);
         
+        if let Some(rules) = self.config_manager.get_rules(package_name) {
+            debug!("📜 使用规则检测器: {} 条规则", rules.rules.len());
+            let config = self.config_manager.get_config(package_name);
+            return Arc::new(RuleBasedDetector::new(
+                rules.clone(),
+                app_name.to_string(),
+                shell_session
+            ).with_config(config));
+        }
+        
         match package_name {
             "com.xingin.xhs" => {
                 debug!("📱 使用小红书专用检测器");
@@ -126,26 +136,17 @@ impl DetectorFactory {
         let adb_path = crate::utils::adb_utils::get_adb_path();
         let shell_session = AdbShellSession::new(device_id.to_string(), adb_path);
         
-        let detector: Arc<dyn AppDetector> = match package_name {
-            "com.xingin.xhs" => {
-                debug!("📱 使用小红书专用检测器");
-                Arc::new(XiaohongshuDetector::new(shell_session))
-            },
-            "com.tencent.mm" => {
-                debug!("💬 使用微信专用检测器");
-                Arc::new(WechatDetector::new(shell_session))
-            },
-            _ => {
-                debug!("🔧 使用通用检测器: {}", package_name);
-                Arc::new(GenericDetector::new(
-                    package_name.to_string(),
-                    package_name.to_string(), // 使用包名作为应用名
-                    shell_session
-                ))
-            }
-        };
+        // 通过全局注册表创建，创建前先热重载有改动的规则文件
+        let mut registry = DetectorRegistry::global()
+            .lock()
+            .map_err(|e| anyhow::anyhow!("检测器注册表锁定失败: {}", e))?;
+        registry.reload_rules();
         
-        Ok(detector)
+        Ok(registry.create_detector_for(
+            package_name,
+            package_name, // 使用包名作为应用名
+            shell_session
+        ))
     }
 }
 
@@ -188,10 +189,24 @@ impl DetectorRegistry {
         self.factory.create_detector(package_name, app_name, shell_session)
     }
     
-    /// 获取全局单例
+    /// 热重载规则文件，返回规则有变化的应用
+    pub fn reload_rules(&mut self) -> Vec<String> {
+        let changed = self.factory.config_manager_mut().reload_changed_rules();
+        if !changed.is_empty() {
+            info!("🔄 检测规则已更新: {:?}", changed);
+        }
+        changed
+    }
+    
+    /// 获取全局单例（首次使用时加载 data/detector_rules 下的规则文件）
     pub fn global() -> &'static std::sync::Mutex<DetectorRegistry> {
         static REGISTRY: std::sync::OnceLock<std::sync::Mutex<DetectorRegistry>> = std::sync::OnceLock::new();
-        REGISTRY.get_or_init(|| std::sync::Mutex::new(DetectorRegistry::new()))
+        REGISTRY.get_or_init(|| {
+            let mut registry = DetectorRegistry::new();
+            let loaded = registry.factory_mut().config_manager_mut().load_rules_dir(&default_rules_dir());
+            info!("📜 已加载 {} 个检测规则文件", loaded);
+            std::sync::Mutex::new(registry)
+        })
     }
 }
 
@@ -201,14 +216,27 @@ impl Default for DetectorRegistry {
     }
 }
 
-/// 便捷函数：创建应用检测器
+/// 便捷函数：创建应用检测器（通过全局注册表，规则文件中定义的应用使用规则检测器）
 pub fn create_app_detector(
     package_name: &str,
     app_name: &str,
     shell_session: AdbShellSession
 ) -> Arc<dyn AppDetector> {
-    let factory = DetectorFactory::new();
-    factory.create_detector(package_name, app_name, shell_session)
+    let mut registry = DetectorRegistry::global()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    registry.reload_rules();
+    registry.create_detector_for(package_name, app_name, shell_session)
+}
+
+/// Tauri命令：重新加载检测规则文件，返回当前已配置规则的应用包名
+#[tauri::command]
+pub fn reload_detector_rules() -> Result<Vec<String>, String> {
+    let mut registry = DetectorRegistry::global()
+        .lock()
+        .map_err(|e| format!("检测器注册表锁定失败: {}", e))?;
+    registry.reload_rules();
+    Ok(registry.factory().config_manager().get_rule_apps())
 }
 
 /// 便捷函数：检查应用是否支持专用检测器
@@ -241,4 +269,58 @@ mod tests {
         assert!(supported_apps.contains(&"com.xingin.xhs"));
         assert!(supported_apps.contains(&"com.tencent.mm"));
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_rule_files_create_rule_detector_and_hot_reload() {
+        let dir = std::env::temp_dir().join(format!("detector_rules_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let douyin = dir.join("douyin.toml");
+        std::fs::write(&douyin, "package = \"com.ss.android.ugc.aweme\"\napp_name = \"抖音\"\n\n[[rules]]\nstate = \"homepage\"\ntext_any = [\"推荐\"]\n").unwrap();
+        std::fs::write(dir.join("broken.json"), "{ not json").unwrap();
+        
+        let mut factory = DetectorFactory::new();
+        assert_eq!(factory.config_manager_mut().load_rules_dir(&dir), 1);
+        
+        let session = AdbShellSession::new("test".to_string(), "adb".to_string());
+        let detector = factory.create_detector("com.ss.android.ugc.aweme", "com.ss.android.ugc.aweme", session);
+        assert_eq!(detector.app_name(), "抖音");
+        // 抖音预置配置的等待时间保留
+        assert_eq!(detector.detection_config().max_wait_time, std::time::Duration::from_secs(40));
+        
+        // 新增文件被发现，删除文件的规则被移除，损坏的文件不影响其他规则
+        std::fs::write(dir.join("kuaishou.json"), r#"{"package": "com.smile.gifmaker", "rules": [{"state": "homepage", "text_any": ["精选"]}]}"#).unwrap();
+        std::fs::remove_file(&douyin).unwrap();
+        let mut changed = factory.config_manager_mut().reload_changed_rules();
+        changed.sort();
+        assert_eq!(changed, vec!["com.smile.gifmaker".to_string(), "com.ss.android.ugc.aweme".to_string()]);
+        assert_eq!(factory.config_manager().get_rule_apps(), vec!["com.smile.gifmaker".to_string()]);
+        assert!(factory.config_manager_mut().reload_changed_rules().is_empty());
+        
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_deleting_one_of_two_rule_files_keeps_package_rules() {
+        let dir = std::env::temp_dir().join(format!("detector_rules_shadow_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base = dir.join("a_douyin.toml");
+        let override_file = dir.join("b_douyin.toml");
+        std::fs::write(&base, "package = \"com.ss.android.ugc.aweme\"\napp_name = \"抖音\"\n\n[[rules]]\nstate = \"homepage\"\ntext_any = [\"推荐\"]\n").unwrap();
+        std::fs::write(&override_file, "package = \"com.ss.android.ugc.aweme\"\napp_name = \"抖音极速版\"\n\n[[rules]]\nstate = \"homepage\"\ntext_any = [\"推荐\"]\n").unwrap();
+
+        let mut factory = DetectorFactory::new();
+        assert_eq!(factory.config_manager_mut().load_rules_dir(&dir), 2);
+        assert_eq!(factory.config_manager().get_rules("com.ss.android.ugc.aweme").unwrap().app_name.as_deref(), Some("抖音极速版"));
+
+        // 删除覆盖文件后回退到另一个文件的规则，而不是移除该应用
+        std::fs::remove_file(&override_file).unwrap();
+        assert_eq!(factory.config_manager_mut().reload_changed_rules(), vec!["com.ss.android.ugc.aweme".to_string()]);
+        assert_eq!(factory.config_manager().get_rules("com.ss.android.ugc.aweme").unwrap().app_name.as_deref(), Some("抖音"));
+
+        std::fs::remove_file(&base).unwrap();
+        factory.config_manager_mut().reload_changed_rules();
+        assert!(factory.config_manager().get_rule_apps().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
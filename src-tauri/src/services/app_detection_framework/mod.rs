@@ -17,11 +17,13 @@
 //! ├── core/                    # 核心抽象
 //! │   ├── detector_trait       # 检测器接口定义
 //! │   ├── detection_result     # 结果类型定义
-//! │   └── detection_config     # 配置管理
+//! │   ├── detection_config     # 配置管理
+//! │   └── detection_rules      # 声明式检测规则
 //! ├── detectors/               # 具体检测器实现
 //! │   ├── xiaohongshu_detector # 小红书检测器
 //! │   ├── wechat_detector      # 微信检测器
-//! │   └── generic_detector     # 通用检测器
+//! │   ├── generic_detector     # 通用检测器
+//! │   └── rule_based_detector  # 规则检测器
 //! └── factory/                 # 检测器工厂
 //!     └── detector_factory     # 工厂实现
 //! ```
@@ -64,7 +66,11 @@
 //! 
 //! ## 扩展新应用
 //! 
-//! 要为新应用添加专用检测器，需要：
+//! 大多数应用只需在 `data/detector_rules/` 下放一个规则文件（TOML 或 JSON，格式见
+//! `core::detection_rules::AppDetectionRules`），工厂会为该包名创建 `RuleBasedDetector`。
+//! 规则文件改动或新增后，下次创建检测器时自动重新加载，无需重新编译。
+//! 
+//! 需要复杂交互逻辑时，再为新应用添加专用检测器：
 //! 
 //! 1. 在 `detectors/` 目录下创建新的检测器文件
 //! 2. 实现 `AppDetector` trait
@@ -92,13 +98,14 @@ pub mod factory;
 pub use core::{
     AppDetector, BaseAppDetector,
     DetectionResult, AppLaunchState, DetectionStats,
-    DetectionConfig, DetectionKeywords, AppConfigManager
+    DetectionConfig, DetectionKeywords, AppConfigManager,
+    AppDetectionRules, RuleState, StateRule
 };
 
 pub use detectors::{
-    XiaohongshuDetector, WechatDetector, GenericDetector
+    XiaohongshuDetector, WechatDetector, GenericDetector, RuleBasedDetector
 };
 
 pub use factory::{
-    DetectorFactory, DetectorRegistry, create_app_detector, is_specialized_app
+    DetectorFactory, DetectorRegistry, create_app_detector, is_specialized_app, reload_detector_rules
 };
\ No newline at end of file
@@ -0,0 +1,350 @@
+// 应用冷/热启动基准测试
+// 冷启动：am force-stop 后 am start -W；热启动：按 Home 退到后台后 am start -W
+// TotalTime/WaitTime 取自 am start -W，"真正可用"时间取自 AppDetector::wait_for_app_ready，
+// 结果按设备型号保存到 data/launch_benchmarks/<型号>.json，用于调整各机型的超时配置
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::services::adb_session_manager::get_device_session;
+use crate::services::app_detection_framework::{DetectionStats, DetectorFactory};
+use crate::services::ui_idle::settle;
+
+const LAUNCH_BENCHMARK_DIR: &str = "data/launch_benchmarks";
+/// 每个型号最多保留的报告数
+const MAX_REPORTS_PER_MODEL: usize = 20;
+const KEYCODE_HOME: i32 = 3;
+
+/// 启动方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchMode {
+    Cold,
+    Warm,
+}
+
+/// `am start -W` 的输出
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AmStartTiming {
+    pub status: Option<String>,
+    /// COLD / WARM / HOT（Android 10+ 才有）
+    pub launch_state: Option<String>,
+    pub total_time_ms: Option<u64>,
+    pub wait_time_ms: Option<u64>,
+}
+
+/// 单次启动样本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchSample {
+    pub mode: LaunchMode,
+    pub iteration: u32,
+    pub am_timing: AmStartTiming,
+    /// 从发出启动命令到检测器判定可用的耗时
+    pub ready_time_ms: Option<u64>,
+    pub ready: bool,
+    pub final_state: String,
+}
+
+/// 耗时分布
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimeSummary {
+    pub count: usize,
+    pub min_ms: u64,
+    pub max_ms: u64,
+    pub mean_ms: u64,
+    pub median_ms: u64,
+    pub p90_ms: u64,
+}
+
+impl TimeSummary {
+    pub fn from_values(values: &[u64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        let percentile = |p: usize| sorted[((sorted.len() * p).div_ceil(100)).saturating_sub(1).min(sorted.len() - 1)];
+        Self {
+            count: sorted.len(),
+            min_ms: sorted[0],
+            max_ms: sorted[sorted.len() - 1],
+            mean_ms: sorted.iter().sum::<u64>() / sorted.len() as u64,
+            median_ms: percentile(50),
+            p90_ms: percentile(90),
+        }
+    }
+}
+
+/// 某种启动方式的统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchModeStats {
+    pub mode: LaunchMode,
+    /// 可用时间统计（elapsed_time 为从启动命令到可用的耗时）
+    pub detection_stats: DetectionStats,
+    pub total_time: TimeSummary,
+    pub wait_time: TimeSummary,
+    pub ready_time: TimeSummary,
+    /// 建议的就绪超时：可用时间 P90 的 1.5 倍
+    pub suggested_ready_timeout_ms: Option<u64>,
+}
+
+/// 一次基准测试报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchBenchmarkReport {
+    pub device_id: String,
+    pub device_model: String,
+    pub manufacturer: String,
+    pub package_name: String,
+    pub iterations: u32,
+    pub started_at: i64,
+    pub samples: Vec<LaunchSample>,
+    pub stats: Vec<LaunchModeStats>,
+}
+
+/// 某个型号的历史报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceModelBenchmarks {
+    pub device_model: String,
+    pub reports: Vec<LaunchBenchmarkReport>,
+}
+
+/// 解析 `am start -W` 输出
+pub fn parse_am_start_output(output: &str) -> AmStartTiming {
+    let mut timing = AmStartTiming::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match key.trim() {
+            "Status" => timing.status = Some(value.to_string()),
+            "LaunchState" => timing.launch_state = Some(value.to_string()),
+            "TotalTime" => timing.total_time_ms = value.parse().ok(),
+            "WaitTime" => timing.wait_time_ms = value.parse().ok(),
+            _ => {}
+        }
+    }
+    timing
+}
+
+/// 汇总同一启动方式的样本
+pub fn summarize(mode: LaunchMode, samples: &[LaunchSample]) -> LaunchModeStats {
+    let samples: Vec<&LaunchSample> = samples.iter().filter(|s| s.mode == mode).collect();
+    let mut detection_stats = DetectionStats::new();
+    let mut ready_times = Vec::new();
+    for sample in &samples {
+        detection_stats.total_detections += 1;
+        if let Some(ready_ms) = sample.ready_time_ms.filter(|_| sample.ready) {
+            detection_stats.successful_detections += 1;
+            ready_times.push(ready_ms);
+        }
+        *detection_stats.state_counts.entry(sample.final_state.clone()).or_insert(0) += 1;
+    }
+    let ready_time = TimeSummary::from_values(&ready_times);
+    if ready_time.count > 0 {
+        detection_stats.average_detection_time = Duration::from_millis(ready_time.mean_ms);
+        detection_stats.fastest_detection = Duration::from_millis(ready_time.min_ms);
+        detection_stats.slowest_detection = Duration::from_millis(ready_time.max_ms);
+    }
+    let values = |f: fn(&AmStartTiming) -> Option<u64>| -> Vec<u64> { samples.iter().filter_map(|s| f(&s.am_timing)).collect() };
+
+    LaunchModeStats {
+        mode,
+        detection_stats,
+        total_time: TimeSummary::from_values(&values(|t| t.total_time_ms)),
+        wait_time: TimeSummary::from_values(&values(|t| t.wait_time_ms)),
+        suggested_ready_timeout_ms: (ready_time.count > 0).then(|| ready_time.p90_ms * 3 / 2),
+        ready_time,
+    }
+}
+
+async fn shell(device_id: &str, command: &str) -> Result<String, String> {
+    let session = get_device_session(device_id).await.map_err(|e| format!("获取设备会话失败: {}", e))?;
+    session
+        .execute_command_with_timeout(command, Duration::from_secs(60))
+        .await
+        .map_err(|e| format!("执行命令失败 ({}): {}", command, e))
+}
+
+/// 解析启动 Activity；解析不到时用包名+LAUNCHER 分类启动
+async fn launch_intent(device_id: &str, package_name: &str) -> String {
+    let resolved = shell(device_id, &format!("cmd package resolve-activity --brief {}", package_name)).await.unwrap_or_default();
+    match resolved.lines().map(str::trim).rfind(|l| l.starts_with(package_name) && l.contains('/')) {
+        Some(component) => format!("-n {}", component),
+        None => format!("-a android.intent.action.MAIN -c android.intent.category.LAUNCHER -p {}", package_name),
+    }
+}
+
+async fn run_sample(device_id: &str, package_name: &str, intent: &str, mode: LaunchMode, iteration: u32) -> Result<LaunchSample, String> {
+    match mode {
+        LaunchMode::Cold => {
+            shell(device_id, &format!("am force-stop {}", package_name)).await?;
+        }
+        LaunchMode::Warm => {
+            shell(device_id, &format!("input keyevent {}", KEYCODE_HOME)).await?;
+        }
+    }
+    settle(device_id, Duration::from_secs(2)).await;
+
+    let started = Instant::now();
+    let output = shell(device_id, &format!("am start -W {}", intent)).await?;
+    let am_timing = parse_am_start_output(&output);
+
+    let detector = DetectorFactory::create_detector_for(package_name, device_id).map_err(|e| format!("创建检测器失败: {}", e))?;
+    let (ready, final_state) = match detector.wait_for_app_ready().await {
+        Ok(result) => (result.state.is_ready(), result.state.name()),
+        Err(e) => (false, format!("Error: {}", e)),
+    };
+    let ready_time_ms = ready.then(|| started.elapsed().as_millis() as u64);
+
+    info!(
+        "⏱️ {:?} 第{}次: TotalTime={:?} WaitTime={:?} 可用={:?}ms",
+        mode, iteration, am_timing.total_time_ms, am_timing.wait_time_ms, ready_time_ms
+    );
+    Ok(LaunchSample { mode, iteration, am_timing, ready_time_ms, ready, final_state })
+}
+
+/// 对应用做冷/热启动基准测试
+pub async fn run_launch_benchmark(
+    device_id: &str,
+    package_name: &str,
+    iterations: u32,
+    modes: &[LaunchMode],
+) -> Result<LaunchBenchmarkReport, String> {
+    let device_model = shell(device_id, "getprop ro.product.model").await?.trim().to_string();
+    let manufacturer = shell(device_id, "getprop ro.product.manufacturer").await?.trim().to_string();
+    let intent = launch_intent(device_id, package_name).await;
+    info!("🏁 启动基准测试: {} @ {} ({}) x{} {:?}", package_name, device_model, device_id, iterations, modes);
+
+    let started_at = chrono::Utc::now().timestamp_millis();
+    let mut samples = Vec::new();
+    for iteration in 1..=iterations {
+        for &mode in modes {
+            // 热启动需要应用已在后台
+            if mode == LaunchMode::Warm && !samples.iter().any(|s: &LaunchSample| s.iteration == iteration) {
+                shell(device_id, &format!("am start -W {}", intent)).await?;
+                settle(device_id, Duration::from_secs(3)).await;
+            }
+            match run_sample(device_id, package_name, &intent, mode, iteration).await {
+                Ok(sample) => samples.push(sample),
+                Err(e) => warn!("⚠️ {:?} 第{}次启动失败: {}", mode, iteration, e),
+            }
+        }
+    }
+
+    let stats = modes.iter().map(|&mode| summarize(mode, &samples)).collect();
+    let report = LaunchBenchmarkReport {
+        device_id: device_id.to_string(),
+        device_model: if device_model.is_empty() { device_id.to_string() } else { device_model },
+        manufacturer,
+        package_name: package_name.to_string(),
+        iterations,
+        started_at,
+        samples,
+        stats,
+    };
+    save_report(&report)?;
+    Ok(report)
+}
+
+static BENCHMARK_FILE_LOCK: Mutex<()> = Mutex::new(());
+
+fn model_path(model: &str) -> PathBuf {
+    let file_name: String = model
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    PathBuf::from(LAUNCH_BENCHMARK_DIR).join(format!("{}.json", file_name))
+}
+
+fn load_model(model: &str) -> DeviceModelBenchmarks {
+    fs::read_to_string(model_path(model))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| DeviceModelBenchmarks { device_model: model.to_string(), reports: Vec::new() })
+}
+
+fn save_report(report: &LaunchBenchmarkReport) -> Result<(), String> {
+    // 锁只保护文件读写顺序，没有需要恢复的内存状态：之前持锁的线程 panic 不影响继续使用
+    let _guard = BENCHMARK_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut history = load_model(&report.device_model);
+    history.reports.push(report.clone());
+    let overflow = history.reports.len().saturating_sub(MAX_REPORTS_PER_MODEL);
+    history.reports.drain(..overflow);
+
+    fs::create_dir_all(LAUNCH_BENCHMARK_DIR).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    let content = serde_json::to_string_pretty(&history).map_err(|e| e.to_string())?;
+    fs::write(model_path(&report.device_model), content).map_err(|e| format!("保存基准测试结果失败: {}", e))
+}
+
+/// 运行应用冷/热启动基准测试
+#[tauri::command]
+pub async fn benchmark_app_launch(
+    device_id: String,
+    package_name: String,
+    iterations: Option<u32>,
+    modes: Option<Vec<LaunchMode>>,
+) -> Result<LaunchBenchmarkReport, String> {
+    let iterations = iterations.unwrap_or(5).max(1);
+    let modes = modes.filter(|m| !m.is_empty()).unwrap_or_else(|| vec![LaunchMode::Cold, LaunchMode::Warm]);
+    run_launch_benchmark(&device_id, &package_name, iterations, &modes).await
+}
+
+/// 获取已保存的基准测试结果（不指定型号时返回全部型号）
+#[tauri::command]
+pub fn get_launch_benchmarks(device_model: Option<String>) -> Result<Vec<DeviceModelBenchmarks>, String> {
+    if let Some(model) = device_model {
+        return Ok(vec![load_model(&model)]);
+    }
+    let Ok(entries) = fs::read_dir(LAUNCH_BENCHMARK_DIR) else { return Ok(Vec::new()) };
+    let mut all: Vec<DeviceModelBenchmarks> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    all.sort_by(|a, b| a.device_model.cmp(&b.device_model));
+    Ok(all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_am_start_output() {
+        let output = "Starting: Intent { cmp=com.xingin.xhs/.index.v2.IndexActivityV2 }\nStatus: ok\nLaunchState: COLD\nActivity: com.xingin.xhs/.index.v2.IndexActivityV2\nTotalTime: 1834\nWaitTime: 1850\nComplete\n";
+        let timing = parse_am_start_output(output);
+        assert_eq!(timing.status.as_deref(), Some("ok"));
+        assert_eq!(timing.launch_state.as_deref(), Some("COLD"));
+        assert_eq!(timing.total_time_ms, Some(1834));
+        assert_eq!(timing.wait_time_ms, Some(1850));
+    }
+
+    #[test]
+    fn test_summarize_fills_detection_stats() {
+        let sample = |mode, iteration, ready_ms: Option<u64>| LaunchSample {
+            mode,
+            iteration,
+            am_timing: AmStartTiming { total_time_ms: Some(1000 + iteration as u64 * 100), ..Default::default() },
+            ready_time_ms: ready_ms,
+            ready: ready_ms.is_some(),
+            final_state: if ready_ms.is_some() { "Ready".to_string() } else { "Loading".to_string() },
+        };
+        let samples = vec![
+            sample(LaunchMode::Cold, 1, Some(3000)),
+            sample(LaunchMode::Cold, 2, Some(4000)),
+            sample(LaunchMode::Cold, 3, None),
+            sample(LaunchMode::Warm, 1, Some(800)),
+        ];
+        let stats = summarize(LaunchMode::Cold, &samples);
+        assert_eq!(stats.detection_stats.total_detections, 3);
+        assert_eq!(stats.detection_stats.successful_detections, 2);
+        assert_eq!(stats.detection_stats.average_detection_time, Duration::from_millis(3500));
+        assert_eq!(stats.detection_stats.state_counts.get("Loading"), Some(&1));
+        assert_eq!(stats.total_time.count, 3);
+        assert_eq!(stats.ready_time.p90_ms, 4000);
+        assert_eq!(stats.suggested_ready_timeout_ms, Some(6000));
+    }
+}
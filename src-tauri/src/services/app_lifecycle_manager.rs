@@ -13,6 +13,7 @@ use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
 use crate::services::adb_service::AdbService;
+use crate::services::launcher_icon_launcher::launch_via_launcher_icon;
 
 /// 应用生命周期操作结果
 #[derive(Debug, Serialize, Clone)]
@@ -55,8 +56,8 @@ pub struct AppLaunchConfig {
 pub enum LaunchMethod {
     ActivityManager,  // 使用 am start
     MonkeyRunner,    // 使用 monkey 命令
-    DesktopIcon,     // 点击桌面图标（需要UI自动化）
-    Auto,            // 自动选择最适合的方法
+    DesktopIcon,     // 点击桌面图标（翻页查找，必要时打开应用抽屉）
+    Auto,            // 按 AM -> Monkey -> 桌面图标 顺序回退
 }
 
 impl Default for AppLaunchConfig {
@@ -119,7 +120,7 @@ impl AppLifecycleManager {
             }
 
             // Step 2.1: 启动应用
-            match self.launch_app(device_id, app_name, &config, &mut logs).await {
+            match self.launch_app(device_id, app_name, &config, retry_count, &mut logs).await {
                 Ok(_) => {
                     logs.push(format!("📱 启动命令执行成功，等待应用就绪..."));
                     
@@ -227,35 +228,54 @@ impl AppLifecycleManager {
     }
 
     /// 启动应用
+    /// Auto 模式下第 N 次尝试从回退链的第 N 个方法开始（命令成功但应用未进前台时也会换方法），
+    /// 当前方法报错时继续尝试链上后面的方法
     async fn launch_app(
         &self, 
         device_id: &str, 
         app_name: &str, 
         config: &AppLaunchConfig, 
+        attempt: u32,
         logs: &mut Vec<String>
     ) -> Result<(), String> {
-        let package_name = self.get_package_name(app_name)
+        let package_name = config.package_name.clone()
+            .or_else(|| self.get_package_name(app_name))
             .ok_or_else(|| format!("无法获取应用 {} 的包名", app_name))?;
 
-        let method = match &config.launch_method {
-            LaunchMethod::Auto => self.choose_best_launch_method(app_name),
-            other => other.clone(),
+        let methods = match &config.launch_method {
+            LaunchMethod::Auto => {
+                let chain = self.launch_method_chain(app_name);
+                let start = (attempt.saturating_sub(1) as usize).min(chain.len() - 1);
+                chain[start..].to_vec()
+            }
+            other => vec![other.clone()],
         };
 
-        logs.push(format!("🚀 启动应用 {} (方法: {:?})", app_name, method));
+        let mut last_error = String::new();
+        for method in methods {
+            logs.push(format!("🚀 启动应用 {} (方法: {:?})", app_name, method));
 
-        match method {
-            LaunchMethod::ActivityManager => {
-                self.launch_via_activity_manager(device_id, &package_name, logs).await
-            }
-            LaunchMethod::MonkeyRunner => {
-                self.launch_via_monkey(device_id, &package_name, logs).await
-            }
-            LaunchMethod::DesktopIcon => {
-                self.launch_via_desktop_icon(device_id, app_name, logs).await
+            let result = match method {
+                LaunchMethod::ActivityManager => {
+                    self.launch_via_activity_manager(device_id, &package_name, logs).await
+                }
+                LaunchMethod::MonkeyRunner => {
+                    self.launch_via_monkey(device_id, &package_name, logs).await
+                }
+                LaunchMethod::DesktopIcon => {
+                    self.launch_via_desktop_icon(device_id, app_name, logs).await
+                }
+                LaunchMethod::Auto => unreachable!(), // 回退链中不包含 Auto
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    logs.push(format!("⚠️ {:?} 启动失败，尝试下一种方法: {}", method, e));
+                    last_error = e;
+                }
             }
-            LaunchMethod::Auto => unreachable!(), // 已经在上面处理了
         }
+        Err(last_error)
     }
 
     /// 等待应用就绪
@@ -333,12 +353,9 @@ impl AppLifecycleManager {
         }
     }
 
-    /// 选择最佳启动方法
-    fn choose_best_launch_method(&self, app_name: &str) -> LaunchMethod {
-        match app_name {
-            "小红书" | "微信" | "支付宝" => LaunchMethod::ActivityManager,
-            _ => LaunchMethod::ActivityManager, // 默认使用AM
-        }
+    /// 自动模式的启动方法回退链
+    fn launch_method_chain(&self, _app_name: &str) -> Vec<LaunchMethod> {
+        vec![LaunchMethod::ActivityManager, LaunchMethod::MonkeyRunner, LaunchMethod::DesktopIcon]
     }
 
     /// 通过Activity Manager启动
@@ -381,13 +398,11 @@ impl AppLifecycleManager {
         }
     }
 
-    /// 通过桌面图标启动（需要UI自动化支持）
+    /// 通过桌面图标启动（回到桌面逐页查找图标，必要时打开应用抽屉）
     async fn launch_via_desktop_icon(&self, device_id: &str, app_name: &str, logs: &mut Vec<String>) -> Result<(), String> {
         logs.push(format!("🖱️ 尝试通过桌面图标启动 {}", app_name));
-        
-        // 这里需要集成UI自动化逻辑来点击桌面图标
-        // 暂时返回未实现错误
-        Err("桌面图标启动方法暂未实现".to_string())
+
+        launch_via_launcher_icon(device_id, &[app_name.to_string()], 8, logs).await.map(|_| ())
     }
 
     /// 检查应用是否安装
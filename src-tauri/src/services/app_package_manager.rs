@@ -0,0 +1,466 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+use crate::services::adb_session_manager::get_device_session;
+use crate::utils::adb_utils::get_adb_path;
+
+/// APK 安装选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstallOptions {
+    /// 允许安装比设备上更旧的版本
+    pub allow_downgrade: bool,
+    /// 版本相同时也重新安装
+    pub force: bool,
+    /// 安装后预授予的运行时权限
+    pub grant_permissions: Vec<String>,
+    /// 安装后禁止应用自行安装更新包（不会屏蔽应用内的更新提示）
+    pub block_self_update: bool,
+    /// APK 版本名（本机没有 aapt 无法读取 APK 时使用）
+    pub apk_version: Option<String>,
+    /// APK 包名（本机没有 aapt 无法读取 APK 时使用）
+    pub package_name: Option<String>,
+}
+
+/// 从 APK 读取到的包信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApkInfo {
+    pub package_name: String,
+    pub version_name: Option<String>,
+    pub version_code: Option<String>,
+}
+
+/// 单台设备的操作结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageOpResult {
+    pub device_id: String,
+    pub package_name: String,
+    /// install / upgrade / downgrade / reinstall / skip / uninstall / clear_data / grant_permissions / block_self_update
+    pub action: String,
+    pub success: bool,
+    pub message: String,
+    /// 操作前设备上的版本
+    pub previous_version: Option<String>,
+    /// 操作后设备上的版本
+    pub current_version: Option<String>,
+}
+
+impl PackageOpResult {
+    fn new(device_id: &str, package_name: &str, action: &str) -> Self {
+        Self {
+            device_id: device_id.to_string(),
+            package_name: package_name.to_string(),
+            action: action.to_string(),
+            success: false,
+            message: String::new(),
+            previous_version: None,
+            current_version: None,
+        }
+    }
+
+    fn finish(mut self, success: bool, message: impl Into<String>) -> Self {
+        self.success = success;
+        self.message = message.into();
+        self
+    }
+}
+
+/// 比较两个版本名（按数字段逐段比较，缺失的段视为 0）
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().unwrap_or(0))
+            .collect()
+    };
+    let (pa, pb) = (parts(a), parts(b));
+    for i in 0..pa.len().max(pb.len()) {
+        let ord = pa.get(i).unwrap_or(&0).cmp(pb.get(i).unwrap_or(&0));
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    Ordering::Equal
+}
+
+/// 解析 `aapt dump badging` 输出中的 package 行
+pub fn parse_badging(output: &str) -> Option<ApkInfo> {
+    let line = output.lines().find(|l| l.starts_with("package:"))?;
+    let attr = |key: &str| -> Option<String> {
+        let start = line.find(&format!("{}='", key))? + key.len() + 2;
+        let end = line[start..].find('\'')? + start;
+        Some(line[start..end].to_string()).filter(|v| !v.is_empty())
+    };
+    Some(ApkInfo {
+        package_name: attr("name")?,
+        version_name: attr("versionName"),
+        version_code: attr("versionCode"),
+    })
+}
+
+/// 从 `dumpsys package` 输出中提取 versionName
+pub fn parse_installed_version(dumpsys_output: &str) -> Option<String> {
+    dumpsys_output
+        .lines()
+        .map(str::trim)
+        .find_map(|l| l.strip_prefix("versionName="))
+        .map(str::to_string)
+}
+
+/// 用本机的 aapt/aapt2 读取 APK 包名与版本
+pub async fn read_apk_info(apk_path: &str) -> Option<ApkInfo> {
+    for tool in ["aapt", "aapt2"] {
+        let mut cmd = Command::new(tool);
+        cmd.args(["dump", "badging", apk_path]);
+        #[cfg(windows)]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+        if let Ok(output) = cmd.output().await {
+            if let Some(info) = parse_badging(&String::from_utf8_lossy(&output.stdout)) {
+                return Some(info);
+            }
+        }
+    }
+    None
+}
+
+/// 应用包管理器：安装/升级/卸载/清除数据/授权
+pub struct AppPackageManager {
+    device_id: String,
+    adb_path: String,
+}
+
+impl AppPackageManager {
+    pub fn new(device_id: String) -> Self {
+        Self {
+            device_id,
+            adb_path: get_adb_path(),
+        }
+    }
+
+    async fn shell(&self, command: &str) -> Result<String> {
+        let session = get_device_session(&self.device_id).await?;
+        session.execute_command_with_timeout(command, Duration::from_secs(60)).await
+    }
+
+    /// 获取设备上已安装的版本名，未安装时返回 None
+    pub async fn installed_version(&self, package_name: &str) -> Result<Option<String>> {
+        let installed = self.shell(&format!("pm list packages {}", package_name)).await?;
+        if !installed.lines().any(|l| l.trim() == format!("package:{}", package_name)) {
+            return Ok(None);
+        }
+        let output = self.shell(&format!("dumpsys package {}", package_name)).await?;
+        Ok(parse_installed_version(&output))
+    }
+
+    /// 安装或升级 APK，按 versionName 与设备上的版本比较决定是否安装
+    pub async fn install_apk(&self, apk_path: &str, apk: Option<&ApkInfo>, options: &InstallOptions) -> PackageOpResult {
+        let package_name = apk
+            .map(|a| a.package_name.clone())
+            .or_else(|| options.package_name.clone())
+            .unwrap_or_default();
+        let apk_version = apk.and_then(|a| a.version_name.clone()).or_else(|| options.apk_version.clone());
+        let mut result = PackageOpResult::new(&self.device_id, &package_name, "install");
+
+        if !Path::new(apk_path).exists() {
+            return result.finish(false, format!("APK文件不存在: {}", apk_path));
+        }
+
+        if !package_name.is_empty() {
+            match self.installed_version(&package_name).await {
+                Ok(version) => result.previous_version = version,
+                Err(e) => return result.finish(false, format!("读取已安装版本失败: {}", e)),
+            }
+        }
+
+        let mut downgrade = false;
+        if let (Some(installed), Some(target)) = (result.previous_version.clone(), apk_version.as_deref()) {
+            match compare_versions(target, &installed) {
+                Ordering::Equal if !options.force => {
+                    result.action = "skip".to_string();
+                    result.current_version = result.previous_version.clone();
+                    let message = format!("已是目标版本 {}", installed);
+                    return self.after_install(result.finish(true, message), options).await;
+                }
+                Ordering::Equal => result.action = "reinstall".to_string(),
+                Ordering::Greater => result.action = "upgrade".to_string(),
+                Ordering::Less if !options.allow_downgrade => {
+                    return result.finish(false, format!("设备版本 {} 比 APK 版本 {} 新，未允许降级", installed, target));
+                }
+                Ordering::Less => {
+                    result.action = "downgrade".to_string();
+                    downgrade = true;
+                }
+            }
+        } else if result.previous_version.is_some() {
+            result.action = "upgrade".to_string();
+        }
+
+        info!("📦 设备 {} {} {} ({:?} -> {:?})", self.device_id, result.action, apk_path, result.previous_version, apk_version);
+        let mut args = vec!["-s", self.device_id.as_str(), "install", "-r"];
+        if downgrade {
+            args.push("-d");
+        }
+        args.push(apk_path);
+
+        let mut cmd = Command::new(&self.adb_path);
+        cmd.args(&args);
+        #[cfg(windows)]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+        let output = match cmd.output().await {
+            Ok(output) => output,
+            Err(e) => return result.finish(false, format!("执行adb install失败: {}", e)),
+        };
+        let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+        if !text.contains("Success") {
+            let reason = text.lines().find(|l| l.contains("Failure")).unwrap_or(text.trim());
+            warn!("❌ 设备 {} 安装失败: {}", self.device_id, reason);
+            return result.finish(false, format!("安装失败: {}", reason));
+        }
+
+        if !package_name.is_empty() {
+            result.current_version = self.installed_version(&package_name).await.ok().flatten();
+        }
+        let message = format!("安装成功，当前版本 {}", result.current_version.as_deref().unwrap_or("未知"));
+        self.after_install(result.finish(true, message), options).await
+    }
+
+    /// 安装后的授权与禁止自更新，失败只追加到消息中
+    async fn after_install(&self, mut result: PackageOpResult, options: &InstallOptions) -> PackageOpResult {
+        if result.package_name.is_empty() {
+            return result;
+        }
+        let mut notes = Vec::new();
+        if !options.grant_permissions.is_empty() {
+            let grant = self.grant_permissions(&result.package_name, &options.grant_permissions).await;
+            if !grant.success {
+                notes.push(grant.message);
+            }
+        }
+        if options.block_self_update {
+            let block = self.block_self_update(&result.package_name).await;
+            if !block.success {
+                notes.push(block.message);
+            }
+        }
+        if !notes.is_empty() {
+            warn!("⚠️ 设备 {} 安装后处理未完全成功: {}", self.device_id, notes.join("; "));
+            result.message = format!("{}；{}", result.message, notes.join("; "));
+        }
+        result
+    }
+
+    /// 卸载应用，keep_data 为 true 时保留数据和缓存
+    pub async fn uninstall(&self, package_name: &str, keep_data: bool) -> PackageOpResult {
+        let mut result = PackageOpResult::new(&self.device_id, package_name, "uninstall");
+        result.previous_version = self.installed_version(package_name).await.ok().flatten();
+        let command = if keep_data {
+            format!("pm uninstall -k {}", package_name)
+        } else {
+            format!("pm uninstall {}", package_name)
+        };
+        match self.shell(&command).await {
+            Ok(output) if output.contains("Success") => result.finish(true, "卸载成功"),
+            Ok(output) => result.finish(false, format!("卸载失败: {}", output.trim())),
+            Err(e) => result.finish(false, format!("卸载失败: {}", e)),
+        }
+    }
+
+    /// 清除应用数据（pm clear）
+    pub async fn clear_data(&self, package_name: &str) -> PackageOpResult {
+        let result = PackageOpResult::new(&self.device_id, package_name, "clear_data");
+        match self.shell(&format!("pm clear {}", package_name)).await {
+            Ok(output) if output.contains("Success") => result.finish(true, "已清除应用数据"),
+            Ok(output) => result.finish(false, format!("清除数据失败: {}", output.trim())),
+            Err(e) => result.finish(false, format!("清除数据失败: {}", e)),
+        }
+    }
+
+    /// 预授予运行时权限（pm grant），逐个授权并汇总失败项
+    pub async fn grant_permissions(&self, package_name: &str, permissions: &[String]) -> PackageOpResult {
+        let result = PackageOpResult::new(&self.device_id, package_name, "grant_permissions");
+        let mut failed = Vec::new();
+        for permission in permissions {
+            // pm grant 成功时没有输出
+            match self.shell(&format!("pm grant {} {}", package_name, permission)).await {
+                Ok(output) if output.trim().is_empty() => {}
+                Ok(output) => failed.push(format!("{} ({})", permission, output.trim())),
+                Err(e) => failed.push(format!("{} ({})", permission, e)),
+            }
+        }
+        if failed.is_empty() {
+            result.finish(true, format!("已授予 {} 项权限", permissions.len()))
+        } else {
+            result.finish(false, format!("授权失败: {}", failed.join(", ")))
+        }
+    }
+
+    /// 禁止应用自行安装更新包（撤销 REQUEST_INSTALL_PACKAGES），避免版本漂移
+    ///
+    /// 只能阻止应用下载后自行安装 APK，应用内的“发现新版本”提示仍会出现，
+    /// 需要由弹窗监视器 `app_update` 关闭；应用商店推送的更新也不受影响。
+    pub async fn block_self_update(&self, package_name: &str) -> PackageOpResult {
+        let result = PackageOpResult::new(&self.device_id, package_name, "block_self_update");
+        match self.shell(&format!("appops set {} REQUEST_INSTALL_PACKAGES deny", package_name)).await {
+            Ok(output) if output.trim().is_empty() => result.finish(
+                true,
+                "已禁止应用自行安装更新包（应用内更新提示仍会出现，由弹窗监视器 app_update 关闭；应用商店更新不受影响）",
+            ),
+            Ok(output) => result.finish(false, format!("该系统不支持禁止自更新: {}", output.trim())),
+            Err(e) => result.finish(false, format!("禁止自更新失败: {}", e)),
+        }
+    }
+}
+
+/// 在多台设备上并发执行同一操作，结果按设备顺序返回
+async fn run_on_devices<F, Fut>(device_ids: Vec<String>, op: F) -> Vec<PackageOpResult>
+where
+    F: Fn(AppPackageManager) -> Fut,
+    Fut: Future<Output = PackageOpResult> + Send + 'static,
+{
+    let mut set = JoinSet::new();
+    for (index, device_id) in device_ids.into_iter().enumerate() {
+        let fut = op(AppPackageManager::new(device_id));
+        set.spawn(async move { (index, fut.await) });
+    }
+    let mut results = Vec::new();
+    while let Some(joined) = set.join_next().await {
+        if let Ok(item) = joined {
+            results.push(item);
+        }
+    }
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// 读取 APK 信息并在单台设备上安装（供脚本步骤使用）
+pub async fn install_apk_on_device(device_id: &str, apk_path: &str, options: &InstallOptions) -> PackageOpResult {
+    let apk = read_apk_info(apk_path).await;
+    AppPackageManager::new(device_id.to_string()).install_apk(apk_path, apk.as_ref(), options).await
+}
+
+/// 批量安装或升级 APK
+#[tauri::command]
+pub async fn install_apk_on_devices(
+    device_ids: Vec<String>,
+    apk_path: String,
+    options: Option<InstallOptions>,
+) -> Result<Vec<PackageOpResult>, String> {
+    let options = options.unwrap_or_default();
+    let apk = read_apk_info(&apk_path).await;
+    if apk.is_none() && options.package_name.is_none() {
+        return Err("无法读取APK包名（未找到aapt），请在选项中指定 package_name".to_string());
+    }
+    info!("📦 向 {} 台设备安装 {} ({:?})", device_ids.len(), apk_path, apk);
+    Ok(run_on_devices(device_ids, |manager| {
+        let (apk_path, apk, options) = (apk_path.clone(), apk.clone(), options.clone());
+        async move { manager.install_apk(&apk_path, apk.as_ref(), &options).await }
+    })
+    .await)
+}
+
+/// 批量卸载应用
+#[tauri::command]
+pub async fn uninstall_app_on_devices(
+    device_ids: Vec<String>,
+    package_name: String,
+    keep_data: Option<bool>,
+) -> Result<Vec<PackageOpResult>, String> {
+    let keep_data = keep_data.unwrap_or(false);
+    Ok(run_on_devices(device_ids, |manager| {
+        let package_name = package_name.clone();
+        async move { manager.uninstall(&package_name, keep_data).await }
+    })
+    .await)
+}
+
+/// 批量清除应用数据
+#[tauri::command]
+pub async fn clear_app_data_on_devices(device_ids: Vec<String>, package_name: String) -> Result<Vec<PackageOpResult>, String> {
+    Ok(run_on_devices(device_ids, |manager| {
+        let package_name = package_name.clone();
+        async move { manager.clear_data(&package_name).await }
+    })
+    .await)
+}
+
+/// 批量预授予运行时权限
+#[tauri::command]
+pub async fn grant_app_permissions_on_devices(
+    device_ids: Vec<String>,
+    package_name: String,
+    permissions: Vec<String>,
+) -> Result<Vec<PackageOpResult>, String> {
+    Ok(run_on_devices(device_ids, |manager| {
+        let (package_name, permissions) = (package_name.clone(), permissions.clone());
+        async move { manager.grant_permissions(&package_name, &permissions).await }
+    })
+    .await)
+}
+
+/// 批量禁止应用自行安装更新
+#[tauri::command]
+pub async fn block_app_self_update_on_devices(device_ids: Vec<String>, package_name: String) -> Result<Vec<PackageOpResult>, String> {
+    Ok(run_on_devices(device_ids, |manager| {
+        let package_name = package_name.clone();
+        async move { manager.block_self_update(&package_name).await }
+    })
+    .await)
+}
+
+/// 查询各设备上已安装的版本（用于发现版本漂移）
+#[tauri::command]
+pub async fn get_app_versions_on_devices(device_ids: Vec<String>, package_name: String) -> Result<Vec<PackageOpResult>, String> {
+    Ok(run_on_devices(device_ids, |manager| {
+        let package_name = package_name.clone();
+        async move {
+            let result = PackageOpResult::new(&manager.device_id, &package_name, "query_version");
+            match manager.installed_version(&package_name).await {
+                Ok(Some(version)) => {
+                    let mut result = result.finish(true, format!("已安装 {}", version));
+                    result.current_version = Some(version);
+                    result
+                }
+                Ok(None) => result.finish(true, "未安装"),
+                Err(e) => result.finish(false, format!("查询失败: {}", e)),
+            }
+        }
+    })
+    .await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("8.12.0", "8.12"), Ordering::Equal);
+        assert_eq!(compare_versions("8.12.1", "8.9.5"), Ordering::Greater);
+        assert_eq!(compare_versions("7.99", "8.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("8.10.0-beta", "8.10.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_parse_badging_and_dumpsys() {
+        let badging = "package: name='com.xingin.xhs' versionCode='8120100' versionName='8.12.0' platformBuildVersionName=''\nsdkVersion:'21'";
+        let info = parse_badging(badging).unwrap();
+        assert_eq!(info.package_name, "com.xingin.xhs");
+        assert_eq!(info.version_name.as_deref(), Some("8.12.0"));
+        assert_eq!(info.version_code.as_deref(), Some("8120100"));
+        assert!(parse_badging("ERROR: dump failed").is_none());
+
+        let dumpsys = "Packages:\n  Package [com.xingin.xhs] (abc):\n    versionCode=8120100 minSdk=21\n    versionName=8.12.0\n";
+        assert_eq!(parse_installed_version(dumpsys).as_deref(), Some("8.12.0"));
+    }
+}
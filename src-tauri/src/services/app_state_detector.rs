@@ -5,6 +5,8 @@ use tokio::time::{sleep, timeout};
 use tracing::{debug, error, info, warn};
 
 use super::adb_shell_session::AdbShellSession;
+use super::popup_watcher::get_popup_watchers;
+use super::ui_tree::UiTree;
 
 /// 应用启动状态枚举
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -362,6 +364,17 @@ impl AppStateDetector {
     /// 处理权限弹窗
     async fn handle_permission_dialog(&self) -> Result<()> {
         info!("🔐 自动处理权限弹窗");
+
+        // 优先交给弹窗监视器按界面定位按钮
+        let device_id = self.shell_session.get_device_id();
+        let watchers = get_popup_watchers();
+        let fired = watchers
+            .check_and_dismiss(device_id, &watchers.settings_for(device_id), None)
+            .await;
+        if !fired.is_empty() {
+            info!("✅ 权限弹窗已由弹窗监视器处理");
+            return Ok(());
+        }
         
         // 尝试点击"允许"按钮的常见位置
         let allow_positions = vec![
@@ -391,24 +404,16 @@ impl AppStateDetector {
 
     /// 提取关键UI元素
     fn extract_key_ui_elements(&self, ui_content: &str) -> Vec<String> {
-        let mut elements = Vec::new();
-        
-        // 提取重要的UI元素
-        for line in ui_content.lines() {
-            if line.contains("text=\"") && line.contains("clickable=\"true\"") {
-                if let Some(text_start) = line.find("text=\"") {
-                    if let Some(text_end) = line[text_start + 6..].find("\"") {
-                        let text = &line[text_start + 6..text_start + 6 + text_end];
-                        if !text.is_empty() && text.len() > 1 {
-                            elements.push(text.to_string());
-                        }
-                    }
-                }
-            }
-        }
-        
-        elements.truncate(10); // 限制元素数量
-        elements
+        let Ok(tree) = UiTree::parse(ui_content) else {
+            return Vec::new();
+        };
+
+        // 提取重要的UI元素：带文本的可点击元素
+        tree.elements()
+            .filter(|n| n.is_clickable() && n.text().len() > 1)
+            .map(|n| n.text().to_string())
+            .take(10) // 限制元素数量
+            .collect()
     }
 
     /// 获取状态描述信息
@@ -1,3 +1,5 @@
+use crate::services::device_health_monitor::get_health_monitor;
+use crate::services::device_preconditions::get_device_preconditions;
 use crate::services::ldplayer_vcf_opener::{LDPlayerVcfOpener, VcfOpenResult};
 use crate::services::multi_brand_vcf_importer::{MultiBrandVcfImporter, MultiBrandImportResult};
 use crate::services::huawei_enhanced_importer::{HuaweiEmuiEnhancedStrategy, ImportExecutionResult};
@@ -6,6 +8,7 @@ use crate::services::vcf_importer::VcfImporter;
 use crate::services::vcf_importer::{Contact, VcfVerifyResult};
 use crate::services::vcf_importer_async::{VcfImportResult, VcfImporterAsync};
 use crate::services::vcf_importer_optimized::VcfImporterOptimized;
+use crate::services::vcard::VCardVersion;
 use crate::services::xiaohongshu_automator::{
     XiaohongshuAutomator, AppStatusResult, NavigationResult, 
     XiaohongshuFollowOptions, XiaohongshuFollowResult
@@ -36,18 +39,22 @@ use tauri::command;
 use tracing::{error, info, warn};
 
 /// 生成VCF文件从联系人列表
+/// version 不填时写 vCard 2.1（兼容性最好）
 #[command]
 pub async fn generate_vcf_file(
     contacts: Vec<Contact>,
     output_path: String,
+    version: Option<VCardVersion>,
 ) -> Result<String, String> {
+    let version = version.unwrap_or_default();
     info!(
-        "生成VCF文件: {} 个联系人 -> {}",
+        "生成VCF文件: {} 个联系人 -> {} (vCard {})",
         contacts.len(),
-        output_path
+        output_path,
+        version.as_str()
     );
 
-    match VcfImporter::generate_vcf_file(contacts, &output_path).await {
+    match VcfImporter::generate_vcf_file_with_version(contacts, &output_path, version).await {
         Ok(path) => {
             info!("VCF文件生成成功: {}", path);
             Ok(path)
@@ -323,9 +330,16 @@ pub async fn import_vcf_contacts(
         deviceId, contactsFilePath
     );
 
-    let importer = VcfImporter::new(deviceId);
+    // 导入前检查设备健康状态（电量、温度、存储等）
+    get_health_monitor().ensure_job_can_start(&deviceId).await?;
+    // 唤醒并解锁设备，导入期间保持常亮
+    get_device_preconditions().prepare(&deviceId).await?;
 
-    match importer.import_vcf_contacts(&contactsFilePath).await {
+    let importer = VcfImporter::new(deviceId.clone());
+    let import_result = importer.import_vcf_contacts(&contactsFilePath).await;
+    get_device_preconditions().release(&deviceId).await;
+
+    match import_result {
         Ok(result) => {
             info!(
                 "VCF导入完成: 成功={} 总数={} 导入={}",
@@ -497,6 +511,7 @@ pub async fn import_and_follow_xiaohongshu(
 
     // 等待联系人同步
     info!("等待联系人同步到小红书...");
+    // 小红书在后台读取通讯录，界面上没有可等待的信号，只能固定留出同步时间
     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
 
     // 2. 创建自动化器并进行状态检查
@@ -677,6 +692,7 @@ pub async fn import_and_follow_xiaohongshu_enhanced(
 
     // 等待联系人同步
     step_details.push("步骤2: 等待联系人同步到小红书(5秒)".to_string());
+    // 小红书在后台读取通讯录，界面上没有可等待的信号，只能固定留出同步时间
     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
 
     // 2. 创建自动化器并进行状态检查
@@ -878,9 +894,16 @@ pub async fn import_vcf_contacts_multi_brand(
         device_id, contacts_file_path
     );
 
-    let mut importer = MultiBrandVcfImporter::new(device_id);
+    // 导入前检查设备健康状态（电量、温度、存储等）
+    get_health_monitor().ensure_job_can_start(&device_id).await?;
+    // 唤醒并解锁设备，导入期间保持常亮
+    get_device_preconditions().prepare(&device_id).await?;
+
+    let mut importer = MultiBrandVcfImporter::new(device_id.clone());
+    let import_result = importer.import_vcf_contacts_multi_brand(&contacts_file_path).await;
+    get_device_preconditions().release(&device_id).await;
 
-    match importer.import_vcf_contacts_multi_brand(&contacts_file_path).await {
+    match import_result {
         Ok(result) => {
             info!(
                 "✅ 多品牌VCF导入完成: 成功={} 总联系人={} 导入={} 失败={} 使用策略={:?} 使用方法={:?} 耗时={}秒",
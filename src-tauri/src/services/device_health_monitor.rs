@@ -0,0 +1,587 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::services::adb_device_tracker::get_device_tracker;
+use crate::services::log_bridge::LOG_COLLECTOR;
+use crate::utils::adb_utils::run_shell;
+
+/// 设备健康快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceHealthSnapshot {
+    pub device_id: String,
+    /// 电量百分比（0-100）
+    pub battery_level: Option<u8>,
+    /// 电池温度（摄氏度）
+    pub battery_temperature: Option<f32>,
+    pub is_charging: Option<bool>,
+    pub screen_on: Option<bool>,
+    pub is_locked: Option<bool>,
+    /// /sdcard 可用空间（MB）
+    pub storage_free_mb: Option<u64>,
+    /// ADB 往返延迟（毫秒），设备无响应时为 None
+    pub adb_latency_ms: Option<u64>,
+    pub timestamp: String,
+}
+
+/// 健康阈值配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthThresholds {
+    pub min_battery_level: u8,
+    pub max_battery_temperature: f32,
+    pub min_storage_free_mb: u64,
+    pub max_adb_latency_ms: u64,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            min_battery_level: 15,
+            max_battery_temperature: 45.0,
+            min_storage_free_mb: 500,
+            max_adb_latency_ms: 3000,
+        }
+    }
+}
+
+/// 健康监控配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthMonitorConfig {
+    /// 轮询间隔（秒）
+    pub poll_interval_secs: u64,
+    pub thresholds: HealthThresholds,
+    /// 是否拒绝在不健康设备上启动任务
+    pub block_unhealthy_jobs: bool,
+}
+
+impl Default for HealthMonitorConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 30,
+            thresholds: HealthThresholds::default(),
+            block_unhealthy_jobs: true,
+        }
+    }
+}
+
+/// 告警类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum HealthAlertKind {
+    LowBattery,
+    Overheating,
+    LowStorage,
+    HighLatency,
+    Unresponsive,
+}
+
+/// 健康告警事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceHealthAlert {
+    pub device_id: String,
+    pub kind: HealthAlertKind,
+    /// true 表示越过阈值，false 表示已恢复
+    pub active: bool,
+    pub message: String,
+    pub timestamp: String,
+}
+
+/// 设备健康监控器
+/// 周期性轮询已跟踪设备的电量、温度、屏幕、存储和ADB延迟，越过阈值时发出事件
+pub struct DeviceHealthMonitor {
+    config: Arc<Mutex<HealthMonitorConfig>>,
+    is_running: Arc<Mutex<bool>>,
+    snapshots: Arc<Mutex<HashMap<String, DeviceHealthSnapshot>>>,
+    active_alerts: Arc<Mutex<HashMap<String, HashSet<HealthAlertKind>>>>,
+    app_handle: Arc<Mutex<Option<AppHandle>>>,
+}
+
+impl DeviceHealthMonitor {
+    pub fn new() -> Self {
+        Self {
+            config: Arc::new(Mutex::new(HealthMonitorConfig::default())),
+            is_running: Arc::new(Mutex::new(false)),
+            snapshots: Arc::new(Mutex::new(HashMap::new())),
+            active_alerts: Arc::new(Mutex::new(HashMap::new())),
+            app_handle: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 设置应用句柄（用于发送事件到前端）
+    pub async fn set_app_handle(&self, handle: AppHandle) {
+        *self.app_handle.lock().await = Some(handle);
+    }
+
+    pub async fn get_config(&self) -> HealthMonitorConfig {
+        self.config.lock().await.clone()
+    }
+
+    pub async fn set_config(&self, config: HealthMonitorConfig) {
+        info!("⚙️ 更新设备健康监控配置: {:?}", config);
+        *self.config.lock().await = config;
+    }
+
+    /// 启动后台监控循环
+    pub async fn start(&self) {
+        let mut is_running = self.is_running.lock().await;
+        if *is_running {
+            return;
+        }
+        *is_running = true;
+        drop(is_running);
+
+        info!("🩺 启动设备健康监控");
+
+        let config = self.config.clone();
+        let is_running = self.is_running.clone();
+        let snapshots = self.snapshots.clone();
+        let active_alerts = self.active_alerts.clone();
+        let app_handle = self.app_handle.clone();
+
+        tokio::spawn(async move {
+            loop {
+                if !*is_running.lock().await {
+                    break;
+                }
+
+                let current_config = config.lock().await.clone();
+                let device_ids = Self::online_device_ids().await;
+
+                for device_id in device_ids {
+                    let snapshot = Self::probe_device(&device_id).await;
+                    let alerts = Self::evaluate(&snapshot, &current_config.thresholds);
+                    Self::publish(&snapshot, alerts, &active_alerts, &app_handle).await;
+                    snapshots.lock().await.insert(device_id, snapshot);
+                }
+
+                sleep(Duration::from_secs(current_config.poll_interval_secs.max(5))).await;
+            }
+            info!("🏁 设备健康监控循环结束");
+        });
+    }
+
+    /// 停止监控
+    pub async fn stop(&self) {
+        *self.is_running.lock().await = false;
+        info!("⏹️ 停止设备健康监控");
+    }
+
+    /// 获取最近一次采集的所有设备快照
+    pub async fn get_snapshots(&self) -> Vec<DeviceHealthSnapshot> {
+        self.snapshots.lock().await.values().cloned().collect()
+    }
+
+    /// 任务启动前的健康检查
+    /// 立即采集一次快照，配置要求拦截时对不健康设备返回错误
+    pub async fn ensure_job_can_start(&self, device_id: &str) -> Result<DeviceHealthSnapshot, String> {
+        let config = self.get_config().await;
+        let snapshot = Self::probe_device(device_id).await;
+        let problems = Self::evaluate(&snapshot, &config.thresholds);
+        self.snapshots.lock().await.insert(device_id.to_string(), snapshot.clone());
+
+        if problems.is_empty() {
+            return Ok(snapshot);
+        }
+
+        let summary = problems
+            .iter()
+            .map(|(_, message)| message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        if config.block_unhealthy_jobs {
+            LOG_COLLECTOR.add_log(
+                "WARN",
+                "DEVICE",
+                "DeviceHealthMonitor",
+                &format!("设备状态不健康，拒绝启动任务: {}", summary),
+                Some(&serde_json::to_string(&snapshot).unwrap_or_default()),
+                Some(device_id),
+            );
+            Err(format!("设备 {} 状态不健康: {}", device_id, summary))
+        } else {
+            warn!("⚠️ 设备 {} 状态不健康但未启用拦截: {}", device_id, summary);
+            Ok(snapshot)
+        }
+    }
+
+    /// 当前在线的设备ID列表
+    async fn online_device_ids() -> Vec<String> {
+        match get_device_tracker() {
+            Ok(tracker) => tracker
+                .get_current_devices()
+                .await
+                .into_iter()
+                .filter(|d| d.status == "device")
+                .map(|d| d.id)
+                .collect(),
+            Err(e) => {
+                warn!("无法获取设备跟踪器: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// 采集单台设备的健康快照
+    pub async fn probe_device(device_id: &str) -> DeviceHealthSnapshot {
+        let mut snapshot = DeviceHealthSnapshot {
+            device_id: device_id.to_string(),
+            battery_level: None,
+            battery_temperature: None,
+            is_charging: None,
+            screen_on: None,
+            is_locked: None,
+            storage_free_mb: None,
+            adb_latency_ms: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+
+        // ADB 延迟：设备无响应时跳过后续采集
+        let start = Instant::now();
+        match run_shell(device_id, "echo ok").await {
+            Ok(output) if output.trim() == "ok" => {
+                snapshot.adb_latency_ms = Some(start.elapsed().as_millis() as u64);
+            }
+            _ => return snapshot,
+        }
+
+        // 其余探测合并为一次 shell 调用，按分隔标记拆分输出
+        if let Ok(output) = run_shell(device_id, &probe_command()).await {
+            let sections = split_probe_sections(&output);
+            if let Some(battery) = sections.get("battery") {
+                let (level, temperature, charging) = parse_battery(battery);
+                snapshot.battery_level = level;
+                snapshot.battery_temperature = temperature;
+                snapshot.is_charging = charging;
+            }
+            if let Some(power) = sections.get("power") {
+                snapshot.screen_on = parse_screen_on(power);
+            }
+            if let Some(window) = sections.get("window") {
+                snapshot.is_locked = parse_keyguard_showing(window);
+            }
+            if let Some(df) = sections.get("df") {
+                snapshot.storage_free_mb = parse_df_available_mb(df);
+            }
+        }
+
+        debug!("🩺 设备健康快照: {:?}", snapshot);
+        snapshot
+    }
+
+    /// 根据阈值评估快照，返回越过阈值的告警
+    fn evaluate(snapshot: &DeviceHealthSnapshot, thresholds: &HealthThresholds) -> Vec<(HealthAlertKind, String)> {
+        let mut problems = Vec::new();
+
+        let latency = match snapshot.adb_latency_ms {
+            Some(latency) => latency,
+            None => {
+                problems.push((HealthAlertKind::Unresponsive, "ADB无响应".to_string()));
+                return problems;
+            }
+        };
+
+        if latency > thresholds.max_adb_latency_ms {
+            problems.push((
+                HealthAlertKind::HighLatency,
+                format!("ADB延迟过高: {}ms (阈值 {}ms)", latency, thresholds.max_adb_latency_ms),
+            ));
+        }
+
+        if let Some(level) = snapshot.battery_level {
+            // 充电中的设备不视为低电量
+            if level < thresholds.min_battery_level && snapshot.is_charging != Some(true) {
+                problems.push((
+                    HealthAlertKind::LowBattery,
+                    format!("电量过低: {}% (阈值 {}%)", level, thresholds.min_battery_level),
+                ));
+            }
+        }
+
+        if let Some(temperature) = snapshot.battery_temperature {
+            if temperature > thresholds.max_battery_temperature {
+                problems.push((
+                    HealthAlertKind::Overheating,
+                    format!("电池温度过高: {:.1}°C (阈值 {:.1}°C)", temperature, thresholds.max_battery_temperature),
+                ));
+            }
+        }
+
+        if let Some(free_mb) = snapshot.storage_free_mb {
+            if free_mb < thresholds.min_storage_free_mb {
+                problems.push((
+                    HealthAlertKind::LowStorage,
+                    format!("存储空间不足: {}MB (阈值 {}MB)", free_mb, thresholds.min_storage_free_mb),
+                ));
+            }
+        }
+
+        problems
+    }
+
+    /// 发送快照和状态变化的告警（仅在越过/恢复阈值时发送，避免重复告警）
+    async fn publish(
+        snapshot: &DeviceHealthSnapshot,
+        problems: Vec<(HealthAlertKind, String)>,
+        active_alerts: &Arc<Mutex<HashMap<String, HashSet<HealthAlertKind>>>>,
+        app_handle: &Arc<Mutex<Option<AppHandle>>>,
+    ) {
+        let handle = app_handle.lock().await.clone();
+        if let Some(handle) = &handle {
+            let _ = handle.emit("device-health-update", snapshot);
+        }
+
+        let mut active_alerts = active_alerts.lock().await;
+        let active = active_alerts.entry(snapshot.device_id.clone()).or_default();
+        let current: HashSet<HealthAlertKind> = problems.iter().map(|(kind, _)| *kind).collect();
+
+        let mut alerts = Vec::new();
+        for (kind, message) in &problems {
+            if !active.contains(kind) {
+                alerts.push(DeviceHealthAlert {
+                    device_id: snapshot.device_id.clone(),
+                    kind: *kind,
+                    active: true,
+                    message: message.clone(),
+                    timestamp: snapshot.timestamp.clone(),
+                });
+            }
+        }
+        for kind in active.iter() {
+            if !current.contains(kind) {
+                alerts.push(DeviceHealthAlert {
+                    device_id: snapshot.device_id.clone(),
+                    kind: *kind,
+                    active: false,
+                    message: format!("{:?} 已恢复正常", kind),
+                    timestamp: snapshot.timestamp.clone(),
+                });
+            }
+        }
+        *active = current;
+        drop(active_alerts);
+
+        for alert in alerts {
+            LOG_COLLECTOR.add_log(
+                if alert.active { "WARN" } else { "INFO" },
+                "DEVICE",
+                "DeviceHealthMonitor",
+                &alert.message,
+                Some(&serde_json::to_string(snapshot).unwrap_or_default()),
+                Some(&alert.device_id),
+            );
+            if let Some(handle) = &handle {
+                let _ = handle.emit("device-health-alert", &alert);
+            }
+        }
+    }
+}
+
+const PROBE_MARKER: &str = "@@probe:";
+
+/// 合并后的探测命令：每段输出前打印 `@@probe:<名称>` 标记
+fn probe_command() -> String {
+    [
+        ("battery", "dumpsys battery"),
+        ("power", "dumpsys power | grep -E 'mWakefulness=|Display Power: state='"),
+        ("window", "dumpsys window | grep -E 'KeyguardShowing|mShowingLockscreen|isStatusBarKeyguard|mCurrentFocus'"),
+        ("df", "df -k /sdcard"),
+    ]
+    .iter()
+    .map(|(name, command)| format!("echo '{}{}'; {}", PROBE_MARKER, name, command))
+    .collect::<Vec<_>>()
+    .join("; ")
+}
+
+/// 按 `@@probe:<名称>` 标记拆分合并探测的输出
+fn split_probe_sections(output: &str) -> HashMap<String, String> {
+    let mut sections: HashMap<String, String> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in output.lines() {
+        if let Some(name) = line.trim().strip_prefix(PROBE_MARKER) {
+            current = Some(name.to_string());
+            sections.entry(name.to_string()).or_default();
+        } else if let Some(name) = &current {
+            let section = sections.entry(name.clone()).or_default();
+            section.push_str(line);
+            section.push('\n');
+        }
+    }
+    sections
+}
+
+/// 解析 `dumpsys battery` 输出：(电量, 温度°C, 是否充电)
+fn parse_battery(output: &str) -> (Option<u8>, Option<f32>, Option<bool>) {
+    let mut level = None;
+    let mut temperature = None;
+    let mut charging = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(value) = line.strip_prefix("level:") {
+            level = value.trim().parse::<u8>().ok();
+        } else if let Some(value) = line.strip_prefix("temperature:") {
+            // 单位为 0.1°C
+            temperature = value.trim().parse::<f32>().ok().map(|t| t / 10.0);
+        } else if line.starts_with("AC powered:")
+            || line.starts_with("USB powered:")
+            || line.starts_with("Wireless powered:")
+        {
+            if line.ends_with("true") {
+                charging = Some(true);
+            } else if charging.is_none() {
+                charging = Some(false);
+            }
+        }
+    }
+
+    (level, temperature, charging)
+}
+
+/// 解析 `dumpsys power` 输出判断屏幕是否点亮
+pub(crate) fn parse_screen_on(output: &str) -> Option<bool> {
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(value) = line.strip_prefix("mWakefulness=") {
+            return Some(value.trim() == "Awake");
+        }
+        if line.starts_with("Display Power: state=") {
+            return Some(line.ends_with("ON"));
+        }
+    }
+    None
+}
+
+/// 解析 `dumpsys window` 输出判断锁屏（keyguard）是否显示
+/// 只认明确的 keyguard 字段；下拉通知栏（NotificationShade 获得焦点）不代表锁屏
+pub(crate) fn parse_keyguard_showing(output: &str) -> Option<bool> {
+    for key in ["isKeyguardShowing=", "mKeyguardShowing=", "mShowingLockscreen=", "isStatusBarKeyguard="] {
+        if let Some(pos) = output.find(key) {
+            return Some(output[pos + key.len()..].starts_with("true"));
+        }
+    }
+    // 旧版系统：锁屏时焦点窗口为 Keyguard
+    let focus_line = output.lines().find(|l| l.contains("mCurrentFocus"))?;
+    if focus_line.contains("Keyguard") {
+        return Some(true);
+    }
+    None
+}
+
+/// 解析 `df -k` 输出获取可用空间（MB）
+fn parse_df_available_mb(output: &str) -> Option<u64> {
+    let line = output.lines().filter(|l| !l.trim().is_empty()).nth(1)?;
+    let columns: Vec<&str> = line.split_whitespace().collect();
+    // Filesystem 1K-blocks Used Available Use% Mounted
+    let available_kb = columns.get(3)?.parse::<u64>().ok()?;
+    Some(available_kb / 1024)
+}
+
+/// 全局设备健康监控器实例
+static GLOBAL_HEALTH_MONITOR: std::sync::OnceLock<DeviceHealthMonitor> = std::sync::OnceLock::new();
+
+/// 获取全局设备健康监控器
+pub fn get_health_monitor() -> &'static DeviceHealthMonitor {
+    GLOBAL_HEALTH_MONITOR.get_or_init(DeviceHealthMonitor::new)
+}
+
+/// 启动设备健康监控
+#[tauri::command]
+pub async fn start_device_health_monitor(
+    app_handle: tauri::AppHandle,
+    config: Option<HealthMonitorConfig>,
+) -> Result<(), String> {
+    let monitor = get_health_monitor();
+    monitor.set_app_handle(app_handle).await;
+    if let Some(config) = config {
+        monitor.set_config(config).await;
+    }
+    monitor.start().await;
+    Ok(())
+}
+
+/// 停止设备健康监控
+#[tauri::command]
+pub async fn stop_device_health_monitor() -> Result<(), String> {
+    get_health_monitor().stop().await;
+    Ok(())
+}
+
+/// 获取最近的设备健康快照
+#[tauri::command]
+pub async fn get_device_health_snapshots() -> Result<Vec<DeviceHealthSnapshot>, String> {
+    Ok(get_health_monitor().get_snapshots().await)
+}
+
+/// 立即检查单台设备健康状态
+#[tauri::command]
+pub async fn check_device_health(device_id: String) -> Result<DeviceHealthSnapshot, String> {
+    Ok(DeviceHealthMonitor::probe_device(&device_id).await)
+}
+
+/// 更新健康监控配置
+#[tauri::command]
+pub async fn update_device_health_config(config: HealthMonitorConfig) -> Result<(), String> {
+    get_health_monitor().set_config(config).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_battery() {
+        let output = "Current Battery Service state:\n  AC powered: false\n  USB powered: true\n  level: 42\n  temperature: 385\n";
+        let (level, temperature, charging) = parse_battery(output);
+        assert_eq!(level, Some(42));
+        assert_eq!(temperature, Some(38.5));
+        assert_eq!(charging, Some(true));
+    }
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem     1K-blocks    Used Available Use% Mounted on\n/data/media     52403200 1024000  2048000  50% /storage/emulated\n";
+        assert_eq!(parse_df_available_mb(output), Some(2000));
+    }
+
+    #[test]
+    fn test_evaluate_thresholds() {
+        let snapshot = DeviceHealthSnapshot {
+            device_id: "test".to_string(),
+            battery_level: Some(5),
+            battery_temperature: Some(50.0),
+            is_charging: Some(false),
+            screen_on: Some(true),
+            is_locked: Some(false),
+            storage_free_mb: Some(100),
+            adb_latency_ms: Some(50),
+            timestamp: String::new(),
+        };
+        let kinds: Vec<HealthAlertKind> = DeviceHealthMonitor::evaluate(&snapshot, &HealthThresholds::default())
+            .into_iter()
+            .map(|(kind, _)| kind)
+            .collect();
+        assert!(kinds.contains(&HealthAlertKind::LowBattery));
+        assert!(kinds.contains(&HealthAlertKind::Overheating));
+        assert!(kinds.contains(&HealthAlertKind::LowStorage));
+        assert!(!kinds.contains(&HealthAlertKind::HighLatency));
+    }
+
+    #[test]
+    fn test_parse_keyguard_requires_keyguard_evidence() {
+        let shade = "  mCurrentFocus=Window{1a2b u0 NotificationShade}\n";
+        assert_eq!(parse_keyguard_showing(shade), None);
+        let locked = "    mShowingLockscreen=true mShowingDream=false mDreamingLockscreen=false\n  mCurrentFocus=Window{1a2b u0 NotificationShade}\n";
+        assert_eq!(parse_keyguard_showing(locked), Some(true));
+        assert_eq!(parse_keyguard_showing("  isKeyguardShowing=false\n"), Some(false));
+
+        let sections = split_probe_sections("@@probe:battery\n  level: 80\n@@probe:df\nFilesystem\n");
+        assert_eq!(sections.get("battery").map(String::as_str), Some("  level: 80\n"));
+        assert!(sections.contains_key("df"));
+    }
+}
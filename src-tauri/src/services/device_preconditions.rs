@@ -0,0 +1,409 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::services::device_health_monitor::{parse_keyguard_showing, parse_screen_on};
+use crate::utils::adb_utils::run_shell;
+use crate::services::log_bridge::LOG_COLLECTOR;
+
+const UNLOCK_SETTINGS_FILE: &str = "data/device_unlock_settings.json";
+const PRECHECK_DUMP_PATH: &str = "/sdcard/ui_precheck.xml";
+
+/// 设备解锁方式
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnlockMethod {
+    /// 无锁屏，仅唤醒
+    None,
+    /// 上滑解锁
+    #[default]
+    Swipe,
+    /// 数字PIN解锁
+    Pin,
+    /// 字符密码解锁
+    Password,
+}
+
+/// 单台设备的解锁配置（保存在 data/device_unlock_settings.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceUnlockSettings {
+    pub device_id: String,
+    pub method: UnlockMethod,
+    /// PIN 或密码，仅 Pin/Password 方式使用。
+    /// 不会返回给前端；保存时为 None 表示保留原密码，空字符串表示清除
+    pub secret: Option<String>,
+    /// 是否已配置密码（仅用于前端展示）
+    #[serde(default)]
+    pub has_secret: bool,
+    /// 任务执行期间是否保持常亮（svc power stayon）
+    pub keep_awake: bool,
+}
+
+impl DeviceUnlockSettings {
+    pub fn new(device_id: &str) -> Self {
+        Self {
+            device_id: device_id.to_string(),
+            method: UnlockMethod::default(),
+            secret: None,
+            has_secret: false,
+            keep_awake: true,
+        }
+    }
+}
+
+/// 前置条件执行报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreconditionReport {
+    pub device_id: String,
+    pub screen_was_on: Option<bool>,
+    pub was_locked: Option<bool>,
+    pub unlocked: bool,
+    pub keep_awake_applied: bool,
+    pub logs: Vec<String>,
+}
+
+/// 常亮状态：保存任务开始前的原始值，引用计数归零时恢复
+struct KeepAwakeState {
+    original_value: String,
+    holders: u32,
+}
+
+/// 设备前置条件管理器：唤醒、解锁、保持常亮、确认锁屏已解除
+pub struct DevicePreconditions {
+    settings: Mutex<HashMap<String, DeviceUnlockSettings>>,
+    keep_awake: Mutex<HashMap<String, KeepAwakeState>>,
+}
+
+impl DevicePreconditions {
+    pub fn new() -> Self {
+        Self {
+            settings: Mutex::new(load_unlock_settings()),
+            keep_awake: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_settings(&self, device_id: &str) -> DeviceUnlockSettings {
+        self.settings
+            .lock()
+            .await
+            .get(device_id)
+            .cloned()
+            .unwrap_or_else(|| DeviceUnlockSettings::new(device_id))
+    }
+
+    /// 返回给前端的配置：去掉密码，仅标记是否已配置
+    pub async fn get_redacted_settings(&self, device_id: &str) -> DeviceUnlockSettings {
+        let mut settings = self.get_settings(device_id).await;
+        settings.has_secret = settings.secret.as_deref().is_some_and(|s| !s.is_empty());
+        settings.secret = None;
+        settings
+    }
+
+    pub async fn save_settings(&self, mut settings: DeviceUnlockSettings) -> Result<(), String> {
+        let mut all = self.settings.lock().await;
+        // 前端拿到的是脱敏配置，未重新输入密码时保留原密码
+        match settings.secret.as_deref() {
+            None => settings.secret = all.get(&settings.device_id).and_then(|s| s.secret.clone()),
+            Some("") => settings.secret = None,
+            Some(_) => {}
+        }
+        settings.has_secret = settings.secret.is_some();
+        all.insert(settings.device_id.clone(), settings);
+
+        if let Some(parent) = std::path::Path::new(UNLOCK_SETTINGS_FILE).parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&*all).map_err(|e| e.to_string())?;
+        fs::write(UNLOCK_SETTINGS_FILE, content).map_err(|e| format!("保存解锁配置失败: {}", e))?;
+        // 配置文件包含解锁密码，限制为仅当前用户可读写
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(UNLOCK_SETTINGS_FILE, fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    /// 任务开始前调用：唤醒并解锁设备，按配置开启常亮。
+    /// 成功后必须在任务结束时调用 `release` 恢复常亮设置。
+    pub async fn prepare(&self, device_id: &str) -> Result<PreconditionReport, String> {
+        let settings = self.get_settings(device_id).await;
+        let mut report = self.ensure_unlocked(device_id).await?;
+
+        if settings.keep_awake {
+            match self.acquire_keep_awake(device_id).await {
+                Ok(()) => {
+                    report.keep_awake_applied = true;
+                    report.logs.push("☀️ 已开启任务期间屏幕常亮".to_string());
+                }
+                Err(e) => {
+                    warn!("⚠️ 开启屏幕常亮失败: {}", e);
+                    report.logs.push(format!("⚠️ 开启屏幕常亮失败: {}", e));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 任务结束后调用：恢复任务开始前的常亮设置
+    pub async fn release(&self, device_id: &str) {
+        let mut states = self.keep_awake.lock().await;
+        let Some(state) = states.get_mut(device_id) else {
+            return;
+        };
+
+        state.holders = state.holders.saturating_sub(1);
+        if state.holders > 0 {
+            return;
+        }
+
+        let original = state.original_value.clone();
+        states.remove(device_id);
+        drop(states);
+
+        let command = format!("settings put global stay_on_while_plugged_in {}", original);
+        match run_shell(device_id, &command).await {
+            Ok(_) => info!("🌙 设备 {} 已恢复常亮设置: {}", device_id, original),
+            Err(e) => warn!("⚠️ 设备 {} 恢复常亮设置失败: {}", device_id, e),
+        }
+    }
+
+    /// 唤醒屏幕并解锁，最终通过 UI dump 确认锁屏已消失
+    pub async fn ensure_unlocked(&self, device_id: &str) -> Result<PreconditionReport, String> {
+        let settings = self.get_settings(device_id).await;
+        let mut report = PreconditionReport {
+            device_id: device_id.to_string(),
+            screen_was_on: None,
+            was_locked: None,
+            unlocked: false,
+            keep_awake_applied: false,
+            logs: Vec::new(),
+        };
+
+        info!("🔓 检查设备 {} 前置条件", device_id);
+
+        // 1. 唤醒屏幕
+        let power = run_shell(device_id, "dumpsys power").await.unwrap_or_default();
+        report.screen_was_on = parse_screen_on(&power);
+        if report.screen_was_on != Some(true) {
+            report.logs.push("📱 屏幕未点亮，发送唤醒键".to_string());
+            run_shell(device_id, "input keyevent KEYCODE_WAKEUP").await?;
+            sleep(Duration::from_millis(800)).await;
+        }
+
+        // 2. 判断是否处于锁屏
+        let window = run_shell(device_id, "dumpsys window").await.unwrap_or_default();
+        report.was_locked = parse_keyguard_showing(&window);
+        if report.was_locked != Some(true) {
+            match self.keyguard_in_ui_dump(device_id).await {
+                Ok(false) => {
+                    report.unlocked = true;
+                    report.logs.push("✅ 设备未锁屏".to_string());
+                    return Ok(report);
+                }
+                Ok(true) => {}
+                // dump 失败时以 dumpsys 结果为准；两者都无法确认时不盲目输入密码
+                Err(e) if report.was_locked == Some(false) => {
+                    warn!("⚠️ 锁屏 UI 检查失败，按 dumpsys 结果视为未锁屏: {}", e);
+                    report.unlocked = true;
+                    report.logs.push("✅ 设备未锁屏（dumpsys）".to_string());
+                    return Ok(report);
+                }
+                Err(e) => return Err(format!("无法确认设备 {} 是否锁屏: {}", device_id, e)),
+            }
+        }
+
+        // 3. 按配置解锁
+        report.logs.push(format!("🔐 使用 {:?} 方式解锁", settings.method));
+        self.perform_unlock(device_id, &settings).await?;
+
+        // 4. 通过 UI dump 确认锁屏已消失
+        for attempt in 1..=3 {
+            sleep(Duration::from_millis(800)).await;
+            let still_locked = match self.keyguard_in_ui_dump(device_id).await {
+                Ok(still_locked) => still_locked,
+                Err(e) => {
+                    warn!("⚠️ 第{}次确认锁屏状态失败: {}", attempt, e);
+                    continue;
+                }
+            };
+            if !still_locked {
+                report.unlocked = true;
+                report.logs.push("✅ UI确认锁屏已解除".to_string());
+                LOG_COLLECTOR.add_log(
+                    "INFO",
+                    "DEVICE",
+                    "DevicePreconditions",
+                    &format!("设备 {} 已解锁", device_id),
+                    None,
+                    Some(device_id),
+                );
+                return Ok(report);
+            }
+            warn!("⚠️ 第{}次确认仍检测到锁屏", attempt);
+        }
+
+        let message = format!("设备 {} 解锁失败：锁屏界面仍然存在，请检查解锁配置", device_id);
+        LOG_COLLECTOR.add_log(
+            "ERROR",
+            "DEVICE",
+            "DevicePreconditions",
+            &message,
+            Some(&report.logs.join("\n")),
+            Some(device_id),
+        );
+        Err(message)
+    }
+
+    async fn perform_unlock(&self, device_id: &str, settings: &DeviceUnlockSettings) -> Result<(), String> {
+        if settings.method == UnlockMethod::None {
+            return Ok(());
+        }
+
+        // 先上滑调出解锁界面
+        let size_output = run_shell(device_id, "wm size").await.unwrap_or_default();
+        let (width, height) = parse_wm_size(&size_output).unwrap_or((1080, 1920));
+        let command = format!(
+            "input swipe {} {} {} {} 300",
+            width / 2,
+            height * 4 / 5,
+            width / 2,
+            height / 5
+        );
+        run_shell(device_id, &command).await?;
+
+        match settings.method {
+            UnlockMethod::Pin | UnlockMethod::Password => {
+                let secret = settings
+                    .secret
+                    .as_deref()
+                    .filter(|s| !s.is_empty())
+                    .ok_or_else(|| format!("设备 {} 未配置解锁密码", device_id))?;
+                sleep(Duration::from_millis(500)).await;
+                // 命令里含有密码：错误信息（如超时）会带出命令原文，只返回固定文案
+                run_shell(device_id, &format!("input text '{}'", secret.replace('\'', "'\\''")))
+                    .await
+                    .map_err(|_| format!("设备 {} 输入解锁密码失败", device_id))?;
+                run_shell(device_id, "input keyevent KEYCODE_ENTER").await?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// dump 失败时返回错误而不是猜测，避免对已解锁设备误输入密码
+    async fn keyguard_in_ui_dump(&self, device_id: &str) -> Result<bool, String> {
+        let command = format!("uiautomator dump {} && cat {}", PRECHECK_DUMP_PATH, PRECHECK_DUMP_PATH);
+        let xml = run_shell(device_id, &command).await?;
+        if !xml.contains("<hierarchy") {
+            return Err("UI dump 输出无效".to_string());
+        }
+        Ok(ui_dump_shows_keyguard(&xml))
+    }
+
+    async fn acquire_keep_awake(&self, device_id: &str) -> Result<(), String> {
+        let mut states = self.keep_awake.lock().await;
+        if let Some(state) = states.get_mut(device_id) {
+            state.holders += 1;
+            return Ok(());
+        }
+
+        let original = run_shell(device_id, "settings get global stay_on_while_plugged_in")
+            .await?
+            .trim()
+            .to_string();
+        let original = if original.parse::<u32>().is_ok() { original } else { "0".to_string() };
+
+        run_shell(device_id, "svc power stayon true").await?;
+        states.insert(device_id.to_string(), KeepAwakeState { original_value: original, holders: 1 });
+        Ok(())
+    }
+}
+
+/// 解析 `wm size` 输出，优先使用 Override size
+fn parse_wm_size(output: &str) -> Option<(u32, u32)> {
+    let parse = |line: &str| {
+        let value = line.split(':').nth(1)?.trim();
+        let (w, h) = value.split_once('x')?;
+        Some((w.trim().parse().ok()?, h.trim().parse().ok()?))
+    };
+    output
+        .lines()
+        .find(|l| l.contains("Override size"))
+        .and_then(parse)
+        .or_else(|| output.lines().find(|l| l.contains("Physical size")).and_then(parse))
+}
+
+/// 判断 UI dump 中是否仍存在锁屏界面元素
+fn ui_dump_shows_keyguard(xml: &str) -> bool {
+    const KEYGUARD_IDS: [&str; 5] = [
+        "com.android.systemui:id/keyguard",
+        "com.android.systemui:id/lock_icon",
+        "com.android.systemui:id/pinEntry",
+        "com.android.systemui:id/passwordEntry",
+        "com.android.systemui:id/lockPatternView",
+    ];
+    KEYGUARD_IDS.iter().any(|id| xml.contains(id))
+}
+
+fn load_unlock_settings() -> HashMap<String, DeviceUnlockSettings> {
+    fs::read_to_string(UNLOCK_SETTINGS_FILE)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 全局设备前置条件管理器实例
+static GLOBAL_PRECONDITIONS: std::sync::OnceLock<DevicePreconditions> = std::sync::OnceLock::new();
+
+/// 获取全局设备前置条件管理器
+pub fn get_device_preconditions() -> &'static DevicePreconditions {
+    GLOBAL_PRECONDITIONS.get_or_init(DevicePreconditions::new)
+}
+
+/// 获取设备解锁配置（密码已脱敏）
+#[tauri::command]
+pub async fn get_device_unlock_settings(device_id: String) -> Result<DeviceUnlockSettings, String> {
+    Ok(get_device_preconditions().get_redacted_settings(&device_id).await)
+}
+
+/// 保存设备解锁配置
+#[tauri::command]
+pub async fn save_device_unlock_settings(settings: DeviceUnlockSettings) -> Result<(), String> {
+    get_device_preconditions().save_settings(settings).await
+}
+
+/// 手动执行设备前置条件（唤醒 + 解锁），不开启常亮
+#[tauri::command]
+pub async fn ensure_device_ready(device_id: String) -> Result<PreconditionReport, String> {
+    get_device_preconditions().ensure_unlocked(&device_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wm_size() {
+        assert_eq!(parse_wm_size("Physical size: 1080x2340\n"), Some((1080, 2340)));
+        assert_eq!(
+            parse_wm_size("Physical size: 1080x2340\nOverride size: 720x1560\n"),
+            Some((720, 1560))
+        );
+        assert_eq!(parse_wm_size("error"), None);
+    }
+
+    #[test]
+    fn test_ui_dump_shows_keyguard() {
+        let locked = r#"<node resource-id="com.android.systemui:id/keyguard_bottom_area" package="com.android.systemui"/>"#;
+        let unlocked = r#"<node resource-id="com.android.launcher3:id/workspace" package="com.android.launcher3"/>"#;
+        assert!(ui_dump_shows_keyguard(locked));
+        assert!(!ui_dump_shows_keyguard(unlocked));
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::State;
+use tracing::info;
+
+use crate::services::page_fingerprint::{current_activity, PageFingerprint};
+use crate::services::ui_tree::locator::unique_xpath;
+use crate::services::ui_tree::{NodeId, UiNode, UiTree};
+use crate::services::xml_snapshot_store::XmlSnapshotStore;
+use crate::types::page_analysis::{ElementBounds, ElementLocator, LocatorStrategy};
+use crate::xml_judgment_service::XmlJudgmentService;
+
+/// 参与稳定性评估的历史快照数上限
+const MAX_HISTORY_SNAPSHOTS: usize = 20;
+
+/// 锚点文本的最大长度（过长的通常是动态内容）
+const MAX_ANCHOR_TEXT_LEN: usize = 16;
+
+/// 各策略的经验稳定性：没有历史快照时作为稳定性，有历史时作为先验参与打分
+fn prior(strategy: &LocatorStrategy) -> f64 {
+    match strategy {
+        LocatorStrategy::ResourceId { .. } => 0.9,
+        LocatorStrategy::ContentDesc { .. } => 0.8,
+        LocatorStrategy::Text { .. } => 0.7,
+        LocatorStrategy::RelativeToAnchor { .. } => 0.6,
+        LocatorStrategy::XPath { .. } => 0.5,
+        LocatorStrategy::NormalizedPosition { .. } => 0.2,
+    }
+}
+
+fn contains_point(bounds: &ElementBounds, x: i32, y: i32) -> bool {
+    bounds.left <= x && x < bounds.right && bounds.top <= y && y < bounds.bottom
+}
+
+fn screen_size(tree: &UiTree) -> Option<(i32, i32)> {
+    tree.elements()
+        .find_map(|n| n.bounds())
+        .map(|b| (b.right, b.bottom))
+        .filter(|&(w, h)| w > 0 && h > 0)
+}
+
+/// 包含坐标的最小节点（可选限定类名）
+fn smallest_at<'a>(tree: &'a UiTree, x: i32, y: i32, class_name: Option<&str>) -> Option<UiNode<'a>> {
+    tree.elements()
+        .filter(|n| class_name.map_or(true, |c| n.class_name() == c))
+        .filter_map(|n| n.bounds().map(|b| (n, b)))
+        .filter(|(_, b)| contains_point(b, x, y))
+        .min_by_key(|(_, b)| b.width() as i64 * b.height() as i64)
+        .map(|(n, _)| n)
+}
+
+/// 按策略在页面中查找节点
+pub fn resolve<'a>(tree: &'a UiTree, strategy: &LocatorStrategy) -> Vec<UiNode<'a>> {
+    match strategy {
+        LocatorStrategy::ResourceId { resource_id } => tree.find_all(|n| n.resource_id() == resource_id),
+        LocatorStrategy::Text { text } => tree.find_all(|n| n.text() == text),
+        LocatorStrategy::ContentDesc { content_desc } => tree.find_all(|n| n.content_desc() == content_desc),
+        LocatorStrategy::XPath { xpath } => tree.select(xpath).unwrap_or_default(),
+        LocatorStrategy::RelativeToAnchor { anchor_text, dx, dy, class_name } => {
+            let mut found: Vec<UiNode> = Vec::new();
+            for anchor in tree.find_all(|n| n.text() == anchor_text) {
+                let Some((ax, ay)) = anchor.center() else { continue };
+                if let Some(node) = smallest_at(tree, ax + dx, ay + dy, Some(class_name)) {
+                    if !found.contains(&node) {
+                        found.push(node);
+                    }
+                }
+            }
+            found
+        }
+        LocatorStrategy::NormalizedPosition { x, y } => screen_size(tree)
+            .and_then(|(w, h)| smallest_at(tree, (x * w as f64) as i32, (y * h as f64) as i32, None))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// 选择锚点：文本在页面中唯一、与目标不是同类重复元素的节点，
+/// 优先与目标共享更深的公共祖先（同一行/同一卡片），其次距离最近
+fn anchor_for(tree: &UiTree, target: &UiNode) -> Option<LocatorStrategy> {
+    let (tx, ty) = target.center()?;
+    let descendants = target.descendants();
+    let lineage: Vec<NodeId> = target.ancestors().map(|a| a.id()).collect();
+    let common_depth = |node: &UiNode| {
+        node.ancestors()
+            .find(|a| lineage.contains(&a.id()))
+            .map(|a| a.depth())
+            .unwrap_or(0)
+    };
+
+    tree.elements()
+        .filter(|n| n != target && !descendants.contains(n))
+        .filter(|n| !n.text().is_empty() && n.text().chars().count() <= MAX_ANCHOR_TEXT_LEN)
+        .filter(|n| target.resource_id().is_empty() || n.resource_id() != target.resource_id())
+        .filter(|n| tree.find_all(|m| m.text() == n.text()).len() == 1)
+        .filter_map(|n| n.center().map(|c| (n, c)))
+        .min_by_key(|(n, (ax, ay))| {
+            (std::cmp::Reverse(common_depth(n)), ((ax - tx) as i64).pow(2) + ((ay - ty) as i64).pow(2))
+        })
+        .map(|(anchor, (ax, ay))| LocatorStrategy::RelativeToAnchor {
+            anchor_text: anchor.text().to_string(),
+            dx: tx - ax,
+            dy: ty - ay,
+            class_name: target.class_name().to_string(),
+        })
+}
+
+/// 为目标节点生成所有可用的候选定位策略
+pub fn candidate_strategies(tree: &UiTree, target: &UiNode) -> Vec<LocatorStrategy> {
+    let mut strategies = Vec::new();
+    if !target.resource_id().is_empty() {
+        strategies.push(LocatorStrategy::ResourceId { resource_id: target.resource_id().to_string() });
+    }
+    if !target.text().is_empty() {
+        strategies.push(LocatorStrategy::Text { text: target.text().to_string() });
+    }
+    if !target.content_desc().is_empty() {
+        strategies.push(LocatorStrategy::ContentDesc { content_desc: target.content_desc().to_string() });
+    }
+    strategies.push(LocatorStrategy::XPath { xpath: unique_xpath(target) });
+    strategies.extend(anchor_for(tree, target));
+    if let (Some((w, h)), Some((cx, cy))) = (screen_size(tree), target.center()) {
+        strategies.push(LocatorStrategy::NormalizedPosition { x: cx as f64 / w as f64, y: cy as f64 / h as f64 });
+    }
+    strategies
+}
+
+/// 只有唯一匹配时返回该节点
+fn unique_match(tree: &UiTree, strategy: &LocatorStrategy) -> Option<NodeId> {
+    match resolve(tree, strategy).as_slice() {
+        [only] => Some(only.id()),
+        _ => None,
+    }
+}
+
+/// 生成并排序目标节点的定位器
+///
+/// 唯一性按当前页面计算；稳定性按同一页面的历史快照计算：每份快照中以各定位器
+/// 唯一命中的节点投票选出“目标”，定位器唯一命中该节点即视为在此快照中稳定。
+/// 当前页面都找不到目标的定位器不会进入回退链。
+pub fn rank_locators(tree: &UiTree, target: &UiNode, history: &[UiTree]) -> Vec<ElementLocator> {
+    let strategies = candidate_strategies(tree, target);
+
+    let mut stable_counts = vec![0usize; strategies.len()];
+    for snapshot in history {
+        let hits: Vec<Option<NodeId>> = strategies.iter().map(|s| unique_match(snapshot, s)).collect();
+        let mut votes: HashMap<NodeId, f64> = HashMap::new();
+        for (strategy, hit) in strategies.iter().zip(&hits) {
+            if let Some(id) = hit {
+                *votes.entry(*id).or_default() += prior(strategy);
+            }
+        }
+        let Some(reference) = votes.into_iter().max_by(|a, b| a.1.total_cmp(&b.1)).map(|(id, _)| id) else {
+            continue;
+        };
+        for (count, hit) in stable_counts.iter_mut().zip(&hits) {
+            if *hit == Some(reference) {
+                *count += 1;
+            }
+        }
+    }
+
+    let mut locators: Vec<ElementLocator> = strategies
+        .into_iter()
+        .zip(stable_counts)
+        .filter_map(|(strategy, stable)| {
+            let matches = resolve(tree, &strategy);
+            if !matches.contains(target) {
+                return None;
+            }
+            let uniqueness = 1.0 / matches.len() as f64;
+            let stability = if history.is_empty() { prior(&strategy) } else { stable as f64 / history.len() as f64 };
+            let score = 0.5 * uniqueness + 0.3 * stability + 0.2 * prior(&strategy);
+            Some(ElementLocator { strategy, uniqueness, stability, score })
+        })
+        .collect();
+    locators.sort_by(|a, b| b.score.total_cmp(&a.score));
+    locators
+}
+
+/// 按回退链依次尝试，返回第一个唯一命中的节点及所用定位器
+pub fn locate<'a, 'l>(tree: &'a UiTree, locators: &'l [ElementLocator]) -> Option<(UiNode<'a>, &'l ElementLocator)> {
+    locators.iter().find_map(|locator| match resolve(tree, &locator.strategy).as_slice() {
+        [only] => Some((*only, locator)),
+        _ => None,
+    })
+}
+
+/// 按 bounds 找到分析结果中的元素对应的节点（同 bounds 的嵌套节点取最内层）
+pub fn node_by_bounds<'a>(tree: &'a UiTree, bounds: &ElementBounds) -> Option<UiNode<'a>> {
+    let key = (bounds.left, bounds.top, bounds.right, bounds.bottom);
+    tree.elements()
+        .filter(|n| n.bounds().map(|b| (b.left, b.top, b.right, b.bottom)) == Some(key))
+        .last()
+}
+
+/// 为选中的元素（按 bounds）生成排序后的定位器回退链，结合同一页面的历史快照评估稳定性
+///
+/// 传入 `xml_content` 时在选取元素所用的 XML 上生成，否则使用设备当前界面
+#[tauri::command]
+pub async fn generate_element_locators(
+    device_id: String,
+    bounds: ElementBounds,
+    xml_content: Option<String>,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<Vec<ElementLocator>, String> {
+    let (xml, activity) = match xml_content.filter(|xml| xml.contains("<hierarchy")) {
+        Some(xml) => (xml, None),
+        None => (XmlJudgmentService::get_ui_xml(&device_id).await?, current_activity(&device_id).await),
+    };
+    let tree = UiTree::parse(&xml).map_err(|e| format!("解析UI失败: {}", e))?;
+    let target = node_by_bounds(&tree, &bounds)
+        .ok_or_else(|| format!("当前页面没有该元素: [{},{}][{},{}]", bounds.left, bounds.top, bounds.right, bounds.bottom))?;
+
+    let fingerprint = PageFingerprint::from_tree(&tree, activity);
+    let history_xmls = {
+        let store = store.lock().map_err(|e| e.to_string())?;
+        store.similar_page_xmls(&fingerprint, MAX_HISTORY_SNAPSHOTS).map_err(|e| e.to_string())?
+    };
+    let history: Vec<UiTree> = history_xmls.iter().filter_map(|xml| UiTree::parse(xml).ok()).collect();
+
+    let locators = rank_locators(&tree, &target, &history);
+    info!("🧭 生成 {} 个候选定位器（参考 {} 份历史快照）", locators.len(), history.len());
+    Ok(locators)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(rows: &[(&str, &str)]) -> String {
+        let items: String = rows
+            .iter()
+            .enumerate()
+            .map(|(i, (name, button))| {
+                let top = 300 + i as i32 * 200;
+                format!(
+                    r#"<node text="{}" resource-id="com.xingin.xhs:id/name" class="android.widget.TextView" bounds="[40,{}][500,{}]" /><node text="{}" resource-id="com.xingin.xhs:id/follow" class="android.widget.Button" clickable="true" bounds="[800,{}][1000,{}]" />"#,
+                    name, top, top + 100, button, top, top + 100
+                )
+            })
+            .collect();
+        format!(
+            r#"<?xml version='1.0' ?><hierarchy rotation="0"><node class="android.widget.FrameLayout" bounds="[0,0][1080,1920]"><node text="通讯录好友" resource-id="com.xingin.xhs:id/title" class="android.widget.TextView" bounds="[0,100][1080,200]" /><node class="androidx.recyclerview.widget.RecyclerView" scrollable="true" bounds="[0,200][1080,1800]">{}</node></node></hierarchy>"#,
+            items
+        )
+    }
+
+    #[test]
+    fn test_candidates_are_ranked_by_uniqueness() {
+        let tree = UiTree::parse(&page(&[("张三", "关注"), ("李四", "已关注")])).unwrap();
+        let target = node_by_bounds(&tree, &ElementBounds { left: 800, top: 300, right: 1000, bottom: 400 }).unwrap();
+        let locators = rank_locators(&tree, &target, &[]);
+
+        // resource-id 有两个匹配，排在唯一的文本定位器之后
+        let first = &locators[0];
+        assert_eq!(first.strategy, LocatorStrategy::Text { text: "关注".into() });
+        let by_id = locators.iter().find(|l| matches!(l.strategy, LocatorStrategy::ResourceId { .. })).unwrap();
+        assert_eq!(by_id.uniqueness, 0.5);
+        let anchor = locators
+            .iter()
+            .find_map(|l| match &l.strategy {
+                LocatorStrategy::RelativeToAnchor { anchor_text, dx, dy, .. } => Some((anchor_text.clone(), *dx, *dy)),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(anchor, ("张三".to_string(), 630, 0));
+        assert!(locators.iter().all(|l| resolve(&tree, &l.strategy).contains(&target)));
+    }
+
+    #[test]
+    fn test_history_stability_and_fallback() {
+        let current = UiTree::parse(&page(&[("张三", "关注"), ("李四", "已关注")])).unwrap();
+        let target = node_by_bounds(&current, &ElementBounds { left: 800, top: 300, right: 1000, bottom: 400 }).unwrap();
+        // 历史快照中列表上方多了一行：文本和位置不再唯一/正确，锚点仍指向“张三”的按钮
+        let history = vec![
+            UiTree::parse(&page(&[("王五", "关注"), ("张三", "关注"), ("李四", "已关注")])).unwrap(),
+            UiTree::parse(&page(&[("张三", "关注"), ("李四", "已关注")])).unwrap(),
+        ];
+        let locators = rank_locators(&current, &target, &history);
+        let stability = |pred: fn(&LocatorStrategy) -> bool| locators.iter().find(|l| pred(&l.strategy)).unwrap().stability;
+        assert_eq!(stability(|s| matches!(s, LocatorStrategy::Text { .. })), 0.5);
+        assert_eq!(stability(|s| matches!(s, LocatorStrategy::NormalizedPosition { .. })), 0.5);
+        assert_eq!(stability(|s| matches!(s, LocatorStrategy::RelativeToAnchor { .. })), 1.0);
+        assert!(matches!(locators[0].strategy, LocatorStrategy::RelativeToAnchor { .. }));
+
+        // 执行时按回退链查找：锚点定位器在新页面唯一命中“张三”的按钮
+        let moved = UiTree::parse(&page(&[("王五", "关注"), ("张三", "关注")])).unwrap();
+        let (node, used) = locate(&moved, &locators).unwrap();
+        assert!(matches!(used.strategy, LocatorStrategy::RelativeToAnchor { .. }));
+        assert_eq!(node.center(), Some((900, 550)));
+    }
+}
@@ -0,0 +1,253 @@
+// 通过桌面图标启动应用
+// 在 am start / monkey 被系统拦截或行为异常的设备上作为兜底启动方式：
+// 回到桌面 -> 逐页左滑查找图标 -> 找不到时打开应用抽屉继续查找 -> 点击图标
+
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::services::adb_session_manager::get_device_session;
+use crate::services::adb_shell_session::AdbShellSession;
+use crate::services::ui_idle::settle;
+use crate::services::ui_tree::{UiNode, UiTree};
+use crate::types::page_analysis::ElementBounds;
+
+/// 打开应用抽屉的入口按钮文本/描述
+const DRAWER_ENTRY_LABELS: &[&str] = &["应用", "所有应用", "全部应用", "应用抽屉", "Apps", "All apps", "App drawer"];
+
+const KEYCODE_HOME: i32 = 3;
+
+/// 翻页方向（手指移动方向的反方向，即内容前进方向）
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PageDirection {
+    /// 从右往左滑，查看下一屏
+    Next,
+    /// 从下往上滑，列表向下滚动
+    Down,
+}
+
+/// 桌面图标启动结果
+#[derive(Debug, Clone, Serialize)]
+pub struct LauncherLaunchOutcome {
+    /// 命中的图标文本
+    pub matched_label: String,
+    /// 在第几页找到（从 0 开始）
+    pub page: usize,
+    /// 是否在应用抽屉中找到
+    pub in_drawer: bool,
+}
+
+/// 屏幕范围：取最外层带 bounds 的节点（hierarchy 根节点本身没有 bounds）
+fn screen_bounds(tree: &UiTree) -> Option<ElementBounds> {
+    tree.nodes().find_map(|n| n.bounds())
+}
+
+fn screen_size(tree: &UiTree) -> (i32, i32) {
+    screen_bounds(tree).map(|b| (b.width(), b.height())).unwrap_or((1080, 2340))
+}
+
+/// 在当前界面中查找应用图标：优先完全匹配文本/描述，其次包含匹配
+pub fn find_launcher_icon<'a>(tree: &'a UiTree, labels: &[String]) -> Option<(UiNode<'a>, String)> {
+    let screen = screen_bounds(tree);
+    let on_screen = |node: &UiNode| -> bool {
+        match (node.bounds(), &screen) {
+            (Some(b), Some(s)) => b.width() > 0 && b.height() > 0 && b.left >= s.left && b.right <= s.right && b.top >= s.top && b.bottom <= s.bottom,
+            (Some(b), None) => b.width() > 0 && b.height() > 0,
+            _ => false,
+        }
+    };
+    let label_of = |node: &UiNode, exact: bool| -> Option<String> {
+        labels.iter().find(|label| {
+            [node.text().trim(), node.content_desc().trim()].iter().any(|value| {
+                !value.is_empty() && if exact { value == &label.as_str() } else { value.contains(label.as_str()) }
+            })
+        }).cloned()
+    };
+
+    for exact in [true, false] {
+        if let Some(node) = tree.find_first(|n| on_screen(n) && label_of(n, exact).is_some()) {
+            let label = label_of(&node, exact)?;
+            return Some((node, label));
+        }
+    }
+    None
+}
+
+/// 页面签名：用于判断翻页后内容是否变化（到达最后一页）
+fn page_signature(tree: &UiTree) -> Vec<String> {
+    tree.nodes()
+        .filter_map(|n| {
+            let label = if n.text().is_empty() { n.content_desc() } else { n.text() };
+            (!label.is_empty()).then(|| format!("{}@{:?}", label, n.bounds().map(|b| (b.left, b.top))))
+        })
+        .collect()
+}
+
+/// 点击节点：本身不可点击时点击最近的可点击祖先
+fn tap_target(node: &UiNode) -> Option<(i32, i32)> {
+    if node.is_clickable() {
+        return node.center();
+    }
+    node.ancestors().find(|a| a.is_clickable()).and_then(|a| a.center()).or_else(|| node.center())
+}
+
+struct LauncherSearch<'s> {
+    device_id: &'s str,
+    session: &'s AdbShellSession,
+    labels: &'s [String],
+    max_pages: usize,
+}
+
+impl LauncherSearch<'_> {
+    async fn current_tree(&self) -> Result<UiTree, String> {
+        let xml = self.session.dump_ui().await.map_err(|e| format!("获取桌面界面失败: {}", e))?;
+        UiTree::parse(&xml).map_err(|e| format!("解析桌面界面失败: {}", e))
+    }
+
+    async fn swipe(&self, tree: &UiTree, direction: PageDirection) -> Result<(), String> {
+        let (width, height) = screen_size(tree);
+        let (x1, y1, x2, y2) = match direction {
+            PageDirection::Next => (width * 85 / 100, height / 2, width * 15 / 100, height / 2),
+            PageDirection::Down => (width / 2, height * 75 / 100, width / 2, height * 30 / 100),
+        };
+        self.session.swipe(x1, y1, x2, y2, 300).await.map_err(|e| format!("滑动失败: {}", e))?;
+        settle(self.device_id, Duration::from_secs(2)).await;
+        Ok(())
+    }
+
+    /// 逐页查找并点击图标；按顺序尝试各翻页方向，内容不再变化时换方向或结束
+    async fn search(&self, directions: &[PageDirection], in_drawer: bool, logs: &mut Vec<String>) -> Result<Option<LauncherLaunchOutcome>, String> {
+        let mut direction_index = 0;
+        let mut tree = self.current_tree().await?;
+        for page in 0..self.max_pages {
+            if let Some((node, label)) = find_launcher_icon(&tree, self.labels) {
+                let (x, y) = tap_target(&node).ok_or_else(|| format!("图标 {} 没有有效坐标", label))?;
+                logs.push(format!("👆 在{}第 {} 页找到图标 \"{}\"，点击 ({}, {})", if in_drawer { "应用抽屉" } else { "桌面" }, page + 1, label, x, y));
+                self.session.tap(x, y).await.map_err(|e| format!("点击图标失败: {}", e))?;
+                return Ok(Some(LauncherLaunchOutcome { matched_label: label, page, in_drawer }));
+            }
+
+            let before = page_signature(&tree);
+            loop {
+                let Some(&direction) = directions.get(direction_index) else { return Ok(None) };
+                self.swipe(&tree, direction).await?;
+                let next = self.current_tree().await?;
+                if page_signature(&next) != before {
+                    tree = next;
+                    break;
+                }
+                // 当前方向已到底，换下一个方向
+                direction_index += 1;
+            }
+        }
+        Ok(None)
+    }
+
+    /// 打开应用抽屉：优先点击入口按钮，否则从底部上滑
+    async fn open_drawer(&self, logs: &mut Vec<String>) -> Result<bool, String> {
+        let tree = self.current_tree().await?;
+        let before = page_signature(&tree);
+        let entry = tree.find_first(|n| {
+            DRAWER_ENTRY_LABELS.iter().any(|label| n.text().trim() == *label || n.content_desc().trim() == *label)
+        });
+        match entry.as_ref().and_then(tap_target) {
+            Some((x, y)) => {
+                logs.push(format!("📂 点击应用抽屉入口 ({}, {})", x, y));
+                self.session.tap(x, y).await.map_err(|e| format!("打开应用抽屉失败: {}", e))?;
+                settle(self.device_id, Duration::from_secs(2)).await;
+            }
+            None => {
+                logs.push("📂 上滑打开应用抽屉".to_string());
+                let (width, height) = screen_size(&tree);
+                self.session.swipe(width / 2, height * 90 / 100, width / 2, height * 30 / 100, 300).await
+                    .map_err(|e| format!("打开应用抽屉失败: {}", e))?;
+                settle(self.device_id, Duration::from_secs(2)).await;
+            }
+        }
+        Ok(page_signature(&self.current_tree().await?) != before)
+    }
+}
+
+/// 回到桌面并通过图标启动应用
+/// labels 为图标可能显示的名称（如 "小红书"），按顺序优先匹配
+pub async fn launch_via_launcher_icon(
+    device_id: &str,
+    labels: &[String],
+    max_pages: usize,
+    logs: &mut Vec<String>,
+) -> Result<LauncherLaunchOutcome, String> {
+    if labels.is_empty() {
+        return Err("未提供图标名称".to_string());
+    }
+    let session = get_device_session(device_id).await.map_err(|e| format!("获取设备会话失败: {}", e))?;
+
+    // 连按两次 Home：第一次回到桌面，第二次回到默认首页
+    for _ in 0..2 {
+        session.key_event(KEYCODE_HOME).await.map_err(|e| format!("返回桌面失败: {}", e))?;
+        settle(device_id, Duration::from_millis(800)).await;
+    }
+    logs.push(format!("🏠 已返回桌面，查找图标: {}", labels.join(" / ")));
+
+    let search = LauncherSearch { device_id, session: &session, labels, max_pages };
+    if let Some(outcome) = search.search(&[PageDirection::Next], false, logs).await? {
+        info!("✅ 桌面图标启动: {:?}", outcome);
+        return Ok(outcome);
+    }
+
+    logs.push("🔍 桌面各页未找到图标，尝试应用抽屉".to_string());
+    session.key_event(KEYCODE_HOME).await.map_err(|e| format!("返回桌面失败: {}", e))?;
+    settle(device_id, Duration::from_millis(800)).await;
+    if !search.open_drawer(logs).await? {
+        warn!("⚠️ 设备 {} 没有可用的应用抽屉", device_id);
+        return Err(format!("桌面上未找到 {} 图标，且该桌面没有应用抽屉", labels[0]));
+    }
+
+    match search.search(&[PageDirection::Down, PageDirection::Next], true, logs).await? {
+        Some(outcome) => {
+            info!("✅ 应用抽屉图标启动: {:?}", outcome);
+            Ok(outcome)
+        }
+        None => Err(format!("桌面和应用抽屉中都未找到 {} 图标", labels[0])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launcher(nodes: &str) -> UiTree {
+        UiTree::parse(&format!(
+            r#"<hierarchy><node class="android.widget.FrameLayout" package="com.miui.home" bounds="[0,0][1080,2340]">{}</node></hierarchy>"#,
+            nodes
+        )).unwrap()
+    }
+
+    #[test]
+    fn test_find_icon_prefers_exact_label() {
+        let tree = launcher(
+            r#"<node text="小红书助手" class="android.widget.TextView" clickable="true" bounds="[0,200][270,470]" />
+               <node text="" content-desc="小红书" class="android.widget.TextView" clickable="true" bounds="[270,200][540,470]" />"#,
+        );
+        let (node, label) = find_launcher_icon(&tree, &["小红书".to_string()]).unwrap();
+        assert_eq!(label, "小红书");
+        assert_eq!(node.center(), Some((405, 335)));
+
+        // 带角标的描述只能包含匹配
+        let tree = launcher(r#"<node content-desc="小红书，3条未读" clickable="true" bounds="[0,200][270,470]" />"#);
+        assert!(find_launcher_icon(&tree, &["小红书".to_string()]).is_some());
+        assert!(find_launcher_icon(&tree, &["微信".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_tap_target_uses_clickable_ancestor_and_skips_offscreen() {
+        let tree = launcher(
+            r#"<node class="android.widget.FrameLayout" clickable="true" bounds="[540,200][810,470]">
+                 <node text="微信" class="android.widget.TextView" clickable="false" bounds="[560,400][790,460]" />
+               </node>
+               <node text="抖音" clickable="true" bounds="[1100,200][1370,470]" />"#,
+        );
+        let (node, _) = find_launcher_icon(&tree, &["微信".to_string()]).unwrap();
+        assert_eq!(tap_target(&node), Some((675, 335)));
+        assert!(find_launcher_icon(&tree, &["抖音".to_string()]).is_none());
+    }
+}
@@ -2,9 +2,12 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tokio::process::Command;
-use tokio::time::{sleep, timeout};
+use tokio::time::timeout;
 use tracing::{error, info, warn};
 
+use crate::services::device_preconditions::get_device_preconditions;
+use crate::services::ui_idle::{settle, wait_until, wait_until_stable, IdleOptions};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VcfOpenResult {
     pub success: bool,
@@ -85,7 +88,7 @@ impl LDPlayerVcfOpener {
             }
         }
 
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(5)).await;
 
         // 步骤4: 导航到下载目录
         match self.navigate_to_downloads().await {
@@ -101,7 +104,7 @@ impl LDPlayerVcfOpener {
             }
         }
 
-        sleep(Duration::from_secs(1)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
 
         // 步骤5: 查找并点击VCF文件
         match self.find_and_click_vcf_file().await {
@@ -117,7 +120,7 @@ impl LDPlayerVcfOpener {
             }
         }
 
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(5)).await;
 
         // 步骤6: 处理应用选择对话框
         match self.handle_app_selection().await {
@@ -131,7 +134,7 @@ impl LDPlayerVcfOpener {
             }
         }
 
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(5)).await;
 
         // 步骤7: 确认导入联系人
         match self.confirm_import_contacts().await {
@@ -187,23 +190,16 @@ impl LDPlayerVcfOpener {
     async fn ensure_device_unlocked(&self) -> Result<()> {
         info!("🔓 检查设备解锁状态");
 
-        // 检查屏幕状态
-        let output = self
-            .execute_adb_command(vec!["shell", "dumpsys", "window", "|", "grep", "mScreenOnEarly"])
+        // 使用通用前置条件：唤醒屏幕、按设备配置解锁并通过UI确认锁屏已消失
+        let report = get_device_preconditions()
+            .ensure_unlocked(&self.device_id)
             .await
-            .unwrap_or_default();
-
-        if output.contains("mScreenOnEarly=true") {
-            info!("📱 设备屏幕已开启");
-        } else {
-            warn!("📱 设备屏幕可能未开启，尝试唤醒");
-            // 发送电源键唤醒设备
-            self.execute_adb_command(vec!["shell", "input", "keyevent", "KEYCODE_POWER"])
-                .await?;
-            sleep(Duration::from_secs(1)).await;
+            .map_err(|e| anyhow::anyhow!(e))?;
+        for line in &report.logs {
+            info!("{}", line);
         }
 
-        // 发送菜单键确保回到主屏幕
+        // 发送HOME键确保回到主屏幕
         self.execute_adb_command(vec!["shell", "input", "keyevent", "KEYCODE_HOME"])
             .await?;
 
@@ -266,7 +262,7 @@ impl LDPlayerVcfOpener {
         }
 
         // 方法2: 通过UI自动化导航
-        sleep(Duration::from_secs(1)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
         
         // 尝试点击Download文件夹（假设在主界面可见）
         // 这里需要根据实际的UI布局来调整坐标
@@ -278,7 +274,7 @@ impl LDPlayerVcfOpener {
 
         for tap_cmd in tap_commands {
             self.execute_adb_command(tap_cmd).await.ok();
-            sleep(Duration::from_millis(500)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
         }
 
         Ok(())
@@ -321,10 +317,11 @@ impl LDPlayerVcfOpener {
                 "shell", "input", "tap", &x.to_string(), &y.to_string()
             ]).await.ok();
             
-            sleep(Duration::from_millis(800)).await;
-            
-            // 检查是否弹出了应用选择对话框或联系人导入界面
-            let ui_after_tap = self.dump_ui_hierarchy().await.unwrap_or_default();
+            // 等界面稳定后检查是否弹出了应用选择对话框或联系人导入界面
+            let ui_after_tap = wait_until_stable(&self.device_id, &IdleOptions::with_timeout(Duration::from_secs(3)))
+                .await
+                .map(|r| r.xml)
+                .unwrap_or_default();
             if ui_after_tap.contains("联系人") || ui_after_tap.contains("contact") || 
                ui_after_tap.contains("导入") || ui_after_tap.contains("import") {
                 info!("✅ 成功点击VCF文件（通过位置尝试）");
@@ -356,7 +353,7 @@ impl LDPlayerVcfOpener {
                         &position.1.to_string()
                     ]).await?;
 
-                    sleep(Duration::from_secs(1)).await;
+                    settle(&self.device_id, Duration::from_secs(3)).await;
 
                     // 点击"始终"或"仅此一次"
                     self.click_always_or_once().await?;
@@ -369,7 +366,7 @@ impl LDPlayerVcfOpener {
         // 如果没有找到特定应用，尝试点击第一个选项
         warn!("⚠️ 未找到联系人应用，尝试点击默认选项");
         self.execute_adb_command(vec!["shell", "input", "tap", "400", "400"]).await?;
-        sleep(Duration::from_secs(1)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
         self.click_always_or_once().await?;
 
         Ok(())
@@ -399,7 +396,7 @@ impl LDPlayerVcfOpener {
             self.execute_adb_command(vec![
                 "shell", "input", "tap", &x.to_string(), &y.to_string()
             ]).await.ok();
-            sleep(Duration::from_millis(500)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
         }
 
         Ok(())
@@ -409,9 +406,10 @@ impl LDPlayerVcfOpener {
     async fn confirm_import_contacts(&self) -> Result<()> {
         info!("✅ 确认导入联系人");
 
-        sleep(Duration::from_secs(2)).await;
-        
-        let ui_dump = self.dump_ui_hierarchy().await?;
+        let ui_dump = match wait_until_stable(&self.device_id, &IdleOptions::with_timeout(Duration::from_secs(5))).await {
+            Ok(result) => result.xml,
+            Err(_) => self.dump_ui_hierarchy().await?,
+        };
         
         // 查找导入相关按钮
         let import_keywords = vec!["导入", "import", "确定", "ok", "完成", "done"];
@@ -434,7 +432,7 @@ impl LDPlayerVcfOpener {
             self.execute_adb_command(vec![
                 "shell", "input", "tap", &x.to_string(), &y.to_string()
             ]).await.ok();
-            sleep(Duration::from_millis(800)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
         }
 
         Ok(())
@@ -444,25 +442,20 @@ impl LDPlayerVcfOpener {
     async fn wait_for_import_completion(&self) -> Result<()> {
         info!("⏳ 等待导入完成");
 
-        // 等待最多30秒
-        for i in 1..=30 {
-            sleep(Duration::from_secs(1)).await;
-            
-            let ui_dump = self.dump_ui_hierarchy().await.unwrap_or_default();
-            
-            // 检查是否出现完成信息
-            if ui_dump.contains("成功") || ui_dump.contains("完成") || 
-               ui_dump.contains("success") || ui_dump.contains("complete") {
-                info!("🎉 检测到导入完成信号");
-                return Ok(());
-            }
-
-            if i % 5 == 0 {
-                info!("⏳ 等待导入完成... ({}/30秒)", i);
-            }
+        // 等待最多30秒，界面稳定且出现完成信息即返回
+        let options = IdleOptions::with_timeout(Duration::from_secs(30));
+        let result = wait_until(&self.device_id, &options, |ui_dump| {
+            ui_dump.contains("成功") || ui_dump.contains("完成") ||
+            ui_dump.contains("success") || ui_dump.contains("complete")
+        })
+        .await;
+
+        // UI 获取失败不影响导入本身，按超时处理
+        match result {
+            Ok(result) if result.condition_met => info!("🎉 检测到导入完成信号 ({}ms)", result.elapsed_ms),
+            Ok(_) => warn!("⚠️ 等待超时，但可能已经完成导入"),
+            Err(e) => warn!("⚠️ 等待期间获取UI失败（{}），但可能已经完成导入", e),
         }
-
-        warn!("⚠️ 等待超时，但可能已经完成导入");
         Ok(())
     }
 
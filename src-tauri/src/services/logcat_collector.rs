@@ -0,0 +1,664 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::utils::adb_utils::run_shell;
+use crate::services::log_bridge::LOG_COLLECTOR;
+use crate::utils::adb_utils::get_adb_path;
+
+const MAX_BUFFERED_LINES: usize = 20_000;
+const LOGCAT_DIR: &str = "data/logcat";
+const PID_REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+/// data/logcat 保留策略：超过天数或超出文件数时从最旧的开始删除
+const LOGCAT_MAX_AGE_DAYS: u64 = 7;
+const LOGCAT_MAX_FILES: usize = 500;
+
+/// logcat 日志级别（按严重程度排序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LogcatLevel {
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogcatLevel {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'V' => Some(Self::Verbose),
+            'D' => Some(Self::Debug),
+            'I' => Some(Self::Info),
+            'W' => Some(Self::Warn),
+            'E' => Some(Self::Error),
+            'F' | 'A' => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    /// 映射为 LogCollector 的日志级别
+    fn as_collector_level(&self) -> &'static str {
+        match self {
+            Self::Verbose | Self::Debug => "DEBUG",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error | Self::Fatal => "ERROR",
+        }
+    }
+}
+
+/// logcat 过滤条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogcatFilter {
+    /// 仅保留这些 tag（为空表示不过滤）
+    pub tags: Vec<String>,
+    /// 仅保留关注应用进程的日志（以及提及包名的系统日志）
+    pub package: Option<String>,
+    /// 缓存的最低级别
+    pub min_level: LogcatLevel,
+    /// 转发到 LogCollector 的最低级别（仅转发关注应用的日志）
+    pub forward_min_level: LogcatLevel,
+}
+
+impl Default for LogcatFilter {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            package: None,
+            min_level: LogcatLevel::Info,
+            forward_min_level: LogcatLevel::Warn,
+        }
+    }
+}
+
+/// 解析后的一行 logcat（threadtime 格式）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogcatLine {
+    /// 设备时间，如 "10-18 12:34:56.789"
+    pub device_time: String,
+    pub pid: u32,
+    pub tid: u32,
+    pub level: LogcatLevel,
+    pub tag: String,
+    pub message: String,
+    /// 主机收到该行的时间，用于按执行区间截取
+    pub received_at: DateTime<Utc>,
+    /// 该行所属的关注应用（按 pid 或消息中的包名判断）
+    #[serde(default)]
+    pub package: Option<String>,
+}
+
+impl LogcatLine {
+    fn to_threadtime(&self) -> String {
+        let level = match self.level {
+            LogcatLevel::Verbose => 'V',
+            LogcatLevel::Debug => 'D',
+            LogcatLevel::Info => 'I',
+            LogcatLevel::Warn => 'W',
+            LogcatLevel::Error => 'E',
+            LogcatLevel::Fatal => 'F',
+        };
+        format!(
+            "{} {:>5} {:>5} {} {}: {}",
+            self.device_time, self.pid, self.tid, level, self.tag, self.message
+        )
+    }
+}
+
+/// 执行期间的 logcat 截取凭据
+pub struct LogcatCapture {
+    device_id: String,
+    started_at: DateTime<Utc>,
+    /// 本次执行关注的应用，截取时只保留该应用的日志
+    package: Option<String>,
+}
+
+/// 读取任务与 pid 刷新任务共享的状态
+#[derive(Default)]
+struct StreamState {
+    /// 关注的包名 -> 引用数（过滤条件与各次执行采集共同持有）
+    watched: Mutex<HashMap<String, usize>>,
+    /// pid -> 包名，由独立任务周期性刷新，避免在读取循环中等待 adb
+    pid_owners: Mutex<HashMap<u32, String>>,
+}
+
+impl StreamState {
+    async fn watch(&self, package: &str) {
+        *self.watched.lock().await.entry(package.to_string()).or_default() += 1;
+    }
+
+    async fn unwatch(&self, package: &str) {
+        let mut watched = self.watched.lock().await;
+        if let Some(count) = watched.get_mut(package) {
+            *count -= 1;
+            if *count == 0 {
+                watched.remove(package);
+            }
+        }
+    }
+
+    /// 判断一行日志属于哪个关注应用
+    async fn owner_of(&self, line: &LogcatLine) -> Option<String> {
+        if let Some(package) = self.pid_owners.lock().await.get(&line.pid) {
+            return Some(package.clone());
+        }
+        self.watched
+            .lock()
+            .await
+            .keys()
+            .find(|package| line.message.contains(package.as_str()))
+            .cloned()
+    }
+}
+
+struct LogcatStream {
+    filter: LogcatFilter,
+    state: Arc<StreamState>,
+    task: JoinHandle<()>,
+    pid_task: JoinHandle<()>,
+    /// 进行中的执行采集数，归零前不停止采集流
+    capture_holders: usize,
+    /// 采集流由执行采集启动（而不是用户手动开启），最后一个采集结束时停止
+    owned_by_captures: bool,
+}
+
+impl LogcatStream {
+    fn abort(&self) {
+        self.task.abort();
+        self.pid_task.abort();
+    }
+}
+
+/// 按设备管理 logcat 采集流
+pub struct LogcatCollector {
+    streams: Mutex<HashMap<String, LogcatStream>>,
+    buffers: Arc<Mutex<HashMap<String, VecDeque<LogcatLine>>>>,
+}
+
+impl LogcatCollector {
+    pub fn new() -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+            buffers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 启动设备的 logcat 采集（已在运行时替换过滤条件并重启）
+    pub async fn start(&self, device_id: &str, filter: LogcatFilter) -> Result<(), String> {
+        let mut streams = self.streams.lock().await;
+        let previous = streams.remove(device_id);
+        if let Some(previous) = &previous {
+            previous.abort();
+        }
+
+        // 保留进行中的执行采集，替换过滤条件后继续为它们采集
+        let (state, capture_holders) = match previous {
+            Some(previous) => {
+                if let Some(package) = &previous.filter.package {
+                    previous.state.unwatch(package).await;
+                }
+                (previous.state, previous.capture_holders)
+            }
+            None => (Arc::new(StreamState::default()), 0),
+        };
+        if let Some(package) = &filter.package {
+            state.watch(package).await;
+        }
+
+        let stream = self.spawn_stream(device_id, filter, state, capture_holders)?;
+        streams.insert(device_id.to_string(), stream);
+        Ok(())
+    }
+
+    fn spawn_stream(
+        &self,
+        device_id: &str,
+        filter: LogcatFilter,
+        state: Arc<StreamState>,
+        capture_holders: usize,
+    ) -> Result<LogcatStream, String> {
+        let mut cmd = Command::new(get_adb_path());
+        cmd.args(["-s", device_id, "logcat", "-v", "threadtime", "-T", "1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+
+        #[cfg(windows)]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let mut child = cmd.spawn().map_err(|e| format!("启动logcat失败: {}", e))?;
+        let stdout = child.stdout.take().ok_or("无法获取logcat输出")?;
+
+        info!("📜 启动设备 {} 的logcat采集: {:?}", device_id, filter);
+        LOG_COLLECTOR.add_log(
+            "INFO",
+            "DEVICE",
+            "LogcatCollector",
+            &format!("开始采集设备 {} 的logcat", device_id),
+            None,
+            Some(device_id),
+        );
+
+        let pid_task = {
+            let device = device_id.to_string();
+            let state = state.clone();
+            tokio::spawn(async move {
+                loop {
+                    let packages: Vec<String> = state.watched.lock().await.keys().cloned().collect();
+                    let mut owners = HashMap::new();
+                    for package in packages {
+                        for pid in resolve_package_pids(&device, &package).await {
+                            owners.insert(pid, package.clone());
+                        }
+                    }
+                    *state.pid_owners.lock().await = owners;
+                    tokio::time::sleep(PID_REFRESH_INTERVAL).await;
+                }
+            })
+        };
+
+        let buffers = self.buffers.clone();
+        let device = device_id.to_string();
+        let stream_filter = filter.clone();
+        let stream_state = state.clone();
+        let task = tokio::spawn(async move {
+            // child 随任务一起释放，kill_on_drop 保证进程退出
+            let _child = child;
+            let mut lines = BufReader::new(stdout).lines();
+
+            while let Ok(Some(raw)) = lines.next_line().await {
+                let Some(mut line) = parse_threadtime_line(&raw) else {
+                    continue;
+                };
+                line.package = stream_state.owner_of(&line).await;
+
+                if !stream_filter.matches(&line) {
+                    continue;
+                }
+
+                // 只转发关注应用的日志，避免其他进程的告警刷屏
+                if line.package.is_some() && line.level >= stream_filter.forward_min_level {
+                    LOG_COLLECTOR.add_log(
+                        line.level.as_collector_level(),
+                        "DEVICE",
+                        &format!("logcat:{}", line.tag),
+                        &line.message,
+                        Some(&raw),
+                        Some(&device),
+                    );
+                }
+
+                let mut buffers = buffers.lock().await;
+                let buffer = buffers.entry(device.clone()).or_default();
+                if buffer.len() >= MAX_BUFFERED_LINES {
+                    buffer.pop_front();
+                }
+                buffer.push_back(line);
+            }
+
+            warn!("📜 设备 {} 的logcat采集已结束", device);
+        });
+
+        Ok(LogcatStream {
+            filter,
+            state,
+            task,
+            pid_task,
+            capture_holders,
+            owned_by_captures: false,
+        })
+    }
+
+    /// 停止设备的 logcat 采集；仍有执行采集在使用时交由最后一个采集结束时停止
+    pub async fn stop(&self, device_id: &str) {
+        let mut streams = self.streams.lock().await;
+        if let Some(stream) = streams.get_mut(device_id) {
+            if stream.capture_holders > 0 {
+                stream.owned_by_captures = true;
+                info!("📜 设备 {} 的logcat仍被 {} 个执行使用，稍后停止", device_id, stream.capture_holders);
+                return;
+            }
+        }
+        if let Some(stream) = streams.remove(device_id) {
+            stream.abort();
+            info!("📜 停止设备 {} 的logcat采集", device_id);
+        }
+    }
+
+    /// 获取正在运行的采集流使用的过滤条件
+    pub async fn get_filter(&self, device_id: &str) -> Option<LogcatFilter> {
+        self.streams.lock().await.get(device_id).map(|s| s.filter.clone())
+    }
+
+    /// 获取最近的 logcat 行
+    pub async fn recent_lines(&self, device_id: &str, limit: usize) -> Vec<LogcatLine> {
+        let buffers = self.buffers.lock().await;
+        let Some(buffer) = buffers.get(device_id) else {
+            return Vec::new();
+        };
+        buffer.iter().skip(buffer.len().saturating_sub(limit)).cloned().collect()
+    }
+
+    /// 获取指定时间之后收到的 logcat 行
+    pub async fn lines_since(&self, device_id: &str, since: DateTime<Utc>) -> Vec<LogcatLine> {
+        let buffers = self.buffers.lock().await;
+        buffers
+            .get(device_id)
+            .map(|buffer| buffer.iter().filter(|l| l.received_at >= since).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 脚本执行开始时调用：确保采集流在运行并记录起始时间。
+    /// 采集流按引用计数共享，多个并发执行结束前不会被停止
+    pub async fn begin_capture(&self, device_id: &str, package: Option<&str>) -> LogcatCapture {
+        let mut streams = self.streams.lock().await;
+        let running = streams.get(device_id).map_or(false, |s| !s.task.is_finished());
+        if !running {
+            if let Some(stale) = streams.remove(device_id) {
+                stale.abort();
+            }
+            match self.spawn_stream(device_id, LogcatFilter::default(), Arc::new(StreamState::default()), 0) {
+                Ok(mut stream) => {
+                    stream.owned_by_captures = true;
+                    streams.insert(device_id.to_string(), stream);
+                }
+                Err(e) => warn!("⚠️ 无法为本次执行启动logcat采集: {}", e),
+            }
+        }
+
+        if let Some(stream) = streams.get_mut(device_id) {
+            stream.capture_holders += 1;
+            if let Some(package) = package {
+                stream.state.watch(package).await;
+            }
+        }
+
+        LogcatCapture {
+            device_id: device_id.to_string(),
+            started_at: Utc::now(),
+            package: package.map(str::to_string),
+        }
+    }
+
+    /// 脚本执行结束时调用：将执行期间的 logcat 写入文件并返回文件路径
+    pub async fn end_capture(&self, capture: LogcatCapture) -> Option<String> {
+        // 给 logcat 管道留出少量时间输出最后的日志
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let lines: Vec<LogcatLine> = self
+            .lines_since(&capture.device_id, capture.started_at)
+            .await
+            .into_iter()
+            .filter(|l| capture.package.is_none() || l.package == capture.package)
+            .collect();
+
+        {
+            let mut streams = self.streams.lock().await;
+            let mut release = false;
+            if let Some(stream) = streams.get_mut(&capture.device_id) {
+                stream.capture_holders = stream.capture_holders.saturating_sub(1);
+                if let Some(package) = &capture.package {
+                    stream.state.unwatch(package).await;
+                }
+                release = stream.capture_holders == 0 && stream.owned_by_captures;
+            }
+            if release {
+                if let Some(stream) = streams.remove(&capture.device_id) {
+                    stream.abort();
+                    info!("📜 停止设备 {} 的logcat采集", capture.device_id);
+                }
+            }
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        let file_name = format!(
+            "{}_{}.log",
+            capture.device_id.replace([':', '/', '\\'], "_"),
+            capture.started_at.format("%Y%m%d_%H%M%S")
+        );
+        let path = Path::new(LOGCAT_DIR).join(file_name);
+        let content: Vec<String> = lines.iter().map(|l| l.to_threadtime()).collect();
+
+        let write_result = std::fs::create_dir_all(LOGCAT_DIR)
+            .and_then(|_| std::fs::write(&path, content.join("\n")));
+        match write_result {
+            Ok(()) => {
+                prune_logcat_dir(&path);
+                Some(path.to_string_lossy().to_string())
+            }
+            Err(e) => {
+                warn!("⚠️ 保存执行期间logcat失败: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// 按保留策略清理 data/logcat（刚写入的文件除外）
+fn prune_logcat_dir(just_written: &Path) {
+    let Ok(dir) = std::fs::read_dir(LOGCAT_DIR) else {
+        return;
+    };
+    let files: Vec<(PathBuf, u64)> = dir
+        .flatten()
+        .filter(|e| e.path().is_file() && e.path() != just_written)
+        .filter_map(|e| {
+            let modified = e.metadata().ok()?.modified().ok()?;
+            Some((e.path(), modified.duration_since(SystemTime::UNIX_EPOCH).ok()?.as_secs()))
+        })
+        .collect();
+    let now_secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    for path in select_expired_logs(files, now_secs, LOGCAT_MAX_FILES.saturating_sub(1)) {
+        match std::fs::remove_file(&path) {
+            Ok(()) => info!("🧹 删除过期logcat: {}", path.display()),
+            Err(e) => warn!("⚠️ 删除过期logcat失败 {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// 选出需要删除的 logcat 文件：超过保留天数的，以及超出文件数上限的最旧文件
+fn select_expired_logs(mut files: Vec<(PathBuf, u64)>, now_secs: u64, max_files: usize) -> Vec<PathBuf> {
+    let max_age_secs = LOGCAT_MAX_AGE_DAYS * 24 * 3600;
+    files.sort_by(|a, b| b.1.cmp(&a.1));
+    files
+        .into_iter()
+        .enumerate()
+        .filter(|(index, (_, modified))| *index >= max_files || now_secs.saturating_sub(*modified) > max_age_secs)
+        .map(|(_, (path, _))| path)
+        .collect()
+}
+
+impl LogcatFilter {
+    /// 设置了 package 时只保留已归属到关注应用的行（见 `LogcatLine::package`）
+    fn matches(&self, line: &LogcatLine) -> bool {
+        if line.level < self.min_level {
+            return false;
+        }
+        if !self.tags.is_empty() && !self.tags.iter().any(|t| t == &line.tag) {
+            return false;
+        }
+        if self.package.is_some() {
+            return line.package.is_some();
+        }
+        true
+    }
+}
+
+/// 解析 `logcat -v threadtime` 的一行：
+/// `10-18 12:34:56.789  1234  5678 I ActivityManager: message`
+fn parse_threadtime_line(raw: &str) -> Option<LogcatLine> {
+    // 依次取出前5个字段：日期、时间、pid、tid、级别，剩余部分为 "tag: message"
+    let mut rest = raw.trim_start();
+    let mut fields = Vec::with_capacity(5);
+    for _ in 0..5 {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    let (date, time) = (fields[0], fields[1]);
+    if !date.contains('-') || !time.contains(':') {
+        return None;
+    }
+    let pid = fields[2].parse().ok()?;
+    let tid = fields[3].parse().ok()?;
+    let level = LogcatLevel::from_char(fields[4].chars().next()?)?;
+
+    // tag 可能包含空格，以第一个 ": " 作为 tag 与消息的分隔
+    let (tag, message) = match rest.split_once(": ") {
+        Some((tag, message)) => (tag.trim_end(), message),
+        None => (rest.trim_end_matches(':').trim_end(), ""),
+    };
+
+    Some(LogcatLine {
+        device_time: format!("{} {}", date, time),
+        pid,
+        tid,
+        level,
+        tag: tag.to_string(),
+        message: message.to_string(),
+        received_at: Utc::now(),
+        package: None,
+    })
+}
+
+/// 通过 pidof 获取应用当前的进程号
+async fn resolve_package_pids(device_id: &str, package: &str) -> Vec<u32> {
+    run_shell(device_id, &format!("pidof {}", package))
+        .await
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|p| p.parse().ok())
+        .collect()
+}
+
+/// 全局 logcat 采集器实例
+static GLOBAL_LOGCAT_COLLECTOR: std::sync::OnceLock<LogcatCollector> = std::sync::OnceLock::new();
+
+/// 获取全局 logcat 采集器
+pub fn get_logcat_collector() -> &'static LogcatCollector {
+    GLOBAL_LOGCAT_COLLECTOR.get_or_init(LogcatCollector::new)
+}
+
+/// 启动设备 logcat 采集
+#[tauri::command]
+pub async fn start_logcat_stream(device_id: String, filter: Option<LogcatFilter>) -> Result<(), String> {
+    get_logcat_collector()
+        .start(&device_id, filter.unwrap_or_default())
+        .await
+}
+
+/// 停止设备 logcat 采集
+#[tauri::command]
+pub async fn stop_logcat_stream(device_id: String) -> Result<(), String> {
+    get_logcat_collector().stop(&device_id).await;
+    Ok(())
+}
+
+/// 获取设备当前的 logcat 过滤条件（未采集时返回 None）
+#[tauri::command]
+pub async fn get_logcat_filter(device_id: String) -> Result<Option<LogcatFilter>, String> {
+    Ok(get_logcat_collector().get_filter(&device_id).await)
+}
+
+/// 获取设备最近的 logcat 行
+#[tauri::command]
+pub async fn get_logcat_lines(device_id: String, limit: Option<usize>) -> Result<Vec<LogcatLine>, String> {
+    Ok(get_logcat_collector()
+        .recent_lines(&device_id, limit.unwrap_or(500))
+        .await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_threadtime_line() {
+        let line = parse_threadtime_line(
+            "10-18 12:34:56.789  1234  5678 E AndroidRuntime: FATAL EXCEPTION: main",
+        )
+        .unwrap();
+        assert_eq!(line.device_time, "10-18 12:34:56.789");
+        assert_eq!(line.pid, 1234);
+        assert_eq!(line.tid, 5678);
+        assert_eq!(line.level, LogcatLevel::Error);
+        assert_eq!(line.tag, "AndroidRuntime");
+        assert_eq!(line.message, "FATAL EXCEPTION: main");
+
+        assert!(parse_threadtime_line("--------- beginning of main").is_none());
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let unowned = parse_threadtime_line("10-18 12:34:56.789  100  100 W Toast: 操作频繁").unwrap();
+        let line = LogcatLine { package: Some("com.xingin.xhs".to_string()), ..unowned.clone() };
+
+        let filter = LogcatFilter {
+            package: Some("com.xingin.xhs".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&line));
+        assert!(!filter.matches(&unowned));
+
+        let filter = LogcatFilter {
+            min_level: LogcatLevel::Error,
+            ..Default::default()
+        };
+        assert!(!filter.matches(&line));
+
+        let filter = LogcatFilter {
+            tags: vec!["ActivityManager".to_string()],
+            ..Default::default()
+        };
+        assert!(!filter.matches(&line));
+    }
+
+    #[tokio::test]
+    async fn test_line_owner_and_log_retention() {
+        let state = StreamState::default();
+        state.watch("com.xingin.xhs").await;
+        state.pid_owners.lock().await.insert(200, "com.xingin.xhs".to_string());
+
+        let by_pid = parse_threadtime_line("10-18 12:34:56.789  200  200 W Toast: 操作频繁").unwrap();
+        let by_message = parse_threadtime_line("10-18 12:34:56.789  1000  1000 I ActivityManager: Start proc com.xingin.xhs").unwrap();
+        let other = parse_threadtime_line("10-18 12:34:56.789  300  300 W Other: noise").unwrap();
+        assert_eq!(state.owner_of(&by_pid).await.as_deref(), Some("com.xingin.xhs"));
+        assert_eq!(state.owner_of(&by_message).await.as_deref(), Some("com.xingin.xhs"));
+        assert_eq!(state.owner_of(&other).await, None);
+
+        state.unwatch("com.xingin.xhs").await;
+        assert!(state.watched.lock().await.is_empty());
+
+        let day = 24 * 3600;
+        let now = 100 * day;
+        let files = vec![
+            (PathBuf::from("new.log"), now - 10),
+            (PathBuf::from("mid.log"), now - day),
+            (PathBuf::from("old.log"), now - 10 * day),
+        ];
+        assert_eq!(select_expired_logs(files.clone(), now, 10), vec![PathBuf::from("old.log")]);
+        assert_eq!(
+            select_expired_logs(files, now, 1),
+            vec![PathBuf::from("mid.log"), PathBuf::from("old.log")]
+        );
+    }
+}
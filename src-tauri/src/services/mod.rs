@@ -10,10 +10,31 @@ pub mod auth_service;
 pub mod contact_automation;
 pub mod error_handling;  // 新增：错误处理模块
 pub mod page_analyzer_service;  // 新增：页面分析服务
+pub mod page_analysis_history;  // 新增：页面分析历史（SQLite）
 pub mod universal_ui_page_analyzer;  // 新增：Universal UI 页面分析器
 // pub mod simple_xml_parser;  // 已删除：简化XML解析器，统一使用智能解析器
 pub mod contact_service;
 pub mod crash_debugger;
+pub mod device_health_monitor;  // 新增：设备健康监控
+pub mod device_preconditions;  // 新增：设备前置条件（唤醒/解锁/常亮）
+pub mod logcat_collector;  // 新增：设备logcat采集
+pub mod screen_recorder;  // 新增：执行过程录屏
+pub mod text_input;  // 新增：Unicode文本输入（ADBKeyBoard）
+pub mod ui_tree;  // 新增：统一UI层次结构模型（quick-xml + XPath）
+pub mod page_fingerprint;  // 新增：页面结构指纹注册表
+pub mod navigation_graph;  // 新增：应用导航图（探索、记录与自动导航）
+pub mod template_matcher;  // 新增：截图模板匹配定位
+pub mod ui_dump_cache;  // 新增：按设备缓存UI dump（输入操作自动失效）
+pub mod ui_idle;  // 新增：等待界面稳定（替代固定sleep）
+pub mod scroll_finder;  // 新增：滚动容器查找元素（到底检测）
+pub mod xml_snapshot_store;  // 新增：可检索的XML快照存储（替代debug_xml缓存）
+pub mod screenshot_annotator;  // 新增：截图标注元素框（步骤证据）
+pub mod element_locator;  // 新增：选中元素的多定位器生成与排序
+pub mod popup_watcher;  // 新增：弹窗监视器（步骤间自动关闭权限/更新/广告等弹窗）
+pub mod app_package_manager;  // 新增：应用包管理（安装/升级/卸载/清除数据/授权）
+pub mod launcher_icon_launcher;  // 新增：通过桌面图标启动应用（翻页/应用抽屉查找）
+pub mod app_launch_benchmark;  // 新增：应用冷/热启动基准测试（按机型保存结果）
+pub mod vcard;  // 新增：vCard 2.1/3.0/4.0 读写
 pub mod employee_service;
 pub mod ldplayer_vcf_opener;
 pub mod log_bridge;
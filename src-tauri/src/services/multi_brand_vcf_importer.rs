@@ -3,9 +3,11 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::process::Command;
-use tokio::time::{sleep, Duration};
+use tokio::time::Duration;
 use tracing::{error, info, warn};
 
+use crate::services::ui_idle::{settle, wait_until, IdleOptions};
+
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
@@ -571,8 +573,8 @@ impl MultiBrandVcfImporter {
                     }
                 }
                 
-                // 每次尝试之间的间隔
-                sleep(Duration::from_secs(2)).await;
+                // 等上一次尝试留下的界面稳定后再试下一个方法
+                settle(&self.device_id, Duration::from_secs(3)).await;
             }
         }
         
@@ -673,7 +675,7 @@ impl MultiBrandVcfImporter {
             ])?;
         }
         
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(8)).await;
         Ok(())
     }
 
@@ -682,8 +684,8 @@ impl MultiBrandVcfImporter {
         info!("导航到导入功能");
         
         // 这里会实现UI自动化逻辑
-        // 目前先返回成功
-        sleep(Duration::from_secs(2)).await;
+        // 目前先等待界面切换完成
+        settle(&self.device_id, Duration::from_secs(3)).await;
         Ok(())
     }
 
@@ -692,8 +694,8 @@ impl MultiBrandVcfImporter {
         info!("选择VCF文件: {}", vcf_file_path);
         
         // 这里会实现文件选择逻辑
-        // 目前先返回成功
-        sleep(Duration::from_secs(2)).await;
+        // 目前先等待文件列表加载完成
+        settle(&self.device_id, Duration::from_secs(3)).await;
         Ok(())
     }
 
@@ -702,7 +704,7 @@ impl MultiBrandVcfImporter {
         info!("确认导入");
         
         // 这里会实现确认导入的逻辑
-        sleep(Duration::from_secs(1)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
         Ok(())
     }
 
@@ -710,8 +712,22 @@ impl MultiBrandVcfImporter {
     async fn wait_for_completion(&self) -> Result<()> {
         info!("等待导入完成");
         
-        // 这里会实现等待逻辑
-        sleep(Duration::from_secs(5)).await;
+        // 出现完成提示且不再显示进度时返回，最多等待30秒
+        let options = IdleOptions::with_timeout(Duration::from_secs(30));
+        let result = wait_until(&self.device_id, &options, |ui_dump| {
+            let finished = ui_dump.contains("导入完成") || ui_dump.contains("已导入") ||
+                ui_dump.contains("成功") || ui_dump.contains("complete");
+            let in_progress = ui_dump.contains("正在导入") || ui_dump.contains("Importing");
+            finished && !in_progress
+        })
+        .await;
+
+        // UI 获取失败或超时不代表导入失败，交给后续验证步骤判断
+        match result {
+            Ok(result) if result.condition_met => info!("检测到导入完成 ({}ms)", result.elapsed_ms),
+            Ok(_) => warn!("等待导入完成超时，继续后续验证"),
+            Err(e) => warn!("等待导入完成期间获取UI失败: {}", e),
+        }
         Ok(())
     }
 
@@ -720,7 +736,7 @@ impl MultiBrandVcfImporter {
         info!("处理权限请求");
         
         // 这里会实现权限处理逻辑
-        sleep(Duration::from_secs(1)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
         Ok(())
     }
 
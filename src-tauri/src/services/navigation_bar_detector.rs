@@ -6,6 +6,7 @@ use tracing::{error, info, warn};
 use regex::Regex;
 
 use crate::services::adb_session_manager::get_device_session;
+use crate::services::ui_tree::{UiNode, UiTree};
 
 /// 导航栏类型枚举
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -216,18 +217,34 @@ impl NavigationBarDetector {
         let ui_content = session.dump_ui().await
             .map_err(|e| anyhow::anyhow!("获取UI结构失败: {}", e))?;
 
+        let mut result = self.detect_in_ui_dump(&ui_content, &config, &target_button_name)?;
+        result.detection_time_ms = start_time.elapsed().as_millis() as u64;
+
+        info!("导航栏检测完成，耗时: {}ms", result.detection_time_ms);
+        Ok(result)
+    }
+
+    /// 在已获取的UI结构中检测导航栏和目标按钮（不访问设备）
+    pub fn detect_in_ui_dump(
+        &self,
+        ui_content: &str,
+        config: &NavigationBarDetectionConfig,
+        target_button_name: &str,
+    ) -> Result<NavigationDetectionResult> {
+        let start_time = std::time::Instant::now();
+
         // 获取屏幕尺寸
-        let screen_size = self.extract_screen_size(&ui_content)?;
+        let screen_size = self.extract_screen_size(ui_content)?;
         info!("屏幕尺寸: {:?}", screen_size);
 
         // 解析UI结构找到导航栏
-        let detected_bars = self.find_navigation_bars(&ui_content, &config, screen_size)?;
+        let detected_bars = self.find_navigation_bars(ui_content, config, screen_size)?;
         
         // 在检测到的导航栏中查找目标按钮
         let target_button = self.find_target_button(
             &detected_bars, 
-            &config, 
-            &target_button_name
+            config, 
+            target_button_name
         )?;
 
         let detection_time = start_time.elapsed().as_millis() as u64;
@@ -245,7 +262,6 @@ impl NavigationBarDetector {
             detection_time_ms: detection_time,
         };
 
-        info!("导航栏检测完成，耗时: {}ms", detection_time);
         Ok(result)
     }
 
@@ -341,8 +357,8 @@ impl NavigationBarDetector {
             let bottom: i32 = caps[5].parse()?;
             let package_name = &caps[6];
 
-            // 检查是否是目标应用的包
-            if package_name != config.package_name {
+            // 检查是否是目标应用的包（未指定包名时不过滤）
+            if !config.package_name.is_empty() && package_name != config.package_name {
                 continue;
             }
 
@@ -529,7 +545,7 @@ impl NavigationBarDetector {
         let (screen_width, screen_height) = screen_size;
         
         match position_config.bar_type {
-            NavigationBarType::Bottom => {
+            NavigationBarType::Bottom | NavigationBarType::Top => {
                 let expected_y = screen_height as f64 * position_config.position_ratio.start;
                 let actual_y = top as f64;
                 let y_diff = (expected_y - actual_y).abs();
@@ -540,44 +556,327 @@ impl NavigationBarDetector {
                 
                 (y_accuracy * 0.6 + width_score * 0.4).max(0.1)
             }
-            _ => 0.5 // 其他类型的基础置信度
+            NavigationBarType::Side => {
+                let expected_x = screen_width as f64 * position_config.position_ratio.start;
+                let x_diff = (expected_x - left as f64).abs();
+                let x_accuracy = 1.0 - (x_diff / screen_width as f64).min(1.0);
+
+                let height_ratio = (bottom - top) as f64 / screen_height as f64;
+                let height_score = if height_ratio > 0.8 { 1.0 } else { height_ratio / 0.8 };
+
+                (x_accuracy * 0.6 + height_score * 0.4).max(0.1)
+            }
+            NavigationBarType::FloatingAction => {
+                // 越接近正方形/圆形越像悬浮按钮
+                let (width, height) = ((right - left) as f64, (bottom - top) as f64);
+                let squareness = width.min(height) / width.max(height).max(1.0);
+                (0.4 + squareness * 0.6).max(0.1)
+            }
         }
     }
 
-    /// 查找顶部导航栏（待实现）
+    /// 查找顶部导航栏（标签条）
+    ///
+    /// 位置比例作用于y轴：容器须完全落在期望范围内、宽度超过半屏，
+    /// 且其中至少有两个横向排列的按钮。
     fn find_top_navigation_bars(
         &self,
-        _ui_content: &str,
-        _config: &NavigationBarDetectionConfig,
-        _screen_width: i32,
-        _screen_height: i32,
+        ui_content: &str,
+        config: &NavigationBarDetectionConfig,
+        screen_width: i32,
+        screen_height: i32,
     ) -> Result<Vec<DetectedNavigationBar>> {
-        Ok(Vec::new()) // TODO: 实现顶部导航栏检测
+        let tree = UiTree::parse(ui_content)?;
+        let position_config = &config.bar_position.position_ratio;
+        let expected_top = (screen_height as f64 * position_config.start) as i32;
+        let expected_bottom = (screen_height as f64 * position_config.end) as i32;
+
+        info!("查找顶部导航栏，期望范围: Y({}-{})", expected_top, expected_bottom);
+
+        let mut bars = Vec::new();
+        for node in tree.elements() {
+            if !is_layout_container(&node) || !package_matches(&node, &config.package_name) {
+                continue;
+            }
+            let Some(b) = node.bounds() else { continue };
+            if b.top < expected_top || b.bottom > expected_bottom {
+                continue;
+            }
+            if b.width() <= screen_width / 2
+                || b.height() < config.bar_position.min_size_threshold
+                || b.height() >= screen_height / 4
+            {
+                continue;
+            }
+
+            let buttons = collect_bar_buttons(&node, BarAxis::Horizontal);
+            if buttons.len() >= 2 && is_laid_out_along(&buttons, BarAxis::Horizontal, b.height()) {
+                info!("发现潜在顶部导航栏: {} bounds=({},{},{},{})", node.class_name(), b.left, b.top, b.right, b.bottom);
+                let bounds = (b.left, b.top, b.right, b.bottom);
+                bars.push(DetectedNavigationBar {
+                    bounds,
+                    bar_type: NavigationBarType::Top,
+                    buttons,
+                    confidence: self.calculate_bar_confidence(&config.bar_position, bounds, (screen_width, screen_height)),
+                });
+            }
+        }
+
+        Ok(keep_innermost_bars(bars))
     }
 
-    /// 查找侧边导航栏（待实现）
+    /// 查找侧边导航栏（左侧抽屉）
+    ///
+    /// 位置比例作用于x轴，约束抽屉左边缘所在范围；`size_ratio` 为抽屉最小宽度占屏幕的比例。
+    /// 容器须纵向占据大半屏幕但不铺满整屏宽度，且其中的按钮纵向排列。
     fn find_side_navigation_bars(
         &self,
-        _ui_content: &str,
-        _config: &NavigationBarDetectionConfig,
-        _screen_width: i32,
-        _screen_height: i32,
+        ui_content: &str,
+        config: &NavigationBarDetectionConfig,
+        screen_width: i32,
+        screen_height: i32,
     ) -> Result<Vec<DetectedNavigationBar>> {
-        Ok(Vec::new()) // TODO: 实现侧边导航栏检测
+        let tree = UiTree::parse(ui_content)?;
+        let position_config = &config.bar_position.position_ratio;
+        let expected_left = (screen_width as f64 * position_config.start) as i32;
+        let expected_right = (screen_width as f64 * position_config.end) as i32;
+        let min_width = ((screen_width as f64 * config.bar_position.size_ratio) as i32)
+            .max(config.bar_position.min_size_threshold);
+
+        info!("查找侧边导航栏，左边缘期望范围: X({}-{}), 最小宽度: {}", expected_left, expected_right, min_width);
+
+        let mut bars = Vec::new();
+        for node in tree.elements() {
+            if !is_layout_container(&node) || !package_matches(&node, &config.package_name) {
+                continue;
+            }
+            let Some(b) = node.bounds() else { continue };
+            if b.left < expected_left || b.left > expected_right {
+                continue;
+            }
+            // 铺满整屏宽度的是页面主体而不是抽屉
+            if b.width() < min_width || b.width() >= screen_width * 95 / 100 || b.height() <= screen_height / 2 {
+                continue;
+            }
+
+            let buttons = collect_bar_buttons(&node, BarAxis::Vertical);
+            if buttons.len() >= 2 && is_laid_out_along(&buttons, BarAxis::Vertical, b.width()) {
+                info!("发现潜在侧边导航栏: {} bounds=({},{},{},{})", node.class_name(), b.left, b.top, b.right, b.bottom);
+                let bounds = (b.left, b.top, b.right, b.bottom);
+                bars.push(DetectedNavigationBar {
+                    bounds,
+                    bar_type: NavigationBarType::Side,
+                    buttons,
+                    confidence: self.calculate_bar_confidence(&config.bar_position, bounds, (screen_width, screen_height)),
+                });
+            }
+        }
+
+        Ok(keep_innermost_bars(bars))
     }
 
-    /// 查找悬浮操作栏（待实现）
+    /// 查找悬浮操作按钮
+    ///
+    /// 位置比例作用于y轴，约束按钮中心所在范围；`size_ratio` 为按钮最大边长占屏幕宽度的比例。
+    /// 悬浮按钮近似方形、可点击、不贴屏幕左右边缘，且同一行没有尺寸相近的其他可点击元素
+    /// （以排除底部导航栏中间的发布按钮这类成排按钮）。每个悬浮按钮单独作为一个结果返回。
     fn find_floating_action_bars(
         &self,
-        _ui_content: &str,
-        _config: &NavigationBarDetectionConfig,
-        _screen_width: i32,
-        _screen_height: i32,
+        ui_content: &str,
+        config: &NavigationBarDetectionConfig,
+        screen_width: i32,
+        screen_height: i32,
     ) -> Result<Vec<DetectedNavigationBar>> {
-        Ok(Vec::new()) // TODO: 实现悬浮操作栏检测
+        let tree = UiTree::parse(ui_content)?;
+        let position_config = &config.bar_position.position_ratio;
+        let expected_top = (screen_height as f64 * position_config.start) as i32;
+        let expected_bottom = (screen_height as f64 * position_config.end) as i32;
+        let max_size = (screen_width as f64 * config.bar_position.size_ratio) as i32;
+
+        info!("查找悬浮操作按钮，期望范围: Y({}-{}), 尺寸: {}-{}", expected_top, expected_bottom,
+              config.bar_position.min_size_threshold, max_size);
+
+        let clickables: Vec<_> = tree.elements().filter(|n| n.is_clickable()).collect();
+        let mut bars = Vec::new();
+        for node in tree.elements() {
+            let is_fab_class = node.class_name().contains("FloatingActionButton");
+            if !(node.is_clickable() || is_fab_class) || !package_matches(&node, &config.package_name) {
+                continue;
+            }
+            let Some(b) = node.bounds() else { continue };
+            let (width, height) = (b.width(), b.height());
+            if width.min(height) < config.bar_position.min_size_threshold || width.max(height) > max_size {
+                continue;
+            }
+            if (width as f64 / height as f64 - 1.0).abs() > 0.35 {
+                continue;
+            }
+            if b.center_y() < expected_top || b.center_y() > expected_bottom || b.left <= 0 || b.right >= screen_width {
+                continue;
+            }
+
+            // 同一行存在尺寸相近的其他可点击元素，说明是成排按钮的一员
+            let in_row = !is_fab_class && clickables.iter().any(|other| {
+                if *other == node || other.ancestors().any(|a| a == node) || node.ancestors().any(|a| a == *other) {
+                    return false;
+                }
+                other.bounds().map_or(false, |o| {
+                    o.top <= b.center_y() && o.bottom >= b.center_y()
+                        && o.width() <= screen_width / 2
+                        && o.height() * 2 >= height && o.height() <= height * 2
+                })
+            });
+            if in_row {
+                continue;
+            }
+
+            let labelled = || std::iter::once(node).chain(node.descendants());
+            let text = labelled().map(|n| n.text().trim()).find(|t| !t.is_empty()).map(str::to_string);
+            let content_desc = labelled().map(|n| n.content_desc().trim()).find(|d| !d.is_empty()).map(str::to_string);
+            let label = text.clone().or_else(|| content_desc.clone());
+            let index = bars.len();
+            let bounds = (b.left, b.top, b.right, b.bottom);
+            info!("发现悬浮操作按钮: {} {:?} bounds=({},{},{},{})", node.class_name(), label, b.left, b.top, b.right, b.bottom);
+            bars.push(DetectedNavigationBar {
+                bounds,
+                bar_type: NavigationBarType::FloatingAction,
+                buttons: vec![DetectedNavigationButton {
+                    name: label.clone().unwrap_or_else(|| format!("fab_{}", index)),
+                    bounds,
+                    text,
+                    content_desc,
+                    clickable: node.is_clickable(),
+                    position_index: 0,
+                    confidence: 0.8,
+                }],
+                confidence: self.calculate_bar_confidence(&config.bar_position, bounds, (screen_width, screen_height)),
+            });
+        }
+
+        Ok(bars)
     }
 }
 
+/// 按钮排列方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BarAxis {
+    Horizontal,
+    Vertical,
+}
+
+/// 可能作为导航栏容器的布局类
+fn is_layout_container(node: &UiNode) -> bool {
+    const CONTAINER_CLASSES: [&str; 5] = ["ViewGroup", "Layout", "RecyclerView", "ListView", "ScrollView"];
+    CONTAINER_CLASSES.iter().any(|c| node.class_name().contains(c))
+}
+
+/// 未指定包名时不过滤
+fn package_matches(node: &UiNode, package_name: &str) -> bool {
+    package_name.is_empty() || node.package() == package_name
+}
+
+/// 节点的显示名称：优先文本，其次content-desc
+fn node_label(node: &UiNode) -> Option<String> {
+    [node.text(), node.content_desc()]
+        .into_iter()
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 收集容器内带标识的按钮
+///
+/// 文本节点通常不可点击，点击区域取它在容器内最近的可点击祖先；
+/// 同一点击区域内的图标和文字合并为一个按钮，名称优先使用文字。
+fn collect_bar_buttons(container: &UiNode, axis: BarAxis) -> Vec<DetectedNavigationButton> {
+    let mut buttons: Vec<DetectedNavigationButton> = Vec::new();
+
+    for node in container.descendants() {
+        let Some(name) = node_label(&node) else { continue };
+        let target = std::iter::once(node)
+            .chain(node.ancestors().take_while(|a| *a != *container))
+            .find(|n| n.is_clickable());
+        let Some(b) = target.unwrap_or(node).bounds() else { continue };
+        let bounds = (b.left, b.top, b.right, b.bottom);
+        let text = Some(node.text().trim()).filter(|t| !t.is_empty()).map(str::to_string);
+        let content_desc = Some(node.content_desc().trim()).filter(|d| !d.is_empty()).map(str::to_string);
+
+        if let Some(existing) = buttons.iter_mut().find(|btn| btn.bounds == bounds) {
+            if existing.text.is_none() && text.is_some() {
+                existing.name = name;
+                existing.text = text;
+            }
+            if existing.content_desc.is_none() {
+                existing.content_desc = content_desc;
+            }
+            continue;
+        }
+
+        let clickable = target.is_some();
+        buttons.push(DetectedNavigationButton {
+            name,
+            bounds,
+            text,
+            content_desc,
+            clickable,
+            position_index: 0,
+            confidence: if clickable { 0.8 } else { 0.6 },
+        });
+    }
+
+    match axis {
+        BarAxis::Horizontal => buttons.sort_by_key(|b| (b.bounds.0, b.bounds.1)),
+        BarAxis::Vertical => buttons.sort_by_key(|b| (b.bounds.1, b.bounds.0)),
+    }
+    for (index, button) in buttons.iter_mut().enumerate() {
+        button.position_index = index;
+    }
+    buttons
+}
+
+/// 布局分析：按钮是否沿指定方向排开
+///
+/// 横向：按钮中心的纵向偏差不超过容器高度的一半，且至少占据两列；
+/// 纵向：至少占据两行（`cross_size` 为容器在另一方向上的尺寸，横向时是高度）。
+fn is_laid_out_along(buttons: &[DetectedNavigationButton], axis: BarAxis, cross_size: i32) -> bool {
+    let centers: Vec<(i32, i32)> = buttons
+        .iter()
+        .map(|b| ((b.bounds.0 + b.bounds.2) / 2, (b.bounds.1 + b.bounds.3) / 2))
+        .collect();
+    let count_distinct = |mut values: Vec<i32>| {
+        values.sort_unstable();
+        values.windows(2).filter(|w| w[1] - w[0] > 10).count() + 1
+    };
+
+    match axis {
+        BarAxis::Horizontal => {
+            let min_y = centers.iter().map(|c| c.1).min().unwrap_or(0);
+            let max_y = centers.iter().map(|c| c.1).max().unwrap_or(0);
+            max_y - min_y <= cross_size / 2 && count_distinct(centers.iter().map(|c| c.0).collect()) >= 2
+        }
+        BarAxis::Vertical => count_distinct(centers.iter().map(|c| c.1).collect()) >= 2,
+    }
+}
+
+/// 嵌套容器可能都满足条件，只保留最内层的那个（内层需包含不少于外层的按钮）
+fn keep_innermost_bars(bars: Vec<DetectedNavigationBar>) -> Vec<DetectedNavigationBar> {
+    let contains = |outer: (i32, i32, i32, i32), inner: (i32, i32, i32, i32)| {
+        outer.0 <= inner.0 && outer.1 <= inner.1 && outer.2 >= inner.2 && outer.3 >= inner.3
+    };
+    bars.iter()
+        .enumerate()
+        .filter(|(i, bar)| {
+            !bars.iter().enumerate().any(|(j, other)| {
+                *i != j
+                    && contains(bar.bounds, other.bounds)
+                    && other.buttons.len() >= bar.buttons.len()
+                    && (other.bounds != bar.bounds || j > *i)
+            })
+        })
+        .map(|(_, bar)| bar.clone())
+        .collect()
+}
+
 /// 预定义的应用导航栏配置
 pub fn create_xiaohongshu_navigation_config() -> NavigationBarDetectionConfig {
     let mut target_buttons = HashMap::new();
@@ -618,6 +917,57 @@ pub fn create_xiaohongshu_navigation_config() -> NavigationBarDetectionConfig {
     }
 }
 
+/// 小红书侧边栏（点击左上角头像后弹出的抽屉）配置
+pub fn create_xiaohongshu_sidebar_config() -> NavigationBarDetectionConfig {
+    let mut target_buttons = HashMap::new();
+
+    // 配置"发现好友"入口
+    target_buttons.insert("发现好友".to_string(), NavigationButtonConfig {
+        text: Some("发现好友".to_string()),
+        content_desc: Some("发现好友".to_string()),
+        resource_id_pattern: None,
+        class_name: None,
+        must_clickable: true,
+        position_in_bar: None,
+    });
+
+    NavigationBarDetectionConfig {
+        package_name: "com.xingin.xhs".to_string(),
+        bar_position: default_bar_position(NavigationBarType::Side),
+        target_buttons,
+        enable_smart_adaptation: true,
+    }
+}
+
+/// 各类导航栏的默认位置配置
+///
+/// 顶部/底部/悬浮按钮的比例作用于y轴，侧边栏的比例作用于x轴（抽屉左边缘）；
+/// 侧边栏和悬浮按钮的 `size_ratio` 分别是最小宽度比例和最大边长比例。
+pub fn default_bar_position(bar_type: NavigationBarType) -> NavigationBarPosition {
+    let (start, end, size_ratio, min_size_threshold) = match bar_type {
+        NavigationBarType::Bottom => (0.85, 1.0, 0.1, 60),
+        NavigationBarType::Top => (0.0, 0.25, 0.08, 60),
+        NavigationBarType::Side => (0.0, 0.05, 0.15, 100),
+        NavigationBarType::FloatingAction => (0.5, 0.95, 0.25, 80),
+    };
+    NavigationBarPosition {
+        bar_type,
+        position_ratio: PositionRatio { start, end },
+        size_ratio,
+        min_size_threshold,
+    }
+}
+
+/// 解析前端传入的导航栏类型（"bottom", "top", "side", "floating"），未知值按底部处理
+fn parse_bar_type(bar_type: &str) -> NavigationBarType {
+    match bar_type {
+        "top" => NavigationBarType::Top,
+        "side" => NavigationBarType::Side,
+        "floating" => NavigationBarType::FloatingAction,
+        _ => NavigationBarType::Bottom,
+    }
+}
+
 /// Tauri命令：检测导航栏并点击目标按钮
 #[command]
 pub async fn detect_and_click_navigation_button(
@@ -626,46 +976,31 @@ pub async fn detect_and_click_navigation_button(
     button_name: String,
     bar_type: String, // "bottom", "top", "side", "floating"
 ) -> Result<NavigationDetectionResult, String> {
-    info!("开始导航栏检测，设备: {}, 应用: {}, 按钮: {}", device_id, app_package, button_name);
+    info!("开始导航栏检测，设备: {}, 应用: {}, 按钮: {}, 类型: {}", device_id, app_package, button_name, bar_type);
     
     let detector = NavigationBarDetector::new(device_id.clone());
+    let bar_type = parse_bar_type(&bar_type);
     
-    // 根据应用包名创建配置
-    let config = match app_package.as_str() {
-        "com.xingin.xhs" => create_xiaohongshu_navigation_config(),
-        _ => {
-            // 通用配置
-            let mut target_buttons = HashMap::new();
-            target_buttons.insert(button_name.clone(), NavigationButtonConfig {
-                text: Some(button_name.clone()),
-                content_desc: Some(button_name.clone()),
-                resource_id_pattern: None,
-                class_name: None,
-                must_clickable: true,
-                position_in_bar: None,
-            });
-
-            NavigationBarDetectionConfig {
-                package_name: app_package,
-                bar_position: NavigationBarPosition {
-                    bar_type: match bar_type.as_str() {
-                        "top" => NavigationBarType::Top,
-                        "side" => NavigationBarType::Side,
-                        "floating" => NavigationBarType::FloatingAction,
-                        _ => NavigationBarType::Bottom,
-                    },
-                    position_ratio: PositionRatio {
-                        start: if bar_type == "bottom" { 0.9 } else { 0.0 },
-                        end: if bar_type == "bottom" { 1.0 } else { 0.2 },
-                    },
-                    size_ratio: 0.07,
-                    min_size_threshold: 80,
-                },
-                target_buttons,
-                enable_smart_adaptation: true,
-            }
-        }
+    // 根据应用包名和导航栏类型创建配置
+    let mut config = match (app_package.as_str(), &bar_type) {
+        ("com.xingin.xhs", NavigationBarType::Bottom) => create_xiaohongshu_navigation_config(),
+        ("com.xingin.xhs", NavigationBarType::Side) => create_xiaohongshu_sidebar_config(),
+        _ => NavigationBarDetectionConfig {
+            package_name: app_package,
+            bar_position: default_bar_position(bar_type),
+            target_buttons: HashMap::new(),
+            enable_smart_adaptation: true,
+        },
     };
+    // 预设里没有的按钮按文本/描述匹配
+    config.target_buttons.entry(button_name.clone()).or_insert_with(|| NavigationButtonConfig {
+        text: Some(button_name.clone()),
+        content_desc: Some(button_name.clone()),
+        resource_id_pattern: None,
+        class_name: None,
+        must_clickable: true,
+        position_in_bar: None,
+    });
     
     // 执行检测
     match detector.detect_navigation_bar(config.clone(), button_name.clone()).await {
@@ -704,20 +1039,7 @@ pub async fn detect_navigation_bar(
     
     let config = NavigationBarDetectionConfig {
         package_name: app_package,
-        bar_position: NavigationBarPosition {
-            bar_type: match bar_type.as_str() {
-                "top" => NavigationBarType::Top,
-                "side" => NavigationBarType::Side,
-                "floating" => NavigationBarType::FloatingAction,
-                _ => NavigationBarType::Bottom,
-            },
-            position_ratio: PositionRatio {
-                start: if bar_type == "bottom" { 0.85 } else { 0.0 },
-                end: if bar_type == "bottom" { 1.0 } else { 0.25 },
-            },
-            size_ratio: 0.1,
-            min_size_threshold: 60,
-        },
+        bar_position: default_bar_position(parse_bar_type(&bar_type)),
         target_buttons: HashMap::new(),
         enable_smart_adaptation: true,
     };
@@ -746,6 +1068,101 @@ pub async fn click_navigation_button(
 pub fn get_navigation_configs() -> Vec<NavigationBarDetectionConfig> {
     vec![
         NavigationBarDetector::create_xiaohongshu_navigation_config(),
+        create_xiaohongshu_sidebar_config(),
         NavigationBarDetector::create_generic_bottom_navigation_config()
     ]
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XHS: &str = "com.xingin.xhs";
+
+    fn node(class: &str, bounds: &str, attrs: &str, children: &str) -> String {
+        format!(
+            r#"<node class="{}" package="{}" bounds="{}" {}>{}</node>"#,
+            class, XHS, bounds, attrs, children
+        )
+    }
+
+    fn config(bar_type: NavigationBarType) -> NavigationBarDetectionConfig {
+        NavigationBarDetectionConfig {
+            package_name: XHS.to_string(),
+            bar_position: default_bar_position(bar_type),
+            target_buttons: HashMap::new(),
+            enable_smart_adaptation: true,
+        }
+    }
+
+    #[test]
+    fn test_side_drawer_detection_finds_discover_friends() {
+        let rows: String = ["发现好友", "创作者中心", "设置"]
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let (top, bottom) = (200 + i * 150, 330 + i * 150);
+                node(
+                    "android.widget.LinearLayout",
+                    &format!("[0,{}][810,{}]", top, bottom),
+                    r#"clickable="true""#,
+                    &format!(
+                        "{}{}",
+                        node("android.widget.ImageView", &format!("[40,{}][120,{}]", top + 25, bottom - 25), r#"clickable="false""#, ""),
+                        node("android.widget.TextView", &format!("[150,{}][500,{}]", top + 30, bottom - 30), &format!(r#"text="{}" clickable="false""#, label), ""),
+                    ),
+                )
+            })
+            .collect();
+        let drawer = node("android.widget.LinearLayout", "[0,0][810,2400]", "", &node("androidx.recyclerview.widget.RecyclerView", "[0,200][810,1800]", "", &rows));
+        let content = node("android.widget.FrameLayout", "[0,0][1080,2400]", "", &node("android.widget.TextView", "[100,300][300,400]", r#"text="推荐""#, ""));
+        let xml = format!("<hierarchy>{}</hierarchy>", node("androidx.drawerlayout.widget.DrawerLayout", "[0,0][1080,2400]", "", &(content + &drawer)));
+
+        let detector = NavigationBarDetector::new("test".to_string());
+        let result = detector.detect_in_ui_dump(&xml, &create_xiaohongshu_sidebar_config(), "发现好友").unwrap();
+
+        assert_eq!(result.screen_size, (1080, 2400));
+        assert_eq!(result.detected_bars.len(), 1);
+        let bar = &result.detected_bars[0];
+        assert_eq!((bar.bar_type.clone(), bar.bounds), (NavigationBarType::Side, (0, 200, 810, 1800)));
+        assert_eq!(bar.buttons.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), vec!["发现好友", "创作者中心", "设置"]);
+        // 点击区域是可点击的整行，而不是文字本身
+        let target = result.target_button.unwrap();
+        assert_eq!((target.bounds, target.clickable), ((0, 200, 810, 330), true));
+
+        // 页面主体铺满整屏，不会被当作顶部导航栏
+        assert!(detector.detect_in_ui_dump(&xml, &config(NavigationBarType::Top), "").unwrap().detected_bars.is_empty());
+    }
+
+    #[test]
+    fn test_top_tabs_and_floating_button_detection() {
+        let tab = |label: &str, left: i32| {
+            node("android.widget.TextView", &format!("[{},120][{},220]", left, left + 160), &format!(r#"text="{}" clickable="true""#, label), "")
+        };
+        let tabs = node("android.widget.LinearLayout", "[0,100][1080,240]", "", &(tab("关注", 200) + &tab("发现", 460) + &tab("附近", 720)));
+        let fab = node("android.widget.ImageButton", "[880,1900][1040,2060]", r#"clickable="true" content-desc="发布笔记""#, "");
+        let bottom_item = |label: &str, left: i32| {
+            node("android.widget.FrameLayout", &format!("[{},2250][{},2400]", left, left + 216), r#"clickable="true""#,
+                 &node("android.widget.TextView", &format!("[{},2290][{},2360]", left + 50, left + 166), &format!(r#"text="{}""#, label), ""))
+        };
+        // 底部导航栏中间的发布按钮与其他按钮成排，不算悬浮按钮
+        let publish = node("android.widget.ImageView", "[480,2260][600,2380]", r#"clickable="true" content-desc="发布""#, "");
+        let bottom = node("android.widget.LinearLayout", "[0,2250][1080,2400]", "",
+                          &(bottom_item("首页", 0) + &bottom_item("购物", 216) + &publish + &bottom_item("消息", 648) + &bottom_item("我", 864)));
+        let xml = format!("<hierarchy>{}</hierarchy>", node("android.widget.FrameLayout", "[0,0][1080,2400]", "", &(tabs + &fab + &bottom)));
+
+        let detector = NavigationBarDetector::new("test".to_string());
+
+        let top = detector.detect_in_ui_dump(&xml, &config(NavigationBarType::Top), "发现").unwrap();
+        assert_eq!(top.detected_bars.len(), 1);
+        assert_eq!(top.detected_bars[0].buttons.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), vec!["关注", "发现", "附近"]);
+        assert_eq!(top.target_button.unwrap().bounds, (460, 120, 620, 220));
+
+        // 范围放宽到屏幕底部，让发布按钮也进入候选
+        let mut floating_config = config(NavigationBarType::FloatingAction);
+        floating_config.bar_position.position_ratio.end = 1.0;
+        let floating = detector.detect_in_ui_dump(&xml, &floating_config, "发布笔记").unwrap();
+        assert_eq!(floating.detected_bars.len(), 1);
+        assert_eq!(floating.detected_bars[0].bar_type, NavigationBarType::FloatingAction);
+        assert_eq!(floating.target_button.unwrap().bounds, (880, 1900, 1040, 2060));
+    }
+}
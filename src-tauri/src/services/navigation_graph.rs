@@ -0,0 +1,728 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::utils::adb_utils::run_shell;
+use crate::services::page_fingerprint::{current_activity, get_page_registry, PageFingerprint};
+use crate::services::ui_tree::locator::unique_xpath;
+use crate::services::ui_tree::{UiNode, UiTree};
+use crate::xml_judgment_service::XmlJudgmentService;
+
+const NAVIGATION_GRAPH_DIR: &str = "data/navigation_graphs";
+
+/// 探索时默认跳过的危险操作关键字
+pub const DEFAULT_DENY_LIST: [&str; 16] = [
+    "删除", "退出", "注销", "卸载", "支付", "付款", "购买", "充值",
+    "发布", "发送", "拨打", "举报", "logout", "delete", "uninstall", "pay",
+];
+
+/// 失败次数超过成功次数该值后，边不再参与寻路
+const MAX_NET_FAILURES: u32 = 3;
+
+/// 导航中途偏离路线时的最大重新规划次数
+const MAX_REPLANS: usize = 3;
+
+/// 当前页面无法识别时，尝试按返回键回到已知页面的次数
+const RECOVERY_BACK_PRESSES: usize = 3;
+
+/// 页面之间的跳转动作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NavAction {
+    /// 点击元素：回放时优先按 XPath / 文本重新定位，找不到再使用记录时的坐标
+    Tap {
+        xpath: Option<String>,
+        text: Option<String>,
+        x: i32,
+        y: i32,
+    },
+    /// 系统返回键
+    Back,
+}
+
+impl NavAction {
+    /// 用于日志的简短描述
+    pub fn describe(&self) -> String {
+        match self {
+            NavAction::Tap { text: Some(text), .. } => format!("点击“{}”", text),
+            NavAction::Tap { xpath: Some(xpath), .. } => format!("点击 {}", xpath),
+            NavAction::Tap { x, y, .. } => format!("点击({}, {})", x, y),
+            NavAction::Back => "返回".to_string(),
+        }
+    }
+
+    /// 两个动作是否指向同一个元素
+    fn same_target(&self, other: &NavAction) -> bool {
+        match (self, other) {
+            (NavAction::Back, NavAction::Back) => true,
+            (
+                NavAction::Tap { xpath: Some(a), .. },
+                NavAction::Tap { xpath: Some(b), .. },
+            ) => a == b,
+            (a, b) => a == b,
+        }
+    }
+}
+
+/// 页面之间的一条边
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NavEdge {
+    pub from: String,
+    pub to: String,
+    pub action: NavAction,
+    #[serde(default)]
+    pub success_count: u32,
+    #[serde(default)]
+    pub failure_count: u32,
+    pub updated_at: String,
+}
+
+impl NavEdge {
+    /// 寻路代价：失败越多代价越高
+    fn cost(&self) -> u32 {
+        1 + self.failure_count.saturating_sub(self.success_count) * 2
+    }
+
+    fn is_usable(&self) -> bool {
+        self.failure_count.saturating_sub(self.success_count) <= MAX_NET_FAILURES
+    }
+}
+
+/// 单个应用的导航图：节点为页面指纹名称，边为页面之间的跳转动作
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NavigationGraph {
+    pub package: String,
+    pub pages: BTreeSet<String>,
+    pub edges: Vec<NavEdge>,
+}
+
+impl NavigationGraph {
+    pub fn new(package: &str) -> Self {
+        Self {
+            package: package.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 记录一次成功的跳转；已有相同的边时累加成功次数，返回是否新增了边
+    pub fn record(&mut self, from: &str, to: &str, action: NavAction) -> bool {
+        self.pages.insert(from.to_string());
+        self.pages.insert(to.to_string());
+        let now = chrono::Local::now().to_rfc3339();
+
+        if let Some(edge) = self
+            .edges
+            .iter_mut()
+            .find(|e| e.from == from && e.to == to && e.action.same_target(&action))
+        {
+            edge.success_count += 1;
+            edge.action = action;
+            edge.updated_at = now;
+            return false;
+        }
+        self.edges.push(NavEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            action,
+            success_count: 1,
+            failure_count: 0,
+            updated_at: now,
+        });
+        true
+    }
+
+    /// 回放后更新边的成功/失败次数
+    pub fn mark(&mut self, edge: &NavEdge, success: bool) {
+        if let Some(existing) = self
+            .edges
+            .iter_mut()
+            .find(|e| e.from == edge.from && e.to == edge.to && e.action.same_target(&edge.action))
+        {
+            if success {
+                existing.success_count += 1;
+            } else {
+                existing.failure_count += 1;
+            }
+            existing.updated_at = chrono::Local::now().to_rfc3339();
+        }
+    }
+
+    /// 两个页面之间代价最小的路径（Dijkstra）；起点即终点时返回空路径
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<NavEdge>> {
+        if from == to {
+            return Some(Vec::new());
+        }
+
+        let mut best: HashMap<&str, u32> = HashMap::new();
+        let mut previous: HashMap<&str, usize> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        best.insert(from, 0);
+        queue.push(Reverse((0u32, from)));
+
+        while let Some(Reverse((cost, page))) = queue.pop() {
+            if page == to {
+                break;
+            }
+            if best.get(page).map_or(false, |&c| cost > c) {
+                continue;
+            }
+            for (index, edge) in self.edges.iter().enumerate() {
+                if edge.from != page || !edge.is_usable() {
+                    continue;
+                }
+                let next = cost + edge.cost();
+                if best.get(edge.to.as_str()).map_or(true, |&c| next < c) {
+                    best.insert(edge.to.as_str(), next);
+                    previous.insert(edge.to.as_str(), index);
+                    queue.push(Reverse((next, edge.to.as_str())));
+                }
+            }
+        }
+
+        let mut path = Vec::new();
+        let mut page = to;
+        while page != from {
+            let edge = &self.edges[*previous.get(page)?];
+            path.push(edge.clone());
+            page = edge.from.as_str();
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// 探索参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExplorationOptions {
+    /// 距起始页面的最大跳转深度
+    pub max_depth: usize,
+    /// 本次探索最多执行的点击次数
+    pub max_actions: usize,
+    /// 每个页面最多尝试的元素数
+    pub max_elements_per_page: usize,
+    /// 每次操作后等待页面稳定的时间
+    pub settle_ms: u64,
+    /// 文本、描述或 resource-id 含有这些关键字的元素不会被点击
+    pub deny_list: Vec<String>,
+}
+
+impl Default for ExplorationOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_actions: 60,
+            max_elements_per_page: 12,
+            settle_ms: 1500,
+            deny_list: DEFAULT_DENY_LIST.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// 探索结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExplorationReport {
+    pub package: String,
+    pub pages_discovered: Vec<String>,
+    pub edges_added: usize,
+    pub actions_taken: usize,
+    pub logs: Vec<String>,
+}
+
+/// 自动导航结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NavigationOutcome {
+    pub success: bool,
+    pub target: String,
+    /// 实际经过的页面（含起点）
+    pub visited: Vec<String>,
+    pub message: String,
+    pub logs: Vec<String>,
+}
+
+/// 某一时刻的页面观测
+pub struct PageObservation {
+    pub xml: String,
+    pub fingerprint: PageFingerprint,
+    /// 识别出的已注册页面
+    pub page: Option<String>,
+}
+
+impl PageObservation {
+    pub fn tree(&self) -> Result<UiTree, String> {
+        UiTree::parse(&self.xml).map_err(|e| e.to_string())
+    }
+}
+
+/// 抓取并识别设备当前页面
+pub async fn observe_page(device_id: &str) -> Result<PageObservation, String> {
+    let xml = XmlJudgmentService::get_ui_xml(device_id).await?;
+    let tree = UiTree::parse(&xml).map_err(|e| e.to_string())?;
+    let fingerprint = PageFingerprint::from_tree(&tree, current_activity(device_id).await);
+    let page = get_page_registry().identify(&fingerprint).await.map(|m| m.name);
+    Ok(PageObservation { xml, fingerprint, page })
+}
+
+/// 根据点击坐标生成可回放的点击动作（定位到包含该坐标的最内层可点击元素）
+pub fn tap_action_at(tree: &UiTree, x: i32, y: i32) -> NavAction {
+    let target = tree
+        .elements()
+        .filter(|n| n.is_clickable())
+        .filter(|n| {
+            n.bounds()
+                .map_or(false, |b| x >= b.left && x <= b.right && y >= b.top && y <= b.bottom)
+        })
+        .max_by_key(|n| n.depth());
+
+    match target {
+        Some(node) => tap_action_for(&node),
+        None => NavAction::Tap { xpath: None, text: None, x, y },
+    }
+}
+
+fn tap_action_for(node: &UiNode) -> NavAction {
+    let (x, y) = node.center().unwrap_or((0, 0));
+    NavAction::Tap {
+        xpath: Some(unique_xpath(node)),
+        text: node_label(node),
+        x,
+        y,
+    }
+}
+
+/// 节点自身或其子孙中第一个非空的文本/描述
+fn node_label(node: &UiNode) -> Option<String> {
+    std::iter::once(*node)
+        .chain(node.descendants())
+        .flat_map(|n| [n.text().trim(), n.content_desc().trim()])
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 元素是否命中探索禁止列表
+pub fn is_denied(node: &UiNode, deny_list: &[String]) -> bool {
+    let mut haystack = node_label(node).unwrap_or_default();
+    haystack.push(' ');
+    haystack.push_str(node.resource_id());
+    let haystack = haystack.to_lowercase();
+    deny_list
+        .iter()
+        .filter(|k| !k.trim().is_empty())
+        .any(|keyword| haystack.contains(&keyword.trim().to_lowercase()))
+}
+
+/// 页面上值得探索的可点击元素（去掉输入框、禁用元素和禁止列表中的元素）
+pub fn exploration_candidates(tree: &UiTree, deny_list: &[String], limit: usize) -> Vec<NavAction> {
+    let mut seen = HashSet::new();
+    tree.elements()
+        .filter(|n| n.is_clickable() && n.is_enabled())
+        .filter(|n| !n.class_name().ends_with("EditText"))
+        .filter(|n| n.bounds().map_or(false, |b| b.width() > 0 && b.height() > 0))
+        .filter(|n| node_label(n).is_some() || !n.resource_id().is_empty())
+        .filter(|n| !is_denied(n, deny_list))
+        .map(|n| tap_action_for(&n))
+        .filter(|action| seen.insert(action.describe()))
+        .take(limit)
+        .collect()
+}
+
+/// 回放动作：点击动作先在当前界面重新定位目标元素
+async fn perform(device_id: &str, action: &NavAction, tree: Option<&UiTree>) -> Result<(), String> {
+    match action {
+        NavAction::Back => run_shell(device_id, "input keyevent 4").await.map(|_| ()),
+        NavAction::Tap { xpath, text, x, y } => {
+            let located = tree.and_then(|tree| {
+                let by_xpath = xpath
+                    .as_deref()
+                    .and_then(|xp| tree.select(xp).ok())
+                    .and_then(|nodes| if nodes.len() == 1 { nodes[0].center() } else { None });
+                by_xpath.or_else(|| {
+                    let text = text.as_deref()?;
+                    tree.find_first(|n| n.text() == text || n.content_desc() == text)
+                        .and_then(|n| n.center())
+                })
+            });
+            let (tx, ty) = located.unwrap_or((*x, *y));
+            run_shell(device_id, &format!("input tap {} {}", tx, ty)).await.map(|_| ())
+        }
+    }
+}
+
+/// 导航图存储（每个应用一个文件：data/navigation_graphs/<包名>.json）
+pub struct NavigationGraphStore {
+    graphs: Mutex<HashMap<String, NavigationGraph>>,
+}
+
+impl NavigationGraphStore {
+    pub fn new() -> Self {
+        Self {
+            graphs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get(&self, package: &str) -> NavigationGraph {
+        let mut graphs = self.graphs.lock().await;
+        graphs
+            .entry(package.to_string())
+            .or_insert_with(|| load_graph(package))
+            .clone()
+    }
+
+    /// 修改导航图并立即持久化
+    pub async fn update<R>(&self, package: &str, f: impl FnOnce(&mut NavigationGraph) -> R) -> Result<R, String> {
+        let mut graphs = self.graphs.lock().await;
+        let graph = graphs
+            .entry(package.to_string())
+            .or_insert_with(|| load_graph(package));
+        let result = f(graph);
+        persist_graph(graph)?;
+        Ok(result)
+    }
+
+    pub async fn clear(&self, package: &str) -> Result<(), String> {
+        self.graphs.lock().await.remove(package);
+        let path = graph_path(package);
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("删除导航图失败: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+fn graph_path(package: &str) -> PathBuf {
+    let file_name: String = package
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '_' { c } else { '_' })
+        .collect();
+    PathBuf::from(NAVIGATION_GRAPH_DIR).join(format!("{}.json", file_name))
+}
+
+fn load_graph(package: &str) -> NavigationGraph {
+    fs::read_to_string(graph_path(package))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| NavigationGraph::new(package))
+}
+
+fn persist_graph(graph: &NavigationGraph) -> Result<(), String> {
+    fs::create_dir_all(NAVIGATION_GRAPH_DIR).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    let content = serde_json::to_string_pretty(graph).map_err(|e| e.to_string())?;
+    fs::write(graph_path(&graph.package), content).map_err(|e| format!("保存导航图失败: {}", e))
+}
+
+/// 全局导航图存储实例
+static GLOBAL_NAVIGATION_GRAPHS: std::sync::OnceLock<NavigationGraphStore> = std::sync::OnceLock::new();
+
+/// 获取全局导航图存储
+pub fn get_navigation_graphs() -> &'static NavigationGraphStore {
+    GLOBAL_NAVIGATION_GRAPHS.get_or_init(NavigationGraphStore::new)
+}
+
+/// 记录一次页面跳转（正常执行脚本时调用）
+pub async fn record_transition(package: &str, from: &str, to: &str, action: NavAction) -> Result<bool, String> {
+    if from == to {
+        return Ok(false);
+    }
+    let added = get_navigation_graphs()
+        .update(package, |graph| graph.record(from, to, action.clone()))
+        .await?;
+    if added {
+        info!("🗺️ 导航图新增跳转: {} --{}--> {}", from, action.describe(), to);
+    }
+    Ok(added)
+}
+
+/// 识别当前页面；无法识别时按返回键尝试回到已知页面
+async fn locate(device_id: &str, settle: Duration, logs: &mut Vec<String>) -> Result<PageObservation, String> {
+    let mut observation = observe_page(device_id).await?;
+    for attempt in 0..RECOVERY_BACK_PRESSES {
+        if observation.page.is_some() {
+            break;
+        }
+        logs.push(format!("↩️ 当前页面未识别，按返回键恢复 ({}/{})", attempt + 1, RECOVERY_BACK_PRESSES));
+        perform(device_id, &NavAction::Back, None).await?;
+        tokio::time::sleep(settle).await;
+        observation = observe_page(device_id).await?;
+    }
+    Ok(observation)
+}
+
+/// 从任意页面导航到目标页面：按导航图寻找最短路径并逐跳回放，偏离路线时重新规划
+pub async fn navigate_to(device_id: &str, package: &str, target: &str, settle: Duration) -> Result<NavigationOutcome, String> {
+    let store = get_navigation_graphs();
+    let mut outcome = NavigationOutcome {
+        target: target.to_string(),
+        ..Default::default()
+    };
+
+    for _ in 0..=MAX_REPLANS {
+        let mut observation = locate(device_id, settle, &mut outcome.logs).await?;
+        let Some(current) = observation.page.clone() else {
+            outcome.message = "无法识别当前页面，导航中止".to_string();
+            return Ok(outcome);
+        };
+        outcome.visited.push(current.clone());
+        if current == target {
+            outcome.success = true;
+            outcome.message = format!("已到达页面: {}", target);
+            return Ok(outcome);
+        }
+
+        let graph = store.get(package).await;
+        let Some(path) = graph.shortest_path(&current, target) else {
+            outcome.message = format!("导航图中没有从 {} 到 {} 的路径", current, target);
+            return Ok(outcome);
+        };
+        outcome.logs.push(format!(
+            "🧭 规划路径: {}",
+            std::iter::once(current.as_str())
+                .chain(path.iter().map(|e| e.to.as_str()))
+                .collect::<Vec<_>>()
+                .join(" → ")
+        ));
+
+        for edge in &path {
+            let tree = observation.tree().ok();
+            perform(device_id, &edge.action, tree.as_ref()).await?;
+            tokio::time::sleep(settle).await;
+            observation = observe_page(device_id).await?;
+
+            let arrived = observation.page.as_deref() == Some(edge.to.as_str());
+            store.update(package, |graph| graph.mark(edge, arrived)).await?;
+            if !arrived {
+                outcome.logs.push(format!(
+                    "⚠️ {} 后未到达 {}（当前: {}），重新规划",
+                    edge.action.describe(),
+                    edge.to,
+                    observation.page.as_deref().unwrap_or("未知页面")
+                ));
+                break;
+            }
+            outcome.logs.push(format!("✅ {} → {}", edge.action.describe(), edge.to));
+            outcome.visited.push(edge.to.clone());
+            if edge.to == target {
+                outcome.success = true;
+                outcome.message = format!("已到达页面: {}", target);
+                return Ok(outcome);
+            }
+        }
+    }
+
+    outcome.message = format!("多次重新规划后仍未到达页面: {}", target);
+    Ok(outcome)
+}
+
+/// 识别当前页面；未注册时以自动生成的名称注册
+async fn identify_or_register(
+    observation: &PageObservation,
+    package: &str,
+    report: &mut ExplorationReport,
+) -> Result<String, String> {
+    if let Some(page) = &observation.page {
+        return Ok(page.clone());
+    }
+    let registry = get_page_registry();
+    // 以指纹哈希命名，删除页面后重新探索也不会与已有名称冲突
+    let base = format!("auto:{}:{}", package, observation.fingerprint.stable_id());
+    let existing: HashSet<String> = registry.list().await.into_iter().map(|s| s.name).collect();
+    let name = (1..)
+        .map(|n| if n == 1 { base.clone() } else { format!("{}-{}", base, n) })
+        .find(|candidate| !existing.contains(candidate))
+        .unwrap_or(base);
+    registry.save(&name, observation.fingerprint.clone()).await?;
+    report.pages_discovered.push(name.clone());
+    report.logs.push(format!("📌 发现新页面: {}", name));
+    Ok(name)
+}
+
+/// 探索应用：从当前页面出发按广度优先点击可点击元素，记录页面跳转并用返回键回到原页面
+pub async fn explore_app(device_id: &str, package: &str, options: ExplorationOptions) -> Result<ExplorationReport, String> {
+    let settle = Duration::from_millis(options.settle_ms);
+    let mut report = ExplorationReport {
+        package: package.to_string(),
+        ..Default::default()
+    };
+
+    let observation = observe_page(device_id).await?;
+    if observation.fingerprint.package != package {
+        return Err(format!("当前前台应用为 {}，请先打开 {}", observation.fingerprint.package, package));
+    }
+    let start = identify_or_register(&observation, package, &mut report).await?;
+    info!("🕷️ 开始探索 {}，起始页面: {}", package, start);
+
+    let mut queue = VecDeque::from([(start.clone(), 0usize)]);
+    let mut explored = HashSet::new();
+
+    'pages: while let Some((page, depth)) = queue.pop_front() {
+        if !explored.insert(page.clone()) {
+            continue;
+        }
+        // 移动到待探索页面
+        let outcome = navigate_to(device_id, package, &page, settle).await?;
+        report.logs.extend(outcome.logs);
+        if !outcome.success {
+            report.logs.push(format!("⚠️ 无法回到页面 {}，跳过: {}", page, outcome.message));
+            continue;
+        }
+
+        let tree = observe_page(device_id).await?.tree()?;
+        let candidates = exploration_candidates(&tree, &options.deny_list, options.max_elements_per_page);
+        report.logs.push(format!("🔎 页面 {} 有 {} 个待探索元素", page, candidates.len()));
+
+        for action in candidates {
+            if report.actions_taken >= options.max_actions {
+                report.logs.push(format!("⏹️ 已达到最大操作次数 {}", options.max_actions));
+                break 'pages;
+            }
+            report.actions_taken += 1;
+            perform(device_id, &action, None).await?;
+            tokio::time::sleep(settle).await;
+
+            let after = observe_page(device_id).await?;
+            if after.fingerprint.package != package {
+                // 跳出了应用（分享、外链等），直接返回
+                report.logs.push(format!("↩️ {} 跳出应用，返回", action.describe()));
+                perform(device_id, &NavAction::Back, None).await?;
+                tokio::time::sleep(settle).await;
+                continue;
+            }
+
+            let next = identify_or_register(&after, package, &mut report).await?;
+            if next == page {
+                continue;
+            }
+            if record_transition(package, &page, &next, action.clone()).await? {
+                report.edges_added += 1;
+            }
+            if depth + 1 < options.max_depth && !explored.contains(&next) {
+                queue.push_back((next.clone(), depth + 1));
+            }
+
+            // 尝试用返回键回到原页面，同时记录返回边
+            perform(device_id, &NavAction::Back, None).await?;
+            tokio::time::sleep(settle).await;
+            let back = observe_page(device_id).await?;
+            if back.page.as_deref() == Some(page.as_str()) {
+                if record_transition(package, &next, &page, NavAction::Back).await? {
+                    report.edges_added += 1;
+                }
+                continue;
+            }
+            let outcome = navigate_to(device_id, package, &page, settle).await?;
+            if !outcome.success {
+                warn!("⚠️ 探索中无法回到页面 {}: {}", page, outcome.message);
+                report.logs.push(format!("⚠️ 无法回到页面 {}，停止探索该页面", page));
+                continue 'pages;
+            }
+        }
+    }
+
+    info!("🕷️ 探索完成: 新页面 {} 个，新跳转 {} 条，操作 {} 次",
+          report.pages_discovered.len(), report.edges_added, report.actions_taken);
+    Ok(report)
+}
+
+/// 获取应用的导航图
+#[tauri::command]
+pub async fn get_navigation_graph(package: String) -> Result<NavigationGraph, String> {
+    Ok(get_navigation_graphs().get(&package).await)
+}
+
+/// 清空应用的导航图
+#[tauri::command]
+pub async fn clear_navigation_graph(package: String) -> Result<(), String> {
+    get_navigation_graphs().clear(&package).await
+}
+
+/// 从当前页面开始探索应用并构建导航图
+#[tauri::command]
+pub async fn explore_app_navigation(
+    device_id: String,
+    package: String,
+    options: Option<ExplorationOptions>,
+) -> Result<ExplorationReport, String> {
+    explore_app(&device_id, &package, options.unwrap_or_default()).await
+}
+
+/// 按导航图从当前页面导航到指定页面
+#[tauri::command]
+pub async fn navigate_to_page(
+    device_id: String,
+    package: String,
+    target_page: String,
+    settle_ms: Option<u64>,
+) -> Result<NavigationOutcome, String> {
+    let settle = Duration::from_millis(settle_ms.unwrap_or(1500));
+    navigate_to(&device_id, &package, &target_page, settle).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation="0"><node class="android.widget.FrameLayout" package="com.xingin.xhs" bounds="[0,0][1080,1920]"><node resource-id="com.xingin.xhs:id/search" class="android.widget.EditText" package="com.xingin.xhs" clickable="true" enabled="true" bounds="[0,0][1080,100]" /><node resource-id="com.xingin.xhs:id/contacts" class="android.widget.LinearLayout" package="com.xingin.xhs" clickable="true" enabled="true" bounds="[0,200][1080,300]"><node text="通讯录朋友" class="android.widget.TextView" package="com.xingin.xhs" bounds="[40,220][400,280]" /></node><node text="退出登录" resource-id="com.xingin.xhs:id/logout" class="android.widget.Button" package="com.xingin.xhs" clickable="true" enabled="true" bounds="[0,400][1080,500]" /><node text="设置" class="android.widget.Button" package="com.xingin.xhs" clickable="true" enabled="false" bounds="[0,600][1080,700]" /></node></hierarchy>"#;
+
+    fn tap(text: &str) -> NavAction {
+        NavAction::Tap { xpath: Some(format!("//node[@text='{}']", text)), text: Some(text.to_string()), x: 0, y: 0 }
+    }
+
+    #[test]
+    fn test_shortest_path_prefers_reliable_edges() {
+        let mut graph = NavigationGraph::new("com.xingin.xhs");
+        graph.record("首页", "我", tap("我"));
+        graph.record("我", "通讯录", tap("通讯录朋友"));
+        graph.record("首页", "发现", tap("发现"));
+        graph.record("发现", "通讯录", tap("通讯录"));
+        graph.record("通讯录", "首页", NavAction::Back);
+        // 重复记录只累加成功次数
+        assert!(!graph.record("首页", "我", tap("我")));
+        assert_eq!(graph.edges.len(), 5);
+
+        let path = graph.shortest_path("通讯录", "发现").unwrap();
+        assert_eq!(path.iter().map(|e| e.to.as_str()).collect::<Vec<_>>(), ["首页", "发现"]);
+        assert_eq!(graph.shortest_path("首页", "首页").unwrap().len(), 0);
+        assert!(graph.shortest_path("首页", "设置").is_none());
+
+        // 经“我”的路线反复失败后改走“发现”
+        let broken = graph.edges[1].clone();
+        for _ in 0..3 {
+            graph.mark(&broken, false);
+        }
+        let path = graph.shortest_path("首页", "通讯录").unwrap();
+        assert_eq!(path[0].to, "发现");
+
+        // 净失败过多的边不再参与寻路
+        let detour = graph.edges[3].clone();
+        for _ in 0..5 {
+            graph.mark(&detour, false);
+        }
+        graph.mark(&broken, false);
+        graph.mark(&broken, false);
+        assert!(graph.shortest_path("首页", "通讯录").is_none());
+    }
+
+    #[test]
+    fn test_exploration_candidates_respect_deny_list() {
+        let tree = UiTree::parse(PAGE).unwrap();
+        let deny: Vec<String> = DEFAULT_DENY_LIST.iter().map(|s| s.to_string()).collect();
+        let candidates = exploration_candidates(&tree, &deny, 10);
+
+        // 输入框、禁用元素和“退出登录”都被排除
+        assert_eq!(candidates.len(), 1);
+        match &candidates[0] {
+            NavAction::Tap { text, x, y, .. } => {
+                assert_eq!(text.as_deref(), Some("通讯录朋友"));
+                assert_eq!((*x, *y), (540, 250));
+            }
+            other => panic!("unexpected action: {:?}", other),
+        }
+
+        // 坐标落在子节点上时定位到可点击的父容器
+        assert_eq!(tap_action_at(&tree, 100, 250), candidates[0]);
+    }
+}
@@ -0,0 +1,284 @@
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult, Row};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::services::page_analyzer_service::PageSnapshot;
+use crate::types::page_analysis::PageAnalysisResult;
+
+/// 一条页面分析历史（不含原始 XML，需要时用 `get_snapshot` 读取）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageAnalysisRecord {
+    pub id: i64,
+    pub device_id: String,
+    pub app_package: String,
+    pub activity_name: String,
+    /// 分析时保存的截图路径
+    pub screenshot_path: Option<String>,
+    /// 分析时间（Unix 秒）
+    pub analyzed_at: u64,
+    pub result: PageAnalysisResult,
+}
+
+/// 历史查询条件，未设置的条件不过滤
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageAnalysisQuery {
+    pub device_id: Option<String>,
+    pub app_package: Option<String>,
+    /// 起始时间（Unix 秒，含）
+    pub since: Option<u64>,
+    /// 截止时间（Unix 秒，含）
+    pub until: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+/// 页面分析历史存储（SQLite）
+pub struct PageAnalysisHistory {
+    conn: Connection,
+}
+
+/// 历史记录保留天数
+pub const HISTORY_MAX_AGE_DAYS: u64 = 30;
+/// 历史记录最多保留条数
+pub const HISTORY_MAX_RECORDS: usize = 500;
+
+const RECORD_COLUMNS: &str = "id, device_id, app_package, activity_name, screenshot_path, analyzed_at, result_json";
+
+impl PageAnalysisHistory {
+    pub fn new() -> SqliteResult<Self> {
+        let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let app_data_dir = current_dir.join("data");
+
+        if let Err(e) = std::fs::create_dir_all(&app_data_dir) {
+            eprintln!("警告：无法创建数据目录 {:?}: {}", app_data_dir, e);
+        }
+
+        Self::open(&app_data_dir.join("page_analysis_history.db"))
+    }
+
+    pub fn open(db_path: &Path) -> SqliteResult<Self> {
+        Self::with_connection(Connection::open(db_path)?)
+    }
+
+    fn with_connection(conn: Connection) -> SqliteResult<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS page_analyses (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                device_id TEXT NOT NULL,
+                app_package TEXT NOT NULL,
+                activity_name TEXT NOT NULL,
+                screenshot_path TEXT,
+                analyzed_at INTEGER NOT NULL,
+                xml_content TEXT NOT NULL,
+                result_json TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_page_analyses_device ON page_analyses (device_id, analyzed_at);
+            CREATE INDEX IF NOT EXISTS idx_page_analyses_app ON page_analyses (app_package, analyzed_at);",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// 保存一次分析结果及其页面快照，返回记录ID
+    pub fn insert(
+        &self,
+        device_id: &str,
+        snapshot: &PageSnapshot,
+        screenshot_path: Option<&str>,
+        analyzed_at: u64,
+        result: &PageAnalysisResult,
+    ) -> SqliteResult<i64> {
+        let result_json = serde_json::to_string(result)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.conn.execute(
+            "INSERT INTO page_analyses (device_id, app_package, activity_name, screenshot_path, analyzed_at, xml_content, result_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                device_id,
+                snapshot.app_package,
+                snapshot.activity_name,
+                screenshot_path,
+                analyzed_at as i64,
+                snapshot.xml_content,
+                result_json,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// 按设备、应用和时间范围查询，最新的在前
+    pub fn query(&self, query: &PageAnalysisQuery) -> SqliteResult<Vec<PageAnalysisRecord>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM page_analyses
+             WHERE (?1 IS NULL OR device_id = ?1)
+               AND (?2 IS NULL OR app_package = ?2)
+               AND (?3 IS NULL OR analyzed_at >= ?3)
+               AND (?4 IS NULL OR analyzed_at <= ?4)
+             ORDER BY analyzed_at DESC, id DESC
+             LIMIT ?5",
+            RECORD_COLUMNS
+        ))?;
+        let limit = query.limit.map(|l| l as i64).unwrap_or(-1);
+        let records = stmt.query_map(
+            params![
+                query.device_id,
+                query.app_package,
+                query.since.map(|t| t as i64),
+                query.until.map(|t| t as i64),
+                limit,
+            ],
+            record_from_row,
+        )?;
+        records.collect()
+    }
+
+    /// 读取记录保存的页面快照（用于重新分析或与其他记录比较）
+    pub fn get_snapshot(&self, id: i64) -> SqliteResult<Option<PageSnapshot>> {
+        self.conn
+            .query_row(
+                "SELECT xml_content, app_package, activity_name FROM page_analyses WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(PageSnapshot {
+                        xml_content: row.get(0)?,
+                        app_package: row.get(1)?,
+                        activity_name: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    /// 删除记录，返回被删除记录的截图路径以便清理文件
+    pub fn delete(&self, id: i64) -> SqliteResult<Option<String>> {
+        let screenshot: Option<Option<String>> = self
+            .conn
+            .query_row("SELECT screenshot_path FROM page_analyses WHERE id = ?1", [id], |row| row.get(0))
+            .optional()?;
+        self.conn.execute("DELETE FROM page_analyses WHERE id = ?1", [id])?;
+        Ok(screenshot.flatten())
+    }
+
+    /// 删除超过保留天数或超出最大条数（从最旧的开始）的记录，返回需要清理的截图路径
+    pub fn prune(&self, now_secs: u64, max_age_days: u64, max_records: usize) -> SqliteResult<Vec<String>> {
+        let cutoff = now_secs.saturating_sub(max_age_days * 24 * 3600) as i64;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, screenshot_path FROM page_analyses
+             WHERE analyzed_at < ?1 OR id NOT IN (
+                 SELECT id FROM page_analyses ORDER BY analyzed_at DESC, id DESC LIMIT ?2
+             )",
+        )?;
+        let expired = stmt
+            .query_map(params![cutoff, max_records as i64], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?))
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        drop(stmt);
+
+        let mut screenshots = Vec::new();
+        for (id, screenshot_path) in expired {
+            self.conn.execute("DELETE FROM page_analyses WHERE id = ?1", [id])?;
+            screenshots.extend(screenshot_path);
+        }
+        Ok(screenshots)
+    }
+}
+
+fn record_from_row(row: &Row) -> SqliteResult<PageAnalysisRecord> {
+    let result_json: String = row.get(6)?;
+    let result = serde_json::from_str(&result_json)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(6, rusqlite::types::Type::Text, Box::new(e)))?;
+    Ok(PageAnalysisRecord {
+        id: row.get(0)?,
+        device_id: row.get(1)?,
+        app_package: row.get(2)?,
+        activity_name: row.get(3)?,
+        screenshot_path: row.get(4)?,
+        analyzed_at: row.get::<_, i64>(5)? as u64,
+        result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::page_analysis::{ElementStatistics, PageInfo, PageType};
+    use std::collections::HashMap;
+
+    fn sample(app_package: &str, timestamp: u64) -> (PageSnapshot, PageAnalysisResult) {
+        let snapshot = PageSnapshot {
+            xml_content: format!("<hierarchy><node package=\"{}\" /></hierarchy>", app_package),
+            app_package: app_package.to_string(),
+            activity_name: ".MainActivity".to_string(),
+        };
+        let result = PageAnalysisResult {
+            page_info: PageInfo {
+                page_name: format!("{}页面", app_package),
+                app_package: app_package.to_string(),
+                activity_name: ".MainActivity".to_string(),
+                page_type: PageType::Home,
+                page_title: None,
+                analysis_timestamp: timestamp,
+            },
+            actionable_elements: vec![],
+            element_statistics: ElementStatistics {
+                total_elements: 0,
+                unique_elements: 0,
+                type_counts: HashMap::new(),
+                group_counts: HashMap::new(),
+            },
+            success: true,
+            error_message: None,
+        };
+        (snapshot, result)
+    }
+
+    #[test]
+    fn test_query_by_device_app_and_time() {
+        let history = PageAnalysisHistory::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        for (device, app, at) in [("dev1", "com.xingin.xhs", 100), ("dev1", "com.tencent.mm", 200), ("dev2", "com.xingin.xhs", 300), ("dev1", "com.xingin.xhs", 400)] {
+            let (snapshot, result) = sample(app, at);
+            history.insert(device, &snapshot, None, at, &result).unwrap();
+        }
+
+        let all_dev1 = history.query(&PageAnalysisQuery { device_id: Some("dev1".into()), ..Default::default() }).unwrap();
+        assert_eq!(all_dev1.iter().map(|r| r.analyzed_at).collect::<Vec<_>>(), vec![400, 200, 100]);
+
+        let xhs_recent = history
+            .query(&PageAnalysisQuery { app_package: Some("com.xingin.xhs".into()), since: Some(150), limit: Some(1), ..Default::default() })
+            .unwrap();
+        assert_eq!(xhs_recent.len(), 1);
+        assert_eq!((xhs_recent[0].device_id.as_str(), xhs_recent[0].analyzed_at), ("dev1", 400));
+        assert_eq!(xhs_recent[0].result.page_info.page_type, PageType::Home);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_and_delete() {
+        let history = PageAnalysisHistory::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let (snapshot, result) = sample("com.xingin.xhs", 100);
+        let id = history.insert("dev1", &snapshot, Some("data/page_history/1.png"), 100, &result).unwrap();
+
+        let stored = history.get_snapshot(id).unwrap().unwrap();
+        assert_eq!(stored.xml_content, snapshot.xml_content);
+        assert_eq!(stored.activity_name, ".MainActivity");
+
+        assert_eq!(history.delete(id).unwrap().as_deref(), Some("data/page_history/1.png"));
+        assert!(history.query(&PageAnalysisQuery::default()).unwrap().is_empty());
+        assert!(history.get_snapshot(id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_prune_by_age_and_count() {
+        let history = PageAnalysisHistory::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let day = 24 * 3600;
+        for (at, path) in [(0, "old.png"), (10 * day, "a.png"), (11 * day, "b.png"), (12 * day, "c.png")] {
+            let (snapshot, result) = sample("com.xingin.xhs", at);
+            history.insert("dev1", &snapshot, Some(path), at, &result).unwrap();
+        }
+
+        let mut removed = history.prune(12 * day, 5, 2).unwrap();
+        removed.sort();
+        assert_eq!(removed, vec!["a.png".to_string(), "old.png".to_string()]);
+        let kept = history.query(&PageAnalysisQuery::default()).unwrap();
+        assert_eq!(kept.iter().map(|r| r.analyzed_at).collect::<Vec<_>>(), vec![12 * day, 11 * day]);
+    }
+}
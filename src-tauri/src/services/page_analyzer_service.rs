@@ -7,6 +7,14 @@ use anyhow::{Result, anyhow};
 use regex::Regex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// 分析时的页面快照：原始 XML 和所在应用/Activity
+#[derive(Debug, Clone)]
+pub struct PageSnapshot {
+    pub xml_content: String,
+    pub app_package: String,
+    pub activity_name: String,
+}
+
 /// 页面分析服务
 /// 独立实现 XML 解析功能，专门用于页面元素分析
 pub struct PageAnalyzerService {
@@ -28,12 +36,12 @@ impl PageAnalyzerService {
         }
     }
 
-    /// 分析当前页面，返回完整的分析结果
-    pub async fn analyze_current_page(
-        &self, 
-        device_id: &str, 
+    /// 分析当前页面，返回完整的分析结果和分析所用的页面快照（获取 UI 失败时快照为 None）
+    pub async fn capture_and_analyze(
+        &self,
+        device_id: &str,
         config: Option<PageAnalysisConfig>
-    ) -> Result<PageAnalysisResult> {
+    ) -> Result<(PageAnalysisResult, Option<PageSnapshot>)> {
         let config = config.unwrap_or_default();
         
         println!("🔍 开始分析页面，设备ID: {}", device_id);
@@ -42,7 +50,7 @@ impl PageAnalyzerService {
         let xml_content = match self.get_ui_hierarchy_xml(device_id).await {
             Ok(xml) => xml,
             Err(e) => {
-                return Ok(PageAnalysisResult {
+                let result = PageAnalysisResult {
                     page_info: self.create_default_page_info(),
                     actionable_elements: vec![],
                     element_statistics: ElementStatistics {
@@ -53,19 +61,35 @@ impl PageAnalyzerService {
                     },
                     success: false,
                     error_message: Some(format!("获取UI层次结构失败: {}", e)),
-                });
+                };
+                return Ok((result, None));
             }
         };
 
+        let (app_package, activity_name) = self.get_current_app_info(device_id).await
+            .unwrap_or_else(|_| ("unknown.app".to_string(), "UnknownActivity".to_string()));
+        let snapshot = PageSnapshot { xml_content, app_package, activity_name };
+        let result = self.analyze_snapshot(&snapshot, &config).await?;
+        Ok((result, Some(snapshot)))
+    }
+
+    /// 用当前分析器分析页面快照（也用于重新分析历史记录）
+    pub async fn analyze_snapshot(
+        &self,
+        snapshot: &PageSnapshot,
+        config: &PageAnalysisConfig
+    ) -> Result<PageAnalysisResult> {
+        let xml_content = &snapshot.xml_content;
+
         // 2. 分析页面基本信息
         let page_info = if config.analyze_page_type {
-            self.analyze_page_info(&xml_content, device_id).await?
+            self.analyze_page_info(xml_content, &snapshot.app_package, &snapshot.activity_name)?
         } else {
             self.create_default_page_info()
         };
 
         // 3. 提取所有可操作元素
-        let all_elements = self.extract_actionable_elements(&xml_content, &config).await?;
+        let all_elements = self.extract_actionable_elements(xml_content, config).await?;
         
         // 4. 元素去重处理
         let deduplicated_elements = if config.enable_deduplication {
@@ -95,16 +119,14 @@ impl PageAnalyzerService {
     }
 
     /// 分析页面信息
-    async fn analyze_page_info(&self, xml_content: &str, device_id: &str) -> Result<PageInfo> {
+    fn analyze_page_info(&self, xml_content: &str, app_package: &str, activity_name: &str) -> Result<PageInfo> {
         let start_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
         let page_type = self.identify_page_type(xml_content);
-        let (app_package, activity_name) = self.get_current_app_info(device_id).await
-            .unwrap_or_else(|_| ("unknown.app".to_string(), "UnknownActivity".to_string()));
 
         Ok(PageInfo {
             page_name: format!("{}页面", app_package),
-            app_package,
-            activity_name,
+            app_package: app_package.to_string(),
+            activity_name: activity_name.to_string(),
             page_type,
             page_title: Some(self.extract_page_title(xml_content)),
             analysis_timestamp: start_time,
@@ -0,0 +1,393 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::utils::adb_utils::run_shell;
+use crate::services::ui_dump_cache::get_ui_dump_cache;
+use crate::services::ui_tree::{UiNode, UiTree};
+use crate::xml_judgment_service::XmlJudgmentService;
+use crate::utils::hash::fnv1a_64;
+
+const FINGERPRINT_REGISTRY_FILE: &str = "data/page_fingerprints.json";
+
+/// 默认判定为同一页面的相似度阈值
+pub const DEFAULT_MATCH_THRESHOLD: f64 = 0.75;
+
+/// 地标文本的最大长度（过长的通常是动态内容）
+const MAX_LANDMARK_LEN: usize = 16;
+
+/// 页面结构指纹：由 Activity、resource-id 集合和地标文本组成
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageFingerprint {
+    pub package: String,
+    pub activity: Option<String>,
+    pub resource_ids: BTreeSet<String>,
+    /// 不在可滚动容器内的短文本（标题、Tab、按钮等稳定文字）
+    pub landmark_texts: BTreeSet<String>,
+}
+
+impl PageFingerprint {
+    /// 从 UI 树计算指纹
+    pub fn from_tree(tree: &UiTree, activity: Option<String>) -> Self {
+        let mut fingerprint = PageFingerprint {
+            activity,
+            ..Default::default()
+        };
+
+        for node in tree.elements() {
+            if fingerprint.package.is_empty() && !node.package().is_empty() {
+                fingerprint.package = node.package().to_string();
+            }
+            if !node.resource_id().is_empty() {
+                fingerprint.resource_ids.insert(node.resource_id().to_string());
+            }
+            if let Some(text) = landmark_text(&node) {
+                fingerprint.landmark_texts.insert(text);
+            }
+        }
+        fingerprint
+    }
+
+    /// 稳定的指纹标识（8 位十六进制），同一指纹在不同运行、不同版本间保持一致
+    pub fn stable_id(&self) -> String {
+        let mut content = format!("{}\n{}\n", self.package, self.activity.as_deref().unwrap_or(""));
+        for id in &self.resource_ids {
+            content.push_str(id);
+            content.push('\n');
+        }
+        content.push('\n');
+        for text in &self.landmark_texts {
+            content.push_str(text);
+            content.push('\n');
+        }
+        format!("{:08x}", fnv1a_64(content.as_bytes()) as u32)
+    }
+
+    /// 与另一个指纹的相似度（0~1）
+    ///
+    /// 包名不同直接为 0；Activity、resource-id 集合、地标文本按权重加权，
+    /// 任一方缺失的维度不参与计算。
+    pub fn similarity(&self, other: &PageFingerprint) -> f64 {
+        if !self.package.is_empty() && !other.package.is_empty() && self.package != other.package {
+            return 0.0;
+        }
+
+        let mut weighted = 0.0;
+        let mut total_weight = 0.0;
+
+        if let (Some(a), Some(b)) = (&self.activity, &other.activity) {
+            weighted += 0.3 * if a == b { 1.0 } else { 0.0 };
+            total_weight += 0.3;
+        }
+        if !self.resource_ids.is_empty() || !other.resource_ids.is_empty() {
+            weighted += 0.45 * jaccard(&self.resource_ids, &other.resource_ids);
+            total_weight += 0.45;
+        }
+        if !self.landmark_texts.is_empty() || !other.landmark_texts.is_empty() {
+            weighted += 0.25 * jaccard(&self.landmark_texts, &other.landmark_texts);
+            total_weight += 0.25;
+        }
+
+        if total_weight == 0.0 {
+            0.0
+        } else {
+            weighted / total_weight
+        }
+    }
+}
+
+/// 已命名的页面签名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageSignature {
+    pub name: String,
+    pub fingerprint: PageFingerprint,
+    /// 判定阈值，默认 DEFAULT_MATCH_THRESHOLD
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+fn default_threshold() -> f64 {
+    DEFAULT_MATCH_THRESHOLD
+}
+
+/// 页面匹配结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageMatch {
+    pub name: String,
+    pub score: f64,
+    pub matched: bool,
+}
+
+/// 页面指纹注册表（保存在 data/page_fingerprints.json）
+pub struct PageFingerprintRegistry {
+    signatures: Mutex<HashMap<String, PageSignature>>,
+}
+
+impl PageFingerprintRegistry {
+    pub fn new() -> Self {
+        Self {
+            signatures: Mutex::new(load_signatures()),
+        }
+    }
+
+    pub async fn list(&self) -> Vec<PageSignature> {
+        let mut all: Vec<PageSignature> = self.signatures.lock().await.values().cloned().collect();
+        all.sort_by(|a, b| a.name.cmp(&b.name));
+        all
+    }
+
+    pub async fn get(&self, name: &str) -> Option<PageSignature> {
+        self.signatures.lock().await.get(name).cloned()
+    }
+
+    /// 保存（或覆盖）命名页面
+    pub async fn save(&self, name: &str, fingerprint: PageFingerprint) -> Result<PageSignature, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("页面名称不能为空".to_string());
+        }
+
+        let now = chrono::Local::now().to_rfc3339();
+        let mut all = self.signatures.lock().await;
+        let signature = PageSignature {
+            name: name.to_string(),
+            fingerprint,
+            threshold: all.get(name).map_or(DEFAULT_MATCH_THRESHOLD, |s| s.threshold),
+            created_at: all.get(name).map_or_else(|| now.clone(), |s| s.created_at.clone()),
+            updated_at: now,
+        };
+        all.insert(signature.name.clone(), signature.clone());
+        persist_signatures(&all)?;
+
+        info!("📌 已保存页面指纹: {} ({} 个resource-id, {} 个地标文本)",
+              signature.name, signature.fingerprint.resource_ids.len(), signature.fingerprint.landmark_texts.len());
+        Ok(signature)
+    }
+
+    pub async fn remove(&self, name: &str) -> Result<bool, String> {
+        let mut all = self.signatures.lock().await;
+        let removed = all.remove(name).is_some();
+        if removed {
+            persist_signatures(&all)?;
+        }
+        Ok(removed)
+    }
+
+    /// 按相似度从高到低返回所有已注册页面
+    pub async fn rank(&self, fingerprint: &PageFingerprint) -> Vec<PageMatch> {
+        let all = self.signatures.lock().await;
+        let mut matches: Vec<PageMatch> = all
+            .values()
+            .map(|s| {
+                let score = s.fingerprint.similarity(fingerprint);
+                PageMatch { name: s.name.clone(), score, matched: score >= s.threshold }
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        matches
+    }
+
+    /// 识别页面：返回得分最高且达到阈值的页面名
+    pub async fn identify(&self, fingerprint: &PageFingerprint) -> Option<PageMatch> {
+        self.rank(fingerprint).await.into_iter().find(|m| m.matched)
+    }
+
+    /// 计算指纹与指定页面的匹配结果；页面未注册时返回 None
+    pub async fn match_named(&self, name: &str, fingerprint: &PageFingerprint) -> Option<PageMatch> {
+        let signature = self.get(name).await?;
+        let score = signature.fingerprint.similarity(fingerprint);
+        Some(PageMatch { name: signature.name, score, matched: score >= signature.threshold })
+    }
+}
+
+fn load_signatures() -> HashMap<String, PageSignature> {
+    fs::read_to_string(FINGERPRINT_REGISTRY_FILE)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn persist_signatures(all: &HashMap<String, PageSignature>) -> Result<(), String> {
+    if let Some(parent) = std::path::Path::new(FINGERPRINT_REGISTRY_FILE).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(all).map_err(|e| e.to_string())?;
+    fs::write(FINGERPRINT_REGISTRY_FILE, content).map_err(|e| format!("保存页面指纹失败: {}", e))
+}
+
+/// 节点是否提供地标文本：短文本且不在可滚动容器内
+fn landmark_text(node: &UiNode) -> Option<String> {
+    let text = node.text().trim();
+    let text = if text.is_empty() { node.content_desc().trim() } else { text };
+    if text.is_empty() || text.chars().count() > MAX_LANDMARK_LEN {
+        return None;
+    }
+    // 纯数字（计数、时间等）不稳定
+    if text.chars().all(|c| c.is_ascii_digit() || c.is_ascii_punctuation() || c.is_whitespace()) {
+        return None;
+    }
+    if node.ancestors().any(|a| a.is_scrollable()) {
+        return None;
+    }
+    Some(text.to_string())
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// 解析 `dumpsys window` 中的焦点窗口，返回完整的 `包名/Activity`
+pub(crate) fn parse_focused_activity(output: &str) -> Option<String> {
+    output
+        .lines()
+        .filter(|l| l.contains("mCurrentFocus") || l.contains("mFocusedApp"))
+        .flat_map(|l| l.split_whitespace())
+        .map(|token| token.trim_end_matches('}'))
+        .find(|token| token.contains('/') && !token.starts_with("Window"))
+        .map(|component| match component.split_once('/') {
+            Some((package, activity)) if activity.starts_with('.') => format!("{}/{}{}", package, package, activity),
+            _ => component.to_string(),
+        })
+}
+
+/// 读取设备当前焦点 Activity
+pub async fn current_activity(device_id: &str) -> Option<String> {
+    let output = run_shell(device_id, "dumpsys window | grep -E 'mCurrentFocus|mFocusedApp'").await.ok()?;
+    parse_focused_activity(&output)
+}
+
+/// 抓取设备当前页面并计算指纹
+pub async fn capture_fingerprint(device_id: &str) -> Result<PageFingerprint, String> {
+    let xml = XmlJudgmentService::get_ui_xml(device_id).await?;
+    let tree = UiTree::parse(&xml).map_err(|e| e.to_string())?;
+    let activity = current_activity(device_id).await;
+    Ok(PageFingerprint::from_tree(&tree, activity))
+}
+
+/// 轮询等待设备进入指定页面，返回最后一次匹配结果
+pub async fn wait_for_page(
+    device_id: &str,
+    name: &str,
+    timeout: Duration,
+    interval: Duration,
+) -> Result<PageMatch, String> {
+    let registry = get_page_registry();
+    if registry.get(name).await.is_none() {
+        return Err(format!("未注册的页面: {}", name));
+    }
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let fingerprint = capture_fingerprint(device_id).await?;
+        let result = registry
+            .match_named(name, &fingerprint)
+            .await
+            .ok_or_else(|| format!("未注册的页面: {}", name))?;
+        if result.matched || tokio::time::Instant::now() + interval > deadline {
+            return Ok(result);
+        }
+        tokio::time::sleep(interval).await;
+        // 等待期间界面可能自行变化，下一轮重新 dump
+        get_ui_dump_cache().invalidate(Some(device_id));
+    }
+}
+
+/// 全局页面指纹注册表实例
+static GLOBAL_PAGE_REGISTRY: std::sync::OnceLock<PageFingerprintRegistry> = std::sync::OnceLock::new();
+
+/// 获取全局页面指纹注册表
+pub fn get_page_registry() -> &'static PageFingerprintRegistry {
+    GLOBAL_PAGE_REGISTRY.get_or_init(PageFingerprintRegistry::new)
+}
+
+/// 将设备当前页面保存为命名页面
+#[tauri::command]
+pub async fn save_current_page_fingerprint(device_id: String, name: String) -> Result<PageSignature, String> {
+    let fingerprint = capture_fingerprint(&device_id).await?;
+    get_page_registry().save(&name, fingerprint).await
+}
+
+/// 列出已保存的页面指纹
+#[tauri::command]
+pub async fn list_page_fingerprints() -> Result<Vec<PageSignature>, String> {
+    Ok(get_page_registry().list().await)
+}
+
+/// 删除页面指纹
+#[tauri::command]
+pub async fn delete_page_fingerprint(name: String) -> Result<bool, String> {
+    get_page_registry().remove(&name).await
+}
+
+/// 识别设备当前页面，返回所有已注册页面的匹配得分（从高到低）
+#[tauri::command]
+pub async fn recognize_page_by_fingerprint(device_id: String) -> Result<Vec<PageMatch>, String> {
+    let fingerprint = capture_fingerprint(&device_id).await?;
+    Ok(get_page_registry().rank(&fingerprint).await)
+}
+
+/// 按指纹识别一份 UI XML 对应的已注册页面（XML 中没有 Activity 信息，仅按结构匹配）。
+/// 与 identify_page_type 返回的分析器页面类型相互独立
+#[tauri::command]
+pub async fn identify_registered_page(xml_content: String) -> Result<Option<PageMatch>, String> {
+    let tree = UiTree::parse(&xml_content).map_err(|e| format!("解析UI XML失败: {}", e))?;
+    let fingerprint = PageFingerprint::from_tree(&tree, None);
+    Ok(get_page_registry().identify(&fingerprint).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation="0"><node class="android.widget.FrameLayout" package="com.xingin.xhs" bounds="[0,0][1080,1920]"><node text="通讯录好友" resource-id="com.xingin.xhs:id/title" class="android.widget.TextView" package="com.xingin.xhs" bounds="[0,100][1080,200]" /><node resource-id="com.xingin.xhs:id/list" class="androidx.recyclerview.widget.RecyclerView" package="com.xingin.xhs" scrollable="true" bounds="[0,200][1080,1800]"><node text="张三" resource-id="com.xingin.xhs:id/name" class="android.widget.TextView" package="com.xingin.xhs" bounds="[100,220][500,300]" /></node></node></hierarchy>"#;
+
+    #[test]
+    fn test_fingerprint_similarity() {
+        let activity = Some("com.xingin.xhs/com.xingin.xhs.ContactsActivity".to_string());
+        let saved = PageFingerprint::from_tree(&UiTree::parse(PAGE).unwrap(), activity.clone());
+        assert_eq!(saved.package, "com.xingin.xhs");
+        assert!(saved.landmark_texts.contains("通讯录好友"));
+        // 列表内的动态内容不作为地标
+        assert!(!saved.landmark_texts.contains("张三"));
+
+        // 列表内容变化不影响识别
+        let scrolled = PAGE.replace("张三", "李四");
+        let current = PageFingerprint::from_tree(&UiTree::parse(&scrolled).unwrap(), activity);
+        assert!((saved.similarity(&current) - 1.0).abs() < f64::EPSILON);
+
+        // 不同 Activity、不同标题
+        let other = PAGE.replace("通讯录好友", "设置").replace("id/list", "id/settings");
+        let other = PageFingerprint::from_tree(
+            &UiTree::parse(&other).unwrap(),
+            Some("com.xingin.xhs/.SettingsActivity".to_string()),
+        );
+        assert!(saved.similarity(&other) < DEFAULT_MATCH_THRESHOLD);
+
+        let mut wechat = saved.clone();
+        wechat.package = "com.tencent.mm".to_string();
+        assert_eq!(saved.similarity(&wechat), 0.0);
+    }
+
+    #[test]
+    fn test_parse_focused_activity() {
+        let output = "  mCurrentFocus=Window{5c1a3b u0 com.xingin.xhs/com.xingin.xhs.index.v2.IndexActivityV2}\n  mFocusedApp=ActivityRecord{8e2 u0 com.xingin.xhs/.index.v2.IndexActivityV2 t123}";
+        assert_eq!(
+            parse_focused_activity(output).as_deref(),
+            Some("com.xingin.xhs/com.xingin.xhs.index.v2.IndexActivityV2")
+        );
+        assert_eq!(
+            parse_focused_activity("mFocusedApp=ActivityRecord{1 u0 com.android.settings/.Settings t5}").as_deref(),
+            Some("com.android.settings/com.android.settings.Settings")
+        );
+        assert_eq!(parse_focused_activity("mCurrentFocus=null"), None);
+    }
+}
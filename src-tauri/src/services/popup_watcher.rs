@@ -0,0 +1,468 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::services::adb_session_manager::get_device_session;
+use crate::services::ui_idle::settle;
+use crate::services::ui_tree::{UiNode, UiTree};
+
+/// 弹窗特征：各条件之间是“与”关系，列表内部是“或”关系，未填写的条件不参与判断
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PopupSignature {
+    /// 任意一个文本出现（匹配 text 和 content-desc 的子串）
+    pub text_any: Vec<String>,
+    /// 任意一个 resource-id 出现（子串匹配）
+    pub resource_id_any: Vec<String>,
+    /// 界面中须有属于该包的节点（系统权限弹窗等）
+    pub package: Option<String>,
+}
+
+/// 关闭弹窗的动作
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DismissAction {
+    /// 点击第一个文本匹配的元素（按列表顺序优先）
+    TapText { texts: Vec<String> },
+    /// 点击 resource-id 匹配的元素
+    TapResourceId { resource_id: String },
+    /// 按返回键
+    Back,
+}
+
+/// 弹窗监视器：界面命中特征时执行关闭动作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PopupWatcher {
+    pub id: String,
+    pub name: String,
+    pub signature: PopupSignature,
+    pub action: DismissAction,
+}
+
+/// 单个监视器的触发统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PopupWatcherStats {
+    pub watcher_id: String,
+    pub fire_count: u64,
+    /// 最后一次触发时间（Unix 毫秒）
+    pub last_fired_at: Option<i64>,
+}
+
+/// 一次触发记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PopupFiring {
+    pub watcher_id: String,
+    pub watcher_name: String,
+    /// 触发时所在的步骤
+    pub step_id: Option<String>,
+    /// 执行的动作描述
+    pub action: String,
+    pub fired_at: i64,
+}
+
+/// 脚本级监视器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PopupWatcherSettings {
+    pub enabled: bool,
+    /// 禁用的内置监视器ID
+    pub disabled: Vec<String>,
+    /// 脚本自定义的监视器（ID与内置相同时覆盖内置）
+    pub custom: Vec<PopupWatcher>,
+    /// 一次检查最多连续关闭的弹窗数（弹窗可能叠加出现）
+    pub max_dismissals: usize,
+}
+
+impl Default for PopupWatcherSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            disabled: Vec::new(),
+            custom: Vec::new(),
+            max_dismissals: 3,
+        }
+    }
+}
+
+/// 命中结果：监视器及要执行的具体操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum PopupDismissal {
+    Tap(i32, i32),
+    Back,
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+/// 内置监视器：系统权限、更新提示、评分邀请、青少年模式、开屏广告
+pub fn builtin_watchers() -> Vec<PopupWatcher> {
+    vec![
+        PopupWatcher {
+            id: "system_permission".to_string(),
+            name: "系统权限弹窗".to_string(),
+            signature: PopupSignature {
+                resource_id_any: strings(&["permission_allow_button", "permission_allow_foreground_only_button"]),
+                ..Default::default()
+            },
+            action: DismissAction::TapText {
+                texts: strings(&["仅在使用中允许", "使用时允许", "始终允许", "允许", "Allow"]),
+            },
+        },
+        PopupWatcher {
+            id: "app_update".to_string(),
+            name: "版本更新提示".to_string(),
+            signature: PopupSignature {
+                text_any: strings(&["发现新版本", "版本更新", "立即更新", "立即升级", "Update now"]),
+                ..Default::default()
+            },
+            action: DismissAction::TapText {
+                texts: strings(&["以后再说", "暂不更新", "稍后再说", "下次再说", "取消", "Later", "Not now"]),
+            },
+        },
+        PopupWatcher {
+            id: "rate_us".to_string(),
+            name: "评分邀请".to_string(),
+            signature: PopupSignature {
+                text_any: strings(&["给我们评分", "去评分", "喜欢我们吗", "五星好评", "Rate us"]),
+                ..Default::default()
+            },
+            action: DismissAction::TapText {
+                texts: strings(&["以后再说", "残忍拒绝", "不了，谢谢", "下次再说", "Not now", "No thanks"]),
+            },
+        },
+        PopupWatcher {
+            id: "teen_mode".to_string(),
+            name: "青少年模式提示".to_string(),
+            signature: PopupSignature {
+                text_any: strings(&["青少年模式", "未成年人模式"]),
+                ..Default::default()
+            },
+            action: DismissAction::TapText {
+                texts: strings(&["我知道了", "知道了", "关闭"]),
+            },
+        },
+        PopupWatcher {
+            id: "splash_ad".to_string(),
+            name: "开屏广告".to_string(),
+            signature: PopupSignature {
+                text_any: strings(&["跳过广告"]),
+                ..Default::default()
+            },
+            action: DismissAction::TapText {
+                texts: strings(&["跳过广告", "跳过"]),
+            },
+        },
+    ]
+}
+
+fn node_has_text(node: &UiNode, pattern: &str) -> bool {
+    node.text().contains(pattern) || node.content_desc().contains(pattern)
+}
+
+/// 点击目标：节点本身不可点击时取最近的可点击祖先
+fn tap_point(node: UiNode) -> Option<(i32, i32)> {
+    std::iter::once(node)
+        .chain(node.ancestors())
+        .find(|n| n.is_clickable())
+        .unwrap_or(node)
+        .center()
+}
+
+impl PopupSignature {
+    fn matches(&self, tree: &UiTree) -> bool {
+        if self.text_any.is_empty() && self.resource_id_any.is_empty() && self.package.is_none() {
+            return false;
+        }
+        if !self.text_any.is_empty()
+            && tree.find_first(|n| self.text_any.iter().any(|t| node_has_text(n, t))).is_none()
+        {
+            return false;
+        }
+        if !self.resource_id_any.is_empty()
+            && tree.find_first(|n| self.resource_id_any.iter().any(|id| n.resource_id().contains(id.as_str()))).is_none()
+        {
+            return false;
+        }
+        if let Some(package) = &self.package {
+            if tree.find_first(|n| n.package() == package).is_none() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl PopupWatcher {
+    /// 界面命中特征时返回要执行的操作；命中但找不到可点击的关闭按钮时返回 None
+    pub fn check(&self, tree: &UiTree) -> Option<PopupDismissal> {
+        if !self.signature.matches(tree) {
+            return None;
+        }
+        match &self.action {
+            DismissAction::TapText { texts } => texts.iter().find_map(|text| {
+                // 精确匹配优先，避免“允许”点到说明文字里的“允许”
+                tree.find_first(|n| n.text().trim() == text.as_str() || n.content_desc().trim() == text.as_str())
+                    .or_else(|| tree.find_first(|n| n.is_clickable() && node_has_text(n, text)))
+                    .and_then(tap_point)
+                    .map(|(x, y)| PopupDismissal::Tap(x, y))
+            }),
+            DismissAction::TapResourceId { resource_id } => tree
+                .find_first(|n| n.resource_id().contains(resource_id.as_str()))
+                .and_then(tap_point)
+                .map(|(x, y)| PopupDismissal::Tap(x, y)),
+            DismissAction::Back => Some(PopupDismissal::Back),
+        }
+    }
+}
+
+impl PopupWatcherSettings {
+    /// 合并内置和脚本自定义的监视器
+    pub fn effective_watchers(&self) -> Vec<PopupWatcher> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let mut watchers: Vec<PopupWatcher> = builtin_watchers()
+            .into_iter()
+            .filter(|w| !self.disabled.contains(&w.id) && !self.custom.iter().any(|c| c.id == w.id))
+            .collect();
+        watchers.extend(self.custom.iter().filter(|w| !self.disabled.contains(&w.id)).cloned());
+        watchers
+    }
+}
+
+/// 在界面上查找第一个命中的监视器
+pub fn find_popup<'a>(watchers: &'a [PopupWatcher], tree: &UiTree) -> Option<(&'a PopupWatcher, PopupDismissal)> {
+    watchers.iter().find_map(|w| w.check(tree).map(|d| (w, d)))
+}
+
+/// 弹窗监视器注册表：执行检查并累计各监视器的触发次数
+pub struct PopupWatcherRegistry {
+    stats: Mutex<HashMap<String, PopupWatcherStats>>,
+    /// 正在执行的脚本为设备指定的监视器配置（应用检测器等不持有脚本配置的调用方从这里读取）
+    device_settings: Mutex<HashMap<String, PopupWatcherSettings>>,
+}
+
+/// 脚本执行期间设备的监视器配置，离开作用域时恢复默认配置
+pub struct ScopedPopupSettings<'a> {
+    registry: &'a PopupWatcherRegistry,
+    device_id: String,
+}
+
+impl Drop for ScopedPopupSettings<'_> {
+    fn drop(&mut self) {
+        self.registry.device_settings.lock().unwrap().remove(&self.device_id);
+    }
+}
+
+impl PopupWatcherRegistry {
+    pub fn new() -> Self {
+        Self {
+            stats: Mutex::new(HashMap::new()),
+            device_settings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 在返回值的生命周期内让该设备上的弹窗检查使用脚本配置
+    pub fn scoped_settings(&self, device_id: &str, settings: PopupWatcherSettings) -> ScopedPopupSettings<'_> {
+        self.device_settings.lock().unwrap().insert(device_id.to_string(), settings);
+        ScopedPopupSettings { registry: self, device_id: device_id.to_string() }
+    }
+
+    /// 设备当前生效的监视器配置：脚本执行中为脚本配置，否则为默认配置
+    pub fn settings_for(&self, device_id: &str) -> PopupWatcherSettings {
+        self.device_settings.lock().unwrap().get(device_id).cloned().unwrap_or_default()
+    }
+
+    fn record(&self, watcher_id: &str, fired_at: i64) {
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(watcher_id.to_string()).or_insert_with(|| PopupWatcherStats {
+            watcher_id: watcher_id.to_string(),
+            ..Default::default()
+        });
+        entry.fire_count += 1;
+        entry.last_fired_at = Some(fired_at);
+    }
+
+    pub fn stats(&self) -> Vec<PopupWatcherStats> {
+        let mut stats: Vec<PopupWatcherStats> = self.stats.lock().unwrap().values().cloned().collect();
+        stats.sort_by(|a, b| b.fire_count.cmp(&a.fire_count).then_with(|| a.watcher_id.cmp(&b.watcher_id)));
+        stats
+    }
+
+    pub fn reset_stats(&self) {
+        self.stats.lock().unwrap().clear();
+    }
+
+    /// 检查当前界面并关闭命中的弹窗，返回本次触发记录（没有弹窗时为空）
+    pub async fn check_and_dismiss(
+        &self,
+        device_id: &str,
+        settings: &PopupWatcherSettings,
+        step_id: Option<&str>,
+    ) -> Vec<PopupFiring> {
+        let watchers = settings.effective_watchers();
+        let mut firings = Vec::new();
+        if watchers.is_empty() {
+            return firings;
+        }
+
+        let session = match get_device_session(device_id).await {
+            Ok(session) => session,
+            Err(e) => {
+                warn!("⚠️ 弹窗检查获取会话失败: {}", e);
+                return firings;
+            }
+        };
+
+        while firings.len() < settings.max_dismissals {
+            let tree = match session.dump_ui().await.map_err(|e| e.to_string()).and_then(|xml| UiTree::parse(&xml).map_err(|e| e.to_string())) {
+                Ok(tree) => tree,
+                Err(e) => {
+                    warn!("⚠️ 弹窗检查获取界面失败: {}", e);
+                    break;
+                }
+            };
+            let Some((watcher, dismissal)) = find_popup(&watchers, &tree) else { break };
+
+            let (result, action) = match dismissal {
+                PopupDismissal::Tap(x, y) => (session.tap(x, y).await, format!("点击({}, {})", x, y)),
+                PopupDismissal::Back => (session.key_event(4).await, "返回键".to_string()),
+            };
+            if let Err(e) = result {
+                warn!("⚠️ 弹窗监视器 {} 执行关闭动作失败: {}", watcher.name, e);
+                break;
+            }
+
+            let fired_at = chrono::Utc::now().timestamp_millis();
+            info!("🧹 弹窗监视器触发: {} ({}) -> {}", watcher.name, watcher.id, action);
+            self.record(&watcher.id, fired_at);
+            firings.push(PopupFiring {
+                watcher_id: watcher.id.clone(),
+                watcher_name: watcher.name.clone(),
+                step_id: step_id.map(str::to_string),
+                action,
+                fired_at,
+            });
+            settle(device_id, Duration::from_secs(2)).await;
+        }
+        firings
+    }
+}
+
+impl Default for PopupWatcherRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 全局弹窗监视器注册表实例
+static GLOBAL_POPUP_WATCHERS: std::sync::OnceLock<PopupWatcherRegistry> = std::sync::OnceLock::new();
+
+/// 获取全局弹窗监视器注册表
+pub fn get_popup_watchers() -> &'static PopupWatcherRegistry {
+    GLOBAL_POPUP_WATCHERS.get_or_init(PopupWatcherRegistry::new)
+}
+
+/// 获取内置弹窗监视器列表
+#[tauri::command]
+pub fn list_popup_watchers() -> Vec<PopupWatcher> {
+    builtin_watchers()
+}
+
+/// 获取各弹窗监视器的触发统计
+#[tauri::command]
+pub fn get_popup_watcher_stats() -> Vec<PopupWatcherStats> {
+    get_popup_watchers().stats()
+}
+
+/// 清空弹窗监视器触发统计
+#[tauri::command]
+pub fn reset_popup_watcher_stats() {
+    get_popup_watchers().reset_stats();
+}
+
+/// 立即检查设备界面并关闭弹窗
+#[tauri::command]
+pub async fn dismiss_popups(device_id: String, settings: Option<PopupWatcherSettings>) -> Result<Vec<PopupFiring>, String> {
+    let settings = settings.unwrap_or_else(|| get_popup_watchers().settings_for(&device_id));
+    Ok(get_popup_watchers().check_and_dismiss(&device_id, &settings, None).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(nodes: &str) -> UiTree {
+        UiTree::parse(&format!(r#"<hierarchy><node class="android.widget.FrameLayout" package="com.example" bounds="[0,0][1080,2400]">{}</node></hierarchy>"#, nodes)).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_watchers_pick_dismiss_button() {
+        let watchers = PopupWatcherSettings::default().effective_watchers();
+
+        let permission = tree(r#"<node package="com.android.permissioncontroller" text="允许“示例”访问您的通讯录吗？" bounds="[100,1000][980,1100]" />
+            <node package="com.android.permissioncontroller" resource-id="com.android.permissioncontroller:id/permission_allow_button" text="允许" clickable="true" bounds="[100,1200][980,1300]" />
+            <node package="com.android.permissioncontroller" resource-id="com.android.permissioncontroller:id/permission_deny_button" text="拒绝" clickable="true" bounds="[100,1300][980,1400]" />"#);
+        let (watcher, dismissal) = find_popup(&watchers, &permission).unwrap();
+        assert_eq!(watcher.id, "system_permission");
+        assert_eq!(dismissal, PopupDismissal::Tap(540, 1250));
+
+        // 文字本身不可点击时点击其可点击的父容器
+        let update = tree(r#"<node text="发现新版本 8.0" bounds="[100,800][980,900]" />
+            <node class="android.widget.LinearLayout" clickable="true" bounds="[100,1500][500,1600]"><node text="以后再说" bounds="[200,1520][400,1580]" /></node>"#);
+        let (watcher, dismissal) = find_popup(&watchers, &update).unwrap();
+        assert_eq!(watcher.id, "app_update");
+        assert_eq!(dismissal, PopupDismissal::Tap(300, 1550));
+
+        // 普通页面里出现“允许”字样不会触发
+        let normal = tree(r#"<node text="允许陌生人评论" clickable="true" bounds="[0,300][1080,400]" />"#);
+        assert!(find_popup(&watchers, &normal).is_none());
+    }
+
+    #[test]
+    fn test_script_settings_disable_and_override_watchers() {
+        let settings: PopupWatcherSettings = serde_json::from_value(serde_json::json!({
+            "disabled": ["teen_mode"],
+            "custom": [{
+                "id": "splash_ad",
+                "name": "抖音开屏广告",
+                "signature": { "resource_id_any": ["splash_skip"] },
+                "action": { "type": "back" }
+            }]
+        }))
+        .unwrap();
+        assert!(settings.enabled);
+        assert_eq!(settings.max_dismissals, 3);
+
+        let watchers = settings.effective_watchers();
+        assert!(!watchers.iter().any(|w| w.id == "teen_mode"));
+        assert_eq!(watchers.iter().filter(|w| w.id == "splash_ad").count(), 1);
+
+        let teen = tree(r#"<node text="青少年模式" bounds="[0,0][100,100]" /><node text="我知道了" clickable="true" bounds="[0,200][100,300]" />"#);
+        assert!(find_popup(&watchers, &teen).is_none());
+        let ad = tree(r#"<node resource-id="com.ss.android.ugc.aweme:id/splash_skip" text="3s" bounds="[900,100][1000,160]" />"#);
+        let (watcher, dismissal) = find_popup(&watchers, &ad).unwrap();
+        assert_eq!((watcher.name.as_str(), dismissal), ("抖音开屏广告", PopupDismissal::Back));
+
+        let disabled = PopupWatcherSettings { enabled: false, ..Default::default() };
+        assert!(disabled.effective_watchers().is_empty());
+
+        let registry = PopupWatcherRegistry::new();
+        registry.record("app_update", 1);
+        registry.record("app_update", 2);
+        registry.record("rate_us", 3);
+        let stats = registry.stats();
+        assert_eq!((stats[0].watcher_id.as_str(), stats[0].fire_count, stats[0].last_fired_at), ("app_update", 2, Some(2)));
+
+        // 脚本执行期间该设备使用脚本配置，结束后恢复默认
+        {
+            let _scope = registry.scoped_settings("dev1", PopupWatcherSettings { enabled: false, ..Default::default() });
+            assert!(!registry.settings_for("dev1").enabled);
+            assert!(registry.settings_for("dev2").enabled);
+        }
+        assert!(registry.settings_for("dev1").enabled);
+    }
+}
@@ -0,0 +1,443 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+use tracing::{info, warn};
+
+use crate::utils::adb_utils::run_shell;
+use crate::services::log_bridge::LOG_COLLECTOR;
+use crate::utils::adb_utils::get_adb_path;
+
+const RECORDINGS_DIR: &str = "data/recordings";
+const RETENTION_POLICY_FILE: &str = "data/recording_retention.json";
+/// screenrecord 单段最长 180 秒，到时自动切分新段
+const SEGMENT_TIME_LIMIT_SECS: u64 = 180;
+
+/// 录屏保留策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingRetentionPolicy {
+    /// 超过天数的录屏将被删除
+    pub max_age_days: u64,
+    /// 录屏目录总大小上限（MB），超出时从最旧的开始删除
+    pub max_total_mb: u64,
+}
+
+impl Default for RecordingRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: 7,
+            max_total_mb: 2048,
+        }
+    }
+}
+
+/// 单次执行的录屏目录信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingEntry {
+    pub name: String,
+    pub path: String,
+    pub segments: Vec<String>,
+    pub size_bytes: u64,
+    pub modified_secs: u64,
+}
+
+/// 正在进行的录屏会话
+pub struct ScreenRecording {
+    device_id: String,
+    recording_id: String,
+    stop_flag: Arc<AtomicBool>,
+    remote_segments: Arc<Mutex<Vec<String>>>,
+    /// 当前分段 screenrecord 进程在设备上的 PID，停止时只向它发送信号
+    current_pid: Arc<Mutex<Option<u32>>>,
+    task: JoinHandle<()>,
+}
+
+/// 录屏服务：分段录制、拉取到本地并按策略清理
+pub struct ScreenRecorder {
+    retention: Mutex<RecordingRetentionPolicy>,
+    /// 进行中的录屏会话，清理时跳过
+    active: std::sync::Mutex<HashSet<String>>,
+}
+
+impl ScreenRecorder {
+    pub fn new() -> Self {
+        let retention = std::fs::read_to_string(RETENTION_POLICY_FILE)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            retention: Mutex::new(retention),
+            active: std::sync::Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub async fn get_retention_policy(&self) -> RecordingRetentionPolicy {
+        self.retention.lock().await.clone()
+    }
+
+    pub async fn set_retention_policy(&self, policy: RecordingRetentionPolicy) -> Result<(), String> {
+        std::fs::create_dir_all("data").map_err(|e| format!("创建数据目录失败: {}", e))?;
+        let content = serde_json::to_string_pretty(&policy).map_err(|e| e.to_string())?;
+        std::fs::write(RETENTION_POLICY_FILE, content).map_err(|e| format!("保存录屏保留策略失败: {}", e))?;
+        *self.retention.lock().await = policy;
+        Ok(())
+    }
+
+    /// 开始录屏，返回会话句柄（执行结束后调用 `stop` 获取本地文件）
+    pub fn start(&self, device_id: &str) -> ScreenRecording {
+        let recording_id = format!(
+            "{}_{}",
+            device_id.replace([':', '/', '\\'], "_"),
+            chrono::Local::now().format("%Y%m%d_%H%M%S")
+        );
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let remote_segments = Arc::new(Mutex::new(Vec::new()));
+        let current_pid = Arc::new(Mutex::new(None));
+        if let Ok(mut active) = self.active.lock() {
+            active.insert(recording_id.clone());
+        }
+
+        info!("🎥 开始录屏: 设备 {} 会话 {}", device_id, recording_id);
+
+        let task = {
+            let device_id = device_id.to_string();
+            let recording_id = recording_id.clone();
+            let stop_flag = stop_flag.clone();
+            let remote_segments = remote_segments.clone();
+            let current_pid = current_pid.clone();
+            tokio::spawn(async move {
+                let mut index = 0;
+                while !stop_flag.load(Ordering::SeqCst) {
+                    index += 1;
+                    let remote = format!("/sdcard/{}_{:03}.mp4", recording_id, index);
+                    remote_segments.lock().await.push(remote.clone());
+
+                    // 后台启动并输出 PID，随后等待该进程结束
+                    let command = format!(
+                        "screenrecord --time-limit {} {} & echo $!; wait $!",
+                        SEGMENT_TIME_LIMIT_SECS, remote
+                    );
+                    // 正常情况下到达时间上限后返回，随后开始下一段
+                    let limit = Duration::from_secs(SEGMENT_TIME_LIMIT_SECS + 20);
+                    match timeout(limit, adb_shell_reporting_pid(&device_id, &command, &current_pid)).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => {
+                            warn!("⚠️ 录屏分段 {} 失败: {}", index, e);
+                            break;
+                        }
+                        Err(_) => {
+                            warn!("⚠️ 录屏分段 {} 超时", index);
+                            break;
+                        }
+                    }
+                }
+            })
+        };
+
+        ScreenRecording {
+            device_id: device_id.to_string(),
+            recording_id,
+            stop_flag,
+            remote_segments,
+            current_pid,
+            task,
+        }
+    }
+
+    /// 停止录屏，拉取所有分段到本地数据目录并返回本地路径
+    pub async fn stop(&self, mut recording: ScreenRecording) -> Vec<String> {
+        recording.stop_flag.store(true, Ordering::SeqCst);
+
+        // SIGINT 让 screenrecord 正常写完 mp4 文件尾。只向本会话的进程发信号，
+        // 不影响其他并行执行的录屏；信号发出时恰好切换分段则重试
+        let mut finished = false;
+        for _ in 0..3 {
+            if let Some(pid) = *recording.current_pid.lock().await {
+                let _ = run_shell(&recording.device_id, &format!("kill -2 {}", pid)).await;
+            }
+            if timeout(Duration::from_secs(5), &mut recording.task).await.is_ok() {
+                finished = true;
+                break;
+            }
+        }
+        if !finished {
+            warn!("⚠️ 等待录屏进程退出超时");
+            recording.task.abort();
+        }
+        sleep(Duration::from_secs(1)).await;
+
+        let local_dir = Path::new(RECORDINGS_DIR).join(&recording.recording_id);
+        if let Err(e) = std::fs::create_dir_all(&local_dir) {
+            warn!("⚠️ 创建录屏目录失败: {}", e);
+            return Vec::new();
+        }
+
+        let mut local_segments = Vec::new();
+        for remote in recording.remote_segments.lock().await.iter() {
+            let file_name = remote.rsplit('/').next().unwrap_or("segment.mp4");
+            let local = local_dir.join(file_name);
+            match adb_pull(&recording.device_id, remote, &local).await {
+                Ok(()) => local_segments.push(local.to_string_lossy().to_string()),
+                Err(e) => warn!("⚠️ 拉取录屏分段失败 {}: {}", remote, e),
+            }
+            let _ = run_shell(&recording.device_id, &format!("rm -f {}", remote)).await;
+        }
+
+        LOG_COLLECTOR.add_log(
+            "INFO",
+            "DEVICE",
+            "ScreenRecorder",
+            &format!("录屏完成: {} 个分段", local_segments.len()),
+            Some(&local_dir.to_string_lossy()),
+            Some(&recording.device_id),
+        );
+
+        if let Ok(mut active) = self.active.lock() {
+            active.remove(&recording.recording_id);
+        }
+        // 刚返回的分段会被执行结果引用，本次清理不删除
+        if let Err(e) = self.cleanup_excluding(&[recording.recording_id.as_str()]).await {
+            warn!("⚠️ 录屏清理失败: {}", e);
+        }
+
+        local_segments
+    }
+
+    /// 列出本地所有录屏
+    pub fn list_recordings(&self) -> Vec<RecordingEntry> {
+        let Ok(dirs) = std::fs::read_dir(RECORDINGS_DIR) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<RecordingEntry> = dirs
+            .flatten()
+            .filter(|d| d.path().is_dir())
+            .map(|d| read_recording_entry(&d.path()))
+            .collect();
+        entries.sort_by(|a, b| b.modified_secs.cmp(&a.modified_secs));
+        entries
+    }
+
+    /// 按保留策略删除过期或超出容量的录屏，返回删除的数量
+    pub async fn cleanup(&self) -> Result<usize, String> {
+        self.cleanup_excluding(&[]).await
+    }
+
+    /// 清理录屏，跳过进行中的会话和 `keep` 中的会话
+    async fn cleanup_excluding(&self, keep: &[&str]) -> Result<usize, String> {
+        let policy = self.get_retention_policy().await;
+        let now_secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut protected: HashSet<String> = keep.iter().map(|k| k.to_string()).collect();
+        if let Ok(active) = self.active.lock() {
+            protected.extend(active.iter().cloned());
+        }
+        let expired = select_expired(&self.list_recordings(), &policy, now_secs, &protected);
+        for entry in &expired {
+            std::fs::remove_dir_all(&entry.path)
+                .map_err(|e| format!("删除录屏失败 {}: {}", entry.path, e))?;
+            info!("🧹 删除过期录屏: {}", entry.name);
+        }
+        Ok(expired.len())
+    }
+}
+
+/// 选出需要删除的录屏：先按时间过期，再按总大小从最旧的开始淘汰
+/// `protected` 中的会话不会被删除，但其大小计入总量
+fn select_expired(
+    entries: &[RecordingEntry],
+    policy: &RecordingRetentionPolicy,
+    now_secs: u64,
+    protected: &HashSet<String>,
+) -> Vec<RecordingEntry> {
+    let max_age_secs = policy.max_age_days * 24 * 3600;
+    let max_total_bytes = policy.max_total_mb * 1024 * 1024;
+
+    let mut sorted = entries.to_vec();
+    sorted.sort_by(|a, b| b.modified_secs.cmp(&a.modified_secs));
+
+    let mut kept_bytes = 0u64;
+    let mut expired = Vec::new();
+    for entry in sorted {
+        if protected.contains(&entry.name) {
+            kept_bytes += entry.size_bytes;
+            continue;
+        }
+        let too_old = now_secs.saturating_sub(entry.modified_secs) > max_age_secs;
+        if too_old || kept_bytes + entry.size_bytes > max_total_bytes {
+            expired.push(entry);
+        } else {
+            kept_bytes += entry.size_bytes;
+        }
+    }
+    expired
+}
+
+fn read_recording_entry(dir: &PathBuf) -> RecordingEntry {
+    let mut segments = Vec::new();
+    let mut size_bytes = 0;
+    let mut modified_secs = 0;
+
+    if let Ok(files) = std::fs::read_dir(dir) {
+        for file in files.flatten() {
+            if let Ok(meta) = file.metadata() {
+                size_bytes += meta.len();
+                let modified = meta
+                    .modified()
+                    .ok()
+                    .and_then(|m| m.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                modified_secs = modified_secs.max(modified);
+            }
+            segments.push(file.path().to_string_lossy().to_string());
+        }
+    }
+    segments.sort();
+
+    RecordingEntry {
+        name: dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        path: dir.to_string_lossy().to_string(),
+        segments,
+        size_bytes,
+        modified_secs,
+    }
+}
+
+/// 执行 `<cmd> & echo $!; wait $!` 形式的命令：读取第一行 PID 写入 `pid_slot`，再等待命令结束
+async fn adb_shell_reporting_pid(device_id: &str, command: &str, pid_slot: &Mutex<Option<u32>>) -> Result<(), String> {
+    let mut cmd = Command::new(get_adb_path());
+    cmd.args(["-s", device_id, "shell", command])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let mut child = cmd.spawn().map_err(|e| format!("执行命令失败: {}", e))?;
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        if let Ok(Some(line)) = lines.next_line().await {
+            *pid_slot.lock().await = line.trim().parse().ok();
+        }
+    }
+
+    let output = child.wait_with_output().await.map_err(|e| format!("执行命令失败: {}", e))?;
+    *pid_slot.lock().await = None;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+async fn adb_pull(device_id: &str, remote: &str, local: &Path) -> Result<(), String> {
+    let mut cmd = Command::new(get_adb_path());
+    cmd.args(["-s", device_id, "pull", remote])
+        .arg(local);
+
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = timeout(Duration::from_secs(120), cmd.output())
+        .await
+        .map_err(|_| "拉取文件超时".to_string())?
+        .map_err(|e| format!("执行命令失败: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+/// 全局录屏服务实例
+static GLOBAL_SCREEN_RECORDER: std::sync::OnceLock<ScreenRecorder> = std::sync::OnceLock::new();
+
+/// 获取全局录屏服务
+pub fn get_screen_recorder() -> &'static ScreenRecorder {
+    GLOBAL_SCREEN_RECORDER.get_or_init(ScreenRecorder::new)
+}
+
+/// 列出本地录屏
+#[tauri::command]
+pub async fn list_screen_recordings() -> Result<Vec<RecordingEntry>, String> {
+    Ok(get_screen_recorder().list_recordings())
+}
+
+/// 获取录屏保留策略
+#[tauri::command]
+pub async fn get_recording_retention_policy() -> Result<RecordingRetentionPolicy, String> {
+    Ok(get_screen_recorder().get_retention_policy().await)
+}
+
+/// 更新录屏保留策略
+#[tauri::command]
+pub async fn set_recording_retention_policy(policy: RecordingRetentionPolicy) -> Result<(), String> {
+    get_screen_recorder().set_retention_policy(policy).await
+}
+
+/// 立即按保留策略清理录屏
+#[tauri::command]
+pub async fn cleanup_screen_recordings() -> Result<usize, String> {
+    get_screen_recorder().cleanup().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, modified_secs: u64, size_mb: u64) -> RecordingEntry {
+        RecordingEntry {
+            name: name.to_string(),
+            path: name.to_string(),
+            segments: Vec::new(),
+            size_bytes: size_mb * 1024 * 1024,
+            modified_secs,
+        }
+    }
+
+    #[test]
+    fn test_select_expired() {
+        let day = 24 * 3600;
+        let now = 100 * day;
+        let policy = RecordingRetentionPolicy {
+            max_age_days: 7,
+            max_total_mb: 100,
+        };
+        let entries = vec![
+            entry("newest", now - day, 60),
+            entry("middle", now - 2 * day, 60),
+            entry("old", now - 10 * day, 1),
+        ];
+
+        let expired: Vec<String> = select_expired(&entries, &policy, now, &HashSet::new())
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(expired, vec!["middle".to_string(), "old".to_string()]);
+
+        // 进行中或刚返回的会话不删除
+        let protected: HashSet<String> = ["old".to_string()].into_iter().collect();
+        let expired: Vec<String> = select_expired(&entries, &policy, now, &protected)
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(expired, vec!["middle".to_string()]);
+    }
+}
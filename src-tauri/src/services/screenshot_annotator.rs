@@ -0,0 +1,383 @@
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::screenshot_service::ScreenshotService;
+use crate::services::template_matcher::RgbaImage;
+use crate::services::ui_tree::{UiNode, UiTree};
+use crate::types::page_analysis::{ElementBounds, ElementType, PageAnalysisResult};
+use crate::xml_judgment_service::XmlJudgmentService;
+
+const EVIDENCE_DIR: &str = "data/step_evidence";
+
+/// 标签最多显示的字符数（过长的 resource-id 截断）
+const MAX_LABEL_CHARS: usize = 28;
+
+const GREEN: [u8; 3] = [46, 204, 113];
+const BLUE: [u8; 3] = [52, 152, 219];
+const YELLOW: [u8; 3] = [241, 196, 15];
+const GRAY: [u8; 3] = [149, 165, 166];
+const RED: [u8; 3] = [231, 76, 60];
+const WHITE: [u8; 3] = [255, 255, 255];
+
+/// 要绘制的一个元素框
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    pub bounds: ElementBounds,
+    /// 标签（仅支持 ASCII，中文等字符显示为 ?）
+    pub label: String,
+    pub color: [u8; 3],
+}
+
+fn class_label(class_name: &str) -> &str {
+    class_name.rsplit('.').next().unwrap_or(class_name)
+}
+
+fn node_label(node: &UiNode) -> String {
+    let mut label = format!("#{} {}", node.id(), class_label(node.class_name()));
+    if let Some((_, id)) = node.resource_id().split_once(":id/") {
+        label.push(' ');
+        label.push_str(id);
+    }
+    label
+}
+
+fn node_color(node: &UiNode) -> [u8; 3] {
+    if node.class_name().contains("EditText") {
+        BLUE
+    } else if node.is_clickable() {
+        GREEN
+    } else if !node.text().is_empty() || !node.content_desc().is_empty() {
+        YELLOW
+    } else {
+        GRAY
+    }
+}
+
+/// 从 UI 树生成标注：可点击、有文本/描述或有 resource-id 的节点
+pub fn tree_annotations(tree: &UiTree) -> Vec<Annotation> {
+    tree.elements()
+        .filter(|n| {
+            n.is_clickable() || !n.text().is_empty() || !n.content_desc().is_empty() || !n.resource_id().is_empty()
+        })
+        .filter_map(|n| {
+            let bounds = n.bounds().filter(|b| b.width() > 0 && b.height() > 0)?;
+            Some(Annotation { bounds, label: node_label(&n), color: node_color(&n) })
+        })
+        .collect()
+}
+
+fn element_type_label(element_type: &ElementType) -> String {
+    match element_type {
+        ElementType::Other(name) => name.clone(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// 从页面分析结果生成标注
+pub fn analysis_annotations(result: &PageAnalysisResult) -> Vec<Annotation> {
+    result
+        .actionable_elements
+        .iter()
+        .map(|element| {
+            let color = match element.element_type {
+                ElementType::EditText => BLUE,
+                ElementType::Button | ElementType::NavigationButton | ElementType::Tab => GREEN,
+                _ if element.is_clickable => GREEN,
+                _ => YELLOW,
+            };
+            Annotation {
+                bounds: element.bounds.clone(),
+                label: format!("{} {}", element.id, element_type_label(&element.element_type)),
+                color,
+            }
+        })
+        .collect()
+}
+
+/// 包含指定坐标的最小节点（即点击实际命中的元素）
+pub fn element_at(tree: &UiTree, x: i32, y: i32) -> Option<ElementBounds> {
+    tree.elements()
+        .filter_map(|n| n.bounds())
+        .filter(|b| b.left <= x && x < b.right && b.top <= y && y < b.bottom)
+        .min_by_key(|b| b.width() as i64 * b.height() as i64)
+}
+
+/// 一次点击的目标：坐标和点击前界面上命中的元素框
+#[derive(Debug, Clone)]
+pub struct TapTarget {
+    pub point: (i32, i32),
+    pub bounds: Option<ElementBounds>,
+}
+
+impl TapTarget {
+    /// 在点击前记录目标；`xml_before` 为点击前的界面，点击后界面已变化，不能再用来定位元素
+    pub fn before_tap(xml_before: Option<&str>, x: i32, y: i32) -> Self {
+        let bounds = xml_before
+            .and_then(|xml| UiTree::parse(xml).ok())
+            .and_then(|tree| element_at(&tree, x, y));
+        Self { point: (x, y), bounds }
+    }
+}
+
+/// 5x7 点阵字形（每行低 5 位，高位在左）；小写按大写显示，不支持的字符显示为 ?
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        ' ' => [0; 7],
+        '#' => [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010],
+        '.' => [0, 0, 0, 0, 0, 0b01100, 0b01100],
+        '_' => [0, 0, 0, 0, 0, 0, 0b11111],
+        '-' => [0, 0, 0, 0b11111, 0, 0, 0],
+        ':' => [0, 0b01100, 0b01100, 0, 0b01100, 0b01100, 0],
+        '/' => [0, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0, 0b00100],
+    }
+}
+
+/// 在 RGBA 图像上绘制标注
+struct Canvas<'a> {
+    image: &'a mut RgbaImage,
+    /// 线宽和字体的缩放倍数（按屏幕宽度，1080 宽约为 2）
+    scale: i32,
+}
+
+impl<'a> Canvas<'a> {
+    fn new(image: &'a mut RgbaImage) -> Self {
+        let scale = (image.width as i32 / 540).max(1);
+        Self { image, scale }
+    }
+
+    /// 按 alpha（0~255）混合一个像素，超出图像的坐标忽略
+    fn blend(&mut self, x: i32, y: i32, color: [u8; 3], alpha: u8) {
+        if x < 0 || y < 0 || x >= self.image.width as i32 || y >= self.image.height as i32 {
+            return;
+        }
+        let offset = (y as usize * self.image.width + x as usize) * 4;
+        let px = &mut self.image.data[offset..offset + 3];
+        for (channel, value) in px.iter_mut().zip(color) {
+            *channel = ((value as u32 * alpha as u32 + *channel as u32 * (255 - alpha as u32)) / 255) as u8;
+        }
+    }
+
+    fn fill(&mut self, left: i32, top: i32, right: i32, bottom: i32, color: [u8; 3], alpha: u8) {
+        let (left, right) = (left.max(0), right.min(self.image.width as i32));
+        let (top, bottom) = (top.max(0), bottom.min(self.image.height as i32));
+        for y in top..bottom {
+            for x in left..right {
+                self.blend(x, y, color, alpha);
+            }
+        }
+    }
+
+    fn rect(&mut self, bounds: &ElementBounds, color: [u8; 3], thickness: i32) {
+        let ElementBounds { left, top, right, bottom } = *bounds;
+        self.fill(left, top, right, top + thickness, color, 255);
+        self.fill(left, bottom - thickness, right, bottom, color, 255);
+        self.fill(left, top, left + thickness, bottom, color, 255);
+        self.fill(right - thickness, top, right, bottom, color, 255);
+    }
+
+    /// 在元素框左上角绘制标签：框上方放得下时画在框外，否则画在框内
+    fn label(&mut self, bounds: &ElementBounds, text: &str, color: [u8; 3]) {
+        let text: String = text.chars().take(MAX_LABEL_CHARS).collect();
+        let (char_w, char_h, pad) = (6 * self.scale, 7 * self.scale, self.scale);
+        let width = text.chars().count() as i32 * char_w + pad * 2;
+        let height = char_h + pad * 2;
+        let left = bounds.left.min(self.image.width as i32 - width).max(0);
+        let top = if bounds.top >= height { bounds.top - height } else { bounds.top };
+
+        self.fill(left, top, left + width, top + height, color, 200);
+        for (i, c) in text.chars().enumerate() {
+            let origin_x = left + pad + i as i32 * char_w;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0b10000 >> col) != 0 {
+                        let (x, y) = (origin_x + col * self.scale, top + pad + row as i32 * self.scale);
+                        self.fill(x, y, x + self.scale, y + self.scale, WHITE, 255);
+                    }
+                }
+            }
+        }
+    }
+
+    fn crosshair(&mut self, x: i32, y: i32, color: [u8; 3]) {
+        let (arm, half) = (12 * self.scale, self.scale);
+        self.fill(x - arm, y - half, x + arm, y + half, color, 255);
+        self.fill(x - half, y - arm, x + half, y + arm, color, 255);
+    }
+}
+
+/// 在截图上绘制元素框和标签，并突出显示匹配/点击的元素和点击位置
+pub fn annotate(
+    image: &mut RgbaImage,
+    annotations: &[Annotation],
+    highlight: Option<&ElementBounds>,
+    tap: Option<(i32, i32)>,
+) {
+    let mut canvas = Canvas::new(image);
+    let scale = canvas.scale;
+    for annotation in annotations {
+        canvas.rect(&annotation.bounds, annotation.color, scale);
+    }
+    // 标签最后画，避免被其他元素框覆盖
+    for annotation in annotations {
+        canvas.label(&annotation.bounds, &annotation.label, annotation.color);
+    }
+    if let Some(bounds) = highlight {
+        canvas.rect(bounds, RED, scale * 3);
+        canvas.label(bounds, "TARGET", RED);
+    }
+    if let Some((x, y)) = tap {
+        canvas.crosshair(x, y, RED);
+    }
+}
+
+/// 读取截图、绘制标注后另存
+pub fn annotate_file(
+    screenshot_path: &Path,
+    annotations: &[Annotation],
+    highlight: Option<&ElementBounds>,
+    tap: Option<(i32, i32)>,
+    output_path: &Path,
+) -> Result<()> {
+    let mut image = RgbaImage::open(screenshot_path)?;
+    annotate(&mut image, annotations, highlight, tap);
+    image.save_png(output_path)
+}
+
+/// 截取当前屏幕并按当前界面标注，保存为步骤证据截图，返回路径
+///
+/// 指定了点击目标时，标出点击坐标并突出显示点击前记录的元素框
+pub async fn capture_step_evidence(device_id: &str, step_id: &str, tap: Option<&TapTarget>) -> Result<String> {
+    let dir = PathBuf::from(EVIDENCE_DIR);
+    let screenshot = ScreenshotService::capture_to_dir(device_id, &dir).await;
+    let raw_path = screenshot
+        .screenshot_path
+        .ok_or_else(|| anyhow!("截图失败: {}", screenshot.error.unwrap_or_default()))?;
+
+    let xml = XmlJudgmentService::get_ui_xml(device_id).await.map_err(|e| anyhow!(e))?;
+    let tree = UiTree::parse(&xml)?;
+    let highlight = tap.and_then(|t| t.bounds.as_ref());
+
+    let output = dir.join(format!("{}_{}.png", step_id, chrono::Local::now().format("%Y%m%d_%H%M%S%3f")));
+    annotate_file(Path::new(&raw_path), &tree_annotations(&tree), highlight, tap.map(|t| t.point), &output)?;
+    let _ = std::fs::remove_file(&raw_path);
+
+    info!("🖍️ 已保存步骤证据截图: {}", output.display());
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// 在已有截图上绘制元素框（来源为 UI XML 或页面分析结果），返回标注图路径
+#[tauri::command]
+pub async fn annotate_screenshot(
+    screenshot_path: String,
+    xml_content: Option<String>,
+    analysis: Option<PageAnalysisResult>,
+    highlight: Option<ElementBounds>,
+    tap_x: Option<i32>,
+    tap_y: Option<i32>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let tap = tap_x.zip(tap_y);
+    let mut annotations = Vec::new();
+    let mut highlight = highlight;
+    if let Some(xml) = &xml_content {
+        let tree = UiTree::parse(xml).map_err(|e| format!("解析UI XML失败: {}", e))?;
+        annotations.extend(tree_annotations(&tree));
+        if highlight.is_none() {
+            highlight = tap.and_then(|(x, y)| element_at(&tree, x, y));
+        }
+    }
+    if let Some(result) = &analysis {
+        annotations.extend(analysis_annotations(result));
+    }
+
+    let input = PathBuf::from(&screenshot_path);
+    let output = output_path.map(PathBuf::from).unwrap_or_else(|| input.with_extension("annotated.png"));
+    annotate_file(&input, &annotations, highlight.as_ref(), tap, &output).map_err(|e| e.to_string())?;
+    info!("🖍️ 已生成标注截图: {}", output.display());
+    Ok(output.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<?xml version='1.0' ?><hierarchy rotation="0"><node class="android.widget.FrameLayout" bounds="[0,0][1080,1920]"><node class="android.widget.LinearLayout" clickable="true" bounds="[0,200][1080,400]"><node text="张三" class="android.widget.TextView" bounds="[40,250][400,350]" /><node text="关注" resource-id="com.xingin.xhs:id/follow_btn" class="android.widget.Button" clickable="true" bounds="[800,250][1000,350]" /></node></node></hierarchy>"#;
+
+    fn pixel(image: &RgbaImage, x: usize, y: usize) -> [u8; 3] {
+        let offset = (y * image.width + x) * 4;
+        [image.data[offset], image.data[offset + 1], image.data[offset + 2]]
+    }
+
+    #[test]
+    fn test_tree_annotations_and_hit_element() {
+        let tree = UiTree::parse(PAGE).unwrap();
+        let annotations = tree_annotations(&tree);
+        assert_eq!(annotations.len(), 3);
+        let follow = annotations.iter().find(|a| a.label.ends_with("Button follow_btn")).unwrap();
+        assert_eq!(follow.color, GREEN);
+
+        let hit = element_at(&tree, 900, 300).unwrap();
+        assert_eq!((hit.left, hit.top, hit.right, hit.bottom), (800, 250, 1000, 350));
+        let row = element_at(&tree, 600, 300).unwrap();
+        assert_eq!((row.left, row.top), (0, 200));
+
+        let target = TapTarget::before_tap(Some(PAGE), 900, 300);
+        assert_eq!(target.bounds.map(|b| (b.left, b.top)), Some((800, 250)));
+        assert!(TapTarget::before_tap(None, 900, 300).bounds.is_none());
+    }
+
+    #[test]
+    fn test_annotate_draws_boxes_and_highlight() {
+        let mut image = RgbaImage { width: 1080, height: 1920, data: vec![0; 1080 * 1920 * 4] };
+        let tree = UiTree::parse(PAGE).unwrap();
+        let highlight = element_at(&tree, 900, 300);
+        annotate(&mut image, &tree_annotations(&tree), highlight.as_ref(), Some((900, 300)));
+
+        // 普通元素框的左边线、突出显示元素的加粗边框、点击位置的十字
+        assert_eq!(pixel(&image, 40, 300), YELLOW);
+        assert_eq!(pixel(&image, 804, 300), RED);
+        assert_eq!(pixel(&image, 900, 300), RED);
+        // 框外区域不受影响
+        assert_eq!(pixel(&image, 600, 1000), [0, 0, 0]);
+    }
+}
@@ -507,6 +507,9 @@ impl From<ExecutionResult> for SmartExecutionResult {
             } else {
                 format!("执行失败: {} 个步骤失败", result.execution_stats.step_stats.failed_steps)
             },
+            logcat_path: None,
+            recordings: Vec::new(),
+            popup_firings: Vec::new(),
         }
     }
 }
\ No newline at end of file
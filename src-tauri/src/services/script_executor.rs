@@ -6,6 +6,8 @@ use tauri::command;
 use tokio::time::sleep;
 use tracing::{error, info, warn};
 
+use crate::services::ui_idle::settle;
+
 // 操作类型枚举
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -422,8 +424,8 @@ impl ScriptExecutor {
             return Err(anyhow::anyhow!("打开应用命令执行失败: {}", error_msg));
         }
 
-        // 等待应用启动
-        sleep(Duration::from_millis(3000)).await;
+        // 等待应用启动完成
+        settle(&self.device_id, Duration::from_secs(5)).await;
 
         Ok(())
     }
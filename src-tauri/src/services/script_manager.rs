@@ -7,6 +7,7 @@ use tracing::{info, warn};
 use chrono::{DateTime, Utc};
 
 use crate::services::smart_script_executor::{SmartScriptStep, SmartExecutionResult, SmartExecutorConfig};
+use crate::services::popup_watcher::PopupWatcherSettings;
 
 /// 智能脚本完整定义
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +46,9 @@ impl Default for SmartScript {
                 auto_verification_enabled: true,
                 smart_recovery_enabled: true,
                 detailed_logging: true,
+                record_screen: false,
+                record_navigation: false,
+                popup_watchers: PopupWatcherSettings::default(),
             },
             metadata: HashMap::new(),
         }
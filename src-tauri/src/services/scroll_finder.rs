@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::utils::adb_utils::run_shell;
+use crate::services::ui_idle::{wait_until_stable, IdleOptions};
+use crate::services::ui_tree::{UiNode, UiTree};
+use crate::types::page_analysis::ElementBounds;
+
+/// 滚动方向：要查看的内容所在方向（`Down` 即手指上滑，显示列表下方内容）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// 滚动查找参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScrollOptions {
+    pub direction: ScrollDirection,
+    /// 最多滚动次数
+    pub max_scrolls: u32,
+    /// 未找到时滚回起始位置
+    pub scroll_back: bool,
+    /// 每次滑动的时长（毫秒）
+    pub swipe_duration_ms: u32,
+    /// 每次滑动后等待界面稳定的超时（毫秒）
+    pub settle_timeout_ms: u64,
+}
+
+impl Default for ScrollOptions {
+    fn default() -> Self {
+        Self {
+            direction: ScrollDirection::Down,
+            max_scrolls: 10,
+            scroll_back: false,
+            swipe_duration_ms: 500,
+            settle_timeout_ms: 3000,
+        }
+    }
+}
+
+/// 一次滑动手势
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Swipe {
+    pub x1: i32,
+    pub y1: i32,
+    pub x2: i32,
+    pub y2: i32,
+    pub duration_ms: u32,
+}
+
+impl Swipe {
+    /// 在容器内按方向滑动半个容器的距离
+    pub fn within(bounds: &ElementBounds, direction: ScrollDirection, duration_ms: u32) -> Self {
+        let (cx, cy) = (bounds.center_x(), bounds.center_y());
+        let (near_y, far_y) = (bounds.top + bounds.height() / 4, bounds.bottom - bounds.height() / 4);
+        let (near_x, far_x) = (bounds.left + bounds.width() / 4, bounds.right - bounds.width() / 4);
+        let (x1, y1, x2, y2) = match direction {
+            ScrollDirection::Down => (cx, far_y, cx, near_y),
+            ScrollDirection::Up => (cx, near_y, cx, far_y),
+            ScrollDirection::Right => (far_x, cy, near_x, cy),
+            ScrollDirection::Left => (near_x, cy, far_x, cy),
+        };
+        Self { x1, y1, x2, y2, duration_ms }
+    }
+
+    pub fn reversed(&self) -> Self {
+        Self { x1: self.x2, y1: self.y2, x2: self.x1, y2: self.y1, duration_ms: self.duration_ms }
+    }
+
+    pub fn shell_command(&self) -> String {
+        format!("input swipe {} {} {} {} {}", self.x1, self.y1, self.x2, self.y2, self.duration_ms)
+    }
+}
+
+fn bounds_key(bounds: &ElementBounds) -> (i32, i32, i32, i32) {
+    (bounds.left, bounds.top, bounds.right, bounds.bottom)
+}
+
+fn is_likely_match(node: &UiNode, target: &str) -> bool {
+    [node.text(), node.content_desc()]
+        .iter()
+        .any(|value| !value.is_empty() && (value.contains(target) || target.contains(value)))
+}
+
+/// 选择要滚动的容器：优先包含疑似目标的可滚动节点，其次面积最大的
+pub fn pick_container<'a>(tree: &'a UiTree, target: Option<&str>) -> Option<UiNode<'a>> {
+    tree.elements()
+        .filter(|n| n.is_scrollable())
+        .filter_map(|n| n.bounds().map(|b| (n, b)))
+        .filter(|(_, b)| b.width() > 0 && b.height() > 0)
+        .max_by_key(|(n, b)| {
+            let likely = target
+                .map(|t| n.descendants().iter().filter(|d| is_likely_match(d, t)).count())
+                .unwrap_or(0);
+            (likely, b.width() as i64 * b.height() as i64)
+        })
+        .map(|(n, _)| n)
+}
+
+/// 容器内容签名：滑动前后签名一致说明已到列表尽头
+fn content_signature(container: &UiNode) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for node in container.descendants() {
+        (node.text(), node.content_desc(), node.resource_id(), node.attr("bounds")).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// 一次滚动查找的状态：记住容器、滚动次数和上次内容签名
+#[derive(Debug)]
+pub struct ScrollSession {
+    options: ScrollOptions,
+    container: Option<ElementBounds>,
+    last_signature: Option<u64>,
+    /// 上次滑动后还未检查内容是否变化
+    awaiting_change: bool,
+    swipes: Vec<Swipe>,
+    reached_end: bool,
+}
+
+impl ScrollSession {
+    pub fn new(options: ScrollOptions) -> Self {
+        Self {
+            options,
+            container: None,
+            last_signature: None,
+            awaiting_change: false,
+            swipes: Vec::new(),
+            reached_end: false,
+        }
+    }
+
+    pub fn scrolls(&self) -> u32 {
+        self.swipes.len() as u32
+    }
+
+    pub fn reached_end(&self) -> bool {
+        self.reached_end
+    }
+
+    /// 找回上次选中的容器（按 bounds），不存在时重新选择；没有可滚动节点时退回整屏
+    fn locate<'a>(&mut self, tree: &'a UiTree, target: Option<&str>) -> (Option<UiNode<'a>>, Option<ElementBounds>) {
+        if let Some(known) = &self.container {
+            let key = bounds_key(known);
+            if let Some(node) = tree.find_first(|n| n.is_scrollable() && n.bounds().map(|b| bounds_key(&b)) == Some(key)) {
+                return (Some(node), self.container.clone());
+            }
+        }
+        if let Some(node) = pick_container(tree, target) {
+            self.container = node.bounds();
+            return (Some(node), self.container.clone());
+        }
+        let screen = tree.root().and_then(|root| {
+            root.bounds().or_else(|| root.children().filter_map(|c| c.bounds()).next())
+        });
+        (tree.root(), screen)
+    }
+
+    /// 记录当前容器内容；上次滑动后内容没有变化时标记到底
+    fn observe(&mut self, node: Option<UiNode>) -> bool {
+        let signature = node.map(|n| content_signature(&n));
+        if std::mem::take(&mut self.awaiting_change) && signature == self.last_signature {
+            self.reached_end = true;
+            return false;
+        }
+        self.last_signature = signature;
+        true
+    }
+
+    /// 滑动后的界面与滑动前相比是否有变化；没有变化即视为到底
+    pub fn content_changed(&mut self, tree: &UiTree) -> bool {
+        let (node, _) = self.locate(tree, None);
+        self.observe(node)
+    }
+
+    /// 根据当前界面计算下一次滑动；到底或次数用完时返回 None
+    pub fn next_swipe(&mut self, tree: &UiTree, target: Option<&str>) -> Option<Swipe> {
+        if self.reached_end || self.scrolls() >= self.options.max_scrolls {
+            return None;
+        }
+        let (node, bounds) = self.locate(tree, target);
+        if !self.observe(node) {
+            return None;
+        }
+        let swipe = Swipe::within(&bounds?, self.options.direction, self.options.swipe_duration_ms);
+        self.awaiting_change = true;
+        self.swipes.push(swipe);
+        Some(swipe)
+    }
+
+    /// 滚回起始位置所需的滑动（逆序、反向）
+    pub fn back_swipes(&self) -> Vec<Swipe> {
+        self.swipes.iter().rev().map(Swipe::reversed).collect()
+    }
+}
+
+/// 滚动查找结果
+#[derive(Debug, Clone)]
+pub struct ScrollFindResult<T> {
+    pub found: Option<T>,
+    pub scrolls: u32,
+    pub reached_end: bool,
+}
+
+/// 滚动容器直到 `find` 在当前界面上返回结果、到达列表尽头或次数用完
+pub async fn scroll_to_find<T, F>(
+    device_id: &str,
+    target: Option<&str>,
+    options: &ScrollOptions,
+    find: F,
+) -> Result<ScrollFindResult<T>, String>
+where
+    F: Fn(&UiTree) -> Option<T>,
+{
+    let idle = IdleOptions::with_timeout(Duration::from_millis(options.settle_timeout_ms));
+    let mut session = ScrollSession::new(options.clone());
+
+    loop {
+        let xml = wait_until_stable(device_id, &idle).await?.xml;
+        let tree = UiTree::parse(&xml).map_err(|e| format!("解析UI失败: {}", e))?;
+        if let Some(found) = find(&tree) {
+            info!("✅ 滚动 {} 次后找到目标", session.scrolls());
+            return Ok(ScrollFindResult { found: Some(found), scrolls: session.scrolls(), reached_end: false });
+        }
+        let Some(swipe) = session.next_swipe(&tree, target) else {
+            if session.reached_end() {
+                info!("📄 列表内容不再变化，已到达尽头");
+            }
+            if options.scroll_back {
+                for back in session.back_swipes() {
+                    if let Err(e) = run_shell(device_id, &back.shell_command()).await {
+                        warn!("⚠️ 滚回失败: {}", e);
+                        break;
+                    }
+                }
+            }
+            return Ok(ScrollFindResult {
+                found: None,
+                scrolls: session.scrolls(),
+                reached_end: session.reached_end(),
+            });
+        };
+        info!("📜 滚动查找 ({}/{}): {}", session.scrolls(), options.max_scrolls, swipe.shell_command());
+        run_shell(device_id, &swipe.shell_command()).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_page(names: &[&str]) -> String {
+        let items: String = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let top = 300 + i as i32 * 200;
+                format!(r#"<node text="{}" class="android.widget.TextView" bounds="[0,{}][1080,{}]" />"#, name, top, top + 200)
+            })
+            .collect();
+        format!(
+            r#"<?xml version='1.0' ?><hierarchy rotation="0"><node class="android.widget.FrameLayout" bounds="[0,0][1080,1920]"><node class="android.widget.HorizontalScrollView" scrollable="true" bounds="[0,100][1080,300]"><node text="推荐" bounds="[0,100][200,300]" /></node><node class="androidx.recyclerview.widget.RecyclerView" scrollable="true" bounds="[0,300][1080,1500]">{}</node></node></hierarchy>"#,
+            items
+        )
+    }
+
+    #[test]
+    fn test_pick_container_and_swipe_geometry() {
+        let tree = UiTree::parse(&list_page(&["张三", "李四"])).unwrap();
+        let container = pick_container(&tree, Some("推荐")).unwrap();
+        assert_eq!(container.class_name(), "android.widget.HorizontalScrollView");
+        let container = pick_container(&tree, Some("王五")).unwrap();
+        assert_eq!(container.class_name(), "androidx.recyclerview.widget.RecyclerView");
+
+        let bounds = container.bounds().unwrap();
+        let swipe = Swipe::within(&bounds, ScrollDirection::Down, 500);
+        assert_eq!((swipe.x1, swipe.y1, swipe.x2, swipe.y2), (540, 1200, 540, 600));
+        assert_eq!(swipe.reversed(), Swipe::within(&bounds, ScrollDirection::Up, 500));
+    }
+
+    #[test]
+    fn test_session_detects_end_of_list() {
+        let options = ScrollOptions { max_scrolls: 5, ..ScrollOptions::default() };
+        let mut session = ScrollSession::new(options);
+        let first = UiTree::parse(&list_page(&["张三", "李四"])).unwrap();
+        let second = UiTree::parse(&list_page(&["王五", "赵六"])).unwrap();
+
+        assert!(session.next_swipe(&first, Some("孙七")).is_some());
+        assert!(session.next_swipe(&second, Some("孙七")).is_some());
+        // 滑动后内容不变：到底
+        assert!(session.next_swipe(&second, Some("孙七")).is_none());
+        assert!(session.reached_end());
+        assert_eq!(session.back_swipes().len(), 2);
+    }
+}
@@ -1,11 +1,13 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 use crate::services::adb_shell_session::AdbShellSession;
 use crate::services::adb_session_manager::get_device_session;
 use crate::services::app_detection_framework::{
     DetectorFactory, DetectionResult, AppLaunchState
 };
+use crate::services::ui_idle::settle;
 use crate::utils::adb_utils::get_adb_path;
 use tracing::{info, warn, error};
 
@@ -232,8 +234,8 @@ impl SmartAppManager {
         )).await;
 
         if monkey_result.is_ok() {
-            // 短暂等待启动
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+            // 等待启动画面稳定
+            settle(&self.device_id, Duration::from_secs(5)).await;
             return true;
         } else {
             startup_issues.push("monkey命令启动失败".to_string());
@@ -248,7 +250,7 @@ impl SmartAppManager {
                 )).await;
 
                 if am_result.is_ok() {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                    settle(&self.device_id, Duration::from_secs(5)).await;
                     return true;
                 } else {
                     startup_issues.push("am start命令启动失败".to_string());
@@ -263,7 +265,7 @@ impl SmartAppManager {
         )).await;
 
         if generic_result.is_ok() {
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(5)).await;
             true
         } else {
             startup_issues.push("所有启动方法都失败".to_string());
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use crate::services::adb_service::AdbService;
+use crate::services::ui_tree::parse_bounds as parse_ui_bounds;
+use crate::services::launcher_icon_launcher::launch_via_launcher_icon;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigationBarConfig {
@@ -70,70 +72,17 @@ impl SmartElementFinderService {
         Ok(is_foreground)
     }
     
-    /// 从桌面启动应用
+    /// 从桌面启动应用（逐页查找桌面图标，必要时打开应用抽屉）
     pub async fn launch_app_from_desktop(&self, device_id: &str, app_name: &str) -> Result<(), String> {
-        // 获取当前UI信息
-        let ui_xml = self.adb_service.dump_ui_hierarchy(device_id).await
-            .map_err(|e| format!("Failed to dump UI: {}", e))?;
-            
-        // 查找应用图标位置
-        if let Some(app_bounds) = self.find_app_icon_bounds(&ui_xml, app_name) {
-            // 计算点击位置（中心点）
-            match Self::calculate_center_position(&app_bounds) {
-                Ok(center) => {
-                    // 点击应用图标
-                    self.adb_service.tap_screen(device_id, center.0, center.1).await
-                        .map_err(|e| format!("Failed to tap app icon: {}", e))?;
-                    Ok(())
-                }
-                Err(e) => Err(format!("无法计算 {} 图标的中心位置: {}", app_name, e))
-            }
-        } else {
-            Err(format!("在桌面上未找到 {} 应用图标", app_name))
-        }
-    }
-    
-    /// 在XML中查找应用图标的bounds
-    fn find_app_icon_bounds(&self, ui_xml: &str, app_name: &str) -> Option<String> {
-        let lines: Vec<&str> = ui_xml.lines().collect();
-        
-        for line in lines {
-            if line.trim().starts_with("<node") && line.contains(&format!("text=\"{}\"", app_name)) {
-                // 提取bounds属性
-                if let Some(start) = line.find("bounds=\"") {
-                    let start = start + 8; // "bounds=\"".len()
-                    if let Some(end) = line[start..].find("\"") {
-                        return Some(line[start..start + end].to_string());
-                    }
-                }
-            }
-        }
-        None
+        let mut logs = Vec::new();
+        launch_via_launcher_icon(device_id, &[app_name.to_string()], 8, &mut logs).await.map(|_| ())
     }
 
     /// 解析bounds字符串 "[x1,y1][x2,y2]" -> ((x1, y1), (x2, y2))
     fn parse_bounds(bounds_str: &str) -> Result<((i32, i32), (i32, i32)), String> {
-        let bounds_str = bounds_str.trim_matches(|c| c == '[' || c == ']');
-        let parts: Vec<&str> = bounds_str.split("][").collect();
-        
-        if parts.len() != 2 {
-            return Err(format!("Invalid bounds format: {}", bounds_str));
-        }
-
-        let parse_coords = |coord_str: &str| -> Result<(i32, i32), String> {
-            let coords: Vec<&str> = coord_str.split(',').collect();
-            if coords.len() != 2 {
-                return Err("Invalid coordinate format".to_string());
-            }
-            let x: i32 = coords[0].parse().map_err(|_| "Invalid x coordinate")?;
-            let y: i32 = coords[1].parse().map_err(|_| "Invalid y coordinate")?;
-            Ok((x, y))
-        };
-
-        let top_left = parse_coords(parts[0])?;
-        let bottom_right = parse_coords(parts[1])?;
-
-        Ok((top_left, bottom_right))
+        parse_ui_bounds(bounds_str)
+            .map(|b| ((b.left, b.top), (b.right, b.bottom)))
+            .ok_or_else(|| format!("Invalid bounds format: {}", bounds_str))
     }
 
     /// 计算元素中心位置
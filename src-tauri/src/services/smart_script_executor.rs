@@ -5,11 +5,30 @@ use std::sync::{Arc, Mutex};
 use tauri::command;
 use tracing::{error, info, warn, debug};
 
+use crate::screenshot_service::ScreenshotService;
 use crate::services::adb_session_manager::get_device_session;
+use crate::services::device_health_monitor::get_health_monitor;
+use crate::services::device_preconditions::get_device_preconditions;
+use crate::services::logcat_collector::get_logcat_collector;
+use crate::services::screen_recorder::get_screen_recorder;
+use crate::services::text_input::type_text;
+use crate::services::ui_dump_cache::get_ui_dump_cache;
+use crate::services::ui_idle::settle;
+use crate::services::ui_tree::{UiDiff, UiNode, UiTree};
+use crate::services::template_matcher::{find_template_in_file, TemplateMatchOptions};
+use crate::services::scroll_finder::{scroll_to_find, ScrollOptions};
+use crate::services::screenshot_annotator::{capture_step_evidence, TapTarget};
+use crate::services::element_locator::locate;
+use crate::services::popup_watcher::{get_popup_watchers, PopupFiring, PopupWatcherSettings};
+use crate::services::app_package_manager::{install_apk_on_device, AppPackageManager, InstallOptions};
+use crate::types::page_analysis::ElementLocator;
+use crate::services::navigation_graph::{navigate_to, observe_page, record_transition, tap_action_at, PageObservation};
+use crate::services::page_fingerprint::{capture_fingerprint, current_activity, get_page_registry, parse_focused_activity, wait_for_page, PageFingerprint};
 use crate::services::error_handling::{ErrorHandler, ErrorHandlingConfig};
 use crate::services::script_execution::ScriptPreprocessor;
 use crate::services::contact_automation::generate_vcf_file;
 use crate::services::vcf_importer::VcfImporter;
+use crate::services::vcard::VCardVersion;
 use crate::services::multi_brand_vcf_importer::MultiBrandVcfImporter;
 
 #[cfg(windows)]
@@ -38,6 +57,11 @@ pub enum SmartActionType {
     // 通讯录自动化操作
     ContactGenerateVcf,
     ContactImportToDevice,
+    // 应用包管理操作
+    AppInstall,
+    AppUninstall,
+    AppClearData,
+    AppGrantPermissions,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +102,15 @@ pub struct SmartExecutionResult {
     pub final_page_state: Option<String>,
     pub extracted_data: HashMap<String, serde_json::Value>,
     pub message: String,
+    /// 本次执行期间的 logcat 日志文件
+    #[serde(default)]
+    pub logcat_path: Option<String>,
+    /// 本次执行的录屏分段文件
+    #[serde(default)]
+    pub recordings: Vec<String>,
+    /// 执行期间弹窗监视器的触发记录
+    #[serde(default)]
+    pub popup_firings: Vec<PopupFiring>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +119,15 @@ pub struct SmartExecutorConfig {
     pub auto_verification_enabled: bool,
     pub smart_recovery_enabled: bool,
     pub detailed_logging: bool,
+    /// 执行期间录制设备屏幕
+    #[serde(default)]
+    pub record_screen: bool,
+    /// 执行点击步骤时把页面跳转记录到应用导航图
+    #[serde(default)]
+    pub record_navigation: bool,
+    /// 步骤执行前及失败后自动关闭弹窗的监视器配置（查找类步骤关闭弹窗后重试一次）
+    #[serde(default)]
+    pub popup_watchers: PopupWatcherSettings,
 }
 
 pub struct SmartScriptExecutor {
@@ -93,6 +135,8 @@ pub struct SmartScriptExecutor {
     pub adb_path: String,
     error_handler: ErrorHandler,
     preprocessor: Arc<Mutex<ScriptPreprocessor>>,
+    /// 当前步骤最后一次点击的目标（点击前记录，用于在证据截图上标出点击的元素）
+    last_tap: Mutex<Option<TapTarget>>,
 }
 
 impl SmartScriptExecutor {
@@ -120,6 +164,7 @@ impl SmartScriptExecutor {
             adb_path,
             error_handler,
             preprocessor: Arc::new(Mutex::new(ScriptPreprocessor::new())),
+            last_tap: Mutex::new(None),
         }
     }
 
@@ -145,6 +190,7 @@ impl SmartScriptExecutor {
         );
         info!("{}", step_details);
         logs.push(step_details);
+        *self.last_tap.lock().unwrap() = None;
 
         let result = match step.step_type {
             // 基础操作类型
@@ -164,18 +210,12 @@ impl SmartScriptExecutor {
                 logs.push("✅ 验证操作".to_string());
                 Ok("验证操作模拟".to_string())
             },
-            SmartActionType::WaitForPageState => {
-                logs.push("⏳ 等待页面状态".to_string());
-                Ok("等待页面状态模拟".to_string())
-            },
+            SmartActionType::WaitForPageState => self.test_wait_for_page_state(&step, &mut logs).await,
             SmartActionType::ExtractElement => {
                 logs.push("� 提取元素".to_string());
                 Ok("提取元素模拟".to_string())
             },
-            SmartActionType::SmartNavigation => {
-                logs.push("🧭 智能导航".to_string());
-                Ok("智能导航模拟".to_string())
-            },
+            SmartActionType::SmartNavigation => self.test_smart_navigation(&step, &mut logs).await,
             // 循环控制类型
             SmartActionType::LoopStart => {
                 logs.push("🔄 循环开始标记".to_string());
@@ -188,10 +228,28 @@ impl SmartScriptExecutor {
             // 通讯录自动化操作
             SmartActionType::ContactGenerateVcf => self.test_contact_generate_vcf(&step, &mut logs).await,
             SmartActionType::ContactImportToDevice => self.test_contact_import_to_device(&step, &mut logs).await,
+            // 应用包管理操作
+            SmartActionType::AppInstall
+            | SmartActionType::AppUninstall
+            | SmartActionType::AppClearData
+            | SmartActionType::AppGrantPermissions => self.test_app_package(&step, &mut logs).await,
         };
 
         let duration = start_time.elapsed().as_millis() as u64;
 
+        // 智能点击/查找类步骤失败时（或参数 capture_evidence 为 true 时）保存标注截图作为证据
+        let mut extracted_data = HashMap::new();
+        let wants_evidence = params.get("capture_evidence").and_then(|v| v.as_bool()).unwrap_or(false);
+        let is_smart_step = matches!(
+            step.step_type,
+            SmartActionType::SmartTap | SmartActionType::BatchMatch | SmartActionType::SmartFindElement
+        );
+        if is_smart_step && (result.is_err() || wants_evidence) {
+            if let Some(path) = self.capture_step_evidence(&step.id, &mut logs).await {
+                extracted_data.insert("evidence_screenshot".to_string(), serde_json::Value::String(path));
+            }
+        }
+
         match result {
             Ok(message) => {
                 logs.push(format!("✅ 执行成功: {}", message));
@@ -207,7 +265,7 @@ impl SmartScriptExecutor {
                     ui_elements: Vec::new(),
                     logs,
                     error_details: None,
-                    extracted_data: HashMap::new(),
+                    extracted_data: extracted_data.clone(),
                 })
             }
             Err(e) => {
@@ -225,7 +283,7 @@ impl SmartScriptExecutor {
                     ui_elements: Vec::new(),
                     logs,
                     error_details: Some(error_msg),
-                    extracted_data: HashMap::new(),
+                    extracted_data,
                 })
             }
         }
@@ -247,14 +305,46 @@ impl SmartScriptExecutor {
             params.get("y").map(|v| v.as_i64().unwrap_or(0)).unwrap_or(0)
         ));
         
+        // 可选的点击后校验：{"expect_change": {"attribute": "text", "from": "关注", "to": "已关注"}}
+        let expect_change = params.get("expect_change").filter(|v| v.is_object());
+        let before_dump = match expect_change {
+            Some(_) => Some(self.execute_ui_dump_with_retry(logs).await?),
+            None => None,
+        };
+        
         // 使用带重试的点击执行
-        match self.execute_click_with_retry(x, y, logs).await {
-            Ok(output) => {
-                logs.push(format!("📤 命令输出: {}", output.trim()));
-                Ok("点击成功".to_string())
-            }
-            Err(e) => Err(e)
+        let output = self.execute_click_with_retry(x, y, logs).await?;
+        logs.push(format!("📤 命令输出: {}", output.trim()));
+        
+        if let (Some(expect), Some(before_dump)) = (expect_change, before_dump) {
+            self.verify_tap_change(x, y, expect, &before_dump, logs).await?;
+            return Ok("点击成功，变化已验证".to_string());
         }
+        Ok("点击成功".to_string())
+    }
+
+    /// 校验点击位置的节点属性按预期变化
+    async fn verify_tap_change(
+        &self,
+        x: i32,
+        y: i32,
+        expect: &serde_json::Value,
+        before_dump: &str,
+        logs: &mut Vec<String>,
+    ) -> Result<()> {
+        let attribute = expect.get("attribute").and_then(|v| v.as_str()).unwrap_or("text");
+        let from = expect.get("from").and_then(|v| v.as_str());
+        let to = expect.get("to").and_then(|v| v.as_str());
+        let wait_ms = expect.get("wait_ms").and_then(|v| v.as_u64()).unwrap_or(800);
+        
+        tokio::time::sleep(tokio::time::Duration::from_millis(wait_ms)).await;
+        let after_dump = self.execute_ui_dump_with_retry(logs).await?;
+        let diff = UiDiff::between(before_dump, &after_dump)?;
+        logs.push(format!("🔍 点击前后UI差异: {}", diff.summary()));
+        
+        let change = diff.expect_change_at(x, y, attribute, from, to)?;
+        logs.push(format!("✅ 属性 {} 已变化: {:?} → {:?}", attribute, change.before, change.after));
+        Ok(())
     }
 
     async fn test_wait(&self, _step: &SmartScriptStep, logs: &mut Vec<String>) -> Result<String> {
@@ -274,10 +364,9 @@ impl SmartScriptExecutor {
         
         // 使用ADB Shell长连接会话执行命令
         let session = get_device_session(&self.device_id).await?;
-        let command = format!("input text '{}'", text);
-        let output = session.execute_command(&command).await?;
+        let method = type_text(&session, text).await?;
         
-        logs.push(format!("命令输出: {}", output));
+        logs.push(format!("输入方式: {:?}", method));
         Ok("输入成功".to_string())
     }
 
@@ -297,8 +386,8 @@ impl SmartScriptExecutor {
             
             logs.push(format!("启动命令输出: {}", output));
             
-            // 等待应用启动
-            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+            // 等待应用启动完成
+            settle(&self.device_id, std::time::Duration::from_secs(5)).await;
             
             Ok("应用启动成功".to_string())
         } else {
@@ -314,6 +403,7 @@ impl SmartScriptExecutor {
             
             let session = get_device_session(&self.device_id).await?;
             let command = format!("input tap {} {}", x, y);
+            self.record_tap(x, y);
             let output = session.execute_command(&command).await?;
             
             logs.push(format!("命令输出: {}", output));
@@ -324,11 +414,22 @@ impl SmartScriptExecutor {
     async fn test_find_element(&self, step: &SmartScriptStep, logs: &mut Vec<String>) -> Result<String> {
         logs.push("🔍 执行智能元素查找测试（带错误处理）".to_string());
         
+        let params: HashMap<String, serde_json::Value> = 
+            serde_json::from_value(step.parameters.clone())?;
+        let template_path = params.get("template_path").and_then(|v| v.as_str()).filter(|p| !p.is_empty());
+        
+        // 图片按钮没有文本/描述，直接按截图模板定位
+        if let (Some(template_path), Some("template")) = (template_path, params.get("strategy").and_then(|v| v.as_str())) {
+            return self.find_element_by_template(template_path, &params, logs).await;
+        }
+        
         // 执行UI dump操作，用传统的重试逻辑
         let ui_dump = self.execute_ui_dump_with_retry(logs).await?;
         
-        let params: HashMap<String, serde_json::Value> = 
-            serde_json::from_value(step.parameters.clone())?;
+        // 步骤保存了定位器回退链时按顺序尝试
+        if let Some(result) = self.find_element_by_locators(&ui_dump, &params, logs).await? {
+            return Ok(result);
+        }
         
         // 记录查找参数
         logs.push("🎯 查找参数:".to_string());
@@ -365,6 +466,19 @@ impl SmartScriptExecutor {
             }
         }
         
+        // 当前屏未找到时按 `scroll` 参数滚动容器继续查找
+        if !element_found {
+            if let Some(result) = self.find_element_by_scrolling(&params, logs).await? {
+                return Ok(result);
+            }
+        }
+        
+        // XML 定位失败时退回截图模板匹配
+        if let (false, Some(template_path)) = (element_found, template_path) {
+            logs.push("🖼️ XML 定位失败，改用截图模板匹配".to_string());
+            return self.find_element_by_template(template_path, &params, logs).await;
+        }
+        
         // 无论是否找到元素，如果有坐标信息，都执行点击操作
         if let Some(bounds) = params.get("bounds") {
             logs.push(format!("  📍 元素边界: {}", bounds));
@@ -416,6 +530,110 @@ impl SmartScriptExecutor {
         }
     }
 
+    /// 滚动查找：`scroll: true` 使用默认参数，也可传 `{ direction, max_scrolls, scroll_back }`
+    ///
+    /// 未配置滚动或没有查找文本时返回 None；滚动后元素位置已变化，点击找到的节点中心而不是录制的 bounds
+    async fn find_element_by_scrolling(
+        &self,
+        params: &HashMap<String, serde_json::Value>,
+        logs: &mut Vec<String>,
+    ) -> Result<Option<String>> {
+        let options = match params.get("scroll") {
+            Some(serde_json::Value::Bool(true)) => ScrollOptions::default(),
+            Some(value @ serde_json::Value::Object(_)) => serde_json::from_value(value.clone())?,
+            _ => return Ok(None),
+        };
+        let text = params.get("element_text").and_then(|v| v.as_str()).filter(|t| !t.is_empty());
+        let desc = params.get("content_desc").and_then(|v| v.as_str()).filter(|t| !t.is_empty());
+        let Some(target) = text.or(desc) else {
+            return Ok(None);
+        };
+        
+        logs.push(format!("📜 当前屏未找到，滚动查找: {} (方向 {:?}, 最多 {} 次)", target, options.direction, options.max_scrolls));
+        let result = scroll_to_find(&self.device_id, Some(target), &options, |tree| {
+            tree.find_first(|n| {
+                text.map_or(false, |t| n.text().contains(t)) || desc.map_or(false, |d| n.content_desc().contains(d))
+            })
+            .and_then(|n| n.center())
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+        
+        let Some((x, y)) = result.found else {
+            let reason = if result.reached_end { "列表已到底" } else { "已达最大滚动次数" };
+            logs.push(format!("❌ 滚动 {} 次后仍未找到: {} ({})", result.scrolls, target, reason));
+            return Ok(None);
+        };
+        logs.push(format!("✅ 滚动 {} 次后找到元素: {} -> 坐标({}, {})", result.scrolls, target, x, y));
+        self.execute_click_with_retry(x, y, logs).await?;
+        Ok(Some(format!("✅ 滚动查找并点击元素: {} -> 坐标({}, {})", target, x, y)))
+    }
+
+    /// 按步骤参数 `locators`（排序后的定位器回退链）查找并点击；未配置或全部未命中时返回 None
+    async fn find_element_by_locators(
+        &self,
+        ui_dump: &str,
+        params: &HashMap<String, serde_json::Value>,
+        logs: &mut Vec<String>,
+    ) -> Result<Option<String>> {
+        let Some(value) = params.get("locators").filter(|v| v.is_array()) else {
+            return Ok(None);
+        };
+        let locators: Vec<ElementLocator> = serde_json::from_value(value.clone())?;
+        if locators.is_empty() {
+            return Ok(None);
+        }
+        
+        let tree = UiTree::parse(ui_dump)?;
+        let Some((node, locator)) = locate(&tree, &locators) else {
+            logs.push(format!("❌ {} 个定位器均未唯一命中元素，改用文本查找", locators.len()));
+            return Ok(None);
+        };
+        let Some((x, y)) = node.center() else {
+            return Ok(None);
+        };
+        logs.push(format!("✅ 定位器命中: {:?} (得分 {:.2}) -> 坐标({}, {})", locator.strategy, locator.score, x, y));
+        self.execute_click_with_retry(x, y, logs).await?;
+        Ok(Some(format!("✅ 通过定位器找到并点击元素: 坐标({}, {})", x, y)))
+    }
+
+    /// 截图模板匹配定位：返回 bounds 和置信度，默认点击匹配中心（`click: false` 时只定位）
+    async fn find_element_by_template(
+        &self,
+        template_path: &str,
+        params: &HashMap<String, serde_json::Value>,
+        logs: &mut Vec<String>,
+    ) -> Result<String> {
+        let mut options = TemplateMatchOptions::default();
+        if let Some(threshold) = params.get("threshold").and_then(|v| v.as_f64()) {
+            options.threshold = threshold as f32;
+        }
+        if let Some(scales) = params.get("scales").and_then(|v| v.as_array()) {
+            options.scales = scales.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect();
+        }
+        options.template_screen_width = params.get("template_screen_width").and_then(|v| v.as_u64()).map(|w| w as u32);
+        logs.push(format!("🖼️ 模板: {} (阈值 {:.2})", template_path, options.threshold));
+        
+        let screenshot = ScreenshotService::capture_to_dir(&self.device_id, std::path::Path::new("data/screenshots")).await;
+        let screenshot_path = screenshot
+            .screenshot_path
+            .ok_or_else(|| anyhow::anyhow!(screenshot.error.unwrap_or_else(|| "截图失败".to_string())))?;
+        let found = find_template_in_file(std::path::Path::new(&screenshot_path), std::path::Path::new(template_path), options).await;
+        let _ = std::fs::remove_file(&screenshot_path);
+        
+        let found = found?.ok_or_else(|| anyhow::anyhow!("截图中未找到模板: {}", template_path))?;
+        let (x, y) = found.center();
+        let b = &found.bounds;
+        logs.push(format!("✅ 模板匹配成功: bounds=[{},{}][{},{}], 置信度 {:.3}, 缩放 {:.2}",
+            b.left, b.top, b.right, b.bottom, found.confidence, found.scale));
+        
+        if params.get("click").and_then(|v| v.as_bool()).unwrap_or(true) {
+            self.execute_click_with_retry(x, y, logs).await?;
+            return Ok(format!("✅ 通过模板找到并点击元素: 坐标({}, {}), 置信度 {:.3}", x, y, found.confidence));
+        }
+        Ok(format!("✅ 通过模板找到元素: bounds=[{},{}][{},{}], 置信度 {:.3}", b.left, b.top, b.right, b.bottom, found.confidence))
+    }
+
     /// 批量匹配方法：动态查找元素，不使用预设坐标
     async fn test_batch_match(&self, step: &SmartScriptStep, logs: &mut Vec<String>) -> Result<String> {
         logs.push("🚀 执行批量匹配操作（动态元素查找）".to_string());
@@ -518,36 +736,21 @@ impl SmartScriptExecutor {
             return result;
         }
         
-        // 通用单个元素匹配逻辑
-        let text_pattern = format!(r#"text="[^"]*{}[^"]*""#, regex::escape(element_text));
-        let content_desc_pattern = format!(r#"content-desc="[^"]*{}[^"]*""#, regex::escape(element_text));
-        
-        let text_regex = regex::Regex::new(&text_pattern).unwrap_or_else(|_| {
-            logs.push(format!("⚠️  正则表达式编译失败: {}", text_pattern));
-            regex::Regex::new(r".*").unwrap()
-        });
-        
-        let content_desc_regex = regex::Regex::new(&content_desc_pattern).unwrap_or_else(|_| {
-            logs.push(format!("⚠️  正则表达式编译失败: {}", content_desc_pattern));
-            regex::Regex::new(r".*").unwrap()
-        });
+        let tree = UiTree::parse(ui_dump)?;
         
-        // 分行搜索UI dump
-        for (line_num, line) in ui_dump.lines().enumerate() {
-            // 检查是否包含目标文本 (text 属性)
-            if text_regex.is_match(line) {
-                logs.push(format!("✅ 在第{}行找到匹配的text属性", line_num + 1));
-                if let Some(coords) = self.extract_bounds_from_line(line, logs) {
-                    return Ok(Some(coords));
-                }
-            }
+        // 通用单个元素匹配逻辑：按文档顺序查找 text 或 content-desc 包含目标文本的节点
+        for node in tree.elements() {
+            let matched_attr = if node.text().contains(element_text) {
+                "text"
+            } else if node.content_desc().contains(element_text) {
+                "content-desc"
+            } else {
+                continue;
+            };
             
-            // 检查是否包含目标文本 (content-desc 属性)
-            if content_desc_regex.is_match(line) {
-                logs.push(format!("✅ 在第{}行找到匹配的content-desc属性", line_num + 1));
-                if let Some(coords) = self.extract_bounds_from_line(line, logs) {
-                    return Ok(Some(coords));
-                }
+            logs.push(format!("✅ 在节点#{}找到匹配的{}属性", node.id(), matched_attr));
+            if let Some(coords) = self.node_center(&node, logs) {
+                return Ok(Some(coords));
             }
         }
         
@@ -555,27 +758,19 @@ impl SmartScriptExecutor {
         Ok(None)
     }
 
-    /// 从UI dump行中提取bounds坐标
-    fn extract_bounds_from_line(&self, line: &str, logs: &mut Vec<String>) -> Option<(i32, i32)> {
-        // 使用正则表达式提取bounds属性
-        let bounds_regex = regex::Regex::new(r#"bounds="\[(\d+),(\d+)\]\[(\d+),(\d+)\]""#).ok()?;
-        
-        if let Some(captures) = bounds_regex.captures(line) {
-            let left: i32 = captures.get(1)?.as_str().parse().ok()?;
-            let top: i32 = captures.get(2)?.as_str().parse().ok()?;
-            let right: i32 = captures.get(3)?.as_str().parse().ok()?;
-            let bottom: i32 = captures.get(4)?.as_str().parse().ok()?;
-            
-            let center_x = (left + right) / 2;
-            let center_y = (top + bottom) / 2;
-            
-            logs.push(format!("📊 提取到bounds: [{},{}][{},{}] -> 中心点({},{})", 
-                left, top, right, bottom, center_x, center_y));
-            
-            Some((center_x, center_y))
-        } else {
-            logs.push("⚠️  该行未找到有效的bounds属性".to_string());
-            None
+    /// 计算节点中心坐标
+    fn node_center(&self, node: &UiNode, logs: &mut Vec<String>) -> Option<(i32, i32)> {
+        match node.bounds() {
+            Some(bounds) => {
+                let (center_x, center_y) = (bounds.center_x(), bounds.center_y());
+                logs.push(format!("📊 提取到bounds: [{},{}][{},{}] -> 中心点({},{})", 
+                    bounds.left, bounds.top, bounds.right, bounds.bottom, center_x, center_y));
+                Some((center_x, center_y))
+            }
+            None => {
+                logs.push("⚠️  该节点未找到有效的bounds属性".to_string());
+                None
+            }
         }
     }
 
@@ -586,80 +781,57 @@ impl SmartScriptExecutor {
         logs.push("🎯🎯🎯 [ENHANCED] 通用批量关注模式启动...".to_string());
         logs.push("🔍🔍🔍 [ENHANCED] 搜索策略：查找所有'关注'按钮，排除'已关注'按钮".to_string());
         
+        let tree = UiTree::parse(ui_dump)?;
         let mut candidates = Vec::new();
         
-        // 构建匹配模式
-        let follow_patterns = [
-            r#"text="关注""#,           // 精确匹配 "关注"
-            r#"text="[^"]*关注[^"]*""#,   // 包含关注的文本
-            r#"content-desc="[^"]*关注[^"]*""#, // content-desc中包含关注
-        ];
-        
-        // 排除模式 - 避免匹配"已关注"相关按钮
-        let exclude_patterns = [
-            r#"text="[^"]*已关注[^"]*""#,
-            r#"text="[^"]*取消关注[^"]*""#,
-            r#"text="[^"]*following[^"]*""#,  // 英文版已关注
-            r#"text="[^"]*unfollow[^"]*""#,   // 英文版取消关注
-            r#"content-desc="[^"]*已关注[^"]*""#,
-            r#"content-desc="[^"]*following[^"]*""#,
-        ];
-        
-        logs.push(format!("🔍 开始扫描UI dump，共{}行", ui_dump.lines().count()));
-        info!("🔍 开始扫描UI dump，共{}行", ui_dump.lines().count());
-        
-        for (line_num, line) in ui_dump.lines().enumerate() {
-            // 首先检查是否匹配排除模式
-            let mut should_exclude = false;
-            for exclude_pattern in &exclude_patterns {
-                if let Ok(regex) = regex::Regex::new(exclude_pattern) {
-                    if regex.is_match(line) {
-                        logs.push(format!("❌ 第{}行被排除: 包含已关注相关文本", line_num + 1));
-                        should_exclude = true;
-                        break;
-                    }
-                }
+        // 排除关键字 - 避免匹配"已关注"相关按钮
+        let exclude_text = ["已关注", "取消关注", "following", "unfollow"];
+        let exclude_desc = ["已关注", "following"];
+        
+        logs.push(format!("🔍 开始扫描UI树，共{}个节点", tree.len()));
+        info!("🔍 开始扫描UI树，共{}个节点", tree.len());
+        
+        for node in tree.elements() {
+            let text = node.text();
+            let desc = node.content_desc();
+            
+            // 首先检查是否匹配排除关键字
+            if exclude_text.iter().any(|k| text.contains(k)) || exclude_desc.iter().any(|k| desc.contains(k)) {
+                logs.push(format!("❌ 节点#{}被排除: 包含已关注相关文本", node.id()));
+                continue;
             }
             
-            if should_exclude {
+            // 优先级: 精确匹配 > 文本包含 > content-desc
+            let priority = if text == "关注" {
+                1
+            } else if text.contains("关注") {
+                2
+            } else if desc.contains("关注") {
+                3
+            } else {
+                continue;
+            };
+            
+            // 进一步验证是否为可点击按钮
+            if !node.is_clickable() {
+                logs.push(format!("⚠️  节点#{}匹配但不可点击，跳过", node.id()));
                 continue;
             }
             
-            // 检查是否匹配关注模式
-            for (pattern_idx, pattern) in follow_patterns.iter().enumerate() {
-                if let Ok(regex) = regex::Regex::new(pattern) {
-                    if regex.is_match(line) {
-                        // 进一步验证是否为可点击按钮
-                        if line.contains(r#"clickable="true""#) {
-                            info!("✅ 第{}行匹配模式{}: 找到可点击关注按钮", line_num + 1, pattern_idx + 1);
-                            logs.push(format!("✅ 第{}行匹配模式{}: 找到可点击关注按钮", line_num + 1, pattern_idx + 1));
-                            
-                            if let Some(coords) = self.extract_bounds_from_line(line, logs) {
-                                // 优先级: 精确匹配 > 文本包含 > content-desc
-                                let priority = match pattern_idx {
-                                    0 => 1, // 精确匹配 "关注"
-                                    1 => 2, // 文本包含关注
-                                    2 => 3, // content-desc包含关注
-                                    _ => 4,
-                                };
-                                
-                                // 记录候选按钮的详细信息
-                                logs.push(format!("📍 候选按钮 {}: 坐标({}, {}), 优先级{}", 
-                                    candidates.len() + 1, coords.0, coords.1, priority));
-                                
-                                candidates.push((coords, priority, line_num + 1, line.to_string()));
-                            }
-                        } else {
-                            logs.push(format!("⚠️  第{}行匹配但不可点击，跳过", line_num + 1));
-                        }
-                        break; // 找到一个匹配就跳出pattern循环
-                    }
-                }
+            info!("✅ 节点#{}匹配优先级{}: 找到可点击关注按钮", node.id(), priority);
+            logs.push(format!("✅ 节点#{}匹配优先级{}: 找到可点击关注按钮", node.id(), priority));
+            
+            if let Some(coords) = self.node_center(&node, logs) {
+                // 记录候选按钮的详细信息
+                logs.push(format!("📍 候选按钮 {}: 坐标({}, {}), 优先级{}", 
+                    candidates.len() + 1, coords.0, coords.1, priority));
+                
+                candidates.push((coords, priority, node));
             }
         }
         
         // 按优先级排序选择最佳候选
-        candidates.sort_by_key(|&(_, priority, _, _)| priority);
+        candidates.sort_by_key(|&(_, priority, _)| priority);
         
         if candidates.is_empty() {
             info!("❌ 未找到任何可用的关注按钮");
@@ -672,17 +844,16 @@ impl SmartScriptExecutor {
         logs.push(format!("🎯 共找到{}个关注按钮候选", candidates.len()));
         
         // 列出所有候选按钮信息
-        for (idx, (coords, priority, line_num, _)) in candidates.iter().enumerate() {
-            logs.push(format!("  📋 候选{}: 第{}行, 坐标({}, {}), 优先级{}", 
-                idx + 1, line_num, coords.0, coords.1, priority));
+        for (idx, (coords, priority, node)) in candidates.iter().enumerate() {
+            logs.push(format!("  📋 候选{}: 节点#{}, 坐标({}, {}), 优先级{}", 
+                idx + 1, node.id(), coords.0, coords.1, priority));
         }
         
         // 选择优先级最高的候选
-        let (best_coords, best_priority, best_line, best_content) = &candidates[0];
-        logs.push(format!("✅ 选择最佳关注按钮: 第{}行，优先级{}，坐标({}, {})", 
-            best_line, best_priority, best_coords.0, best_coords.1));
-        logs.push(format!("📝 按钮内容预览: {}", 
-            best_content.chars().take(100).collect::<String>()));
+        let (best_coords, best_priority, best_node) = &candidates[0];
+        logs.push(format!("✅ 选择最佳关注按钮: 节点#{}，优先级{}，坐标({}, {})", 
+            best_node.id(), best_priority, best_coords.0, best_coords.1));
+        logs.push(format!("📝 按钮内容预览: {:?}", best_node));
         
         // 最终验证坐标的合理性
         if best_coords.0 <= 0 || best_coords.1 <= 0 || best_coords.0 > 2000 || best_coords.1 > 3000 {
@@ -740,10 +911,14 @@ impl SmartScriptExecutor {
         Err(last_error.unwrap_or_else(|| anyhow::anyhow!("UI dump 获取失败")))
     }
 
-    /// 尝试执行 UI dump
+    /// 尝试执行 UI dump（同一界面在输入操作前复用缓存）
     async fn try_ui_dump(&self) -> Result<String> {
-        let session = get_device_session(&self.device_id).await?;
-        session.execute_command("uiautomator dump /sdcard/ui_dump.xml && cat /sdcard/ui_dump.xml").await
+        get_ui_dump_cache()
+            .get_or_dump(&self.device_id, || async {
+                let session = get_device_session(&self.device_id).await?;
+                session.execute_command("uiautomator dump /sdcard/ui_dump.xml && cat /sdcard/ui_dump.xml").await
+            })
+            .await
     }
 
     /// 带重试机制的点击执行
@@ -751,6 +926,7 @@ impl SmartScriptExecutor {
         logs.push("👆 开始执行点击操作（带重试机制）...".to_string());
         
         let command = format!("input tap {} {}", x, y);
+        self.record_tap(x, y);
         let max_retries = 2;
         let mut last_error: Option<anyhow::Error> = None;
         
@@ -778,6 +954,27 @@ impl SmartScriptExecutor {
         Err(last_error.unwrap_or_else(|| anyhow::anyhow!("点击操作失败")))
     }
 
+    /// 点击前记录目标：用缓存中的点击前界面定位命中的元素，不额外 dump
+    fn record_tap(&self, x: i32, y: i32) {
+        let xml_before = get_ui_dump_cache().peek(Some(&self.device_id));
+        *self.last_tap.lock().unwrap() = Some(TapTarget::before_tap(xml_before.as_deref(), x, y));
+    }
+
+    /// 截取当前屏幕并标注元素框，突出显示本步骤点击的元素，返回证据截图路径
+    async fn capture_step_evidence(&self, step_id: &str, logs: &mut Vec<String>) -> Option<String> {
+        let tap = self.last_tap.lock().unwrap().clone();
+        match capture_step_evidence(&self.device_id, step_id, tap.as_ref()).await {
+            Ok(path) => {
+                logs.push(format!("🖍️ 已保存标注截图: {}", path));
+                Some(path)
+            }
+            Err(e) => {
+                logs.push(format!("⚠️ 保存标注截图失败: {}", e));
+                None
+            }
+        }
+    }
+
     /// 尝试执行点击
     async fn try_click(&self, command: &str) -> Result<String> {
         let session = get_device_session(&self.device_id).await?;
@@ -797,6 +994,26 @@ impl SmartScriptExecutor {
     async fn test_recognize_page(&self, step: &SmartScriptStep, logs: &mut Vec<String>) -> Result<String> {
         logs.push("执行页面识别测试".to_string());
         
+        let params: HashMap<String, serde_json::Value> = 
+            serde_json::from_value(step.parameters.clone())?;
+        let expected_page = params.get("expected_page").and_then(|v| v.as_str());
+        let registry = get_page_registry();
+        
+        // 已注册的命名页面：按结构指纹匹配
+        if let Some(name) = expected_page {
+            if registry.get(name).await.is_some() {
+                let fingerprint = capture_fingerprint(&self.device_id).await.map_err(|e| anyhow::anyhow!(e))?;
+                if let Some(result) = registry.match_named(name, &fingerprint).await {
+                    logs.push(format!("🧬 页面指纹匹配 '{}': 相似度 {:.2}", name, result.score));
+                    return if result.matched {
+                        Ok("页面识别成功".to_string())
+                    } else {
+                        Err(anyhow::anyhow!("当前页面不是 '{}'（相似度 {:.2}）", name, result.score))
+                    };
+                }
+            }
+        }
+        
         let session = get_device_session(&self.device_id).await?;
         
         // 获取当前Activity
@@ -804,12 +1021,9 @@ impl SmartScriptExecutor {
         logs.push(format!("当前Activity: {}", current_activity.trim()));
         
         // 获取UI结构进行页面识别
-        let ui_dump = session.execute_command("uiautomator dump /sdcard/ui_dump.xml && cat /sdcard/ui_dump.xml").await?;
+        let ui_dump = self.try_ui_dump().await?;
         
-        let params: HashMap<String, serde_json::Value> = 
-            serde_json::from_value(step.parameters.clone())?;
-        
-        if let Some(expected_page) = params.get("expected_page").and_then(|v| v.as_str()) {
+        if let Some(expected_page) = expected_page {
             if ui_dump.contains(expected_page) || current_activity.contains(expected_page) {
                 logs.push(format!("成功识别页面: {}", expected_page));
                 Ok("页面识别成功".to_string())
@@ -818,10 +1032,133 @@ impl SmartScriptExecutor {
                 Ok("页面识别完成，但未匹配预期".to_string())
             }
         } else {
+            // 未指定页面时，尝试用已注册的页面指纹识别
+            if let Ok(tree) = UiTree::parse(&ui_dump) {
+                let fingerprint = PageFingerprint::from_tree(&tree, parse_focused_activity(&current_activity));
+                match registry.identify(&fingerprint).await {
+                    Some(result) => logs.push(format!("🧬 识别为已注册页面 '{}'（相似度 {:.2}）", result.name, result.score)),
+                    None => logs.push("🧬 未匹配任何已注册页面".to_string()),
+                }
+            }
             Ok("页面识别测试完成".to_string())
         }
     }
 
+    /// 等待页面状态：已注册页面按指纹轮询，否则轮询页面文本/Activity
+    async fn test_wait_for_page_state(&self, step: &SmartScriptStep, logs: &mut Vec<String>) -> Result<String> {
+        let params: HashMap<String, serde_json::Value> = 
+            serde_json::from_value(step.parameters.clone())?;
+        let page = params
+            .get("page")
+            .or_else(|| params.get("expected_page"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("缺少参数: page"))?;
+        let timeout = std::time::Duration::from_millis(params.get("timeout_ms").and_then(|v| v.as_u64()).unwrap_or(10000));
+        let interval = std::time::Duration::from_millis(params.get("interval_ms").and_then(|v| v.as_u64()).unwrap_or(1000));
+        logs.push(format!("⏳ 等待页面 '{}'（超时 {:?}）", page, timeout));
+        
+        if get_page_registry().get(page).await.is_some() {
+            let result = wait_for_page(&self.device_id, page, timeout, interval)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+            logs.push(format!("🧬 页面指纹匹配 '{}': 相似度 {:.2}", page, result.score));
+            return if result.matched {
+                Ok(format!("已进入页面: {}", page))
+            } else {
+                Err(anyhow::anyhow!("等待页面 '{}' 超时（最后相似度 {:.2}）", page, result.score))
+            };
+        }
+        
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let ui_dump = self.try_ui_dump().await.unwrap_or_default();
+            let activity = current_activity(&self.device_id).await.unwrap_or_default();
+            if ui_dump.contains(page) || activity.contains(page) {
+                logs.push(format!("✅ 页面文本/Activity 包含 '{}'", page));
+                return Ok(format!("已进入页面: {}", page));
+            }
+            if tokio::time::Instant::now() + interval > deadline {
+                return Err(anyhow::anyhow!("等待页面 '{}' 超时", page));
+            }
+            tokio::time::sleep(interval).await;
+            get_ui_dump_cache().invalidate(Some(&self.device_id));
+        }
+    }
+
+    /// 按应用导航图从当前页面导航到目标页面
+    async fn test_smart_navigation(&self, step: &SmartScriptStep, logs: &mut Vec<String>) -> Result<String> {
+        let params: HashMap<String, serde_json::Value> = 
+            serde_json::from_value(step.parameters.clone())?;
+        let target = params
+            .get("target_page")
+            .or_else(|| params.get("page"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("缺少参数: target_page"))?;
+        // 未指定包名时使用目标页面指纹所属的应用
+        let package = match params.get("package").and_then(|v| v.as_str()) {
+            Some(package) => package.to_string(),
+            None => get_page_registry()
+                .get(target)
+                .await
+                .map(|s| s.fingerprint.package)
+                .ok_or_else(|| anyhow::anyhow!("未注册的页面: {}", target))?,
+        };
+        let settle = std::time::Duration::from_millis(params.get("settle_ms").and_then(|v| v.as_u64()).unwrap_or(1500));
+        logs.push(format!("🧭 智能导航: {} → '{}'", package, target));
+        
+        let outcome = navigate_to(&self.device_id, &package, target, settle)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        logs.extend(outcome.logs);
+        if outcome.success {
+            Ok(outcome.message)
+        } else {
+            Err(anyhow::anyhow!(outcome.message))
+        }
+    }
+
+    /// 记录点击步骤引起的页面跳转
+    async fn record_navigation_step(&self, step: &SmartScriptStep, before: &PageObservation, logs: &mut Vec<String>) {
+        let Some(from) = before.page.as_deref() else { return };
+        let (Some(x), Some(y)) = (
+            step.parameters.get("x").and_then(|v| v.as_i64()),
+            step.parameters.get("y").and_then(|v| v.as_i64()),
+        ) else {
+            return;
+        };
+        // 等待跳转后的界面稳定再识别，避免识别到过渡动画中的页面
+        settle(&self.device_id, std::time::Duration::from_secs(3)).await;
+        let Ok(after) = observe_page(&self.device_id).await else { return };
+        let Some(to) = after.page.as_deref() else { return };
+        if from == to || after.fingerprint.package != before.fingerprint.package {
+            return;
+        }
+        let Ok(tree) = before.tree() else { return };
+        let action = tap_action_at(&tree, x as i32, y as i32);
+        match record_transition(&before.fingerprint.package, from, to, action).await {
+            Ok(true) => logs.push(format!("🗺️ 导航图已记录跳转: {} → {}", from, to)),
+            Ok(false) => {}
+            Err(e) => warn!("记录导航跳转失败: {}", e),
+        }
+    }
+
+    /// 步骤失败后按导航图回到 `recovery_page` 参数指定的页面
+    async fn recover_to_page(&self, step: &SmartScriptStep, logs: &mut Vec<String>) {
+        let Some(page) = step.parameters.get("recovery_page").and_then(|v| v.as_str()) else { return };
+        let Some(signature) = get_page_registry().get(page).await else {
+            logs.push(format!("⚠️ 恢复页面未注册: {}", page));
+            return;
+        };
+        logs.push(format!("🛟 尝试恢复到页面: {}", page));
+        match navigate_to(&self.device_id, &signature.fingerprint.package, page, std::time::Duration::from_millis(1500)).await {
+            Ok(outcome) => {
+                logs.extend(outcome.logs);
+                logs.push(format!("{} {}", if outcome.success { "✅" } else { "⚠️" }, outcome.message));
+            }
+            Err(e) => logs.push(format!("⚠️ 恢复失败: {}", e)),
+        }
+    }
+
     async fn test_contact_generate_vcf(&self, step: &SmartScriptStep, logs: &mut Vec<String>) -> Result<String> {
         logs.push("🗂️ 开始VCF文件生成测试".to_string());
         
@@ -887,8 +1224,11 @@ impl SmartScriptExecutor {
                     std::fs::create_dir_all(parent)?;
                 }
                 
-                // 调用VCF生成服务
-                match VcfImporter::generate_vcf_file(contacts, &output_path).await {
+                // 调用VCF生成服务（vcard_version 可选 2.1/3.0/4.0，默认 2.1）
+                let version = params.get("vcard_version")
+                    .and_then(|v| serde_json::from_value::<VCardVersion>(v.clone()).ok())
+                    .unwrap_or_default();
+                match VcfImporter::generate_vcf_file_with_version(contacts, &output_path, version).await {
                     Ok(_) => {
                         logs.push(format!("✅ VCF文件生成成功: {}", output_path));
                         Ok(format!("VCF文件生成成功: {}", output_path))
@@ -1012,6 +1352,54 @@ impl SmartScriptExecutor {
         }
     }
 
+    /// 应用包管理步骤：安装/升级、卸载、清除数据、预授权
+    async fn test_app_package(&self, step: &SmartScriptStep, logs: &mut Vec<String>) -> Result<String> {
+        let params = &step.parameters;
+        let package_name = params.get("package_name").and_then(|v| v.as_str()).unwrap_or("");
+        let string_list = |key: &str| -> Vec<String> {
+            params.get(key)
+                .and_then(|v| v.as_array())
+                .map(|items| items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                .unwrap_or_default()
+        };
+        let manager = AppPackageManager::new(self.device_id.clone());
+
+        let result = match step.step_type {
+            SmartActionType::AppInstall => {
+                let apk_path = params.get("apk_path").and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("缺少参数 apk_path"))?;
+                let mut options: InstallOptions = serde_json::from_value(params.clone()).unwrap_or_default();
+                if options.grant_permissions.is_empty() {
+                    options.grant_permissions = string_list("permissions");
+                }
+                logs.push(format!("📦 安装APK: {}", apk_path));
+                install_apk_on_device(&self.device_id, apk_path, &options).await
+            }
+            _ if package_name.is_empty() => return Err(anyhow::anyhow!("缺少参数 package_name")),
+            SmartActionType::AppUninstall => {
+                let keep_data = params.get("keep_data").and_then(|v| v.as_bool()).unwrap_or(false);
+                logs.push(format!("🗑️ 卸载应用: {}", package_name));
+                manager.uninstall(package_name, keep_data).await
+            }
+            SmartActionType::AppClearData => {
+                logs.push(format!("🧽 清除应用数据: {}", package_name));
+                manager.clear_data(package_name).await
+            }
+            _ => {
+                let permissions = string_list("permissions");
+                logs.push(format!("🔐 预授予权限: {} ({}项)", package_name, permissions.len()));
+                manager.grant_permissions(package_name, &permissions).await
+            }
+        };
+
+        logs.push(format!("{} {} [{}] {}", if result.success { "✅" } else { "❌" }, result.package_name, result.action, result.message));
+        if result.success {
+            Ok(result.message)
+        } else {
+            Err(anyhow::anyhow!(result.message))
+        }
+    }
+
     /// 执行智能脚本（批量执行多个步骤）
     pub async fn execute_smart_script(&self, steps: Vec<SmartScriptStep>, config: Option<SmartExecutorConfig>) -> Result<SmartExecutionResult> {
         let start_time = std::time::Instant::now();
@@ -1020,6 +1408,7 @@ impl SmartScriptExecutor {
         let mut failed_steps = 0u32;
         let skipped_steps = 0u32;
         let mut extracted_data = HashMap::new();
+        let mut popup_firings = Vec::new();
         
         // 默认配置
         let config = config.unwrap_or(SmartExecutorConfig {
@@ -1027,10 +1416,35 @@ impl SmartScriptExecutor {
             auto_verification_enabled: true,
             smart_recovery_enabled: true,
             detailed_logging: true,
+            record_screen: false,
+            record_navigation: false,
+            popup_watchers: PopupWatcherSettings::default(),
         });
+        // 执行期间应用检测器等内部弹窗检查也使用脚本的监视器配置
+        let _popup_scope = get_popup_watchers().scoped_settings(&self.device_id, config.popup_watchers.clone());
 
         info!("🚀 开始批量执行智能脚本，总共 {} 个步骤", steps.len());
         logs.push(format!("🚀 开始批量执行智能脚本，总共 {} 个步骤", steps.len()));
+
+        // 0. 设备健康检查：不健康的设备拒绝启动任务
+        if let Err(e) = get_health_monitor().ensure_job_can_start(&self.device_id).await {
+            logs.push(format!("🩺 设备健康检查未通过: {}", e));
+            return Ok(SmartExecutionResult {
+                success: false,
+                total_steps: steps.len() as u32,
+                executed_steps: 0,
+                failed_steps: 0,
+                skipped_steps: steps.len() as u32,
+                duration_ms: start_time.elapsed().as_millis() as u64,
+                logs,
+                final_page_state: None,
+                extracted_data: HashMap::new(),
+                message: e,
+                logcat_path: None,
+                recordings: Vec::new(),
+                popup_firings: Vec::new(),
+            });
+        }
         
         // 详细记录每个传入步骤的信息
         info!("📋 前端发送的完整脚本步骤详情:");
@@ -1075,6 +1489,9 @@ impl SmartScriptExecutor {
                     final_page_state: None,
                     extracted_data: HashMap::new(),
                     message: format!("控制流预处理失败: {}", e),
+                    logcat_path: None,
+                    recordings: Vec::new(),
+                    popup_firings: Vec::new(),
                 });
             }
         };
@@ -1082,6 +1499,50 @@ impl SmartScriptExecutor {
         // 2. 使用预处理后的步骤进行执行
         let enabled_steps = processed_steps;
 
+        // 3. 设备前置条件：唤醒、解锁并在执行期间保持常亮
+        match get_device_preconditions().prepare(&self.device_id).await {
+            Ok(report) => logs.extend(report.logs),
+            Err(e) => {
+                logs.push(format!("🔒 设备前置条件未满足: {}", e));
+                return Ok(SmartExecutionResult {
+                    success: false,
+                    total_steps: enabled_steps.len() as u32,
+                    executed_steps: 0,
+                    failed_steps: 0,
+                    skipped_steps: enabled_steps.len() as u32,
+                    duration_ms: start_time.elapsed().as_millis() as u64,
+                    logs,
+                    final_page_state: None,
+                    extracted_data: HashMap::new(),
+                    message: e,
+                    logcat_path: None,
+                    recordings: Vec::new(),
+                    popup_firings: Vec::new(),
+                });
+            }
+        }
+
+        // 4. 采集本次执行期间的 logcat，便于排查应用崩溃或风控提示
+        // 只截取脚本操作的应用的日志（取步骤参数中第一个包名）
+        let target_package = enabled_steps.iter().find_map(|step| {
+            ["package_name", "package"]
+                .iter()
+                .find_map(|key| step.parameters.get(key).and_then(|v| v.as_str()))
+                .filter(|p| !p.is_empty())
+                .map(str::to_string)
+        });
+        let logcat_capture = get_logcat_collector()
+            .begin_capture(&self.device_id, target_package.as_deref())
+            .await;
+
+        // 5. 可选：录制执行过程，便于回看失败的完整过程
+        let recording = if config.record_screen {
+            logs.push("🎥 已开启执行录屏".to_string());
+            Some(get_screen_recorder().start(&self.device_id))
+        } else {
+            None
+        };
+
         logs.push(format!("📋 已启用的步骤: {} 个", enabled_steps.len()));
         
         // 执行每个步骤
@@ -1104,13 +1565,44 @@ impl SmartScriptExecutor {
             info!("{}", detailed_info);
             logs.push(detailed_info);
 
+            // 记录导航图时，先识别点击前的页面
+            let before_page = if config.record_navigation && matches!(step.step_type, SmartActionType::Tap) {
+                observe_page(&self.device_id).await.ok()
+            } else {
+                None
+            };
+
+            // 执行前先关闭遮挡界面的弹窗
+            if step_touches_ui(&step.step_type) {
+                self.dismiss_popups(&config.popup_watchers, &step.id, &mut popup_firings, &mut logs).await;
+            }
+
             // 执行单个步骤
-            match self.execute_single_step(step.clone()).await {
+            let mut step_result = self.execute_single_step(step.clone()).await;
+
+            // 执行过程中也可能弹出新的弹窗：失败后再检查一次，
+            // 查找类步骤失败多半是弹窗挡住了目标，关闭弹窗后重试一次
+            let step_failed = !matches!(&step_result, Ok(result) if result.success);
+            if step_failed && step_touches_ui(&step.step_type) {
+                let dismissed = self.dismiss_popups(&config.popup_watchers, &step.id, &mut popup_firings, &mut logs).await;
+                if dismissed > 0 && is_find_step(&step.step_type) && step_result.is_ok() {
+                    if let Ok(failed) = &step_result {
+                        logs.extend(failed.logs.iter().cloned());
+                    }
+                    logs.push(format!("🔁 已关闭弹窗，重试步骤: {}", step.name));
+                    step_result = self.execute_single_step(step.clone()).await;
+                }
+            }
+
+            match step_result {
                 Ok(result) => {
                     if result.success {
                         executed_steps += 1;
                         logs.push(format!("✅ 步骤成功: {} (耗时: {}ms)", 
                             step.name, step_start.elapsed().as_millis()));
+                        if let Some(before) = &before_page {
+                            self.record_navigation_step(step, before, &mut logs).await;
+                        }
                         
                         // 合并提取的数据
                         for (key, value) in result.extracted_data {
@@ -1120,6 +1612,11 @@ impl SmartScriptExecutor {
                         failed_steps += 1;
                         logs.push(format!("❌ 步骤失败: {} - {}", step.name, result.message));
                         
+                        // 继续执行时，先按导航图回到步骤指定的恢复页面
+                        if config.continue_on_error && config.smart_recovery_enabled {
+                            self.recover_to_page(step, &mut logs).await;
+                        }
+                        
                         // 如果不继续执行错误，则中断
                         if !config.continue_on_error {
                             logs.push("⏸️ 遇到错误，停止执行后续步骤".to_string());
@@ -1150,6 +1647,20 @@ impl SmartScriptExecutor {
             }
         }
 
+        // 恢复任务开始前的常亮设置
+        get_device_preconditions().release(&self.device_id).await;
+        let logcat_path = get_logcat_collector().end_capture(logcat_capture).await;
+        if let Some(path) = &logcat_path {
+            logs.push(format!("📜 执行期间logcat已保存: {}", path));
+        }
+        let recordings = match recording {
+            Some(recording) => get_screen_recorder().stop(recording).await,
+            None => Vec::new(),
+        };
+        if !recordings.is_empty() {
+            logs.push(format!("🎥 执行录屏已保存: {} 个分段", recordings.len()));
+        }
+
         let total_duration = start_time.elapsed().as_millis() as u64;
         let success = failed_steps == 0 && executed_steps > 0;
 
@@ -1173,11 +1684,31 @@ impl SmartScriptExecutor {
             final_page_state: None,
             extracted_data,
             message,
+            logcat_path,
+            recordings,
+            popup_firings,
         };
 
         Ok(result)
     }
 
+    /// 运行弹窗监视器并把触发记录写入日志，返回关闭的弹窗数
+    async fn dismiss_popups(
+        &self,
+        settings: &PopupWatcherSettings,
+        step_id: &str,
+        firings: &mut Vec<PopupFiring>,
+        logs: &mut Vec<String>,
+    ) -> usize {
+        let fired = get_popup_watchers().check_and_dismiss(&self.device_id, settings, Some(step_id)).await;
+        for firing in &fired {
+            logs.push(format!("🧹 弹窗监视器[{}]已关闭弹窗: {}", firing.watcher_name, firing.action));
+        }
+        let count = fired.len();
+        firings.extend(fired);
+        count
+    }
+
     async fn execute_adb_command(&self, args: &[&str]) -> Result<std::process::Output> {
         let mut cmd = std::process::Command::new(&self.adb_path);
         cmd.args(args);
@@ -1189,6 +1720,29 @@ impl SmartScriptExecutor {
     }
 }
 
+/// 需要操作界面的步骤（执行前及失败后检查弹窗）
+fn step_touches_ui(step_type: &SmartActionType) -> bool {
+    !matches!(
+        step_type,
+        SmartActionType::Wait
+            | SmartActionType::LoopStart
+            | SmartActionType::LoopEnd
+            | SmartActionType::ContactGenerateVcf
+            | SmartActionType::AppInstall
+            | SmartActionType::AppUninstall
+            | SmartActionType::AppClearData
+            | SmartActionType::AppGrantPermissions
+    )
+}
+
+/// 按元素查找的步骤（失败后检查弹窗并重试）
+fn is_find_step(step_type: &SmartActionType) -> bool {
+    matches!(
+        step_type,
+        SmartActionType::SmartTap | SmartActionType::SmartFindElement | SmartActionType::BatchMatch
+    )
+}
+
 #[tauri::command]
 pub async fn execute_single_step_test(
     device_id: String,
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tokio::process::Command as AsyncCommand;
+use std::time::Duration;
+use crate::services::ui_idle::{settle, wait_until_stable, IdleOptions};
+use crate::services::ui_tree::UiTree;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VcfOpenResult {
@@ -37,7 +40,7 @@ pub async fn smart_vcf_opener(device_id: String) -> Result<VcfOpenResult, String
             Ok(result) => result,
             Err(e) => {
                 println!("❌ 执行操作失败: {}", e);
-                tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+                settle(&device_id, Duration::from_secs(2)).await;
                 continue;
             }
         };
@@ -54,8 +57,7 @@ pub async fn smart_vcf_opener(device_id: String) -> Result<VcfOpenResult, String
             });
         }
         
-        // 等待UI更新
-        tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+        // 下一轮获取UI时会等待界面稳定，无需固定等待
     }
     
     Err(format!("超过最大尝试次数 ({})，操作未完成", MAX_ATTEMPTS))
@@ -67,46 +69,13 @@ struct ActionResult {
     is_complete: bool,
 }
 
-/// 获取当前UI状态
+/// 获取当前UI状态（等待界面稳定后的dump）
 async fn get_current_ui_state(device_id: &str) -> Result<String, String> {
-    // 刷新UI dump
-    let mut dump_cmd = AsyncCommand::new("adb");
-    dump_cmd.args(&["-s", device_id, "shell", "uiautomator", "dump"]);
-    
-    #[cfg(windows)]
-    {
-        dump_cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-    
-    let _ = dump_cmd.output().await;
-    
-    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-    
-    // 读取UI XML
-    let mut read_cmd = AsyncCommand::new("adb");
-    read_cmd.args(&["-s", device_id, "shell", "cat", "/sdcard/window_dump.xml"]);
-    
-    #[cfg(windows)]
-    {
-        read_cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-    }
-    
-    let result = read_cmd.output()
-        .await
-        .map_err(|e| format!("执行adb命令失败: {}", e))?;
-    
-    if !result.status.success() {
-        let error = String::from_utf8_lossy(&result.stderr);
-        return Err(format!("读取UI失败: {}", error));
-    }
-    
-    let xml_content = String::from_utf8_lossy(&result.stdout).to_string();
-    
-    if xml_content.trim().is_empty() {
-        return Err("UI内容为空".to_string());
+    let result = wait_until_stable(device_id, &IdleOptions::with_timeout(Duration::from_secs(5))).await?;
+    if !result.stable {
+        println!("⚠️ 界面在 {}ms 内未稳定，使用最后一次UI", result.elapsed_ms);
     }
-    
-    Ok(xml_content)
+    Ok(result.xml)
 }
 
 /// 分析UI状态并执行相应操作
@@ -283,50 +252,13 @@ async fn click_coordinates(device_id: &str, x: i32, y: i32) -> Result<(), String
 
 /// 从XML中查找文本的坐标
 fn find_text_coordinates(xml_content: &str, text: &str) -> Result<(i32, i32), String> {
-    // 查找包含指定文本的node
-    for line in xml_content.lines() {
-        if line.contains(&format!("text=\"{}\"", text)) && line.contains("bounds=") {
-            if let Some(bounds_start) = line.find("bounds=\"") {
-                let bounds_start = bounds_start + 8;
-                if let Some(bounds_end) = line[bounds_start..].find('"') {
-                    let bounds_str = &line[bounds_start..bounds_start + bounds_end];
-                    return parse_bounds_to_center(bounds_str);
-                }
-            }
-        }
-    }
-    
-    Err(format!("未找到文本: {}", text))
-}
-
-/// 解析bounds字符串并返回中心坐标
-fn parse_bounds_to_center(bounds_str: &str) -> Result<(i32, i32), String> {
-    // bounds格式: "[left,top][right,bottom]"
-    let parts: Vec<&str> = bounds_str.split("][").collect();
+    let tree = UiTree::parse(xml_content).map_err(|e| e.to_string())?;
     
-    if parts.len() != 2 {
-        return Err("bounds格式错误".to_string());
-    }
-    
-    let left_top = parts[0].trim_start_matches('[');
-    let right_bottom = parts[1].trim_end_matches(']');
-    
-    let left_top_coords: Vec<i32> = left_top.split(',')
-        .map(|s| s.parse().unwrap_or(0))
-        .collect();
-    
-    let right_bottom_coords: Vec<i32> = right_bottom.split(',')
-        .map(|s| s.parse().unwrap_or(0))
-        .collect();
-    
-    if left_top_coords.len() != 2 || right_bottom_coords.len() != 2 {
-        return Err("坐标解析错误".to_string());
-    }
-    
-    let center_x = (left_top_coords[0] + right_bottom_coords[0]) / 2;
-    let center_y = (left_top_coords[1] + right_bottom_coords[1]) / 2;
-    
-    Ok((center_x, center_y))
+    // 查找包含指定文本的node
+    tree.find_first(|n| n.text() == text)
+        .ok_or_else(|| format!("未找到文本: {}", text))?
+        .center()
+        .ok_or_else(|| "bounds格式错误".to_string())
 }
 
 /// 打开文件管理器
@@ -0,0 +1,497 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::screenshot_service::ScreenshotService;
+use crate::types::page_analysis::ElementBounds;
+
+const TEMPLATE_DIR: &str = "data/templates";
+
+/// 粗搜索时缩小后的模板最短边不少于该像素数
+const MIN_COARSE_SIDE: usize = 12;
+
+/// 粗搜索阶段保留的候选位置数
+const COARSE_CANDIDATES: usize = 3;
+
+/// RGBA8 图像
+pub struct RgbaImage {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// 解码 PNG，统一转换为 8 位 RGBA
+    pub fn from_png(bytes: &[u8]) -> Result<Self> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().context("PNG 头解析失败")?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).context("PNG 解码失败")?;
+        let (width, height) = (info.width as usize, info.height as usize);
+
+        let channels = match info.color_type {
+            png::ColorType::Grayscale => 1,
+            png::ColorType::GrayscaleAlpha => 2,
+            png::ColorType::Rgb => 3,
+            png::ColorType::Rgba => 4,
+            other => return Err(anyhow!("不支持的 PNG 颜色类型: {:?}", other)),
+        };
+        let mut data = Vec::with_capacity(width * height * 4);
+        for row in buf[..info.buffer_size()].chunks(info.line_size) {
+            for px in row[..width * channels].chunks(channels) {
+                match channels {
+                    1 => data.extend_from_slice(&[px[0], px[0], px[0], 255]),
+                    2 => data.extend_from_slice(&[px[0], px[0], px[0], px[1]]),
+                    3 => data.extend_from_slice(&[px[0], px[1], px[2], 255]),
+                    _ => data.extend_from_slice(px),
+                }
+            }
+        }
+        Ok(Self { width, height, data })
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("读取图片失败: {}", path.display()))?;
+        Self::from_png(&bytes)
+    }
+
+    /// 裁剪区域（超出图像的部分被截断）
+    pub fn crop(&self, bounds: &ElementBounds) -> Result<Self> {
+        let left = bounds.left.clamp(0, self.width as i32) as usize;
+        let top = bounds.top.clamp(0, self.height as i32) as usize;
+        let right = bounds.right.clamp(0, self.width as i32) as usize;
+        let bottom = bounds.bottom.clamp(0, self.height as i32) as usize;
+        if right <= left || bottom <= top {
+            return Err(anyhow!("裁剪区域为空: [{},{}][{},{}]", bounds.left, bounds.top, bounds.right, bounds.bottom));
+        }
+
+        let mut data = Vec::with_capacity((right - left) * (bottom - top) * 4);
+        for y in top..bottom {
+            let start = (y * self.width + left) * 4;
+            data.extend_from_slice(&self.data[start..start + (right - left) * 4]);
+        }
+        Ok(Self { width: right - left, height: bottom - top, data })
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::File::create(path).with_context(|| format!("创建文件失败: {}", path.display()))?;
+        let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+        Ok(())
+    }
+
+    pub fn to_gray(&self) -> GrayImage {
+        let pixels = self
+            .data
+            .chunks(4)
+            .map(|px| 0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32)
+            .collect();
+        GrayImage { width: self.width, height: self.height, pixels }
+    }
+}
+
+/// 灰度图像（匹配在灰度上进行）
+#[derive(Debug, Clone)]
+pub struct GrayImage {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<f32>,
+}
+
+impl GrayImage {
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(RgbaImage::open(path)?.to_gray())
+    }
+
+    fn at(&self, x: usize, y: usize) -> f32 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// 双线性缩放
+    fn resize(&self, scale: f32) -> Self {
+        let width = ((self.width as f32 * scale).round() as usize).max(1);
+        let height = ((self.height as f32 * scale).round() as usize).max(1);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let sy = ((y as f32 + 0.5) / scale - 0.5).clamp(0.0, (self.height - 1) as f32);
+            let (y0, fy) = (sy.floor() as usize, sy.fract());
+            let y1 = (y0 + 1).min(self.height - 1);
+            for x in 0..width {
+                let sx = ((x as f32 + 0.5) / scale - 0.5).clamp(0.0, (self.width - 1) as f32);
+                let (x0, fx) = (sx.floor() as usize, sx.fract());
+                let x1 = (x0 + 1).min(self.width - 1);
+                let top = self.at(x0, y0) * (1.0 - fx) + self.at(x1, y0) * fx;
+                let bottom = self.at(x0, y1) * (1.0 - fx) + self.at(x1, y1) * fx;
+                pixels.push(top * (1.0 - fy) + bottom * fy);
+            }
+        }
+        Self { width, height, pixels }
+    }
+
+    /// 按整数倍均值下采样
+    fn downsample(&self, factor: usize) -> Self {
+        if factor <= 1 {
+            return self.clone();
+        }
+        let width = (self.width / factor).max(1);
+        let height = (self.height / factor).max(1);
+        let area = (factor * factor) as f32;
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0.0;
+                for dy in 0..factor {
+                    for dx in 0..factor {
+                        sum += self.at((x * factor + dx).min(self.width - 1), (y * factor + dy).min(self.height - 1));
+                    }
+                }
+                pixels.push(sum / area);
+            }
+        }
+        Self { width, height, pixels }
+    }
+}
+
+/// 积分图：O(1) 求任意窗口的像素和与平方和
+struct Integral {
+    stride: usize,
+    sum: Vec<f64>,
+    sq: Vec<f64>,
+}
+
+impl Integral {
+    fn new(image: &GrayImage) -> Self {
+        let stride = image.width + 1;
+        let mut sum = vec![0.0; stride * (image.height + 1)];
+        let mut sq = vec![0.0; stride * (image.height + 1)];
+        for y in 0..image.height {
+            let (mut row_sum, mut row_sq) = (0.0, 0.0);
+            for x in 0..image.width {
+                let v = image.at(x, y) as f64;
+                row_sum += v;
+                row_sq += v * v;
+                sum[(y + 1) * stride + x + 1] = sum[y * stride + x + 1] + row_sum;
+                sq[(y + 1) * stride + x + 1] = sq[y * stride + x + 1] + row_sq;
+            }
+        }
+        Self { stride, sum, sq }
+    }
+
+    fn window(&self, x: usize, y: usize, w: usize, h: usize) -> (f64, f64) {
+        let s = self.stride;
+        let rect = |t: &[f64]| t[(y + h) * s + x + w] - t[y * s + x + w] - t[(y + h) * s + x] + t[y * s + x];
+        (rect(&self.sum), rect(&self.sq))
+    }
+}
+
+/// 去均值后的模板
+struct PreparedTemplate {
+    width: usize,
+    height: usize,
+    zero_mean: Vec<f32>,
+    norm: f64,
+}
+
+impl PreparedTemplate {
+    fn new(template: &GrayImage) -> Option<Self> {
+        let n = template.pixels.len() as f32;
+        let mean = template.pixels.iter().sum::<f32>() / n;
+        let zero_mean: Vec<f32> = template.pixels.iter().map(|v| v - mean).collect();
+        let norm: f64 = zero_mean.iter().map(|v| (*v as f64).powi(2)).sum();
+        // 纯色模板无法做相关匹配
+        (norm > 1e-6).then_some(Self { width: template.width, height: template.height, zero_mean, norm })
+    }
+
+    /// 模板放在 (x, y) 处的归一化互相关系数（-1~1）
+    fn ncc(&self, screen: &GrayImage, integral: &Integral, x: usize, y: usize) -> f32 {
+        let n = (self.width * self.height) as f64;
+        let (sum, sq) = integral.window(x, y, self.width, self.height);
+        let variance = sq - sum * sum / n;
+        if variance <= 1e-6 {
+            return 0.0;
+        }
+        let mut dot = 0.0f64;
+        for ty in 0..self.height {
+            let row = &screen.pixels[(y + ty) * screen.width + x..][..self.width];
+            let tmpl = &self.zero_mean[ty * self.width..][..self.width];
+            dot += row.iter().zip(tmpl).map(|(a, b)| a * b).sum::<f32>() as f64;
+        }
+        (dot / (variance * self.norm).sqrt()) as f32
+    }
+}
+
+/// 模板匹配参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateMatchOptions {
+    /// 最低置信度（归一化互相关系数）
+    pub threshold: f32,
+    /// 相对缩放比例
+    pub scales: Vec<f32>,
+    /// 截取模板时的屏幕宽度；提供时先按当前屏幕宽度等比换算再叠加 scales
+    pub template_screen_width: Option<u32>,
+}
+
+impl Default for TemplateMatchOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.8,
+            scales: vec![0.8, 0.9, 1.0, 1.1, 1.25],
+            template_screen_width: None,
+        }
+    }
+}
+
+/// 模板匹配结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateMatch {
+    pub bounds: ElementBounds,
+    pub confidence: f32,
+    pub scale: f32,
+}
+
+impl TemplateMatch {
+    pub fn center(&self) -> (i32, i32) {
+        (self.bounds.center_x(), self.bounds.center_y())
+    }
+}
+
+/// 在截图中查找模板，返回置信度最高且达到阈值的位置
+///
+/// 每个缩放比例先在下采样后的图像上粗搜索，再在原分辨率的邻域内精确定位。
+pub fn find_template(screen: &GrayImage, template: &GrayImage, options: &TemplateMatchOptions) -> Option<TemplateMatch> {
+    let base = options
+        .template_screen_width
+        .filter(|w| *w > 0)
+        .map_or(1.0, |w| screen.width as f32 / w as f32);
+    let scales: Vec<f32> = if options.scales.is_empty() { vec![1.0] } else { options.scales.clone() };
+
+    let full_integral = Integral::new(screen);
+    let mut coarse_cache: Vec<(usize, GrayImage, Integral)> = Vec::new();
+    let mut best: Option<TemplateMatch> = None;
+
+    for scale in scales.iter().map(|s| s * base) {
+        let scaled = if (scale - 1.0).abs() < 1e-3 { template.clone() } else { template.resize(scale) };
+        if scaled.width > screen.width || scaled.height > screen.height || scaled.width.min(scaled.height) < 4 {
+            continue;
+        }
+        let Some(prepared) = PreparedTemplate::new(&scaled) else { continue };
+
+        // 1. 粗搜索
+        let factor = (scaled.width.min(scaled.height) / MIN_COARSE_SIDE).clamp(1, 4);
+        if !coarse_cache.iter().any(|(f, _, _)| *f == factor) {
+            let coarse = screen.downsample(factor);
+            let integral = Integral::new(&coarse);
+            coarse_cache.push((factor, coarse, integral));
+        }
+        let (_, coarse, coarse_integral) = coarse_cache.iter().find(|(f, _, _)| *f == factor).unwrap();
+        let Some(coarse_template) = PreparedTemplate::new(&scaled.downsample(factor)) else { continue };
+        if coarse_template.width > coarse.width || coarse_template.height > coarse.height {
+            continue;
+        }
+
+        let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+        for y in 0..=coarse.height - coarse_template.height {
+            for x in 0..=coarse.width - coarse_template.width {
+                let score = coarse_template.ncc(coarse, coarse_integral, x, y);
+                push_candidate(&mut candidates, (score, x, y), coarse_template.width.min(coarse_template.height) / 2);
+            }
+        }
+
+        // 2. 原分辨率邻域精确定位
+        for (_, cx, cy) in candidates {
+            let radius = factor + 1;
+            let x_range = (cx * factor).saturating_sub(radius)..=(cx * factor + radius).min(screen.width - prepared.width);
+            let y_range = (cy * factor).saturating_sub(radius)..=(cy * factor + radius).min(screen.height - prepared.height);
+            for y in y_range {
+                for x in x_range.clone() {
+                    let confidence = prepared.ncc(screen, &full_integral, x, y);
+                    if best.as_ref().map_or(true, |b| confidence > b.confidence) {
+                        best = Some(TemplateMatch {
+                            bounds: ElementBounds {
+                                left: x as i32,
+                                top: y as i32,
+                                right: (x + prepared.width) as i32,
+                                bottom: (y + prepared.height) as i32,
+                            },
+                            confidence,
+                            scale,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    best.filter(|m| m.confidence >= options.threshold)
+}
+
+/// 维护得分最高的若干个彼此不重叠的候选
+fn push_candidate(candidates: &mut Vec<(f32, usize, usize)>, candidate: (f32, usize, usize), min_distance: usize) {
+    let (score, x, y) = candidate;
+    let near = |c: &(f32, usize, usize)| x.abs_diff(c.1) <= min_distance && y.abs_diff(c.2) <= min_distance;
+    if let Some(pos) = candidates.iter().position(near) {
+        if candidates[pos].0 >= score {
+            return;
+        }
+        candidates.remove(pos);
+    } else if candidates.len() >= COARSE_CANDIDATES && candidates.last().map_or(false, |c| c.0 >= score) {
+        return;
+    }
+    let pos = candidates.iter().position(|c| c.0 < score).unwrap_or(candidates.len());
+    candidates.insert(pos, candidate);
+    candidates.truncate(COARSE_CANDIDATES);
+}
+
+/// 在截图文件中查找模板文件（CPU 密集，放到阻塞线程执行）
+pub async fn find_template_in_file(
+    screenshot_path: &Path,
+    template_path: &Path,
+    options: TemplateMatchOptions,
+) -> Result<Option<TemplateMatch>> {
+    let screenshot_path = screenshot_path.to_path_buf();
+    let template_path = template_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let screen = GrayImage::open(&screenshot_path)?;
+        let template = GrayImage::open(&template_path)?;
+        let started = std::time::Instant::now();
+        let result = find_template(&screen, &template, &options);
+        info!("🖼️ 模板匹配完成: {:?} (耗时 {}ms)",
+              result.as_ref().map(|m| m.confidence), started.elapsed().as_millis());
+        Ok(result)
+    })
+    .await?
+}
+
+/// 从截图中裁剪出的模板
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateInfo {
+    pub path: String,
+    pub width: usize,
+    pub height: usize,
+    /// 截图时的屏幕宽度，匹配时作为 template_screen_width 传入
+    pub screen_width: usize,
+}
+
+/// 在设备当前截图中查找模板
+#[tauri::command]
+pub async fn find_template_on_screen(
+    device_id: String,
+    template_path: String,
+    options: Option<TemplateMatchOptions>,
+    app_handle: tauri::AppHandle,
+) -> Result<Option<TemplateMatch>, String> {
+    let screenshot = ScreenshotService::capture_screenshot(&device_id, &app_handle).await;
+    let screenshot_path = screenshot
+        .screenshot_path
+        .ok_or_else(|| screenshot.error.unwrap_or_else(|| "截图失败".to_string()))?;
+    find_template_in_file(Path::new(&screenshot_path), Path::new(&template_path), options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 从截图裁剪模板，保存到 data/templates/<name>.png
+#[tauri::command]
+pub async fn save_template_from_screenshot(
+    screenshot_path: String,
+    bounds: ElementBounds,
+    name: String,
+) -> Result<TemplateInfo, String> {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    if name.is_empty() {
+        return Err("模板名称不能为空".to_string());
+    }
+
+    let screen = RgbaImage::open(Path::new(&screenshot_path)).map_err(|e| e.to_string())?;
+    let template = screen.crop(&bounds).map_err(|e| e.to_string())?;
+    let path: PathBuf = [TEMPLATE_DIR, &format!("{}.png", name)].iter().collect();
+    template.save_png(&path).map_err(|e| e.to_string())?;
+    info!("🖼️ 已保存模板: {} ({}x{})", path.display(), template.width, template.height);
+
+    Ok(TemplateInfo {
+        path: path.to_string_lossy().to_string(),
+        width: template.width,
+        height: template.height,
+        screen_width: screen.width,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 带噪声纹理的背景，中间放一个“图标”
+    fn synthetic_screen(width: usize, height: usize, icon_at: (usize, usize), icon: &GrayImage) -> GrayImage {
+        let mut pixels: Vec<f32> = (0..width * height)
+            .map(|i| ((i * 7919 % 97) as f32) * 0.6 + 80.0)
+            .collect();
+        for y in 0..icon.height {
+            for x in 0..icon.width {
+                pixels[(icon_at.1 + y) * width + icon_at.0 + x] = icon.at(x, y);
+            }
+        }
+        GrayImage { width, height, pixels }
+    }
+
+    fn icon(size: usize) -> GrayImage {
+        let pixels = (0..size * size)
+            .map(|i| {
+                let (x, y) = (i % size, i / size);
+                let (dx, dy) = (x as f32 - size as f32 / 2.0, y as f32 - size as f32 / 2.0);
+                if (dx * dx + dy * dy).sqrt() < size as f32 / 3.0 { 250.0 } else if x < size / 4 { 10.0 } else { 120.0 }
+            })
+            .collect();
+        GrayImage { width: size, height: size, pixels }
+    }
+
+    #[test]
+    fn test_find_template_exact_and_scaled() {
+        let template = icon(24);
+        let screen = synthetic_screen(160, 240, (97, 150), &template);
+        let found = find_template(&screen, &template, &TemplateMatchOptions::default()).unwrap();
+        assert_eq!((found.bounds.left, found.bounds.top), (97, 150));
+        assert!(found.confidence > 0.99);
+
+        // 模板在分辨率更高的设备上截取：按屏幕宽度换算后仍能找到
+        let big_template = template.resize(1.5);
+        let options = TemplateMatchOptions { template_screen_width: Some(240), ..Default::default() };
+        let found = find_template(&screen, &big_template, &options).unwrap();
+        assert!((found.bounds.left - 97).abs() <= 2 && (found.bounds.top - 150).abs() <= 2);
+        assert!(found.confidence > 0.8);
+
+        // 画面中没有该图标
+        let other = synthetic_screen(160, 240, (0, 0), &GrayImage { width: 1, height: 1, pixels: vec![90.0] });
+        assert!(find_template(&other, &template, &TemplateMatchOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_png_round_trip_and_crop() {
+        let rgba = RgbaImage {
+            width: 4,
+            height: 3,
+            data: (0..48).map(|i| (i * 5) as u8).collect(),
+        };
+        let path = std::env::temp_dir().join(format!("template_matcher_{}.png", std::process::id()));
+        rgba.save_png(&path).unwrap();
+        let decoded = RgbaImage::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!((decoded.width, decoded.height), (4, 3));
+        assert_eq!(decoded.data, rgba.data);
+
+        let cropped = decoded.crop(&ElementBounds { left: 1, top: 1, right: 3, bottom: 10 }).unwrap();
+        assert_eq!((cropped.width, cropped.height), (2, 2));
+        assert_eq!(&cropped.data[..4], &rgba.data[20..24]);
+    }
+}
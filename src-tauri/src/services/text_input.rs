@@ -0,0 +1,145 @@
+use anyhow::Result;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::services::adb_shell_session::AdbShellSession;
+
+/// ADBKeyBoard 输入法（https://github.com/senzhk/ADBKeyBoard）
+pub const ADB_KEYBOARD_IME: &str = "com.android.adbkeyboard/.AdbIME";
+/// ADBKeyBoard 接收 base64 文本的广播动作
+const ADB_KEYBOARD_B64_ACTION: &str = "ADB_INPUT_B64";
+
+/// 实际使用的文本输入方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextInputMethod {
+    /// `input text`（仅限ASCII）
+    InputText,
+    /// 通过 ADBKeyBoard 广播输入
+    AdbKeyboard,
+}
+
+/// 输入文本：ASCII 使用转义后的 `input text`，其他字符（中文、emoji）以及含 `%` 的文本通过 ADBKeyBoard 广播输入
+pub async fn type_text(session: &AdbShellSession, text: &str) -> Result<TextInputMethod> {
+    if text.is_empty() {
+        return Ok(TextInputMethod::InputText);
+    }
+
+    if can_use_input_text(text) {
+        let command = format!("input text {}", escape_input_text(text));
+        session.execute_command(&command).await?;
+        return Ok(TextInputMethod::InputText);
+    }
+
+    type_text_with_adb_keyboard(session, text).await?;
+    Ok(TextInputMethod::AdbKeyboard)
+}
+
+/// 通过 ADBKeyBoard 输入任意 Unicode 文本，完成后恢复用户原输入法
+async fn type_text_with_adb_keyboard(session: &AdbShellSession, text: &str) -> Result<()> {
+    let installed = session.execute_command("ime list -a -s").await?;
+    if !installed.lines().any(|l| l.trim() == ADB_KEYBOARD_IME) {
+        return Err(anyhow::anyhow!(
+            "设备未安装ADBKeyBoard输入法，无法输入非ASCII文本: {}",
+            ADB_KEYBOARD_IME
+        ));
+    }
+
+    let previous_ime = session
+        .execute_command("settings get secure default_input_method")
+        .await?
+        .trim()
+        .to_string();
+
+    // 记录 ADBKeyBoard 原本是否已启用，输入完成后恢复
+    let enabled = session.execute_command("ime list -s").await?;
+    let was_enabled = enabled.lines().any(|l| l.trim() == ADB_KEYBOARD_IME);
+
+    let switched = previous_ime != ADB_KEYBOARD_IME;
+    if switched {
+        if !was_enabled {
+            session.execute_command(&format!("ime enable {}", ADB_KEYBOARD_IME)).await?;
+        }
+        session.execute_command(&format!("ime set {}", ADB_KEYBOARD_IME)).await?;
+        // 等待输入法切换生效
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    }
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text.as_bytes());
+    let result = session
+        .execute_command(&format!(
+            "am broadcast -a {} --es msg {}",
+            ADB_KEYBOARD_B64_ACTION, encoded
+        ))
+        .await;
+
+    if switched && !previous_ime.is_empty() && previous_ime != "null" {
+        if let Err(e) = session.execute_command(&format!("ime set {}", previous_ime)).await {
+            warn!("⚠️ 恢复原输入法失败 {}: {}", previous_ime, e);
+        } else {
+            info!("⌨️ 已恢复原输入法: {}", previous_ime);
+        }
+    }
+    if !was_enabled {
+        if let Err(e) = session.execute_command(&format!("ime disable {}", ADB_KEYBOARD_IME)).await {
+            warn!("⚠️ 停用ADBKeyBoard失败: {}", e);
+        }
+    }
+
+    let output = result?;
+    if !output.contains("result=0") && !output.contains("Broadcast completed") {
+        return Err(anyhow::anyhow!("ADBKeyBoard广播未成功: {}", output.trim()));
+    }
+    Ok(())
+}
+
+/// 是否可以直接使用 `input text`（可打印ASCII，不含换行）
+fn is_plain_ascii(text: &str) -> bool {
+    text.chars().all(|c| c.is_ascii() && !c.is_ascii_control())
+}
+
+/// `input text` 会把 `%s` 当作空格且无法转义 `%`，含 `%` 的文本改走 ADBKeyBoard
+fn can_use_input_text(text: &str) -> bool {
+    is_plain_ascii(text) && !text.contains('%')
+}
+
+/// 转义 `input text` 参数：空格替换为 %s，shell 特殊字符加反斜杠
+fn escape_input_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() * 2);
+    for c in text.chars() {
+        match c {
+            ' ' => escaped.push_str("%s"),
+            '\\' | '\'' | '"' | '`' | '$' | '&' | '|' | ';' | '<' | '>' | '(' | ')' | '*' | '?'
+            | '~' | '#' | '!' | '[' | ']' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_input_text() {
+        assert_eq!(escape_input_text("hello world"), "hello%sworld");
+        assert_eq!(escape_input_text("a&b;c"), "a\\&b\\;c");
+        assert_eq!(escape_input_text("it's $5"), "it\\'s%s\\$5");
+    }
+
+    #[test]
+    fn test_is_plain_ascii() {
+        assert!(is_plain_ascii("search keyword 123"));
+        assert!(!is_plain_ascii("小红书"));
+        assert!(!is_plain_ascii("hi 😀"));
+        assert!(!is_plain_ascii("line1\nline2"));
+
+        assert!(can_use_input_text("search keyword 123"));
+        assert!(!can_use_input_text("100%success"));
+    }
+}
@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::utils::adb_utils::{execute_command_hidden, get_adb_path};
+
+/// 刚 dump 完的短时间内直接复用，不检查变化信号
+const SIGNATURE_CHECK_AFTER: Duration = Duration::from_millis(500);
+/// 变化信号一致时缓存的最长有效期
+const MAX_AGE: Duration = Duration::from_secs(30);
+/// 设备无法提供变化信号时的有效期：没有输入操作时界面也可能自行变化（加载、动画、推送）
+const UNSIGNED_MAX_AGE: Duration = Duration::from_secs(1);
+
+/// 界面变化信号：前台窗口 + 截图哈希，一次 shell 调用，远快于 uiautomator dump
+const SIGNATURE_COMMAND: &str = "dumpsys window | grep mCurrentFocus; screencap | md5sum";
+
+/// 会改变界面的 shell 命令前缀（输入、启动/停止应用、清数据、卸载、亮灭屏）
+const INPUT_COMMANDS: [&str; 8] = [
+    "input ",
+    "am start",
+    "am broadcast",
+    "am force-stop",
+    "monkey ",
+    "pm clear",
+    "pm uninstall",
+    "svc power",
+];
+
+#[derive(Clone)]
+struct CachedDump {
+    xml: String,
+    captured_at: Instant,
+    /// dump 前采集的变化信号（前台窗口 + 截图哈希），设备不支持时为 None
+    signature: Option<String>,
+}
+
+/// 缓存条目的可用状态
+#[derive(Debug, PartialEq)]
+enum Freshness {
+    /// 直接使用
+    Fresh,
+    /// 需要比对变化信号后才能使用
+    NeedsSignature,
+    /// 已过期
+    Expired,
+}
+
+impl CachedDump {
+    fn freshness(&self) -> Freshness {
+        let age = self.captured_at.elapsed();
+        if age < SIGNATURE_CHECK_AFTER {
+            Freshness::Fresh
+        } else if self.signature.is_some() && age < MAX_AGE {
+            Freshness::NeedsSignature
+        } else if self.signature.is_none() && age < UNSIGNED_MAX_AGE {
+            Freshness::Fresh
+        } else {
+            Freshness::Expired
+        }
+    }
+}
+
+#[derive(Default)]
+struct DeviceEntry {
+    /// 每次输入操作加一；dump 期间发生输入时不写入缓存
+    generation: u64,
+    dump: Option<CachedDump>,
+}
+
+/// 缓存统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiDumpCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+}
+
+/// 按设备缓存 UI dump，任何输入操作都会使对应设备的缓存失效；
+/// 没有输入时通过前台窗口 + 截图哈希判断界面是否自行变化
+pub struct UiDumpCache {
+    devices: Mutex<HashMap<String, DeviceEntry>>,
+    /// 采集变化信号（阻塞调用），便于测试替换
+    signature_probe: fn(&str) -> Option<String>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+impl UiDumpCache {
+    pub fn new() -> Self {
+        Self::with_signature_probe(probe_ui_signature)
+    }
+
+    fn with_signature_probe(signature_probe: fn(&str) -> Option<String>) -> Self {
+        Self {
+            devices: Mutex::new(HashMap::new()),
+            signature_probe,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    /// 读取缓存条目，同时返回当前代数
+    fn lookup(&self, device_id: &str) -> (Option<CachedDump>, u64) {
+        let devices = self.devices.lock().unwrap();
+        match devices.get(device_id) {
+            Some(entry) => (entry.dump.clone(), entry.generation),
+            None => (None, 0),
+        }
+    }
+
+    /// 写入缓存；dump 期间发生过输入（代数变化）或内容无效时丢弃
+    fn store(&self, device_id: &str, generation: u64, xml: &str, signature: Option<String>) {
+        if !is_valid_dump(xml) {
+            return;
+        }
+        let mut devices = self.devices.lock().unwrap();
+        let entry = devices.entry(device_id.to_string()).or_default();
+        if entry.generation == generation {
+            entry.dump = Some(CachedDump { xml: xml.to_string(), captured_at: Instant::now(), signature });
+        }
+    }
+
+    /// 变化信号一致：刷新缓存时间，短时间内再次读取无需重新比对
+    fn touch(&self, device_id: &str, generation: u64) {
+        let mut devices = self.devices.lock().unwrap();
+        if let Some(entry) = devices.get_mut(device_id) {
+            if entry.generation == generation {
+                if let Some(dump) = entry.dump.as_mut() {
+                    dump.captured_at = Instant::now();
+                }
+            }
+        }
+    }
+
+    /// 判断缓存是否可用；需要比对时调用 `probe` 采集当前信号。
+    /// 返回 (可用的 XML, 已采集的信号)，未命中时信号可直接用于写入新 dump
+    fn resolve<P>(&self, device_id: &str, cached: Option<CachedDump>, generation: u64, probe: P) -> (Option<String>, Option<Option<String>>)
+    where
+        P: FnOnce() -> Option<String>,
+    {
+        let Some(cached) = cached else {
+            return (None, None);
+        };
+        match cached.freshness() {
+            Freshness::Fresh => (Some(cached.xml), None),
+            Freshness::Expired => (None, None),
+            Freshness::NeedsSignature => {
+                let signature = probe();
+                if signature.is_some() && signature == cached.signature {
+                    self.touch(device_id, generation);
+                    (Some(cached.xml), None)
+                } else {
+                    debug!("🔄 界面变化信号不一致，重新 dump: {}", device_id);
+                    (None, Some(signature))
+                }
+            }
+        }
+    }
+
+    /// 在阻塞线程中采集变化信号
+    async fn probe_async(&self, device_id: &str) -> Option<String> {
+        let probe = self.signature_probe;
+        let device_id = device_id.to_string();
+        tokio::task::spawn_blocking(move || probe(&device_id)).await.ok().flatten()
+    }
+
+    fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 返回缓存的 dump，没有时调用 `dump` 获取并写入缓存
+    pub async fn get_or_dump<E, F, Fut>(&self, device_id: &str, dump: F) -> Result<String, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+    {
+        let (cached, generation) = self.lookup(device_id);
+        let needs_probe = cached.as_ref().is_some_and(|c| c.freshness() == Freshness::NeedsSignature);
+        let probed = if needs_probe { Some(self.probe_async(device_id).await) } else { None };
+        let (xml, signature) = self.resolve(device_id, cached, generation, || probed.flatten());
+        if let Some(xml) = xml {
+            debug!("📦 使用缓存的UI dump: {}", device_id);
+            self.record(true);
+            return Ok(xml);
+        }
+        self.record(false);
+        let signature = match signature {
+            Some(signature) => signature,
+            None => self.probe_async(device_id).await,
+        };
+        let xml = dump().await?;
+        self.store(device_id, generation, &xml, signature);
+        Ok(xml)
+    }
+
+    /// 强制重新 dump 并更新缓存（轮询等待界面变化时使用）
+    pub async fn refresh<E, F, Fut>(&self, device_id: &str, dump: F) -> Result<String, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+    {
+        let (_, generation) = self.lookup(device_id);
+        let signature = self.probe_async(device_id).await;
+        let xml = dump().await?;
+        self.store(device_id, generation, &xml, signature);
+        Ok(xml)
+    }
+
+    /// 强制重新 dump 但不采集变化信号，结果按无信号条目短时缓存
+    ///
+    /// 高频轮询（等待界面静止）本身就在比对相邻两次 dump，每轮再跑一次截图哈希只会拖慢轮询
+    pub async fn refresh_unsigned<E, F, Fut>(&self, device_id: &str, dump: F) -> Result<String, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+    {
+        let (_, generation) = self.lookup(device_id);
+        let xml = dump().await?;
+        self.store(device_id, generation, &xml, None);
+        Ok(xml)
+    }
+
+    /// 同步版本，供阻塞式调用方使用；未指定设备时不走缓存
+    pub fn get_or_dump_blocking<E>(
+        &self,
+        device_id: Option<&str>,
+        dump: impl FnOnce() -> Result<String, E>,
+    ) -> Result<String, E> {
+        let Some(device_id) = device_id else {
+            return dump();
+        };
+        let (cached, generation) = self.lookup(device_id);
+        let probe = || (self.signature_probe)(device_id);
+        let (xml, signature) = self.resolve(device_id, cached, generation, probe);
+        if let Some(xml) = xml {
+            self.record(true);
+            return Ok(xml);
+        }
+        self.record(false);
+        let signature = signature.unwrap_or_else(probe);
+        let xml = dump()?;
+        self.store(device_id, generation, &xml, signature);
+        Ok(xml)
+    }
+
+    /// 同步版本的强制刷新（轮询、重试时使用）
+    pub fn refresh_blocking<E>(
+        &self,
+        device_id: Option<&str>,
+        dump: impl FnOnce() -> Result<String, E>,
+    ) -> Result<String, E> {
+        let Some(device_id) = device_id else {
+            return dump();
+        };
+        let (_, generation) = self.lookup(device_id);
+        let signature = (self.signature_probe)(device_id);
+        let xml = dump()?;
+        self.store(device_id, generation, &xml, signature);
+        Ok(xml)
+    }
+
+    /// 读取仍然有效的缓存而不触发 dump（不比对变化信号，仅用于对比参考）
+    pub fn peek(&self, device_id: Option<&str>) -> Option<String> {
+        let (cached, _) = self.lookup(device_id?);
+        cached.filter(|c| c.freshness() != Freshness::Expired).map(|c| c.xml)
+    }
+
+    /// 使设备缓存失效；未指定设备时清空全部
+    pub fn invalidate(&self, device_id: Option<&str>) {
+        let mut devices = self.devices.lock().unwrap();
+        match device_id {
+            Some(id) => {
+                let entry = devices.entry(id.to_string()).or_default();
+                entry.generation += 1;
+                entry.dump = None;
+            }
+            None => {
+                for entry in devices.values_mut() {
+                    entry.generation += 1;
+                    entry.dump = None;
+                }
+            }
+        }
+        self.invalidations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 执行 shell 命令前后调用：输入类命令使缓存失效
+    pub fn note_shell_command(&self, device_id: Option<&str>, command: &str) {
+        if is_input_command(command) {
+            self.invalidate(device_id);
+        }
+    }
+
+    /// 执行 adb 命令前后调用（参数形如 `-s <id> shell input tap x y`）
+    pub fn note_adb_args<S: AsRef<str>>(&self, args: &[S]) {
+        let args: Vec<&str> = args.iter().map(|a| a.as_ref()).collect();
+        let Some(shell) = args.iter().position(|a| *a == "shell") else {
+            return;
+        };
+        let device_id = args
+            .iter()
+            .position(|a| *a == "-s")
+            .and_then(|i| args.get(i + 1).copied());
+        self.note_shell_command(device_id, &args[shell + 1..].join(" "));
+    }
+
+    pub fn stats(&self) -> UiDumpCacheStats {
+        UiDumpCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 命令（可能是 `&&` / `;` 串联的多条）中是否包含会改变界面的操作
+pub(crate) fn is_input_command(command: &str) -> bool {
+    command
+        .split(|c| c == ';' || c == '&' || c == '|')
+        .map(str::trim_start)
+        .any(|part| INPUT_COMMANDS.iter().any(|prefix| part.starts_with(prefix)))
+}
+
+/// 采集设备的界面变化信号；设备不支持 screencap/md5sum 时返回 None
+fn probe_ui_signature(device_id: &str) -> Option<String> {
+    let output = execute_command_hidden(&get_adb_path(), &["-s", device_id, "shell", SIGNATURE_COMMAND]).ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    // md5sum 输出 32 位十六进制哈希；没有哈希说明截图失败，信号不可靠
+    let has_hash = text
+        .split_whitespace()
+        .any(|w| w.len() == 32 && w.chars().all(|c| c.is_ascii_hexdigit()));
+    has_hash.then(|| text.trim().to_string())
+}
+
+fn is_valid_dump(xml: &str) -> bool {
+    xml.contains("<hierarchy") && !xml.contains("ERROR:") && !xml.contains("null root node")
+}
+
+/// 全局 UI dump 缓存实例
+static GLOBAL_UI_DUMP_CACHE: std::sync::OnceLock<UiDumpCache> = std::sync::OnceLock::new();
+
+/// 获取全局 UI dump 缓存
+pub fn get_ui_dump_cache() -> &'static UiDumpCache {
+    GLOBAL_UI_DUMP_CACHE.get_or_init(UiDumpCache::new)
+}
+
+/// 获取 UI dump 缓存命中统计
+#[tauri::command]
+pub async fn get_ui_dump_cache_stats() -> Result<UiDumpCacheStats, String> {
+    Ok(get_ui_dump_cache().stats())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = "<?xml version='1.0' ?><hierarchy rotation=\"0\"><node text=\"关注\" /></hierarchy>";
+
+    fn no_signature(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_cache_hit_and_input_invalidation() {
+        let cache = UiDumpCache::with_signature_probe(no_signature);
+        let mut dumps = 0;
+        let mut dump = || -> Result<String, String> {
+            dumps += 1;
+            Ok(DUMP.to_string())
+        };
+
+        assert_eq!(cache.get_or_dump_blocking(Some("dev1"), &mut dump).unwrap(), DUMP);
+        assert_eq!(cache.get_or_dump_blocking(Some("dev1"), &mut dump).unwrap(), DUMP);
+        assert_eq!(cache.stats().hits, 1);
+
+        // 其他设备、非输入命令不影响
+        cache.note_adb_args(&["-s", "dev2", "shell", "input", "tap", "1", "2"]);
+        cache.note_shell_command(Some("dev1"), "dumpsys window | grep mCurrentFocus");
+        cache.get_or_dump_blocking(Some("dev1"), &mut dump).unwrap();
+        assert_eq!(cache.stats().misses, 1);
+
+        // 输入操作后重新 dump
+        cache.note_adb_args(&["-s", "dev1", "shell", "input", "tap", "1", "2"]);
+        cache.get_or_dump_blocking(Some("dev1"), &mut dump).unwrap();
+        assert_eq!(cache.stats().misses, 2);
+        drop(dump);
+        assert_eq!(dumps, 2);
+
+        // 无效内容不缓存
+        cache.invalidate(Some("dev1"));
+        let _ = cache.get_or_dump_blocking(Some("dev1"), || Ok::<_, String>("ERROR: null root node returned by UiTestAutomationBridge.".to_string()));
+        let (cached, _) = cache.lookup("dev1");
+        assert!(cached.is_none());
+    }
+
+    #[test]
+    fn test_dump_during_input_is_not_cached() {
+        let cache = UiDumpCache::with_signature_probe(no_signature);
+        let (_, generation) = cache.lookup("dev1");
+        cache.invalidate(Some("dev1"));
+        cache.store("dev1", generation, DUMP, None);
+        assert!(cache.lookup("dev1").0.is_none());
+
+        assert!(is_input_command("input keyevent 4"));
+        assert!(is_input_command("sleep 1 && input tap 10 20"));
+        assert!(is_input_command("am start -n com.xingin.xhs/.index.v2.IndexActivityV2"));
+        assert!(!is_input_command("uiautomator dump /sdcard/ui_dump.xml && cat /sdcard/ui_dump.xml"));
+    }
+
+    #[test]
+    fn test_app_lifecycle_commands_invalidate() {
+        let cache = UiDumpCache::with_signature_probe(no_signature);
+        let dump = || Ok::<_, String>(DUMP.to_string());
+
+        for command in [
+            "am force-stop com.xingin.xhs",
+            "pm clear com.xingin.xhs",
+            "pm uninstall com.xingin.xhs",
+            "svc power stayon true",
+        ] {
+            assert!(is_input_command(command), "{} 应视为改变界面的命令", command);
+            cache.get_or_dump_blocking(Some("dev1"), dump).unwrap();
+            cache.note_shell_command(Some("dev1"), command);
+            assert!(cache.peek(Some("dev1")).is_none(), "{} 之后缓存应失效", command);
+        }
+
+        assert!(!is_input_command("pm list packages"));
+        assert!(!is_input_command("am stack list"));
+    }
+
+    fn forbidden_probe(_: &str) -> Option<String> {
+        panic!("空闲轮询不应采集变化信号");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_unsigned_skips_signature_probe() {
+        let cache = UiDumpCache::with_signature_probe(forbidden_probe);
+        let xml = cache.refresh_unsigned("dev1", || async { Ok::<_, String>(DUMP.to_string()) }).await.unwrap();
+        assert_eq!(xml, DUMP);
+
+        // 结果按无信号条目缓存：短时间内可用，过期后失效
+        assert!(cache.peek(Some("dev1")).is_some());
+        age_entry(&cache, "dev1", UNSIGNED_MAX_AGE);
+        assert!(cache.peek(Some("dev1")).is_none());
+    }
+
+    static SCREEN_VERSION: AtomicU64 = AtomicU64::new(0);
+
+    fn screen_signature(_: &str) -> Option<String> {
+        Some(format!("mCurrentFocus=Window{{home}} {}", SCREEN_VERSION.load(Ordering::SeqCst)))
+    }
+
+    /// 把缓存条目的时间往前推，模拟已经超过免检窗口
+    fn age_entry(cache: &UiDumpCache, device_id: &str, age: Duration) {
+        let mut devices = cache.devices.lock().unwrap();
+        let dump = devices.get_mut(device_id).unwrap().dump.as_mut().unwrap();
+        dump.captured_at = Instant::now() - age;
+    }
+
+    #[test]
+    fn test_signature_detects_changes_without_input() {
+        let cache = UiDumpCache::with_signature_probe(screen_signature);
+        let dump = || Ok::<_, String>(DUMP.to_string());
+
+        cache.get_or_dump_blocking(Some("dev1"), dump).unwrap();
+        age_entry(&cache, "dev1", Duration::from_secs(2));
+
+        // 截图未变化：复用缓存
+        cache.get_or_dump_blocking(Some("dev1"), dump).unwrap();
+        assert_eq!(cache.stats().hits, 1);
+
+        // 没有输入但界面自行变化（加载完成、推送）：重新 dump
+        SCREEN_VERSION.fetch_add(1, Ordering::SeqCst);
+        age_entry(&cache, "dev1", Duration::from_secs(2));
+        cache.get_or_dump_blocking(Some("dev1"), dump).unwrap();
+        assert_eq!(cache.stats().misses, 2);
+
+        // 无信号的条目只在短时间内有效
+        let unsigned = UiDumpCache::with_signature_probe(no_signature);
+        unsigned.get_or_dump_blocking(Some("dev1"), dump).unwrap();
+        assert!(unsigned.peek(Some("dev1")).is_some());
+        age_entry(&unsigned, "dev1", UNSIGNED_MAX_AGE);
+        assert!(unsigned.peek(Some("dev1")).is_none());
+    }
+}
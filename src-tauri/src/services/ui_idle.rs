@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::utils::adb_utils::run_shell;
+use crate::services::ui_dump_cache::get_ui_dump_cache;
+use crate::utils::hash::fnv1a_64;
+
+/// 等待界面稳定的参数
+#[derive(Debug, Clone)]
+pub struct IdleOptions {
+    /// 连续多少次 dump 结构一致才算稳定
+    pub stable_dumps: u32,
+    /// 两次 dump 之间的间隔
+    pub interval: Duration,
+    /// 最长等待时间
+    pub timeout: Duration,
+}
+
+impl Default for IdleOptions {
+    fn default() -> Self {
+        Self {
+            stable_dumps: 2,
+            interval: Duration::from_millis(300),
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl IdleOptions {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self { timeout, ..Self::default() }
+    }
+}
+
+/// 等待结果，`xml` 为最后一次（稳定时即稳定的）dump
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiIdleResult {
+    pub xml: String,
+    pub stable: bool,
+    pub condition_met: bool,
+    pub dumps: u32,
+    pub elapsed_ms: u64,
+}
+
+/// 记录连续相同 dump 的次数
+#[derive(Debug, Default)]
+struct StabilityTracker {
+    last_hash: Option<u64>,
+    streak: u32,
+}
+
+impl StabilityTracker {
+    /// 记录一次 dump，返回当前连续一致的次数
+    fn observe(&mut self, xml: &str) -> u32 {
+        let hash = hierarchy_hash(xml);
+        if self.last_hash == Some(hash) {
+            self.streak += 1;
+        } else {
+            self.last_hash = Some(hash);
+            self.streak = 1;
+        }
+        self.streak
+    }
+}
+
+/// 层次结构哈希：只取 `<hierarchy>` 部分，忽略 XML 头和 dump 命令的输出尾巴
+///
+/// 使用稳定哈希，结果可持久化（XML 快照去重依赖它）
+pub(crate) fn hierarchy_hash(xml: &str) -> u64 {
+    let start = xml.find("<hierarchy").unwrap_or(0);
+    let end = xml
+        .rfind("</hierarchy>")
+        .map(|i| i + "</hierarchy>".len())
+        .unwrap_or(xml.len());
+    fnv1a_64(xml[start..end.max(start)].as_bytes())
+}
+
+/// 跳过缓存直接 dump 当前界面，成功的结果写回缓存（不采集变化信号）
+async fn fresh_dump(device_id: &str) -> Result<String, String> {
+    get_ui_dump_cache()
+        .refresh_unsigned(device_id, || async {
+            let xml = run_shell(
+                device_id,
+                "uiautomator dump /sdcard/ui_idle.xml >/dev/null && cat /sdcard/ui_idle.xml",
+            )
+            .await?;
+            if xml.contains("<hierarchy") {
+                Ok(xml)
+            } else {
+                Err(format!("UI dump 无效: {}", xml.trim()))
+            }
+        })
+        .await
+}
+
+/// 轮询层次结构直到连续 `stable_dumps` 次一致且满足条件，或超时
+///
+/// 超时时返回最后一次 dump（`stable`/`condition_met` 标明实际状态），只有一次 dump 都没成功时返回错误
+pub async fn wait_until<F>(device_id: &str, options: &IdleOptions, condition: F) -> Result<UiIdleResult, String>
+where
+    F: Fn(&str) -> bool,
+{
+    let started = Instant::now();
+    let mut tracker = StabilityTracker::default();
+    let mut last_xml: Option<String> = None;
+    let mut last_error = String::new();
+    let mut dumps = 0;
+
+    loop {
+        match fresh_dump(device_id).await {
+            Ok(xml) => {
+                dumps += 1;
+                let streak = tracker.observe(&xml);
+                let condition_met = condition(&xml);
+                if streak >= options.stable_dumps.max(1) && condition_met {
+                    debug!("✅ 界面已稳定: {} ({}次dump, {}ms)", device_id, dumps, started.elapsed().as_millis());
+                    return Ok(UiIdleResult {
+                        xml,
+                        stable: true,
+                        condition_met,
+                        dumps,
+                        elapsed_ms: started.elapsed().as_millis() as u64,
+                    });
+                }
+                last_xml = Some(xml);
+            }
+            Err(e) => last_error = e,
+        }
+
+        if started.elapsed() + options.interval > options.timeout {
+            break;
+        }
+        sleep(options.interval).await;
+    }
+
+    match last_xml {
+        Some(xml) => {
+            let condition_met = condition(&xml);
+            warn!("⏰ 等待界面稳定超时: {} ({}ms)", device_id, started.elapsed().as_millis());
+            Ok(UiIdleResult {
+                stable: tracker.streak >= options.stable_dumps.max(1),
+                condition_met,
+                xml,
+                dumps,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            })
+        }
+        None => Err(format!("等待界面稳定失败，无法获取UI: {}", last_error)),
+    }
+}
+
+/// 轮询层次结构直到连续 `stable_dumps` 次一致，或超时
+pub async fn wait_until_stable(device_id: &str, options: &IdleOptions) -> Result<UiIdleResult, String> {
+    wait_until(device_id, options, |_| true).await
+}
+
+/// 替代固定 sleep：等待界面稳定，失败时仅记录日志
+pub async fn settle(device_id: &str, timeout: Duration) {
+    if let Err(e) = wait_until_stable(device_id, &IdleOptions::with_timeout(timeout)).await {
+        warn!("⚠️ 等待界面稳定失败，继续执行: {}", e);
+    }
+}
+
+/// 等待设备界面稳定并返回稳定后的 dump
+#[tauri::command]
+pub async fn wait_for_ui_idle(
+    device_id: String,
+    timeout_ms: Option<u64>,
+    stable_dumps: Option<u32>,
+) -> Result<UiIdleResult, String> {
+    let mut options = IdleOptions::default();
+    if let Some(ms) = timeout_ms {
+        options.timeout = Duration::from_millis(ms);
+    }
+    if let Some(n) = stable_dumps {
+        options.stable_dumps = n;
+    }
+    info!("⏳ 等待界面稳定: {} (超时{}ms)", device_id, options.timeout.as_millis());
+    wait_until_stable(&device_id, &options).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_counts_consecutive_identical_dumps() {
+        let loading = "<?xml version='1.0' ?><hierarchy><node text=\"加载中\" /></hierarchy>";
+        let loaded = "<?xml version='1.0' ?><hierarchy><node text=\"通讯录\" /></hierarchy>";
+        let mut tracker = StabilityTracker::default();
+
+        assert_eq!(tracker.observe(loading), 1);
+        assert_eq!(tracker.observe(loaded), 1);
+        assert_eq!(tracker.observe(loaded), 2);
+        assert_eq!(tracker.observe(loading), 1);
+    }
+
+    #[test]
+    fn test_hash_ignores_dump_header_and_trailer() {
+        let a = "<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation=\"0\"><node /></hierarchy>";
+        let b = "<?xml version='1.0' ?><hierarchy rotation=\"0\"><node /></hierarchy>UI hierchary dumped to: /sdcard/ui_idle.xml";
+        let c = "<?xml version='1.0' ?><hierarchy rotation=\"90\"><node /></hierarchy>";
+        assert_eq!(hierarchy_hash(a), hierarchy_hash(b));
+        assert_ne!(hierarchy_hash(a), hierarchy_hash(c));
+    }
+}
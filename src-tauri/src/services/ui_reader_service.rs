@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tokio::process::Command as AsyncCommand;
 
+use crate::services::ui_tree::{UiNode, UiTree};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UIElement {
     pub text: String,
@@ -143,57 +145,29 @@ async fn get_ui_dump(device_id: &str) -> Result<String, String> {
 
 /// 解析XML内容获取UI元素
 pub fn parse_ui_elements(xml_content: &str) -> Result<Vec<UIElement>, String> {
-    let mut elements = Vec::new();
-    
-    // 使用字符串匹配解析元素
-    let lines: Vec<&str> = xml_content.lines().collect();
-    
-    for line in lines {
-        if line.contains("<node") && line.contains("bounds=") {
-            if let Ok(element) = parse_node_element(line) {
-                elements.push(element);
-            }
-        }
-    }
-    
+    let tree = UiTree::parse(xml_content).map_err(|e| format!("解析UI XML失败: {}", e))?;
+
+    let elements: Vec<UIElement> = tree
+        .elements()
+        .filter(|node| node.attr("bounds").is_some())
+        .map(|node| to_ui_element(&node))
+        .collect();
+
     println!("🔍 解析到 {} 个UI元素", elements.len());
     Ok(elements)
 }
 
-/// 解析单个node元素
-fn parse_node_element(line: &str) -> Result<UIElement, String> {
-    // 提取各个属性
-    let text = extract_attribute(line, "text").unwrap_or_default();
-    let resource_id = extract_attribute(line, "resource-id").unwrap_or_default();
-    let class = extract_attribute(line, "class").unwrap_or_default();
-    let package = extract_attribute(line, "package").unwrap_or_default();
-    let content_desc = extract_attribute(line, "content-desc").unwrap_or_default();
-    let bounds = extract_attribute(line, "bounds").unwrap_or_default();
-    
-    let clickable = extract_attribute(line, "clickable")
-        .unwrap_or("false".to_string()) == "true";
-    
-    Ok(UIElement {
-        text,
-        resource_id,
-        class,
-        package,
-        content_desc,
-        clickable,
-        bounds,
-    })
-}
-
-/// 提取XML属性值
-fn extract_attribute(line: &str, attr_name: &str) -> Option<String> {
-    let pattern = format!("{}=\"", attr_name);
-    if let Some(start) = line.find(&pattern) {
-        let start_pos = start + pattern.len();
-        if let Some(end) = line[start_pos..].find('"') {
-            return Some(line[start_pos..start_pos + end].to_string());
-        }
+/// 将统一模型中的节点转换为 UIElement
+fn to_ui_element(node: &UiNode) -> UIElement {
+    UIElement {
+        text: node.text().to_string(),
+        resource_id: node.resource_id().to_string(),
+        class: node.class_name().to_string(),
+        package: node.package().to_string(),
+        content_desc: node.content_desc().to_string(),
+        clickable: node.is_clickable(),
+        bounds: node.attr("bounds").unwrap_or_default().to_string(),
     }
-    None
 }
 
 /// 分析当前UI状态并识别页面类型
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::locator::absolute_xpath;
+use super::node::{parse_bounds, UiNode, UiTree};
+
+/// 完全一致配对的键：结构路径 + 参与比较的属性
+type ExactKey = (String, Vec<(String, String)>);
+
+/// 变化节点的摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffNode {
+    pub xpath: String,
+    pub class_name: String,
+    pub resource_id: String,
+    pub text: String,
+    pub content_desc: String,
+    pub bounds: Option<String>,
+}
+
+impl DiffNode {
+    fn from_node(node: &UiNode) -> Self {
+        Self {
+            xpath: absolute_xpath(node),
+            class_name: node.class_name().to_string(),
+            resource_id: node.resource_id().to_string(),
+            text: node.text().to_string(),
+            content_desc: node.content_desc().to_string(),
+            bounds: node.attr("bounds").map(|b| b.to_string()),
+        }
+    }
+}
+
+/// 单个属性的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeChange {
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// 位置发生移动的节点（层级路径或 bounds 不同，语义属性相同）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovedNode {
+    pub before: DiffNode,
+    pub after: DiffNode,
+}
+
+/// 属性发生变化的节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedNode {
+    pub before: DiffNode,
+    pub after: DiffNode,
+    pub changes: Vec<AttributeChange>,
+}
+
+/// 两份 UI 层次结构之间的结构化差异
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiDiff {
+    pub added: Vec<DiffNode>,
+    pub removed: Vec<DiffNode>,
+    pub moved: Vec<MovedNode>,
+    pub changed: Vec<ChangedNode>,
+    pub unchanged_count: usize,
+    /// 相似度（0~1，1 表示完全相同）
+    pub similarity: f64,
+}
+
+/// 比较属性时忽略的字段（随位置变化，移动由 moved 单独报告）
+const IGNORED_ATTRS: [&str; 1] = ["index"];
+
+impl UiDiff {
+    /// 计算两棵树的差异
+    ///
+    /// 配对顺序：路径和属性完全一致 → 语义身份一致（视为移动）→ 路径一致（视为属性变化），
+    /// 剩余节点分别记为删除/新增。
+    pub fn compute(before: &UiTree, after: &UiTree) -> Self {
+        let old: Vec<UiNode> = before.elements().collect();
+        let new: Vec<UiNode> = after.elements().collect();
+        let mut old_paired = vec![false; old.len()];
+        let mut new_paired = vec![false; new.len()];
+        let mut diff = UiDiff::default();
+        // 配对成功但有变化的节点数（移动或属性变化）
+        let mut partial = 0usize;
+
+        let old_paths: Vec<String> = old.iter().map(structural_path).collect();
+        let new_paths: Vec<String> = new.iter().map(structural_path).collect();
+
+        // 1. 完全一致
+        let mut exact: HashMap<ExactKey, Vec<usize>> = HashMap::new();
+        for (i, node) in new.iter().enumerate() {
+            exact
+                .entry((new_paths[i].clone(), comparable_attrs(node)))
+                .or_default()
+                .push(i);
+        }
+        for (i, node) in old.iter().enumerate() {
+            let key = (old_paths[i].clone(), comparable_attrs(node));
+            if let Some(j) = take_first(&mut exact, &key, &new_paired) {
+                old_paired[i] = true;
+                new_paired[j] = true;
+                diff.unchanged_count += 1;
+            }
+        }
+
+        // 2. 语义身份一致：视为移动
+        let mut by_identity: HashMap<(String, String, String, String), Vec<usize>> = HashMap::new();
+        for (j, node) in new.iter().enumerate().filter(|(j, _)| !new_paired[*j]) {
+            by_identity.entry(identity(node)).or_default().push(j);
+        }
+        for (i, node) in old.iter().enumerate() {
+            if old_paired[i] {
+                continue;
+            }
+            if let Some(j) = take_first(&mut by_identity, &identity(node), &new_paired) {
+                old_paired[i] = true;
+                new_paired[j] = true;
+                partial += 1;
+                let changes: Vec<AttributeChange> = attribute_changes(node, &new[j])
+                    .into_iter()
+                    .filter(|c| c.name != "bounds")
+                    .collect();
+                if old_paths[i] == new_paths[j] && node.attr("bounds") == new[j].attr("bounds") {
+                    // 路径与位置相同，只有状态属性变化
+                    diff.changed.push(ChangedNode {
+                        before: DiffNode::from_node(node),
+                        after: DiffNode::from_node(&new[j]),
+                        changes,
+                    });
+                } else {
+                    diff.moved.push(MovedNode {
+                        before: DiffNode::from_node(node),
+                        after: DiffNode::from_node(&new[j]),
+                    });
+                    if !changes.is_empty() {
+                        diff.changed.push(ChangedNode {
+                            before: DiffNode::from_node(node),
+                            after: DiffNode::from_node(&new[j]),
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+
+        // 3. 路径一致：视为同一节点的属性变化
+        let mut by_path: HashMap<String, Vec<usize>> = HashMap::new();
+        for j in (0..new.len()).filter(|j| !new_paired[*j]) {
+            by_path.entry(new_paths[j].clone()).or_default().push(j);
+        }
+        for i in 0..old.len() {
+            if old_paired[i] {
+                continue;
+            }
+            if let Some(j) = take_first(&mut by_path, &old_paths[i], &new_paired) {
+                old_paired[i] = true;
+                new_paired[j] = true;
+                partial += 1;
+                diff.changed.push(ChangedNode {
+                    before: DiffNode::from_node(&old[i]),
+                    after: DiffNode::from_node(&new[j]),
+                    changes: attribute_changes(&old[i], &new[j]),
+                });
+            }
+        }
+
+        // 4. 剩余节点
+        diff.removed = (0..old.len())
+            .filter(|i| !old_paired[*i])
+            .map(|i| DiffNode::from_node(&old[i]))
+            .collect();
+        diff.added = (0..new.len())
+            .filter(|j| !new_paired[*j])
+            .map(|j| DiffNode::from_node(&new[j]))
+            .collect();
+
+        let total = old.len().max(new.len());
+        diff.similarity = if total == 0 {
+            1.0
+        } else {
+            (diff.unchanged_count as f64 + 0.5 * partial as f64) / total as f64
+        };
+        diff
+    }
+
+    /// 解析两份 dump 并计算差异
+    pub fn between(before_xml: &str, after_xml: &str) -> Result<Self> {
+        Ok(Self::compute(&UiTree::parse(before_xml)?, &UiTree::parse(after_xml)?))
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty() && self.changed.is_empty()
+    }
+
+    /// 一行摘要，用于日志
+    pub fn summary(&self) -> String {
+        format!(
+            "新增{} 删除{} 移动{} 属性变化{} 未变{} 相似度{:.2}",
+            self.added.len(),
+            self.removed.len(),
+            self.moved.len(),
+            self.changed.len(),
+            self.unchanged_count,
+            self.similarity
+        )
+    }
+
+    /// 查找变化前 bounds 包含指定坐标、且指定属性发生变化的最内层节点
+    pub fn attribute_change_at(&self, x: i32, y: i32, attribute: &str) -> Option<&AttributeChange> {
+        self.changed
+            .iter()
+            .filter(|c| {
+                c.before
+                    .bounds
+                    .as_deref()
+                    .and_then(parse_bounds)
+                    .is_some_and(|b| x >= b.left && x <= b.right && y >= b.top && y <= b.bottom)
+            })
+            .filter_map(|c| c.changes.iter().find(|a| a.name == attribute).map(|a| (c, a)))
+            .max_by_key(|(c, _)| c.before.xpath.matches('/').count())
+            .map(|(_, a)| a)
+    }
+
+    /// 校验坐标处节点的属性变化，例如点击后按钮文本由“关注”变为“已关注”
+    ///
+    /// `from`/`to` 为 None 时不约束对应一侧的取值。
+    pub fn expect_change_at(
+        &self,
+        x: i32,
+        y: i32,
+        attribute: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<&AttributeChange> {
+        let change = self
+            .attribute_change_at(x, y, attribute)
+            .ok_or_else(|| anyhow!("坐标({}, {})处的节点属性 {} 没有变化（{}）", x, y, attribute, self.summary()))?;
+
+        let matches = |expected: Option<&str>, actual: &Option<String>| {
+            expected.is_none_or(|e| actual.as_deref() == Some(e))
+        };
+        if matches(from, &change.before) && matches(to, &change.after) {
+            Ok(change)
+        } else {
+            Err(anyhow!(
+                "属性 {} 的变化不符合预期: {:?} → {:?}，期望 {:?} → {:?}",
+                attribute,
+                change.before,
+                change.after,
+                from,
+                to
+            ))
+        }
+    }
+}
+
+/// 结构路径：从根到节点的“类名 + resource-id + 同级序号”链
+fn structural_path(node: &UiNode) -> String {
+    let mut steps: Vec<String> = std::iter::once(*node)
+        .chain(node.ancestors())
+        .filter(|n| n.tag() == "node")
+        .map(|n| format!("{}#{}[{}]", n.class_name(), n.resource_id(), n.position()))
+        .collect();
+    steps.reverse();
+    steps.join("/")
+}
+
+/// 语义身份：类名、resource-id、文本、描述
+fn identity(node: &UiNode) -> (String, String, String, String) {
+    (
+        node.class_name().to_string(),
+        node.resource_id().to_string(),
+        node.text().to_string(),
+        node.content_desc().to_string(),
+    )
+}
+
+fn comparable_attrs(node: &UiNode) -> Vec<(String, String)> {
+    let mut attrs: Vec<(String, String)> = node
+        .attributes()
+        .iter()
+        .filter(|(k, _)| !IGNORED_ATTRS.contains(&k.as_str()))
+        .cloned()
+        .collect();
+    attrs.sort();
+    attrs
+}
+
+fn attribute_changes(before: &UiNode, after: &UiNode) -> Vec<AttributeChange> {
+    let mut names: Vec<&str> = before
+        .attributes()
+        .iter()
+        .chain(after.attributes())
+        .map(|(k, _)| k.as_str())
+        .filter(|k| !IGNORED_ATTRS.contains(k))
+        .collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (b, a) = (before.attr(name), after.attr(name));
+            (b != a).then(|| AttributeChange {
+                name: name.to_string(),
+                before: b.map(str::to_string),
+                after: a.map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+fn take_first<K: std::hash::Hash + Eq>(
+    map: &mut HashMap<K, Vec<usize>>,
+    key: &K,
+    paired: &[bool],
+) -> Option<usize> {
+    let candidates = map.get_mut(key)?;
+    let pos = candidates.iter().position(|&j| !paired[j])?;
+    Some(candidates.remove(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ui_tree::tests::LIST_DUMP;
+
+    #[test]
+    fn test_ui_diff() {
+        let before = UiTree::parse(LIST_DUMP).unwrap();
+        assert!(UiDiff::compute(&before, &before).is_unchanged());
+
+        // 点击第一个关注按钮后变为已关注
+        let tapped = LIST_DUMP.replacen(r#"text="关注""#, r#"text="已关注""#, 1);
+        let diff = UiDiff::between(LIST_DUMP, &tapped).unwrap();
+        assert_eq!(diff.changed.len(), 1);
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.moved.is_empty());
+        assert!(diff.similarity < 1.0 && diff.similarity > 0.8);
+        let change = diff.expect_change_at(900, 260, "text", Some("关注"), Some("已关注")).unwrap();
+        assert_eq!(change.after.as_deref(), Some("已关注"));
+        // 第二个按钮没有变化
+        assert!(diff.expect_change_at(900, 360, "text", None, None).is_err());
+        assert!(diff.expect_change_at(900, 260, "text", None, Some("关注")).is_err());
+
+        // 列表顶部插入新项：原有项被识别为移动，新项为新增
+        let inserted = LIST_DUMP.replacen(
+            r#"<node index="0" class="android.widget.LinearLayout""#,
+            r#"<node index="0" text="新用户" class="android.widget.TextView" bounds="[0,100][1080,200]" /><node index="0" class="android.widget.LinearLayout""#,
+            1,
+        );
+        let diff = UiDiff::between(LIST_DUMP, &inserted).unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].text, "新用户");
+        assert!(diff.removed.is_empty());
+        assert!(!diff.moved.is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use super::node::{UiNode, UiTree};
+
+/// 参与唯一性判断的语义属性（按稳定性排序）
+const SEMANTIC_ATTRS: [&str; 3] = ["resource-id", "content-desc", "text"];
+
+/// 生成能唯一定位该节点的最短 XPath
+///
+/// 优先使用仅由属性谓词组成的表达式；只有当属性无法区分时才引入位置下标
+/// （锚定到最近的可唯一定位祖先，或退化为从根开始的绝对路径）。
+pub fn unique_xpath(node: &UiNode) -> String {
+    let tree = node.tree();
+
+    // 1. 纯属性谓词：单属性 → 属性组合
+    let mut unique: Vec<String> = predicate_candidates(node)
+        .into_iter()
+        .map(|pred| format!("//node[{}]", pred))
+        .filter(|xpath| selects_only(tree, xpath, node))
+        .collect();
+    if let Some(best) = shortest(&mut unique) {
+        return best;
+    }
+
+    // 2. 需要位置下标：在候选中取最短
+    let mut indexed = Vec::new();
+    if let Some(pred) = predicate_candidates(node).into_iter().next() {
+        let all = format!("//node[{}]", pred);
+        if let Ok(matches) = tree.select(&all) {
+            if let Some(pos) = matches.iter().position(|m| m == node) {
+                indexed.push(format!("({})[{}]", all, pos + 1));
+            }
+        }
+    }
+    if let Some(anchored) = anchored_xpath(node) {
+        indexed.push(anchored);
+    }
+    indexed.push(absolute_xpath(node));
+
+    indexed.retain(|xpath| selects_only(tree, xpath, node));
+    shortest(&mut indexed).unwrap_or_else(|| absolute_xpath(node))
+}
+
+/// 从根开始的绝对路径，仅在同名兄弟存在时添加下标
+pub fn absolute_xpath(node: &UiNode) -> String {
+    let mut steps: Vec<String> = std::iter::once(*node)
+        .chain(node.ancestors())
+        .map(|n| location_step(&n))
+        .collect();
+    steps.reverse();
+    format!("/{}", steps.join("/"))
+}
+
+/// 锚定到最近的可用属性唯一定位的祖先，再用逐级路径到达目标
+fn anchored_xpath(node: &UiNode) -> Option<String> {
+    let tree = node.tree();
+    let mut steps = vec![location_step(node)];
+
+    for ancestor in node.ancestors() {
+        if ancestor.tag() != "node" {
+            break;
+        }
+        let anchor = predicate_candidates(&ancestor)
+            .into_iter()
+            .map(|pred| format!("//node[{}]", pred))
+            .find(|xpath| selects_only(tree, xpath, &ancestor));
+        if let Some(anchor) = anchor {
+            steps.reverse();
+            return Some(format!("{}/{}", anchor, steps.join("/")));
+        }
+        steps.push(location_step(&ancestor));
+    }
+    None
+}
+
+/// 单个路径步骤：优先用类名作为名称测试，同名兄弟多于一个时追加下标
+fn location_step(node: &UiNode) -> String {
+    let class_name = node.class_name();
+    let name = if is_valid_name(class_name) { class_name } else { node.tag() };
+
+    let Some(parent) = node.parent() else {
+        return name.to_string();
+    };
+    // 与 XPath 求值一致：名称测试同时匹配标签名和 class 属性
+    let same_name: Vec<UiNode> = parent
+        .children()
+        .filter(|n| n.tag() == name || n.class_name() == name)
+        .collect();
+    if same_name.len() <= 1 {
+        return name.to_string();
+    }
+    let pos = same_name.iter().position(|n| n == node).unwrap_or(0);
+    format!("{}[{}]", name, pos + 1)
+}
+
+/// 节点可用的属性谓词（单属性在前，组合在后）
+fn predicate_candidates(node: &UiNode) -> Vec<String> {
+    let present: Vec<(&str, &str)> = SEMANTIC_ATTRS
+        .iter()
+        .filter_map(|&attr| {
+            let value = node.attr(attr).unwrap_or("");
+            (!value.is_empty() && xpath_literal(value).is_some()).then_some((attr, value))
+        })
+        .collect();
+
+    let mut preds: Vec<String> = present.iter().map(|&(a, v)| attr_predicate(a, v)).collect();
+
+    for i in 0..present.len() {
+        for j in i + 1..present.len() {
+            preds.push(format!(
+                "{} and {}",
+                attr_predicate(present[i].0, present[i].1),
+                attr_predicate(present[j].0, present[j].1)
+            ));
+        }
+    }
+
+    let class_name = node.class_name();
+    if !class_name.is_empty() && xpath_literal(class_name).is_some() {
+        let class_pred = attr_predicate("class", class_name);
+        for &(a, v) in &present {
+            preds.push(format!("{} and {}", class_pred, attr_predicate(a, v)));
+        }
+        if present.len() >= 2 {
+            let all: Vec<String> = present.iter().map(|&(a, v)| attr_predicate(a, v)).collect();
+            preds.push(format!("{} and {}", class_pred, all.join(" and ")));
+        }
+    }
+    preds
+}
+
+fn attr_predicate(attr: &str, value: &str) -> String {
+    format!("@{}={}", attr, xpath_literal(value).unwrap_or_default())
+}
+
+/// XPath 字符串字面量；同时含有单双引号时无法表示，返回 None
+pub fn xpath_literal(value: &str) -> Option<String> {
+    if !value.contains('\'') {
+        Some(format!("'{}'", value))
+    } else if !value.contains('"') {
+        Some(format!("\"{}\"", value))
+    } else {
+        None
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '$'))
+}
+
+fn selects_only(tree: &UiTree, xpath: &str, node: &UiNode) -> bool {
+    matches!(tree.select(xpath).as_deref(), Ok([only]) if only == node)
+}
+
+fn shortest(candidates: &mut [String]) -> Option<String> {
+    candidates.sort_by_key(|c| c.chars().count());
+    candidates.first().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ui_tree::tests::{DUMP, LIST_DUMP};
+
+    #[test]
+    fn test_unique_xpath() {
+        let tree = UiTree::parse(DUMP).unwrap();
+        let title = tree.find_first(|n| n.text() == "通讯录好友").unwrap();
+        let xpath = unique_xpath(&title);
+        assert_eq!(xpath, "//node[@text='通讯录好友']");
+
+        // 属性相同的节点需要位置下标，且每个 XPath 只命中自身
+        let tree = UiTree::parse(LIST_DUMP).unwrap();
+        let buttons = tree.find_all(|n| n.text() == "关注");
+        assert_eq!(buttons.len(), 2);
+        let xpaths: Vec<String> = buttons.iter().map(unique_xpath).collect();
+        assert_ne!(xpaths[0], xpaths[1]);
+        for (button, xpath) in buttons.iter().zip(&xpaths) {
+            assert!(xpath.contains('['));
+            assert_eq!(tree.select(xpath).unwrap(), vec![*button]);
+        }
+
+        let absolute = absolute_xpath(&buttons[1]);
+        assert_eq!(
+            absolute,
+            "/hierarchy/android.widget.FrameLayout/android.widget.LinearLayout[2]/android.widget.Button"
+        );
+        assert_eq!(tree.select(&absolute).unwrap(), vec![buttons[1]]);
+    }
+}
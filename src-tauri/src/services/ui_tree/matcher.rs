@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::locator::unique_xpath;
+use super::node::{parse_bounds, UiNode, UiTree};
+
+/// 匹配策略
+///
+/// - `Absolute`：所有字段（含 bounds/index）精确相等
+/// - `Strict`：语义字段精确相等；位置字段不过滤，仅用于排序
+/// - `Relaxed`：text/content-desc 包含匹配，resource-id/class 允许省略前缀，忽略大小写；位置仅用于排序
+/// - `Positionless`：语义字段精确相等，完全忽略位置字段
+/// - `Standard`：跨设备稳定匹配，resource-id 忽略包名前缀，文本去除首尾空白后比较；忽略位置字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchStrategy {
+    Absolute,
+    Strict,
+    Relaxed,
+    Positionless,
+    Standard,
+}
+
+impl MatchStrategy {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "absolute" => Ok(Self::Absolute),
+            "strict" => Ok(Self::Strict),
+            "relaxed" => Ok(Self::Relaxed),
+            "positionless" => Ok(Self::Positionless),
+            "standard" => Ok(Self::Standard),
+            other => Err(anyhow!("未知的匹配策略: {}", other)),
+        }
+    }
+
+    /// 位置字段是否参与过滤
+    fn filters_position(self) -> bool {
+        self == Self::Absolute
+    }
+
+    /// 位置字段是否参与排序
+    fn ranks_position(self) -> bool {
+        matches!(self, Self::Absolute | Self::Strict | Self::Relaxed)
+    }
+}
+
+/// 单个匹配结果
+#[derive(Debug, Clone)]
+pub struct NodeMatch<'a> {
+    pub node: UiNode<'a>,
+    /// 得分越高越可信
+    pub score: f64,
+    /// 能唯一定位该节点的 XPath
+    pub xpath: String,
+}
+
+const POSITION_FIELDS: [&str; 2] = ["bounds", "index"];
+
+/// 按策略匹配节点，返回按得分排序的全部结果
+///
+/// `values` 中留空的字段视为“任意”；`xpath` 字段会先把候选限制在该表达式的结果内。
+pub fn match_nodes<'a>(
+    tree: &'a UiTree,
+    strategy: MatchStrategy,
+    fields: &[String],
+    values: &HashMap<String, String>,
+) -> Result<Vec<NodeMatch<'a>>> {
+    let active: Vec<(&str, &str)> = fields
+        .iter()
+        .filter_map(|f| {
+            let value = values.get(f)?.trim();
+            (!value.is_empty()).then_some((f.as_str(), value))
+        })
+        .collect();
+
+    let scope: Option<Vec<UiNode<'a>>> = match active.iter().find(|(f, _)| *f == "xpath") {
+        Some((_, xpath)) => Some(tree.select(xpath)?),
+        None => None,
+    };
+
+    let target_center = active
+        .iter()
+        .find(|(f, _)| *f == "bounds")
+        .and_then(|(_, v)| parse_bounds(v))
+        .map(|b| (b.center_x(), b.center_y()));
+
+    let mut matches: Vec<NodeMatch<'a>> = tree
+        .elements()
+        .filter(|node| scope.as_ref().is_none_or(|s| s.contains(node)))
+        .filter_map(|node| {
+            let mut exact = 0usize;
+            for &(field, expected) in &active {
+                if field == "xpath" {
+                    continue;
+                }
+                let actual = node.attr(field).unwrap_or("");
+                if POSITION_FIELDS.contains(&field) {
+                    if strategy.filters_position() && actual != expected {
+                        return None;
+                    }
+                    if strategy.ranks_position() && actual == expected {
+                        exact += 1;
+                    }
+                    continue;
+                }
+                if actual == expected {
+                    exact += 1;
+                } else if !field_matches(strategy, field, actual, expected) {
+                    return None;
+                }
+            }
+
+            // 位置接近度：中心点距离越近得分越高（0~1）
+            let proximity = match (strategy.ranks_position(), target_center, node.center()) {
+                (true, Some((tx, ty)), Some((x, y))) => {
+                    let distance = (((x - tx).pow(2) + (y - ty).pow(2)) as f64).sqrt();
+                    1.0 / (1.0 + distance / 100.0)
+                }
+                _ => 0.0,
+            };
+
+            Some(NodeMatch {
+                node,
+                score: exact as f64 + proximity,
+                xpath: String::new(),
+            })
+        })
+        .collect();
+
+    // 得分降序；同分按文档顺序
+    matches.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.node.id().cmp(&b.node.id()))
+    });
+    for m in &mut matches {
+        m.xpath = unique_xpath(&m.node);
+    }
+    Ok(matches)
+}
+
+/// 非精确相等时，按策略判断字段是否仍然匹配
+fn field_matches(strategy: MatchStrategy, field: &str, actual: &str, expected: &str) -> bool {
+    match strategy {
+        MatchStrategy::Absolute | MatchStrategy::Strict | MatchStrategy::Positionless => false,
+        MatchStrategy::Standard => match field {
+            "resource-id" => strip_package(actual) == strip_package(expected),
+            "text" | "content-desc" => actual.trim() == expected,
+            _ => false,
+        },
+        MatchStrategy::Relaxed => {
+            let actual = actual.to_lowercase();
+            let expected = expected.to_lowercase();
+            match field {
+                "text" | "content-desc" => actual.contains(&expected),
+                "resource-id" => strip_package(&actual) == strip_package(&expected),
+                "class" => actual.ends_with(&format!(".{}", expected)),
+                _ => actual == expected,
+            }
+        }
+    }
+}
+
+/// 去掉 resource-id 的包名前缀（`com.app:id/name` → `name`）
+fn strip_package(resource_id: &str) -> &str {
+    resource_id.rsplit('/').next().unwrap_or(resource_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ui_tree::tests::LIST_DUMP;
+
+    #[test]
+    fn test_match_strategies() {
+        let tree = UiTree::parse(LIST_DUMP).unwrap();
+        let values = |pairs: &[(&str, &str)]| -> std::collections::HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let fields = |names: &[&str]| -> Vec<String> { names.iter().map(|s| s.to_string()).collect() };
+
+        // 位置字段在 strict 下只影响排序：全部返回，靠近目标的排第一
+        let criteria = values(&[("text", "关注"), ("bounds", "[800,320][1000,400]")]);
+        let matches = match_nodes(&tree, MatchStrategy::Strict, &fields(&["text", "bounds"]), &criteria).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].node.attr("bounds"), Some("[800,320][1000,400]"));
+        assert!(matches[0].score > matches[1].score);
+
+        // absolute 下位置字段参与过滤
+        let matches = match_nodes(&tree, MatchStrategy::Absolute, &fields(&["text", "bounds"]), &criteria).unwrap();
+        assert_eq!(matches.len(), 1);
+
+        // positionless 完全忽略位置：按文档顺序
+        let matches = match_nodes(&tree, MatchStrategy::Positionless, &fields(&["text", "bounds"]), &criteria).unwrap();
+        assert_eq!(matches[0].node.attr("bounds"), Some("[800,220][1000,300]"));
+
+        // standard 忽略 resource-id 包名；strict 不忽略
+        let criteria = values(&[("resource-id", "com.other:id/follow")]);
+        assert_eq!(match_nodes(&tree, MatchStrategy::Standard, &fields(&["resource-id"]), &criteria).unwrap().len(), 2);
+        assert!(match_nodes(&tree, MatchStrategy::Strict, &fields(&["resource-id"]), &criteria).unwrap().is_empty());
+
+        // relaxed 支持包含与类名简写
+        let criteria = values(&[("text", "李"), ("class", "TextView")]);
+        let matches = match_nodes(&tree, MatchStrategy::Relaxed, &fields(&["text", "class"]), &criteria).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(tree.select(&matches[0].xpath).unwrap(), vec![matches[0].node]);
+
+        assert!(MatchStrategy::parse("fuzzy").is_err());
+    }
+}
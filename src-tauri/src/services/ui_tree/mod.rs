@@ -0,0 +1,95 @@
+//! UI层次结构统一模型
+//! 基于 quick-xml 解析 uiautomator dump，提供父/子/兄弟导航、类型化属性、XPath 查询、
+//! 唯一 XPath 生成、按策略的节点匹配以及两份 dump 的结构化差异，
+//! 供所有需要读取 UI XML 的服务共用
+
+pub mod diff;
+pub mod locator;
+pub mod matcher;
+pub mod node;
+pub mod xpath;
+
+pub use diff::UiDiff;
+pub use matcher::{match_nodes, MatchStrategy};
+pub use node::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 压缩为单行的 dump（行解析器无法处理）；子模块测试共用
+    pub(super) const DUMP: &str = r#"UI hierchary dumped to: /sdcard/window_dump.xml
+<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation="0"><node index="0" text="" resource-id="" class="android.widget.FrameLayout" package="com.xingin.xhs" clickable="false" bounds="[0,0][1080,1920]"><node index="0" text="通讯录好友" resource-id="com.xingin.xhs:id/title" class="android.widget.TextView" package="com.xingin.xhs" clickable="false" bounds="[0,100][1080,200]" /><node index="1" text="" resource-id="com.xingin.xhs:id/list" class="androidx.recyclerview.widget.RecyclerView" package="com.xingin.xhs" scrollable="true" bounds="[0,200][1080,1800]"><node index="0" text="张三" resource-id="com.xingin.xhs:id/name" class="android.widget.TextView" package="com.xingin.xhs" bounds="[100,220][500,300]" /><node index="1" text="关注" resource-id="com.xingin.xhs:id/follow" class="android.widget.Button" package="com.xingin.xhs" clickable="true" bounds="[800,220][1000,300]" /><node index="2" text="李四 &amp; 王五" resource-id="com.xingin.xhs:id/name" class="android.widget.TextView" package="com.xingin.xhs" bounds="[100,320][500,400]" /><node index="3" text="已关注" resource-id="com.xingin.xhs:id/follow" class="android.widget.Button" package="com.xingin.xhs" clickable="true" enabled="false" bounds="[800,320][1000,400]" /></node></node></hierarchy>"#;
+
+    fn texts(nodes: &[UiNode]) -> Vec<String> {
+        nodes.iter().map(|n| n.text().to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_and_navigate() {
+        let tree = UiTree::parse(DUMP).unwrap();
+        assert_eq!(tree.root().unwrap().tag(), "hierarchy");
+        assert_eq!(tree.elements().count(), 7);
+
+        let follow = tree.find_first(|n| n.text() == "关注").unwrap();
+        assert!(follow.is_clickable());
+        assert!(follow.is_enabled());
+        assert_eq!(follow.center(), Some((900, 260)));
+        assert_eq!(follow.prev_sibling().unwrap().text(), "张三");
+        assert_eq!(follow.next_sibling().unwrap().text(), "李四 & 王五");
+        assert!(follow.parent().unwrap().is_scrollable());
+        assert_eq!(follow.ancestors().count(), 3);
+        assert_eq!(follow.depth(), 3);
+
+        let list = follow.parent().unwrap();
+        assert_eq!(list.children().count(), 4);
+        assert_eq!(list.descendants().len(), 4);
+        assert!(!tree.find_first(|n| n.text() == "已关注").unwrap().is_enabled());
+    }
+
+    #[test]
+    fn test_xpath_queries() {
+        let tree = UiTree::parse(DUMP).unwrap();
+
+        let names = tree.select("//node[@resource-id='com.xingin.xhs:id/name']").unwrap();
+        assert_eq!(texts(&names), vec!["张三", "李四 & 王五"]);
+
+        let buttons = tree.select("//android.widget.Button[@clickable='true' and not(@enabled='false')]").unwrap();
+        assert_eq!(texts(&buttons), vec!["关注"]);
+
+        let contains = tree.select("//*[contains(@text, '王五')]").unwrap();
+        assert_eq!(contains.len(), 1);
+
+        let last = tree.select("//node[@scrollable='true']/node[last()]").unwrap();
+        assert_eq!(texts(&last), vec!["已关注"]);
+
+        let second = tree.select("(//node[@resource-id='com.xingin.xhs:id/follow'])[2]").unwrap();
+        assert_eq!(texts(&second), vec!["已关注"]);
+
+        let sibling = tree
+            .select("//node[text()='张三']/following-sibling::node[1]")
+            .unwrap();
+        assert_eq!(texts(&sibling), vec!["关注"]);
+
+        let parent = tree.select("//node[@text='关注']/..").unwrap();
+        assert_eq!(parent[0].class_name(), "androidx.recyclerview.widget.RecyclerView");
+
+        let absolute = tree.select("/hierarchy/node/node[1]").unwrap();
+        assert_eq!(texts(&absolute), vec!["通讯录好友"]);
+
+        let union = tree.select("//node[@index=0 and @text!=''] | //node[@text='已关注']").unwrap();
+        assert_eq!(texts(&union), vec!["通讯录好友", "张三", "已关注"]);
+
+        let with_child = tree.select("//node[node[@text='张三']]").unwrap();
+        assert!(with_child[0].is_scrollable());
+
+        let relative = with_child[0].select("./node[starts-with(@text, '李')]").unwrap();
+        assert_eq!(relative.len(), 1);
+
+        assert!(tree.select("//node[@text='unclosed]").is_err());
+        assert!(tree.select("//node[unknown(@text)]").is_err());
+    }
+
+    // 两个列表项的关注按钮文本和 resource-id 完全相同
+    pub(super) const LIST_DUMP: &str = r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation="0"><node index="0" class="android.widget.FrameLayout" resource-id="com.xingin.xhs:id/root" bounds="[0,0][1080,1920]"><node index="0" class="android.widget.LinearLayout" bounds="[0,200][1080,300]"><node index="0" text="张三" class="android.widget.TextView" bounds="[100,220][500,300]" /><node index="1" text="关注" resource-id="com.xingin.xhs:id/follow" class="android.widget.Button" clickable="true" bounds="[800,220][1000,300]" /></node><node index="1" class="android.widget.LinearLayout" bounds="[0,300][1080,400]"><node index="0" text="李四" class="android.widget.TextView" bounds="[100,320][500,400]" /><node index="1" text="关注" resource-id="com.xingin.xhs:id/follow" class="android.widget.Button" clickable="true" bounds="[800,320][1000,400]" /></node></node></hierarchy>"#;
+}
@@ -0,0 +1,376 @@
+use anyhow::{anyhow, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::types::page_analysis::ElementBounds;
+
+/// 节点在树中的编号（按文档顺序分配）
+pub type NodeId = usize;
+
+/// 单个 XML 元素的原始数据
+#[derive(Debug, Clone)]
+pub struct UiNodeData {
+    pub tag: String,
+    pub attributes: Vec<(String, String)>,
+    pub parent: Option<NodeId>,
+    pub children: Vec<NodeId>,
+    pub depth: usize,
+}
+
+/// UI 层次结构树（uiautomator dump 的统一解析结果）
+///
+/// 节点以数组形式存储，编号即文档顺序，父子关系通过编号互相引用。
+#[derive(Debug, Clone, Default)]
+pub struct UiTree {
+    nodes: Vec<UiNodeData>,
+}
+
+impl UiTree {
+    /// 解析 uiautomator dump 输出
+    ///
+    /// 兼容 `uiautomator dump && cat` 前后附带的提示文字，以及压缩成单行的 XML。
+    pub fn parse(xml_content: &str) -> Result<Self> {
+        let start = xml_content
+            .find("<?xml")
+            .or_else(|| xml_content.find("<hierarchy"))
+            .or_else(|| xml_content.find('<'))
+            .ok_or_else(|| anyhow!("UI dump中没有XML内容"))?;
+
+        let mut reader = Reader::from_str(&xml_content[start..]);
+        reader.config_mut().trim_text(true);
+
+        let mut tree = UiTree::default();
+        let mut stack: Vec<NodeId> = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) => {
+                    let id = tree.push_node(e, stack.last().copied());
+                    stack.push(id);
+                }
+                Ok(Event::Empty(ref e)) => {
+                    tree.push_node(e, stack.last().copied());
+                }
+                Ok(Event::End(_)) => {
+                    stack.pop();
+                    // 根元素结束后忽略尾部的其他输出
+                    if stack.is_empty() && !tree.nodes.is_empty() {
+                        break;
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    // 已解析出部分节点时容忍截断的 dump
+                    if tree.nodes.is_empty() {
+                        return Err(anyhow!("XML解析错误: {}", e));
+                    }
+                    break;
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        if tree.nodes.is_empty() {
+            return Err(anyhow!("UI dump中没有任何节点"));
+        }
+        Ok(tree)
+    }
+
+    fn push_node(&mut self, element: &BytesStart, parent: Option<NodeId>) -> NodeId {
+        let tag = String::from_utf8_lossy(element.name().as_ref()).to_string();
+        let attributes = element
+            .attributes()
+            .flatten()
+            .map(|attr| {
+                let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+                let value = attr
+                    .unescape_value()
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).to_string());
+                (key, value)
+            })
+            .collect();
+
+        let id = self.nodes.len();
+        let depth = parent.map_or(0, |p| self.nodes[p].depth + 1);
+        self.nodes.push(UiNodeData {
+            tag,
+            attributes,
+            parent,
+            children: Vec::new(),
+            depth,
+        });
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(id);
+        }
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 根元素（通常为 `hierarchy`）
+    pub fn root(&self) -> Option<UiNode<'_>> {
+        (!self.nodes.is_empty()).then(|| self.node(0))
+    }
+
+    pub fn node(&self, id: NodeId) -> UiNode<'_> {
+        UiNode { tree: self, id }
+    }
+
+    pub(crate) fn data(&self, id: NodeId) -> &UiNodeData {
+        &self.nodes[id]
+    }
+
+    /// 按文档顺序遍历所有节点
+    pub fn nodes(&self) -> impl Iterator<Item = UiNode<'_>> {
+        (0..self.nodes.len()).map(move |id| self.node(id))
+    }
+
+    /// 按文档顺序遍历所有 `node` 元素（跳过 `hierarchy` 根）
+    pub fn elements(&self) -> impl Iterator<Item = UiNode<'_>> {
+        self.nodes().filter(|n| n.tag() == "node")
+    }
+
+    /// 查找满足条件的节点
+    pub fn find_all<F>(&self, predicate: F) -> Vec<UiNode<'_>>
+    where
+        F: Fn(&UiNode) -> bool,
+    {
+        self.nodes().filter(|n| predicate(n)).collect()
+    }
+
+    /// 查找第一个满足条件的节点
+    pub fn find_first<F>(&self, predicate: F) -> Option<UiNode<'_>>
+    where
+        F: Fn(&UiNode) -> bool,
+    {
+        self.nodes().find(|n| predicate(n))
+    }
+
+    /// 使用 XPath（1.0 子集）查询节点
+    pub fn select(&self, xpath: &str) -> Result<Vec<UiNode<'_>>> {
+        let ids = super::xpath::select(self, None, xpath)?;
+        Ok(ids.into_iter().map(|id| self.node(id)).collect())
+    }
+}
+
+/// 树中节点的轻量引用，提供属性读取与导航
+#[derive(Clone, Copy)]
+pub struct UiNode<'a> {
+    tree: &'a UiTree,
+    id: NodeId,
+}
+
+impl<'a> std::fmt::Debug for UiNode<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UiNode")
+            .field("id", &self.id)
+            .field("class", &self.class_name())
+            .field("text", &self.text())
+            .field("resource_id", &self.resource_id())
+            .field("bounds", &self.attr("bounds"))
+            .finish()
+    }
+}
+
+impl<'a> PartialEq for UiNode<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.tree, other.tree) && self.id == other.id
+    }
+}
+
+impl<'a> UiNode<'a> {
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn tree(&self) -> &'a UiTree {
+        self.tree
+    }
+
+    fn data(&self) -> &'a UiNodeData {
+        self.tree.data(self.id)
+    }
+
+    pub fn tag(&self) -> &'a str {
+        &self.data().tag
+    }
+
+    pub fn depth(&self) -> usize {
+        self.data().depth
+    }
+
+    pub fn attributes(&self) -> &'a [(String, String)] {
+        &self.data().attributes
+    }
+
+    /// 读取原始属性值
+    pub fn attr(&self, name: &str) -> Option<&'a str> {
+        self.data()
+            .attributes
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 读取布尔属性（缺失时返回 `default`）
+    pub fn attr_bool(&self, name: &str, default: bool) -> bool {
+        self.attr(name).map_or(default, |v| v == "true")
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.attr("text").unwrap_or("")
+    }
+
+    pub fn resource_id(&self) -> &'a str {
+        self.attr("resource-id").unwrap_or("")
+    }
+
+    pub fn class_name(&self) -> &'a str {
+        self.attr("class").unwrap_or("")
+    }
+
+    pub fn content_desc(&self) -> &'a str {
+        self.attr("content-desc").unwrap_or("")
+    }
+
+    pub fn package(&self) -> &'a str {
+        self.attr("package").unwrap_or("")
+    }
+
+    /// uiautomator 的 `index` 属性（同级序号）
+    pub fn index(&self) -> Option<usize> {
+        self.attr("index").and_then(|v| v.parse().ok())
+    }
+
+    pub fn bounds(&self) -> Option<ElementBounds> {
+        self.attr("bounds").and_then(parse_bounds)
+    }
+
+    /// 元素中心点坐标
+    pub fn center(&self) -> Option<(i32, i32)> {
+        self.bounds().map(|b| (b.center_x(), b.center_y()))
+    }
+
+    pub fn is_clickable(&self) -> bool {
+        self.attr_bool("clickable", false)
+    }
+
+    pub fn is_long_clickable(&self) -> bool {
+        self.attr_bool("long-clickable", false)
+    }
+
+    pub fn is_scrollable(&self) -> bool {
+        self.attr_bool("scrollable", false)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.attr_bool("enabled", true)
+    }
+
+    pub fn is_focusable(&self) -> bool {
+        self.attr_bool("focusable", false)
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.attr_bool("focused", false)
+    }
+
+    pub fn is_checkable(&self) -> bool {
+        self.attr_bool("checkable", false)
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.attr_bool("checked", false)
+    }
+
+    pub fn is_selected(&self) -> bool {
+        self.attr_bool("selected", false)
+    }
+
+    pub fn is_password(&self) -> bool {
+        self.attr_bool("password", false)
+    }
+
+    pub fn parent(&self) -> Option<UiNode<'a>> {
+        self.data().parent.map(|id| self.tree.node(id))
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = UiNode<'a>> + 'a {
+        let tree = self.tree;
+        self.data().children.iter().map(move |&id| tree.node(id))
+    }
+
+    pub fn child(&self, position: usize) -> Option<UiNode<'a>> {
+        self.data().children.get(position).map(|&id| self.tree.node(id))
+    }
+
+    /// 兄弟节点列表（包含自身）
+    fn siblings(&self) -> &'a [NodeId] {
+        match self.data().parent {
+            Some(parent) => &self.tree.data(parent).children,
+            None => &[],
+        }
+    }
+
+    /// 在父节点中的位置（从0开始）
+    pub fn position(&self) -> usize {
+        self.siblings().iter().position(|&id| id == self.id).unwrap_or(0)
+    }
+
+    pub fn next_sibling(&self) -> Option<UiNode<'a>> {
+        let siblings = self.siblings();
+        let pos = siblings.iter().position(|&id| id == self.id)?;
+        siblings.get(pos + 1).map(|&id| self.tree.node(id))
+    }
+
+    pub fn prev_sibling(&self) -> Option<UiNode<'a>> {
+        let siblings = self.siblings();
+        let pos = siblings.iter().position(|&id| id == self.id)?;
+        pos.checked_sub(1).map(|p| self.tree.node(siblings[p]))
+    }
+
+    /// 从父节点到根的祖先链
+    pub fn ancestors(&self) -> impl Iterator<Item = UiNode<'a>> + 'a {
+        std::iter::successors(self.parent(), |n| n.parent())
+    }
+
+    /// 所有后代节点（文档顺序）
+    pub fn descendants(&self) -> Vec<UiNode<'a>> {
+        let mut result = Vec::new();
+        let mut stack: Vec<NodeId> = self.data().children.iter().rev().copied().collect();
+        while let Some(id) = stack.pop() {
+            result.push(self.tree.node(id));
+            stack.extend(self.tree.data(id).children.iter().rev());
+        }
+        result
+    }
+
+    /// 以当前节点为上下文执行 XPath 查询
+    pub fn select(&self, xpath: &str) -> Result<Vec<UiNode<'a>>> {
+        let ids = super::xpath::select(self.tree, Some(self.id), xpath)?;
+        Ok(ids.into_iter().map(|id| self.tree.node(id)).collect())
+    }
+}
+
+/// 解析 bounds 字符串 "[left,top][right,bottom]"
+pub fn parse_bounds(bounds_str: &str) -> Option<ElementBounds> {
+    let inner = bounds_str.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let (left_top, right_bottom) = inner.split_once("][")?;
+    let (left, top) = left_top.split_once(',')?;
+    let (right, bottom) = right_bottom.split_once(',')?;
+    Some(ElementBounds {
+        left: left.trim().parse().ok()?,
+        top: top.trim().parse().ok()?,
+        right: right.trim().parse().ok()?,
+        bottom: bottom.trim().parse().ok()?,
+    })
+}
@@ -0,0 +1,821 @@
+//! XPath 1.0 子集，作用于 [`UiTree`]
+//!
+//! 支持：
+//! - 绝对/相对路径、`//`、`.`、`..`、`*`
+//! - 轴：child、descendant、descendant-or-self、parent、ancestor、ancestor-or-self、
+//!   following-sibling、preceding-sibling、self、attribute（`@`）
+//! - 节点名既可匹配标签名（`node`），也可匹配 `class` 属性（如 `android.widget.TextView`）
+//! - 谓词：位置 `[1]`、`[last()]`、`(//node)[2]`、比较 `= != < <= > >=`、`and`/`or`、并集 `|`
+//! - 函数：contains、starts-with、ends-with、not、last、position、count、
+//!   string-length、normalize-space、string、number、true、false、text()
+
+use anyhow::{anyhow, Result};
+
+use super::node::{NodeId, UiTree};
+
+/// 文档节点（所有元素之上的虚拟根）
+const DOCUMENT: NodeId = usize::MAX;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Slash,
+    DoubleSlash,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    At,
+    Comma,
+    Pipe,
+    Dot,
+    DotDot,
+    ColonColon,
+    Star,
+    Op(&'static str),
+    Name(String),
+    Literal(String),
+    Number(f64),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            ' ' | '\t' | '\n' | '\r' => i += 1,
+            '/' if next == Some('/') => {
+                tokens.push(Token::DoubleSlash);
+                i += 2;
+            }
+            '/' => {
+                tokens.push(Token::Slash);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '@' => {
+                tokens.push(Token::At);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '|' => {
+                tokens.push(Token::Pipe);
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                i += 1;
+            }
+            ':' if next == Some(':') => {
+                tokens.push(Token::ColonColon);
+                i += 2;
+            }
+            '=' => {
+                tokens.push(Token::Op("="));
+                i += 1;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Op("!="));
+                i += 2;
+            }
+            '<' | '>' => {
+                let op = match (c, next) {
+                    ('<', Some('=')) => "<=",
+                    ('>', Some('=')) => ">=",
+                    ('<', _) => "<",
+                    _ => ">",
+                };
+                tokens.push(Token::Op(op));
+                i += op.len();
+            }
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| anyhow!("XPath字符串未闭合: {}", input))?;
+                tokens.push(Token::Literal(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            '.' if next == Some('.') => {
+                tokens.push(Token::DotDot);
+                i += 2;
+            }
+            '.' if !next.is_some_and(|n| n.is_ascii_digit()) => {
+                tokens.push(Token::Dot);
+                i += 1;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text
+                    .parse()
+                    .map_err(|_| anyhow!("XPath数字格式错误: {}", text))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() {
+                    let ch = chars[i];
+                    // 名称中允许 '.'、'-'、'$'（如 android.widget.TextView、resource-id）
+                    let single_colon = ch == ':' && chars.get(i + 1) != Some(&':');
+                    if ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | '$') || single_colon {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Name(chars[start..i].iter().collect()));
+            }
+            _ => return Err(anyhow!("XPath包含无法识别的字符 '{}': {}", c, input)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis {
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    Parent,
+    Ancestor,
+    AncestorOrSelf,
+    FollowingSibling,
+    PrecedingSibling,
+    Itself,
+    Attribute,
+}
+
+impl Axis {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "child" => Self::Child,
+            "descendant" => Self::Descendant,
+            "descendant-or-self" => Self::DescendantOrSelf,
+            "parent" => Self::Parent,
+            "ancestor" => Self::Ancestor,
+            "ancestor-or-self" => Self::AncestorOrSelf,
+            "following-sibling" => Self::FollowingSibling,
+            "preceding-sibling" => Self::PrecedingSibling,
+            "self" => Self::Itself,
+            "attribute" => Self::Attribute,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum NodeTest {
+    Any,
+    Name(String),
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Expr>,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Path { absolute: bool, steps: Vec<Step> },
+    Union(Vec<Expr>),
+    /// 带谓词的括号表达式，如 `(//node)[2]`
+    Filter(Box<Expr>, Vec<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Compare(&'static str, Box<Expr>, Box<Expr>),
+    Function(String, Vec<Expr>),
+    Literal(String),
+    Number(f64),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(ref t) if *t == expected => Ok(()),
+            other => Err(anyhow!("XPath语法错误：期望 {:?}，实际 {:?}", expected, other)),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(n)) if n == keyword)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.is_keyword("or") {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut left = self.parse_equality()?;
+        while self.is_keyword("and") {
+            self.pos += 1;
+            let right = self.parse_equality()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_equality(&mut self) -> Result<Expr> {
+        let mut left = self.parse_relational()?;
+        while let Some(Token::Op(op @ ("=" | "!="))) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.parse_relational()?;
+            left = Expr::Compare(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_relational(&mut self) -> Result<Expr> {
+        let mut left = self.parse_union()?;
+        while let Some(Token::Op(op @ ("<" | "<=" | ">" | ">="))) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.parse_union()?;
+            left = Expr::Compare(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_union(&mut self) -> Result<Expr> {
+        let first = self.parse_primary()?;
+        if self.peek() != Some(&Token::Pipe) {
+            return Ok(first);
+        }
+        let mut parts = vec![first];
+        while self.peek() == Some(&Token::Pipe) {
+            self.pos += 1;
+            parts.push(self.parse_primary()?);
+        }
+        Ok(Expr::Union(parts))
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.peek().cloned() {
+            Some(Token::Literal(s)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(s))
+            }
+            Some(Token::Number(n)) => {
+                self.pos += 1;
+                Ok(Expr::Number(n))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                let mut predicates = Vec::new();
+                while self.peek() == Some(&Token::LBracket) {
+                    self.pos += 1;
+                    predicates.push(self.parse_or()?);
+                    self.expect(Token::RBracket)?;
+                }
+                if predicates.is_empty() {
+                    Ok(expr)
+                } else {
+                    Ok(Expr::Filter(Box::new(expr), predicates))
+                }
+            }
+            Some(Token::Name(name))
+                if self.peek_at(1) == Some(&Token::LParen) && name != "text" && name != "node" =>
+            {
+                self.pos += 2;
+                let mut args = Vec::new();
+                if self.peek() != Some(&Token::RParen) {
+                    loop {
+                        args.push(self.parse_or()?);
+                        if self.peek() == Some(&Token::Comma) {
+                            self.pos += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(Token::RParen)?;
+                Ok(Expr::Function(name, args))
+            }
+            _ => self.parse_path(),
+        }
+    }
+
+    fn parse_path(&mut self) -> Result<Expr> {
+        let mut steps = Vec::new();
+        let absolute = match self.peek() {
+            Some(Token::Slash) => {
+                self.pos += 1;
+                // 单独的 "/" 表示文档根
+                if !self.starts_step() {
+                    return Ok(Expr::Path { absolute: true, steps });
+                }
+                true
+            }
+            Some(Token::DoubleSlash) => {
+                self.pos += 1;
+                steps.push(descendant_or_self());
+                true
+            }
+            _ => false,
+        };
+
+        steps.push(self.parse_step()?);
+        loop {
+            match self.peek() {
+                Some(Token::Slash) => {
+                    self.pos += 1;
+                }
+                Some(Token::DoubleSlash) => {
+                    self.pos += 1;
+                    steps.push(descendant_or_self());
+                }
+                _ => break,
+            }
+            steps.push(self.parse_step()?);
+        }
+
+        Ok(Expr::Path { absolute, steps })
+    }
+
+    fn starts_step(&self) -> bool {
+        matches!(
+            self.peek(),
+            Some(Token::Name(_) | Token::Star | Token::At | Token::Dot | Token::DotDot)
+        )
+    }
+
+    fn parse_step(&mut self) -> Result<Step> {
+        match self.peek().cloned() {
+            Some(Token::Dot) => {
+                self.pos += 1;
+                return Ok(Step { axis: Axis::Itself, test: NodeTest::Any, predicates: Vec::new() });
+            }
+            Some(Token::DotDot) => {
+                self.pos += 1;
+                return Ok(Step { axis: Axis::Parent, test: NodeTest::Any, predicates: Vec::new() });
+            }
+            _ => {}
+        }
+
+        let mut axis = Axis::Child;
+        if self.peek() == Some(&Token::At) {
+            self.pos += 1;
+            axis = Axis::Attribute;
+        } else if let (Some(Token::Name(name)), Some(Token::ColonColon)) = (self.peek(), self.peek_at(1)) {
+            axis = Axis::from_name(name).ok_or_else(|| anyhow!("XPath不支持的轴: {}", name))?;
+            self.pos += 2;
+        }
+
+        let test = match self.next() {
+            Some(Token::Star) => NodeTest::Any,
+            // text() 视为 @text（uiautomator 的文本保存在属性中）
+            Some(Token::Name(name)) if name == "text" && self.peek() == Some(&Token::LParen) => {
+                self.expect(Token::LParen)?;
+                self.expect(Token::RParen)?;
+                axis = Axis::Attribute;
+                NodeTest::Name("text".to_string())
+            }
+            Some(Token::Name(name)) if name == "node" && self.peek() == Some(&Token::LParen) => {
+                self.expect(Token::LParen)?;
+                self.expect(Token::RParen)?;
+                NodeTest::Any
+            }
+            Some(Token::Name(name)) => NodeTest::Name(name),
+            other => return Err(anyhow!("XPath语法错误：期望节点名，实际 {:?}", other)),
+        };
+
+        let mut predicates = Vec::new();
+        while self.peek() == Some(&Token::LBracket) {
+            self.pos += 1;
+            predicates.push(self.parse_or()?);
+            self.expect(Token::RBracket)?;
+        }
+
+        Ok(Step { axis, test, predicates })
+    }
+}
+
+fn descendant_or_self() -> Step {
+    Step { axis: Axis::DescendantOrSelf, test: NodeTest::Any, predicates: Vec::new() }
+}
+
+fn parse(xpath: &str) -> Result<Expr> {
+    let mut parser = Parser { tokens: tokenize(xpath)?, pos: 0 };
+    let expr = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(anyhow!("XPath语法错误：多余的内容 {:?}", &parser.tokens[parser.pos..]));
+    }
+    Ok(expr)
+}
+
+/// 求值结果
+#[derive(Debug, Clone)]
+enum Value {
+    Nodes(Vec<NodeId>),
+    /// 属性轴的结果
+    Strings(Vec<String>),
+    Str(String),
+    Num(f64),
+    Bool(bool),
+}
+
+struct Context {
+    node: NodeId,
+    position: usize,
+    size: usize,
+}
+
+struct Evaluator<'t> {
+    tree: &'t UiTree,
+}
+
+impl<'t> Evaluator<'t> {
+    fn children(&self, id: NodeId) -> Vec<NodeId> {
+        if id == DOCUMENT {
+            return if self.tree.is_empty() { Vec::new() } else { vec![0] };
+        }
+        self.tree.data(id).children.clone()
+    }
+
+    fn parent(&self, id: NodeId) -> Option<NodeId> {
+        if id == DOCUMENT {
+            return None;
+        }
+        Some(self.tree.data(id).parent.unwrap_or(DOCUMENT))
+    }
+
+    fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut result = Vec::new();
+        let mut stack: Vec<NodeId> = self.children(id).into_iter().rev().collect();
+        while let Some(current) = stack.pop() {
+            result.push(current);
+            stack.extend(self.tree.data(current).children.iter().rev());
+        }
+        result
+    }
+
+    fn siblings(&self, id: NodeId) -> (Vec<NodeId>, usize) {
+        let siblings = match self.parent(id) {
+            Some(parent) => self.children(parent),
+            None => vec![id],
+        };
+        let pos = siblings.iter().position(|&s| s == id).unwrap_or(0);
+        (siblings, pos)
+    }
+
+    /// 按轴方向排列的候选节点（反向轴按距离由近到远，用于位置谓词）
+    fn axis_nodes(&self, axis: Axis, id: NodeId) -> Vec<NodeId> {
+        match axis {
+            Axis::Child => self.children(id),
+            Axis::Descendant => self.descendants(id),
+            Axis::DescendantOrSelf => {
+                let mut nodes = vec![id];
+                nodes.extend(self.descendants(id));
+                nodes
+            }
+            Axis::Parent => self.parent(id).into_iter().collect(),
+            Axis::Ancestor => std::iter::successors(self.parent(id), |&p| self.parent(p)).collect(),
+            Axis::AncestorOrSelf => std::iter::successors(Some(id), |&p| self.parent(p)).collect(),
+            Axis::FollowingSibling => {
+                let (siblings, pos) = self.siblings(id);
+                siblings[pos + 1..].to_vec()
+            }
+            Axis::PrecedingSibling => {
+                let (siblings, pos) = self.siblings(id);
+                siblings[..pos].iter().rev().copied().collect()
+            }
+            Axis::Itself => vec![id],
+            Axis::Attribute => Vec::new(),
+        }
+    }
+
+    fn matches_test(&self, test: &NodeTest, id: NodeId) -> bool {
+        match test {
+            NodeTest::Any => id != DOCUMENT,
+            NodeTest::Name(name) => {
+                if id == DOCUMENT {
+                    return false;
+                }
+                let node = self.tree.node(id);
+                node.tag() == name || node.class_name() == name
+            }
+        }
+    }
+
+    fn attribute_values(&self, test: &NodeTest, id: NodeId) -> Vec<String> {
+        if id == DOCUMENT {
+            return Vec::new();
+        }
+        let node = self.tree.node(id);
+        match test {
+            NodeTest::Any => node.attributes().iter().map(|(_, v)| v.clone()).collect(),
+            NodeTest::Name(name) => node.attr(name).map(|v| vec![v.to_string()]).unwrap_or_default(),
+        }
+    }
+
+    fn eval_path(&self, absolute: bool, steps: &[Step], ctx: &Context) -> Result<Value> {
+        let mut current = vec![if absolute { DOCUMENT } else { ctx.node }];
+
+        for (index, step) in steps.iter().enumerate() {
+            if step.axis == Axis::Attribute {
+                if index != steps.len() - 1 {
+                    return Err(anyhow!("XPath中属性步骤只能位于路径末尾"));
+                }
+                let values = current
+                    .iter()
+                    .flat_map(|&id| self.attribute_values(&step.test, id))
+                    .collect();
+                return Ok(Value::Strings(values));
+            }
+
+            let mut next = Vec::new();
+            for &id in &current {
+                let mut candidates: Vec<NodeId> = self
+                    .axis_nodes(step.axis, id)
+                    .into_iter()
+                    .filter(|&n| self.matches_test(&step.test, n))
+                    .collect();
+                for predicate in &step.predicates {
+                    candidates = self.filter(&candidates, predicate)?;
+                }
+                next.extend(candidates);
+            }
+            // 文档顺序去重（编号即文档顺序，文档节点排在最前）
+            next.sort_by_key(|&id| if id == DOCUMENT { 0 } else { id + 1 });
+            next.dedup();
+            current = next;
+        }
+
+        Ok(Value::Nodes(current))
+    }
+
+    fn filter(&self, candidates: &[NodeId], predicate: &Expr) -> Result<Vec<NodeId>> {
+        let size = candidates.len();
+        let mut kept = Vec::new();
+        for (i, &id) in candidates.iter().enumerate() {
+            let ctx = Context { node: id, position: i + 1, size };
+            let keep = match self.eval(predicate, &ctx)? {
+                Value::Num(n) => (n - (i + 1) as f64).abs() < f64::EPSILON,
+                other => to_bool(&other),
+            };
+            if keep {
+                kept.push(id);
+            }
+        }
+        Ok(kept)
+    }
+
+    fn eval(&self, expr: &Expr, ctx: &Context) -> Result<Value> {
+        Ok(match expr {
+            Expr::Path { absolute, steps } => {
+                if steps.is_empty() {
+                    Value::Nodes(vec![DOCUMENT])
+                } else {
+                    self.eval_path(*absolute, steps, ctx)?
+                }
+            }
+            Expr::Union(parts) => {
+                let mut nodes = Vec::new();
+                for part in parts {
+                    match self.eval(part, ctx)? {
+                        Value::Nodes(n) => nodes.extend(n),
+                        _ => return Err(anyhow!("XPath并集的操作数必须是节点集")),
+                    }
+                }
+                nodes.sort_by_key(|&id| if id == DOCUMENT { 0 } else { id + 1 });
+                nodes.dedup();
+                Value::Nodes(nodes)
+            }
+            Expr::Filter(inner, predicates) => {
+                let Value::Nodes(mut nodes) = self.eval(inner, ctx)? else {
+                    return Err(anyhow!("XPath谓词只能作用于节点集"));
+                };
+                for predicate in predicates {
+                    nodes = self.filter(&nodes, predicate)?;
+                }
+                Value::Nodes(nodes)
+            }
+            Expr::Or(a, b) => Value::Bool(to_bool(&self.eval(a, ctx)?) || to_bool(&self.eval(b, ctx)?)),
+            Expr::And(a, b) => Value::Bool(to_bool(&self.eval(a, ctx)?) && to_bool(&self.eval(b, ctx)?)),
+            Expr::Compare(op, a, b) => {
+                let left = self.eval(a, ctx)?;
+                let right = self.eval(b, ctx)?;
+                Value::Bool(self.compare(op, &left, &right))
+            }
+            Expr::Literal(s) => Value::Str(s.clone()),
+            Expr::Number(n) => Value::Num(*n),
+            Expr::Function(name, args) => self.call(name, args, ctx)?,
+        })
+    }
+
+    fn call(&self, name: &str, args: &[Expr], ctx: &Context) -> Result<Value> {
+        let arg = |i: usize| -> Result<Value> {
+            match args.get(i) {
+                Some(expr) => self.eval(expr, ctx),
+                None => Ok(Value::Nodes(vec![ctx.node])),
+            }
+        };
+        let expect_args = |n: usize| -> Result<()> {
+            if args.len() == n {
+                Ok(())
+            } else {
+                Err(anyhow!("XPath函数 {}() 需要 {} 个参数", name, n))
+            }
+        };
+
+        Ok(match name {
+            "contains" => {
+                expect_args(2)?;
+                Value::Bool(self.to_string(&arg(0)?).contains(&self.to_string(&arg(1)?)))
+            }
+            "starts-with" => {
+                expect_args(2)?;
+                Value::Bool(self.to_string(&arg(0)?).starts_with(&self.to_string(&arg(1)?)))
+            }
+            "ends-with" => {
+                expect_args(2)?;
+                Value::Bool(self.to_string(&arg(0)?).ends_with(&self.to_string(&arg(1)?)))
+            }
+            "not" => {
+                expect_args(1)?;
+                Value::Bool(!to_bool(&arg(0)?))
+            }
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "last" => Value::Num(ctx.size as f64),
+            "position" => Value::Num(ctx.position as f64),
+            "count" => {
+                expect_args(1)?;
+                match arg(0)? {
+                    Value::Nodes(n) => Value::Num(n.len() as f64),
+                    Value::Strings(s) => Value::Num(s.len() as f64),
+                    _ => return Err(anyhow!("count() 的参数必须是节点集")),
+                }
+            }
+            "string" => Value::Str(self.to_string(&arg(0)?)),
+            "string-length" => Value::Num(self.to_string(&arg(0)?).chars().count() as f64),
+            "normalize-space" => Value::Str(
+                self.to_string(&arg(0)?).split_whitespace().collect::<Vec<_>>().join(" "),
+            ),
+            "number" => Value::Num(self.to_number(&arg(0)?)),
+            _ => return Err(anyhow!("XPath不支持的函数: {}()", name)),
+        })
+    }
+
+    /// 节点的字符串值：uiautomator 节点取 text 属性
+    fn node_string(&self, id: NodeId) -> String {
+        if id == DOCUMENT {
+            return String::new();
+        }
+        self.tree.node(id).text().to_string()
+    }
+
+    fn to_string(&self, value: &Value) -> String {
+        match value {
+            Value::Nodes(n) => n.first().map(|&id| self.node_string(id)).unwrap_or_default(),
+            Value::Strings(s) => s.first().cloned().unwrap_or_default(),
+            Value::Str(s) => s.clone(),
+            Value::Num(n) => format_number(*n),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+
+    fn to_number(&self, value: &Value) -> f64 {
+        match value {
+            Value::Num(n) => *n,
+            Value::Bool(b) => if *b { 1.0 } else { 0.0 },
+            other => self.to_string(other).trim().parse().unwrap_or(f64::NAN),
+        }
+    }
+
+    /// 节点集/属性集展开为字符串列表（用于存在量词比较）
+    fn string_list(&self, value: &Value) -> Option<Vec<String>> {
+        match value {
+            Value::Nodes(n) => Some(n.iter().map(|&id| self.node_string(id)).collect()),
+            Value::Strings(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    fn compare(&self, op: &str, left: &Value, right: &Value) -> bool {
+        // 布尔比较
+        if (matches!(left, Value::Bool(_)) || matches!(right, Value::Bool(_))) && (op == "=" || op == "!=") {
+            let equal = to_bool(left) == to_bool(right);
+            return if op == "=" { equal } else { !equal };
+        }
+
+        let left_list = self
+            .string_list(left)
+            .unwrap_or_else(|| vec![self.to_string(left)]);
+        let right_list = self
+            .string_list(right)
+            .unwrap_or_else(|| vec![self.to_string(right)]);
+        let numeric = matches!(left, Value::Num(_)) || matches!(right, Value::Num(_)) || !matches!(op, "=" | "!=");
+
+        left_list.iter().any(|l| {
+            right_list.iter().any(|r| {
+                if numeric {
+                    let (l, r) = (l.trim().parse::<f64>(), r.trim().parse::<f64>());
+                    let (Ok(l), Ok(r)) = (l, r) else {
+                        return op == "!=";
+                    };
+                    match op {
+                        "=" => l == r,
+                        "!=" => l != r,
+                        "<" => l < r,
+                        "<=" => l <= r,
+                        ">" => l > r,
+                        _ => l >= r,
+                    }
+                } else if op == "=" {
+                    l == r
+                } else {
+                    l != r
+                }
+            })
+        })
+    }
+}
+
+fn to_bool(value: &Value) -> bool {
+    match value {
+        Value::Nodes(n) => !n.is_empty(),
+        Value::Strings(s) => !s.is_empty(),
+        Value::Str(s) => !s.is_empty(),
+        Value::Num(n) => *n != 0.0 && !n.is_nan(),
+        Value::Bool(b) => *b,
+    }
+}
+
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.is_finite() {
+        format!("{}", n as i64)
+    } else {
+        n.to_string()
+    }
+}
+
+/// 执行 XPath 查询，返回按文档顺序排列的节点编号
+pub(crate) fn select(tree: &UiTree, context: Option<NodeId>, xpath: &str) -> Result<Vec<NodeId>> {
+    let expr = parse(xpath)?;
+    let evaluator = Evaluator { tree };
+    let ctx = Context { node: context.unwrap_or(DOCUMENT), position: 1, size: 1 };
+
+    match evaluator.eval(&expr, &ctx)? {
+        Value::Nodes(nodes) => Ok(nodes.into_iter().filter(|&id| id != DOCUMENT).collect()),
+        _ => Err(anyhow!("XPath表达式的结果不是节点集: {}", xpath)),
+    }
+}
+
+/// 校验 XPath 语法
+pub fn validate(xpath: &str) -> Result<()> {
+    parse(xpath).map(|_| ())
+}
@@ -51,6 +51,7 @@ impl AppConfigManager {
             user_guidance: true, // 默认启用用户引导
             timeout: Some(30), // 默认30秒超时
             retry_count: Some(3), // 默认重试3次
+            scroll: None, // 默认只查找当前屏
         })
     }
     
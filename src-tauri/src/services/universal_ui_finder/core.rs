@@ -2,6 +2,10 @@
 
 use regex::Regex;
 use std::collections::HashMap;
+use crate::services::scroll_finder::{ScrollSession, Swipe};
+use crate::services::ui_dump_cache::get_ui_dump_cache;
+use crate::services::ui_idle::settle;
+use crate::services::ui_tree::UiTree;
 use crate::services::universal_ui_finder::{FindRequest, FindError};
 use crate::services::universal_ui_finder::logger::{InteractiveLogger, ElementSearchStep, PreActionStep};
 
@@ -45,11 +49,15 @@ impl UIFinderCore {
         
         let mut retry_count = 0;
         let max_retries = request.retry_count.unwrap_or(3);
+        let mut scroll_session = request.scroll.clone().map(ScrollSession::new);
+        // 重试时必须重新 dump，否则每次都读到同一份缓存
+        let mut force_refresh = false;
         
         loop {
             // 步骤1: 获取UI布局
             logger.log_element_search(&request.target_text, ElementSearchStep::DumpingUI);
-            let xml_content = self.get_ui_dump()?;
+            let xml_content = self.get_ui_dump(force_refresh)?;
+            force_refresh = true;
             
             // 步骤2: 解析XML
             logger.log_element_search(&request.target_text, ElementSearchStep::Parsing);
@@ -60,6 +68,13 @@ impl UIFinderCore {
             logger.log_element_search(&request.target_text, ElementSearchStep::Filtering(candidates.len()));
             
             if candidates.is_empty() {
+                // 滚动查找：当前屏没有时滚动容器，直到列表到底
+                if let Some(session) = scroll_session.as_mut() {
+                    if self.scroll_once(session, &xml_content, request, logger).await? {
+                        continue;
+                    }
+                }
+                
                 if retry_count >= max_retries {
                     logger.log_element_search(&request.target_text, ElementSearchStep::NotFound);
                     
@@ -92,6 +107,40 @@ impl UIFinderCore {
         }
     }
     
+    /// 执行一次滚动；到底或次数用完时返回 false（按需滚回起始位置）
+    async fn scroll_once(&self, session: &mut ScrollSession, xml_content: &str, request: &FindRequest, logger: &mut InteractiveLogger)
+        -> Result<bool, FindError> {
+        
+        if session.reached_end() {
+            return Ok(false);
+        }
+        let tree = UiTree::parse(xml_content).map_err(|e| FindError::ExecutionFailed(e.to_string()))?;
+        let Some(swipe) = session.next_swipe(&tree, Some(&request.target_text)) else {
+            logger.log_element_search(&request.target_text, ElementSearchStep::ReachedEnd(session.scrolls()));
+            if request.scroll.as_ref().map_or(false, |o| o.scroll_back) {
+                for back in session.back_swipes() {
+                    self.perform_swipe(&back, request).await?;
+                }
+            }
+            return Ok(false);
+        };
+        
+        logger.log_element_search(&request.target_text, ElementSearchStep::Scrolling(session.scrolls()));
+        self.perform_swipe(&swipe, request).await?;
+        Ok(true)
+    }
+    
+    /// 滑动并等待界面稳定
+    async fn perform_swipe(&self, swipe: &Swipe, request: &FindRequest) -> Result<(), FindError> {
+        self.swipe(swipe.x1, swipe.y1, swipe.x2, swipe.y2, swipe.duration_ms as i32)?;
+        let settle_ms = request.scroll.as_ref().map_or(3000, |o| o.settle_timeout_ms);
+        match &self.device_id {
+            Some(device) => settle(device, tokio::time::Duration::from_millis(settle_ms)).await,
+            None => tokio::time::sleep(tokio::time::Duration::from_millis(swipe.duration_ms as u64 + 500)).await,
+        }
+        Ok(())
+    }
+    
     /// 获取屏幕尺寸
     fn get_screen_size(&self) -> Result<(i32, i32), Box<dyn std::error::Error>> {
         let mut cmd = std::process::Command::new(&self.adb_path);
@@ -115,26 +164,34 @@ impl UIFinderCore {
         Ok((1080, 1920))
     }
     
-    /// 获取UI布局XML
-    fn get_ui_dump(&self) -> Result<String, FindError> {
-        let mut cmd = std::process::Command::new(&self.adb_path);
-        if let Some(device) = &self.device_id {
-            cmd.arg("-s").arg(device);
-        }
-        cmd.args(&["exec-out", "uiautomator", "dump", "/dev/stdout"]);
-        
-        let output = cmd.output().map_err(|e| {
-            FindError::ExecutionFailed(format!("ADB命令执行失败: {}", e))
-        })?;
-        
-        if !output.status.success() {
-            return Err(FindError::ExecutionFailed(format!(
-                "UI dump失败: {}", 
-                String::from_utf8_lossy(&output.stderr)
-            )));
+    /// 获取UI布局XML；`fresh` 为 true 时跳过缓存重新 dump
+    fn get_ui_dump(&self, fresh: bool) -> Result<String, FindError> {
+        let dump = || {
+            let mut cmd = std::process::Command::new(&self.adb_path);
+            if let Some(device) = &self.device_id {
+                cmd.arg("-s").arg(device);
+            }
+            cmd.args(&["exec-out", "uiautomator", "dump", "/dev/stdout"]);
+            
+            let output = cmd.output().map_err(|e| {
+                FindError::ExecutionFailed(format!("ADB命令执行失败: {}", e))
+            })?;
+            
+            if !output.status.success() {
+                return Err(FindError::ExecutionFailed(format!(
+                    "UI dump失败: {}", 
+                    String::from_utf8_lossy(&output.stderr)
+                )));
+            }
+            
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        };
+        let cache = get_ui_dump_cache();
+        if fresh {
+            cache.refresh_blocking(self.device_id.as_deref(), dump)
+        } else {
+            cache.get_or_dump_blocking(self.device_id.as_deref(), dump)
         }
-        
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
     
     /// 解析所有UI元素
@@ -398,6 +455,7 @@ impl UIFinderCore {
         let output = cmd.output().map_err(|e| {
             FindError::ExecutionFailed(format!("滑动命令执行失败: {}", e))
         })?;
+        get_ui_dump_cache().invalidate(self.device_id.as_deref());
         
         if !output.status.success() {
             return Err(FindError::ExecutionFailed(format!(
@@ -83,6 +83,7 @@ async fn run_xiaohongshu_examples(finder: &mut UniversalUIFinder)
         user_guidance: true,
         timeout: Some(30),
         retry_count: Some(3),
+        scroll: None,
     };
     
     match finder.find_and_click(request).await {
@@ -247,6 +248,7 @@ async fn example_find_only(finder: &mut UniversalUIFinder)
         user_guidance: false, // 禁用用户交互
         timeout: Some(10),
         retry_count: Some(1),
+        scroll: None,
     };
     
     match finder.find_element_only(request).await {
@@ -278,6 +280,7 @@ async fn example_error_handling(finder: &mut UniversalUIFinder)
         user_guidance: true, // 启用用户交互
         timeout: Some(10),
         retry_count: Some(2),
+        scroll: None,
     };
     
     match finder.find_and_click(request).await {
@@ -353,6 +356,7 @@ async fn run_direct_adb_examples(finder: &mut UniversalUIFinder)
         user_guidance: false, // 禁用用户交互，加快测试
         timeout: Some(10),
         retry_count: Some(1),
+        scroll: None,
     };
     
     match finder.find_and_click(direct_request).await {
@@ -4,6 +4,9 @@ use std::process::Command;
 use std::time::{Duration, Instant};
 use crate::services::universal_ui_finder::{FindRequest, ClickResult, UniversalUIElement, FindError};
 use crate::services::universal_ui_finder::logger::{InteractiveLogger, ClickExecutionStep};
+use crate::services::ui_dump_cache::get_ui_dump_cache;
+use crate::services::ui_tree::UiDiff;
+use tracing::debug;
 
 pub struct ActionExecutor {
     adb_path: String,
@@ -29,6 +32,9 @@ impl ActionExecutor {
         let (x, y) = element.bounds.center();
         logger.log_click_execution(&request.target_text, ClickExecutionStep::CalculatingPosition(x, y));
         
+        // 点击前的UI直接复用查找时的 dump（仍在缓存中），不额外执行 dump
+        let before_dump = get_ui_dump_cache().peek(self.device_id.as_deref());
+        
         // 执行点击命令
         logger.log_click_execution(&request.target_text, ClickExecutionStep::Clicking);
         let click_success = self.perform_click(x, y).is_ok();
@@ -53,7 +59,7 @@ impl ActionExecutor {
         
         // 验证操作结果
         logger.log_click_execution(&request.target_text, ClickExecutionStep::Verifying);
-        let verification_result = self.verify_click_result(request).await;
+        let verification_result = self.verify_click_result(request, before_dump.as_deref()).await;
         
         let final_success = click_success && verification_result.is_ok();
         
@@ -85,6 +91,7 @@ impl ActionExecutor {
         let output = cmd.output().map_err(|e| {
             FindError::ExecutionFailed(format!("点击命令创建失败: {}", e))
         })?;
+        get_ui_dump_cache().invalidate(self.device_id.as_deref());
         
         if !output.status.success() {
             return Err(FindError::ExecutionFailed(format!(
@@ -97,7 +104,7 @@ impl ActionExecutor {
     }
     
     /// 验证点击结果
-    async fn verify_click_result(&self, request: &FindRequest) -> Result<bool, String> {
+    async fn verify_click_result(&self, request: &FindRequest, before_dump: Option<&str>) -> Result<bool, String> {
         // 等待界面变化
         tokio::time::sleep(tokio::time::Duration::from_millis(800)).await;
         
@@ -110,8 +117,8 @@ impl ActionExecutor {
                 if verification_passed {
                     Ok(true)
                 } else {
-                    // 简单验证：检查UI是否发生变化
-                    Ok(self.detect_ui_change(&xml_content))
+                    // 通用验证：比较点击前后的UI结构是否发生变化
+                    Ok(self.detect_ui_change(before_dump, &xml_content))
                 }
             },
             Err(e) => Err(format!("获取验证UI失败: {}", e))
@@ -162,33 +169,37 @@ impl ActionExecutor {
     }
     
     /// 检测UI是否发生变化 (通用验证方法)
-    fn detect_ui_change(&self, xml_content: &str) -> bool {
-        // 检查是否有新的界面元素出现
-        let change_indicators = [
-            "返回", "back", "关闭", "取消", 
-            "标题", "title", "页面", "page",
-            "列表", "list", "内容", "content"
-        ];
+    fn detect_ui_change(&self, before_dump: Option<&str>, xml_content: &str) -> bool {
+        let Some(before_dump) = before_dump else {
+            // 没有点击前的快照，无法比较，视为已变化
+            return true;
+        };
         
-        change_indicators.iter().any(|&indicator| {
-            xml_content.to_lowercase().contains(indicator)
-        })
+        match UiDiff::between(before_dump, xml_content) {
+            Ok(diff) => {
+                debug!("🔍 点击前后UI差异: {}", diff.summary());
+                !diff.is_unchanged()
+            }
+            Err(_) => true,
+        }
     }
     
     /// 获取当前UI dump
     fn get_current_ui_dump(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let mut cmd = Command::new(&self.adb_path);
-        if let Some(device) = &self.device_id {
-            cmd.arg("-s").arg(device);
-        }
-        cmd.args(&["exec-out", "uiautomator", "dump", "/dev/stdout"]);
-        
-        let output = cmd.output()?;
-        if !output.status.success() {
-            return Err(format!("UI dump失败: {}", String::from_utf8_lossy(&output.stderr)).into());
-        }
-        
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        get_ui_dump_cache().get_or_dump_blocking(self.device_id.as_deref(), || {
+            let mut cmd = Command::new(&self.adb_path);
+            if let Some(device) = &self.device_id {
+                cmd.arg("-s").arg(device);
+            }
+            cmd.args(&["exec-out", "uiautomator", "dump", "/dev/stdout"]);
+            
+            let output = cmd.output()?;
+            if !output.status.success() {
+                return Err(format!("UI dump失败: {}", String::from_utf8_lossy(&output.stderr)).into());
+            }
+            
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        })
     }
     
     /// 执行长按操作 (扩展功能)
@@ -206,6 +217,7 @@ impl ActionExecutor {
         let output = cmd.output().map_err(|e| {
             FindError::ExecutionFailed(format!("长按命令执行失败: {}", e))
         })?;
+        get_ui_dump_cache().invalidate(self.device_id.as_deref());
         
         Ok(output.status.success())
     }
@@ -258,6 +270,7 @@ impl ActionExecutor {
         let output = cmd.output().map_err(|e| {
             FindError::ExecutionFailed(format!("滑动命令执行失败: {}", e))
         })?;
+        get_ui_dump_cache().invalidate(self.device_id.as_deref());
         
         Ok(output.status.success())
     }
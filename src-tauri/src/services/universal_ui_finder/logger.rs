@@ -114,6 +114,12 @@ impl InteractiveLogger {
             ElementSearchStep::MultipleFound(count) => {
                 println!("   ⚠️  找到 {} 个可能的匹配", count);
                 println!("      将选择置信度最高的元素");
+            },
+            ElementSearchStep::Scrolling(count) => {
+                println!("   📜 当前屏未找到，滚动容器继续查找 (第{}次)", count);
+            },
+            ElementSearchStep::ReachedEnd(count) => {
+                println!("   📄 滚动 {} 次后列表已到底", count);
             }
         }
     }
@@ -275,6 +281,8 @@ pub enum ElementSearchStep {
     Found(crate::services::universal_ui_finder::UniversalUIElement, f32), // element, confidence
     NotFound,
     MultipleFound(usize), // count
+    Scrolling(u32), // 第几次滚动
+    ReachedEnd(u32), // 滚动次数
 }
 
 /// 预操作步骤枚举
@@ -14,6 +14,7 @@ pub use detector::*;
 pub use executor::*;
 
 use tokio::time::Duration;
+use crate::services::scroll_finder::ScrollOptions;
 
 /// 通用UI查找器 - 主入口
 /// 
@@ -120,6 +121,7 @@ impl UniversalUIFinder {
             user_guidance: true, // 保持用户交互
             timeout: Some(30),
             retry_count: Some(3),
+            scroll: None,
         };
         
         self.find_and_click(request).await
@@ -137,6 +139,7 @@ impl UniversalUIFinder {
             user_guidance: true,
             timeout: Some(30),
             retry_count: Some(3),
+            scroll: None,
         };
         
         self.find_and_click(request).await
@@ -220,6 +223,9 @@ pub struct FindRequest {
     
     /// 重试次数
     pub retry_count: Option<u32>,
+    
+    /// 滚动查找：当前屏未找到时滚动可滚动容器继续查找 (None 表示只查找当前屏)
+    pub scroll: Option<ScrollOptions>,
 }
 
 /// 点击结果
@@ -5,7 +5,8 @@
  */
 
 use std::collections::HashMap;
-use quick_xml::{Reader, events::Event};
+use crate::services::ui_tree::{UiNode, UiTree};
+use crate::services::xml_snapshot_store::{describe_snapshot, store_snapshot, XmlSnapshotStore};
 use serde::{Deserialize, Serialize};
 use anyhow::Result as AnyResult;
 use tracing::{info, warn, error};
@@ -113,45 +114,21 @@ impl UniversalUIPageAnalyzer {
 
     /// 解析XML内容，提取UI元素（增强版）
     fn parse_xml_elements(&self, xml_content: &str) -> AnyResult<Vec<UIElement>> {
+        let tree = UiTree::parse(xml_content)?;
         let mut elements = Vec::new();
-        let mut reader = Reader::from_str(xml_content);
-        reader.config_mut().trim_text(true);
         
-        let mut buf = Vec::new();
-        let mut current_depth = 0;
-        let mut id_counter = 0;
-        
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(ref e)) => {
-                    current_depth += 1;
-                    
-                    if e.name().as_ref() == b"node" {
-                        id_counter += 1;
-                        let element_id = format!("element_{}", id_counter);
-                        
-                        if let Ok(mut element) = self.parse_node_attributes(e, &element_id, current_depth) {
-                            // 应用智能分类逻辑（基于SmartElementFinderService）
-                            element = self.apply_smart_classification(&element, xml_content);
-                            
-                            // 只保留有价值的元素
-                            if self.is_valuable_element(&element) {
-                                elements.push(element);
-                            }
-                        }
-                    }
-                }
-                Ok(Event::End(_)) => {
-                    current_depth -= 1;
-                }
-                Ok(Event::Eof) => break,
-                Err(e) => {
-                    error!("XML解析错误: {}", e);
-                    break;
-                }
-                _ => {}
+        for (index, node) in tree.elements().enumerate() {
+            let element_id = format!("element_{}", index + 1);
+            // 与旧实现保持一致：hierarchy 根节点深度记为1
+            let mut element = self.to_ui_element(&node, &element_id, node.depth() as u32 + 1);
+            
+            // 应用智能分类逻辑（基于SmartElementFinderService）
+            element = self.apply_smart_classification(&element, xml_content);
+            
+            // 只保留有价值的元素
+            if self.is_valuable_element(&element) {
+                elements.push(element);
             }
-            buf.clear();
         }
         
         // 应用后处理：排序和优化
@@ -161,111 +138,34 @@ impl UniversalUIPageAnalyzer {
         Ok(processed_elements)
     }
 
-    /// 解析节点属性
-    fn parse_node_attributes(&self, element: &quick_xml::events::BytesStart, element_id: &str, depth: u32) -> AnyResult<UIElement> {
-        let mut text = String::new();
-        let mut resource_id = None;
-        let mut class_name = String::new();
-        let mut content_desc = String::new();
-        let mut bounds = ElementBounds { left: 0, top: 0, right: 0, bottom: 0 };
-        let mut is_clickable = false;
-        let mut is_scrollable = false;
-        let mut is_enabled = true;
-        let mut is_focused = false;
-        let mut is_selected = false;
-        
-        for attr in element.attributes() {
-            if let Ok(attr) = attr {
-                match attr.key.as_ref() {
-                    b"text" => {
-                        text = String::from_utf8_lossy(&attr.value).to_string();
-                    }
-                    b"resource-id" => {
-                        let id = String::from_utf8_lossy(&attr.value).to_string();
-                        if !id.is_empty() {
-                            resource_id = Some(id);
-                        }
-                    }
-                    b"class" => {
-                        class_name = String::from_utf8_lossy(&attr.value).to_string();
-                    }
-                    b"content-desc" => {
-                        content_desc = String::from_utf8_lossy(&attr.value).to_string();
-                    }
-                    b"bounds" => {
-                        if let Some(parsed_bounds) = self.parse_bounds(&String::from_utf8_lossy(&attr.value)) {
-                            bounds = parsed_bounds;
-                        }
-                    }
-                    b"clickable" => {
-                        is_clickable = String::from_utf8_lossy(&attr.value) == "true";
-                    }
-                    b"scrollable" => {
-                        is_scrollable = String::from_utf8_lossy(&attr.value) == "true";
-                    }
-                    b"enabled" => {
-                        is_enabled = String::from_utf8_lossy(&attr.value) == "true";
-                    }
-                    b"focused" => {
-                        is_focused = String::from_utf8_lossy(&attr.value) == "true";
-                    }
-                    b"selected" => {
-                        is_selected = String::from_utf8_lossy(&attr.value) == "true";
-                    }
-                    _ => {}
-                }
-            }
-        }
+    /// 将UI树节点转换为分析器元素
+    fn to_ui_element(&self, node: &UiNode, element_id: &str, depth: u32) -> UIElement {
+        let text = node.text().to_string();
+        let class_name = node.class_name().to_string();
+        let content_desc = node.content_desc().to_string();
+        let resource_id = Some(node.resource_id()).filter(|id| !id.is_empty()).map(str::to_string);
+        let is_clickable = node.is_clickable();
 
         // 智能分类元素类型
         let element_type = self.classify_element_type(&class_name, &text, is_clickable, &content_desc);
 
-        Ok(UIElement {
+        UIElement {
             id: element_id.to_string(),
             element_type,
             text,
             content_desc,
             resource_id,
             class_name,
-            bounds,
+            bounds: node.bounds().unwrap_or(ElementBounds { left: 0, top: 0, right: 0, bottom: 0 }),
             is_clickable,
-            is_scrollable,
-            is_enabled,
-            is_focused,
-            is_selected,
+            is_scrollable: node.is_scrollable(),
+            is_enabled: node.is_enabled(),
+            is_focused: node.is_focused(),
+            is_selected: node.is_selected(),
             children: Vec::new(),
             parent: None,
             depth,
-        })
-    }
-
-    /// 解析bounds字符串 "[left,top][right,bottom]"
-    fn parse_bounds(&self, bounds_str: &str) -> Option<ElementBounds> {
-        let bounds_str = bounds_str.trim();
-        if bounds_str.starts_with('[') && bounds_str.ends_with(']') {
-            let coords: Vec<&str> = bounds_str
-                .trim_start_matches('[')
-                .trim_end_matches(']')
-                .split("][")
-                .collect();
-            
-            if coords.len() == 2 {
-                let left_top: Vec<&str> = coords[0].split(',').collect();
-                let right_bottom: Vec<&str> = coords[1].split(',').collect();
-                
-                if left_top.len() == 2 && right_bottom.len() == 2 {
-                    if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
-                        left_top[0].parse::<i32>(),
-                        left_top[1].parse::<i32>(),
-                        right_bottom[0].parse::<i32>(),
-                        right_bottom[1].parse::<i32>(),
-                    ) {
-                        return Some(ElementBounds { left, top, right, bottom });
-                    }
-                }
-            }
         }
-        None
     }
 
     /// 智能分类元素类型（基于SmartElementFinderService逻辑）
@@ -494,10 +394,9 @@ impl UniversalUIPageAnalyzer {
 #[tauri::command]
 pub async fn analyze_universal_ui_page(
     device_id: String,
+    store: tauri::State<'_, std::sync::Mutex<XmlSnapshotStore>>,
 ) -> Result<String, String> {
     use crate::xml_judgment_service::XmlJudgmentService;
-    use std::fs;
-    use chrono::Utc;
     
     info!("🔍 开始分析页面，设备ID: {}", device_id);
     
@@ -506,36 +405,21 @@ pub async fn analyze_universal_ui_page(
         Ok(xml_content) => {
             info!("✅ 成功获取XML内容，长度: {}", xml_content.len());
             
-            // 保存XML到本地文件用于调试分析  
-            let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-            let filename = format!("ui_dump_{}_{}.xml", device_id, timestamp);
-            
-            // 保存到项目根目录的debug_xml文件夹，避免触发Tauri重新编译
-            let project_root = std::env::current_dir()
-                .unwrap_or_else(|_| std::path::PathBuf::from("."))
-                .parent()
-                .unwrap_or_else(|| std::path::Path::new(".."))
-                .to_path_buf();
-            let debug_dir = project_root.join("debug_xml");
-            let filepath = debug_dir.join(&filename);
-            
-            // 确保debug_xml目录存在
-            if let Err(e) = fs::create_dir_all(&debug_dir) {
-                warn!("创建debug_xml目录失败: {}", e);
-            }
-            
-            // 保存XML文件
-            match fs::write(&filepath, &xml_content) {
-                Ok(_) => {
-                    info!("📄 XML已保存到: {}", filepath.display());
+            // 保存到XML快照存储（按内容去重，可全文检索）
+            let snapshot = describe_snapshot(&device_id, xml_content, false).await;
+            match store.lock() {
+                Ok(store) => {
+                    if let Err(e) = store_snapshot(&store, &snapshot) {
+                        warn!("保存XML快照失败: {}", e);
+                    }
                 },
                 Err(e) => {
-                    warn!("保存XML文件失败: {}", e);
+                    warn!("保存XML快照失败: {}", e);
                 }
             }
             
             // 返回完整的XML内容供前端解析
-            Ok(xml_content)
+            Ok(snapshot.xml_content)
         },
         Err(e) => {
             error!("❌ 获取设备UI XML失败: {}", e);
@@ -0,0 +1,660 @@
+// vCard 读写
+// 支持 2.1 / 3.0 / 4.0：解析时处理折行、QUOTED-PRINTABLE 软换行和 CHARSET，
+// 写出时按版本转义；2.1 的非 ASCII 内容使用 CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE（与安卓系统导出格式一致），
+// 3.0/4.0 直接写 UTF-8 并按 75 字节折行
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tracing::info;
+
+use crate::services::vcf_importer::Contact;
+
+/// vCard 版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VCardVersion {
+    #[default]
+    #[serde(rename = "2.1")]
+    V21,
+    #[serde(rename = "3.0")]
+    V30,
+    #[serde(rename = "4.0")]
+    V40,
+}
+
+impl VCardVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VCardVersion::V21 => "2.1",
+            VCardVersion::V30 => "3.0",
+            VCardVersion::V40 => "4.0",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "2.1" => Some(VCardVersion::V21),
+            "3.0" => Some(VCardVersion::V30),
+            "4.0" => Some(VCardVersion::V40),
+            _ => None,
+        }
+    }
+}
+
+/// 结构化姓名（N）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructuredName {
+    pub family: String,
+    pub given: String,
+    pub additional: String,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+/// 带类型的值（TEL / EMAIL），类型统一为小写，如 cell、home、work、pref
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypedValue {
+    pub value: String,
+    pub types: Vec<String>,
+}
+
+/// 地址（ADR）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Address {
+    pub types: Vec<String>,
+    pub po_box: String,
+    pub extended: String,
+    pub street: String,
+    pub locality: String,
+    pub region: String,
+    pub postal_code: String,
+    pub country: String,
+}
+
+/// 一张名片
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VCard {
+    pub version: VCardVersion,
+    pub formatted_name: String,
+    pub name: StructuredName,
+    pub phones: Vec<TypedValue>,
+    pub emails: Vec<TypedValue>,
+    /// ORG 的各级（单位;部门;...）
+    pub org: Vec<String>,
+    pub title: Option<String>,
+    pub addresses: Vec<Address>,
+    pub notes: Vec<String>,
+}
+
+fn phone_key(phone: &str) -> String {
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    match digits.strip_prefix("86") {
+        Some(rest) if rest.len() == 11 => rest.to_string(),
+        _ => digits,
+    }
+}
+
+/// 格式化中国手机号为 +86 格式，其他号码原样返回
+pub fn format_chinese_phone(phone: &str) -> String {
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.len() == 11 && digits.starts_with('1') {
+        format!("+86 {} {} {}", &digits[0..3], &digits[3..7], &digits[7..11])
+    } else {
+        phone.trim().to_string()
+    }
+}
+
+/// 拆分一个字段中填写的多个值（逗号、分号、顿号、斜杠分隔）
+fn split_multi(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split([',', ';', '，', '；', '、', '/'])
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+impl VCard {
+    /// 添加电话，号码数字相同（忽略 +86 和分隔符）时合并类型而不重复添加
+    pub fn add_phone(&mut self, value: &str, types: &[&str]) {
+        let key = phone_key(value);
+        if key.is_empty() {
+            return;
+        }
+        if let Some(existing) = self.phones.iter_mut().find(|p| phone_key(&p.value) == key) {
+            for t in types {
+                if !existing.types.iter().any(|e| e == t) {
+                    existing.types.push(t.to_string());
+                }
+            }
+            return;
+        }
+        self.phones.push(TypedValue { value: value.to_string(), types: types.iter().map(|t| t.to_string()).collect() });
+    }
+
+    /// 从导入用的联系人数据生成名片
+    pub fn from_contact(contact: &Contact) -> Self {
+        Self::from_fields(&contact.name, &contact.phone, &contact.email, &contact.address, &contact.occupation)
+    }
+
+    /// 由导入表格中的原始字段构建（异步导入器有自己的 Contact 类型）
+    pub fn from_fields(name: &str, phone: &str, email: &str, address: &str, occupation: &str) -> Self {
+        let name = name.trim().to_string();
+        let mut card = VCard {
+            formatted_name: name.clone(),
+            name: StructuredName { family: name, ..Default::default() },
+            ..Default::default()
+        };
+        for phone in split_multi(phone) {
+            card.add_phone(&format_chinese_phone(phone), &["cell"]);
+        }
+        for email in split_multi(email) {
+            if !card.emails.iter().any(|e| e.value.eq_ignore_ascii_case(email)) {
+                card.emails.push(TypedValue { value: email.to_string(), types: Vec::new() });
+            }
+        }
+        if !address.trim().is_empty() {
+            card.addresses.push(Address { street: address.trim().to_string(), ..Default::default() });
+        }
+        if !occupation.trim().is_empty() {
+            card.notes.push(occupation.trim().to_string());
+        }
+        card
+    }
+
+    /// 显示名：FN 为空时由 N 拼出
+    pub fn display_name(&self) -> String {
+        if !self.formatted_name.is_empty() {
+            return self.formatted_name.clone();
+        }
+        let n = &self.name;
+        [&n.prefix, &n.family, &n.given, &n.additional, &n.suffix]
+            .iter()
+            .filter(|p| !p.is_empty())
+            .map(|p| p.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+// ========== 解析 ==========
+
+/// 一行属性：[分组.]名称;参数:值
+struct ContentLine {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl ContentLine {
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    fn types(&self) -> Vec<String> {
+        self.params
+            .iter()
+            .filter(|(k, _)| k == "TYPE")
+            .flat_map(|(_, v)| v.split(','))
+            .map(|t| t.trim().to_ascii_lowercase())
+            .filter(|t| !t.is_empty() && t != "internet" && t != "voice")
+            .collect()
+    }
+}
+
+/// 按不在双引号内的分隔符拆分
+fn split_unquoted(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == sep && !in_quotes {
+            parts.push(&s[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn parse_content_line(line: &str) -> Option<ContentLine> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        c == ':' && !in_quotes
+    })?.0;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut head_parts = split_unquoted(head, ';').into_iter();
+    let name = head_parts.next()?.trim();
+    // 去掉分组前缀（item1.TEL）
+    let name = name.rsplit('.').next().unwrap_or(name).to_ascii_uppercase();
+
+    let params = head_parts
+        .map(|param| match param.split_once('=') {
+            Some((k, v)) => (k.trim().to_ascii_uppercase(), v.trim().trim_matches('"').to_string()),
+            // 2.1 的裸参数：编码名或类型
+            None => {
+                let token = param.trim().to_ascii_uppercase();
+                match token.as_str() {
+                    "QUOTED-PRINTABLE" | "BASE64" | "8BIT" | "7BIT" => ("ENCODING".to_string(), token),
+                    _ => ("TYPE".to_string(), token),
+                }
+            }
+        })
+        .collect();
+    Some(ContentLine { name, params, value: value.to_string() })
+}
+
+fn is_quoted_printable(line: &str) -> bool {
+    line.split(':').next().unwrap_or("").to_ascii_uppercase().contains("QUOTED-PRINTABLE")
+}
+
+/// 展开折行：空白开头的行接到上一行；QUOTED-PRINTABLE 以 '=' 结尾的软换行接下一行
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in input.lines() {
+        if let Some(last) = lines.last_mut() {
+            if is_quoted_printable(last) && last.ends_with('=') {
+                // 软换行后的空白属于值本身，不能去掉
+                last.pop();
+                last.push_str(raw);
+                continue;
+            }
+            if raw.starts_with(' ') || raw.starts_with('\t') {
+                last.push_str(&raw[1..]);
+                continue;
+            }
+        }
+        if !raw.trim().is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+fn decode_quoted_printable(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    while i < bytes.len() {
+        if bytes[i] == b'=' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                out.push(hi << 4 | lo);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+/// 解码属性值（QUOTED-PRINTABLE + CHARSET），BASE64 内容（照片等）返回 None
+fn decode_value(line: &ContentLine) -> Option<String> {
+    let encoding = line.param("ENCODING").unwrap_or("").to_ascii_uppercase();
+    match encoding.as_str() {
+        "QUOTED-PRINTABLE" => {
+            let bytes = decode_quoted_printable(&line.value);
+            let charset = line.param("CHARSET").unwrap_or("UTF-8");
+            let encoding = encoding_rs::Encoding::for_label(charset.as_bytes()).unwrap_or(encoding_rs::UTF_8);
+            Some(encoding.decode(&bytes).0.into_owned())
+        }
+        "BASE64" | "B" => None,
+        _ => Some(line.value.clone()),
+    }
+}
+
+/// 按未转义的分号拆分结构化值，并对各部分反转义
+fn split_components(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ';' => parts.push(unescape(&std::mem::take(&mut current))),
+            _ => current.push(c),
+        }
+    }
+    parts.push(unescape(&current));
+    parts
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other @ (',' | ';' | '\\' | ':')) => out.push(other),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// 解析 vCard 文本（可包含多张名片）
+pub fn parse_vcards(input: &str) -> Result<Vec<VCard>, String> {
+    let input = input.trim_start_matches('\u{feff}');
+    let mut cards = Vec::new();
+    let mut current: Option<VCard> = None;
+
+    for (index, raw) in unfold(input).iter().enumerate() {
+        let Some(line) = parse_content_line(raw) else { continue };
+        match line.name.as_str() {
+            "BEGIN" if line.value.trim().eq_ignore_ascii_case("VCARD") => current = Some(VCard::default()),
+            "END" if line.value.trim().eq_ignore_ascii_case("VCARD") => {
+                let card = current.take().ok_or_else(|| format!("第 {} 行 END:VCARD 没有对应的 BEGIN", index + 1))?;
+                cards.push(card);
+            }
+            _ => {
+                let Some(card) = current.as_mut() else { continue };
+                let Some(value) = decode_value(&line) else { continue };
+                match line.name.as_str() {
+                    "VERSION" => card.version = VCardVersion::parse(&value).unwrap_or_default(),
+                    "FN" => card.formatted_name = unescape(&value),
+                    "N" => {
+                        let mut parts = split_components(&value).into_iter();
+                        let mut next = || parts.next().unwrap_or_default();
+                        card.name = StructuredName { family: next(), given: next(), additional: next(), prefix: next(), suffix: next() };
+                    }
+                    "TEL" => {
+                        let number = unescape(&value);
+                        let number = number.strip_prefix("tel:").unwrap_or(&number).trim().to_string();
+                        let types = line.types();
+                        card.add_phone(&number, &types.iter().map(String::as_str).collect::<Vec<_>>());
+                    }
+                    "EMAIL" => card.emails.push(TypedValue { value: unescape(&value).trim().to_string(), types: line.types() }),
+                    "ORG" => card.org = split_components(&value).into_iter().filter(|p| !p.is_empty()).collect(),
+                    "TITLE" => card.title = Some(unescape(&value)),
+                    "ADR" => {
+                        let mut parts = split_components(&value).into_iter();
+                        let mut next = || parts.next().unwrap_or_default();
+                        card.addresses.push(Address {
+                            types: line.types(),
+                            po_box: next(),
+                            extended: next(),
+                            street: next(),
+                            locality: next(),
+                            region: next(),
+                            postal_code: next(),
+                            country: next(),
+                        });
+                    }
+                    "NOTE" => card.notes.push(unescape(&value)),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if current.is_some() {
+        return Err("vCard 缺少 END:VCARD".to_string());
+    }
+    Ok(cards)
+}
+
+// ========== 写出 ==========
+
+/// 按版本转义文本值（结构化值的各部分分别转义）
+fn escape(value: &str, version: VCardVersion) -> String {
+    match version {
+        // 2.1 换行由 QUOTED-PRINTABLE 编码，但读取时反斜杠转义对所有版本生效，反斜杠和逗号同样要转义
+        VCardVersion::V21 => value.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,"),
+        _ => value
+            .replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace("\r\n", "\\n")
+            .replace('\n', "\\n"),
+    }
+}
+
+/// QUOTED-PRINTABLE 编码，prefix_len 为首行属性名部分的长度，每行不超过 76 字符
+fn encode_quoted_printable(value: &str, prefix_len: usize) -> String {
+    let normalized = value.replace("\r\n", "\n").replace('\n', "\r\n");
+    let bytes = normalized.as_bytes();
+    let mut out = String::new();
+    let mut line_len = prefix_len;
+    for (i, &b) in bytes.iter().enumerate() {
+        let is_last = i + 1 == bytes.len();
+        let mut token = if (b'!'..=b'~').contains(&b) && b != b'=' || b == b' ' && !is_last {
+            (b as char).to_string()
+        } else {
+            format!("={:02X}", b)
+        };
+        // 预留软换行的 '='
+        if line_len + token.len() > 75 {
+            out.push_str("=\r\n");
+            line_len = 0;
+        }
+        // 续行开头的空格编码为 =20，避免被读取方当作折行缩进去掉
+        if line_len == 0 && b == b' ' {
+            token = "=20".to_string();
+        }
+        line_len += token.len();
+        out.push_str(&token);
+    }
+    out
+}
+
+/// 3.0/4.0 折行：每行不超过 75 字节，不拆开 UTF-8 字符
+fn fold_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut line_bytes = 0;
+    for c in line.chars() {
+        if line_bytes + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            line_bytes = 1;
+        }
+        out.push(c);
+        line_bytes += c.len_utf8();
+    }
+    out
+}
+
+fn type_params(types: &[String], version: VCardVersion) -> Vec<String> {
+    if types.is_empty() {
+        return Vec::new();
+    }
+    match version {
+        VCardVersion::V21 => types.iter().map(|t| t.to_ascii_uppercase()).collect(),
+        VCardVersion::V30 => vec![format!("TYPE={}", types.iter().map(|t| t.to_ascii_uppercase()).collect::<Vec<_>>().join(","))],
+        VCardVersion::V40 => vec![format!("TYPE={}", types.iter().map(|t| t.to_ascii_lowercase()).collect::<Vec<_>>().join(","))],
+    }
+}
+
+fn write_property(out: &mut String, version: VCardVersion, name: &str, params: &[String], components: &[&str]) {
+    let value = components.iter().map(|c| escape(c, version)).collect::<Vec<_>>().join(";");
+    let mut head = std::iter::once(name.to_string()).chain(params.iter().cloned()).collect::<Vec<_>>();
+
+    if version == VCardVersion::V21 {
+        if value.is_ascii() && !value.contains(['\r', '\n']) {
+            out.push_str(&format!("{}:{}\r\n", head.join(";"), value));
+        } else {
+            head.push("CHARSET=UTF-8".to_string());
+            head.push("ENCODING=QUOTED-PRINTABLE".to_string());
+            let head = head.join(";");
+            out.push_str(&format!("{}:{}\r\n", head, encode_quoted_printable(&value, head.len() + 1)));
+        }
+    } else {
+        out.push_str(&fold_line(&format!("{}:{}", head.join(";"), value)));
+        out.push_str("\r\n");
+    }
+}
+
+/// 将名片写为指定版本的 vCard 文本（CRLF 换行）
+pub fn write_vcards(cards: &[VCard], version: VCardVersion) -> String {
+    let mut out = String::new();
+    for card in cards {
+        out.push_str("BEGIN:VCARD\r\n");
+        out.push_str(&format!("VERSION:{}\r\n", version.as_str()));
+
+        let n = &card.name;
+        write_property(&mut out, version, "N", &[], &[&n.family, &n.given, &n.additional, &n.prefix, &n.suffix]);
+        write_property(&mut out, version, "FN", &[], &[&card.display_name()]);
+        for phone in &card.phones {
+            write_property(&mut out, version, "TEL", &type_params(&phone.types, version), &[&phone.value]);
+        }
+        for email in &card.emails {
+            write_property(&mut out, version, "EMAIL", &type_params(&email.types, version), &[&email.value]);
+        }
+        if !card.org.is_empty() {
+            write_property(&mut out, version, "ORG", &[], &card.org.iter().map(String::as_str).collect::<Vec<_>>());
+        }
+        if let Some(title) = &card.title {
+            write_property(&mut out, version, "TITLE", &[], &[title]);
+        }
+        for adr in &card.addresses {
+            write_property(
+                &mut out,
+                version,
+                "ADR",
+                &type_params(&adr.types, version),
+                &[&adr.po_box, &adr.extended, &adr.street, &adr.locality, &adr.region, &adr.postal_code, &adr.country],
+            );
+        }
+        for note in &card.notes {
+            write_property(&mut out, version, "NOTE", &[], &[note]);
+        }
+        out.push_str("END:VCARD\r\n");
+    }
+    out
+}
+
+/// 由联系人列表生成 vCard 文件
+pub fn write_contacts_vcf(contacts: &[Contact], output_path: &str, version: VCardVersion) -> Result<usize, String> {
+    let cards: Vec<VCard> = contacts.iter().map(VCard::from_contact).collect();
+    if let Some(parent) = std::path::Path::new(output_path).parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+    }
+    fs::write(output_path, write_vcards(&cards, version)).map_err(|e| format!("写入VCF文件失败: {}", e))?;
+    info!("📇 已生成 vCard {} 文件: {} ({} 个联系人)", version.as_str(), output_path, cards.len());
+    Ok(cards.len())
+}
+
+/// 解析 VCF 文件
+#[tauri::command]
+pub fn parse_vcf_file(file_path: String) -> Result<Vec<VCard>, String> {
+    let bytes = fs::read(&file_path).map_err(|e| format!("读取VCF文件失败: {}", e))?;
+    // 旧文件可能是 GBK 编码（未声明 CHARSET）
+    let content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        Err(e) => encoding_rs::GBK.decode(e.as_bytes()).0.into_owned(),
+    };
+    parse_vcards(&content)
+}
+
+/// 将 VCF 文件转换为指定版本
+#[tauri::command]
+pub fn convert_vcf_file(input_path: String, output_path: String, version: VCardVersion) -> Result<usize, String> {
+    let cards = parse_vcf_file(input_path)?;
+    fs::write(&output_path, write_vcards(&cards, version)).map_err(|e| format!("写入VCF文件失败: {}", e))?;
+    Ok(cards.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quoted_printable_and_folded_lines() {
+        let input = "BEGIN:VCARD\r\nVERSION:2.1\r\nN;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:=E5=BC=A0=E4=B8=89;;;;\r\n\
+FN;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:=E5=BC=A0=\r\n=E4=B8=89\r\nTEL;CELL:+86 138 0013 8000\r\nTEL;TYPE=CELL:13800138000\r\n\
+TEL;WORK;VOICE:010-12345678\r\nEND:VCARD\r\n\
+BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Li\\, Si\r\nN:Li;Si;;;\r\nORG:Acme\\; Inc;Sales\r\nNOTE:line one\\nline two that is folded\r\n  across lines\r\n\
+EMAIL;TYPE=INTERNET,WORK:li@example.com\r\nEND:VCARD\r\n";
+        let cards = parse_vcards(input).unwrap();
+        assert_eq!(cards.len(), 2);
+
+        assert_eq!(cards[0].version, VCardVersion::V21);
+        assert_eq!(cards[0].formatted_name, "张三");
+        assert_eq!(cards[0].name.family, "张三");
+        // 同一号码的两种写法合并为一条
+        assert_eq!(cards[0].phones.len(), 2);
+        assert_eq!(cards[0].phones[1].types, vec!["work"]);
+
+        assert_eq!(cards[1].formatted_name, "Li, Si");
+        assert_eq!(cards[1].org, vec!["Acme; Inc", "Sales"]);
+        assert_eq!(cards[1].notes, vec!["line one\nline two that is folded across lines"]);
+        assert_eq!(cards[1].emails[0].types, vec!["work"]);
+    }
+
+    #[test]
+    fn test_write_round_trip_all_versions() {
+        let contact = Contact {
+            id: "1".to_string(),
+            name: "欧阳娜娜".to_string(),
+            phone: "13800138000, +86 138-0013-8000，13900139000".to_string(),
+            email: "a@b.com".to_string(),
+            address: "北京市朝阳区建国路88号; 3层".to_string(),
+            occupation: "摄影师，旅行博主，这是一段足够长的备注，用来检查 QUOTED-PRINTABLE 软换行和 3.0/4.0 的折行是否正确".to_string(),
+        };
+        let card = VCard::from_contact(&contact);
+        assert_eq!(card.phones.len(), 2);
+
+        for version in [VCardVersion::V21, VCardVersion::V30, VCardVersion::V40] {
+            let text = write_vcards(std::slice::from_ref(&card), version);
+            assert!(text.lines().all(|l| l.len() <= 76), "{:?} 行过长:\n{}", version, text);
+            assert_eq!(text.matches("TEL").count(), 2);
+            if version == VCardVersion::V21 {
+                assert!(text.contains("FN;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:"));
+            }
+
+            let parsed = parse_vcards(&text).unwrap();
+            assert_eq!(parsed.len(), 1);
+            let mut expected = card.clone();
+            expected.version = version;
+            assert_eq!(parsed[0], expected, "{:?} 往返不一致:\n{}", version, text);
+        }
+
+        // 软换行恰好落在空格处时，空格不能丢
+        for offset in 0..8 {
+            let value = format!("{}{}", "x".repeat(offset), " ab".repeat(40));
+            let line = format!("NOTE;ENCODING=QUOTED-PRINTABLE:{}", encode_quoted_printable(&value, 31));
+            let unfolded = unfold(&line);
+            assert_eq!(unfolded.len(), 1);
+            let encoded = unfolded[0].split_once(':').unwrap().1;
+            assert_eq!(String::from_utf8(decode_quoted_printable(encoded)).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_v21_round_trip_keeps_backslashes() {
+        let contact = Contact {
+            id: "2".to_string(),
+            name: "Wang, Wu".to_string(),
+            phone: "13800138000".to_string(),
+            email: String::new(),
+            address: "C:\\new\\table; 2".to_string(),
+            occupation: "路径 C:\\new\\notes\\n 不是换行".to_string(),
+        };
+        let card = VCard::from_contact(&contact);
+
+        let text = write_vcards(std::slice::from_ref(&card), VCardVersion::V21);
+        let parsed = parse_vcards(&text).unwrap();
+        assert_eq!(parsed.len(), 1);
+        let mut expected = card.clone();
+        expected.version = VCardVersion::V21;
+        assert_eq!(parsed[0], expected, "2.1 往返不一致:\n{}", text);
+    }
+}
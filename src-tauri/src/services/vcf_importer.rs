@@ -5,6 +5,11 @@ use std::process::Command;
 use tokio::time::{sleep, Duration};
 use tracing::{error, info, warn};
 
+use crate::services::popup_watcher::get_popup_watchers;
+use crate::services::ui_idle::settle;
+use crate::services::ui_tree::UiTree;
+use crate::services::vcard::{write_contacts_vcf, VCardVersion};
+
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
@@ -126,57 +131,19 @@ impl VcfImporter {
 
     /// 生成VCF文件
     pub async fn generate_vcf_file(contacts: Vec<Contact>, output_path: &str) -> Result<String> {
-        info!("开始生成VCF文件: {}", output_path);
-
-        let mut vcf_content = String::new();
-
-        for contact in &contacts {
-            vcf_content.push_str("BEGIN:VCARD\n");
-            vcf_content.push_str("VERSION:2.1\n");
-            vcf_content.push_str(&format!("FN:{}\n", contact.name));
-            vcf_content.push_str(&format!("N:{};;\n", contact.name));
-
-            if !contact.phone.is_empty() {
-                // 格式化中国手机号为+86格式
-                let formatted_phone = Self::format_chinese_phone(&contact.phone);
-                vcf_content.push_str(&format!("TEL;CELL:{}\n", formatted_phone));
-                vcf_content.push_str(&format!("TEL;TYPE=CELL:{}\n", formatted_phone));
-            }
-
-            if !contact.email.is_empty() {
-                vcf_content.push_str(&format!("EMAIL:{}\n", contact.email));
-            }
-
-            if !contact.address.is_empty() {
-                vcf_content.push_str(&format!("ADR:;;{};;;;\n", contact.address));
-            }
-
-            if !contact.occupation.is_empty() {
-                vcf_content.push_str(&format!("NOTE:{}\n", contact.occupation));
-            }
+        Self::generate_vcf_file_with_version(contacts, output_path, VCardVersion::default()).await
+    }
 
-            vcf_content.push_str("END:VCARD\n");
-        }
+    /// 生成指定版本的VCF文件（转义、CHARSET、折行由 vcard 模块处理）
+    pub async fn generate_vcf_file_with_version(contacts: Vec<Contact>, output_path: &str, version: VCardVersion) -> Result<String> {
+        info!("开始生成VCF文件: {} (vCard {})", output_path, version.as_str());
 
-        // 写入文件
-        fs::write(output_path, vcf_content)
-            .with_context(|| format!("写入VCF文件失败: {}", output_path))?;
+        let count = write_contacts_vcf(&contacts, output_path, version).map_err(|e| anyhow::anyhow!(e))?;
 
-        info!("VCF文件生成完成: {} 个联系人", contacts.len());
+        info!("VCF文件生成完成: {} 个联系人", count);
         Ok(output_path.to_string())
     }
 
-    /// 格式化中国手机号
-    fn format_chinese_phone(phone: &str) -> String {
-        let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
-
-        if digits.len() == 11 && digits.starts_with('1') {
-            format!("+86 {} {} {}", &digits[0..3], &digits[3..7], &digits[7..11])
-        } else {
-            phone.to_string()
-        }
-    }
-
     /// 执行VCF导入（增强版，支持多路径传输）
     pub async fn import_vcf_contacts(&self, contacts_file_path: &str) -> Result<VcfImportResult> {
         let start_time = std::time::Instant::now();
@@ -410,29 +377,29 @@ impl VcfImporter {
 
         // 1. 启动联系人应用
         self.open_contacts_app().await?;
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
 
         // 检查权限对话框
         self.handle_permission_dialog().await?;
 
         // 2. 点击抽屉菜单按钮
         self.adb_tap(49, 98).await?;
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
 
         // 3. 点击设置选项
         self.adb_tap(280, 210).await?;
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
 
         // 4. 点击导入选项
         self.adb_tap(960, 817).await?;
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
 
         // 再次检查权限对话框
         self.handle_permission_dialog().await?;
 
         // 5. 点击VCF文件选项
         self.adb_tap(959, 509).await?;
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
 
         // 6. 在文件选择器中选择VCF文件（使用优化版本）
         self.select_vcf_file_in_picker_optimized("contacts_import.vcf")
@@ -498,7 +465,7 @@ impl VcfImporter {
         if !ui_content.contains("Download") && !ui_content.contains("下载") {
             info!("当前不在下载目录，尝试导航到下载文件夹");
             self.navigate_to_download_folder().await?;
-            sleep(Duration::from_millis(2000)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
         }
 
         // 重新获取UI内容
@@ -508,7 +475,7 @@ impl VcfImporter {
         if let Some((x, y)) = self.find_vcf_file_coordinates(&ui_content, target_filename) {
             info!("找到VCF文件位置: ({}, {})", x, y);
             self.adb_tap(x, y).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
         } else {
             // 尝试其他可能的VCF文件名
             let possible_names = vec![
@@ -546,7 +513,7 @@ impl VcfImporter {
         info!("🧭 导航到Download文件夹（使用优化坐标）");
 
         // 等待文件选择器界面稳定
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
 
         // 获取当前UI状态
         let ui_content = self.get_file_picker_ui_dump().await?;
@@ -565,12 +532,12 @@ impl VcfImporter {
             // 点击显示根目录/侧边栏
             info!("点击显示根目录按钮: (63, 98)");
             self.adb_tap(63, 98).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
 
             // 点击下载文件夹
             info!("点击下载文件夹: (280, 338)");
             self.adb_tap(280, 338).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
 
             return Ok(());
         }
@@ -580,7 +547,7 @@ impl VcfImporter {
             info!("✅ 发现Download文件夹，直接点击");
             if let Some((x, y)) = self.find_folder_coordinates(&ui_content, "Download", "下载") {
                 self.adb_tap(x, y).await?;
-                sleep(Duration::from_secs(2)).await;
+                settle(&self.device_id, Duration::from_secs(2)).await;
                 return Ok(());
             }
         }
@@ -592,13 +559,13 @@ impl VcfImporter {
             // 步骤1: 点击"显示根目录"按钮打开侧边栏
             info!("📋 点击显示根目录按钮");
             self.adb_tap(63, 98).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
 
             // 步骤2: 点击侧边栏中的"下载"文件夹
             // 基于调试结果，"下载"文件夹在侧边栏中的坐标是 (280, 338)
             info!("📋 点击侧边栏中的'下载'文件夹");
             self.adb_tap(280, 338).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
 
             // 验证导航结果
             let verify_ui = self.get_file_picker_ui_dump().await?;
@@ -626,7 +593,7 @@ impl VcfImporter {
         for (x, y, desc) in navigation_attempts.iter() {
             info!("🎯 尝试点击: {} ({}, {})", desc, x, y);
             self.adb_tap(*x, *y).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
 
             // 检查是否成功
             let test_ui = self.get_file_picker_ui_dump().await?;
@@ -650,7 +617,7 @@ impl VcfImporter {
                 "file:///sdcard/Download/",
             ]);
 
-        sleep(Duration::from_secs(1)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
 
         Ok(())
     }
@@ -662,41 +629,26 @@ impl VcfImporter {
         eng_name: &str,
         chn_name: &str,
     ) -> Option<(i32, i32)> {
-        let names_to_search = vec![eng_name, chn_name];
+        let tree = UiTree::parse(ui_content).ok()?;
 
-        for name in names_to_search {
-            if let Some(start_pos) = ui_content.find(name) {
+        for name in [eng_name, chn_name] {
+            let node = tree
+                .elements()
+                .find(|n| n.text().contains(name) || n.content_desc().contains(name));
+            if let Some(node) = node {
                 info!("找到文件夹名: {}", name);
 
-                // 查找包含文件夹名的节点的bounds属性
-                let before_name = &ui_content[..start_pos];
-
-                // 向前查找最近的bounds属性
-                if let Some(bounds_start) = before_name.rfind("bounds=\"[") {
-                    let bounds_str = &before_name[bounds_start + 8..];
-                    if let Some(bounds_end) = bounds_str.find("]") {
-                        let bounds = &bounds_str[..bounds_end];
-                        if let Some((left_top, right_bottom)) = bounds.split_once("][") {
-                            if let (Some((left, top)), Some((right, bottom))) =
-                                (left_top.split_once(","), right_bottom.split_once(","))
-                            {
-                                if let (Ok(l), Ok(t), Ok(r), Ok(b)) = (
-                                    left.parse::<i32>(),
-                                    top.parse::<i32>(),
-                                    right.parse::<i32>(),
-                                    bottom.parse::<i32>(),
-                                ) {
-                                    let center_x = (l + r) / 2;
-                                    let center_y = (t + b) / 2;
-                                    info!(
-                                        "文件夹 {} 解析到坐标: ({}, {})",
-                                        name, center_x, center_y
-                                    );
-                                    return Some((center_x, center_y));
-                                }
-                            }
-                        }
-                    }
+                // 优先点击包含文件夹名的可点击容器
+                let target = std::iter::once(node)
+                    .chain(node.ancestors())
+                    .find(|n| n.is_clickable())
+                    .unwrap_or(node);
+                if let Some((center_x, center_y)) = target.center() {
+                    info!(
+                        "文件夹 {} 解析到坐标: ({}, {})",
+                        name, center_x, center_y
+                    );
+                    return Some((center_x, center_y));
                 }
             }
         }
@@ -718,7 +670,7 @@ impl VcfImporter {
         if !ui_content.contains("Download") && !ui_content.contains("下载") {
             info!("当前不在下载目录，尝试导航到下载文件夹");
             self.navigate_to_download_folder_optimized().await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
         }
 
         // 重新获取UI内容
@@ -729,7 +681,7 @@ impl VcfImporter {
         {
             info!("找到VCF文件位置: ({}, {})", x, y);
             self.adb_tap(x, y).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
         } else {
             // 尝试其他可能的VCF文件名
             let possible_names = vec![
@@ -767,7 +719,7 @@ impl VcfImporter {
         info!("🧭 导航到Download文件夹（使用Python脚本验证的坐标）");
 
         // 等待文件选择器界面稳定
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
 
         // 获取当前UI状态
         let ui_content = self.get_file_picker_ui_dump().await?;
@@ -786,12 +738,12 @@ impl VcfImporter {
             // 点击显示根目录/侧边栏按钮
             info!("点击显示根目录按钮: (63, 98)");
             self.adb_tap(63, 98).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
 
             // 点击下载文件夹
             info!("点击下载文件夹: (280, 338)");
             self.adb_tap(280, 338).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
 
             return Ok(());
         }
@@ -801,7 +753,7 @@ impl VcfImporter {
             info!("✅ 发现Download文件夹，直接点击");
             if let Some((x, y)) = self.find_folder_coordinates(&ui_content, "Download", "下载") {
                 self.adb_tap(x, y).await?;
-                sleep(Duration::from_secs(2)).await;
+                settle(&self.device_id, Duration::from_secs(2)).await;
                 return Ok(());
             }
         }
@@ -817,30 +769,23 @@ impl VcfImporter {
     ) -> Option<(i32, i32)> {
         info!("🔍 查找VCF文件坐标: {} (使用Python算法)", filename);
 
-        let lines: Vec<&str> = ui_content.split('\n').collect();
+        if let Ok(tree) = UiTree::parse(ui_content) {
+            let file_nodes = tree.find_all(|n| n.text().contains(filename));
 
-        // 第一种方法：查找包含VCF文件名的行的bounds
-        for line in &lines {
-            if line.contains(filename) && line.contains("bounds=") {
-                if let Some(coords) = self.parse_bounds_from_line(line) {
-                    info!("📋 从VCF文件行解析坐标: {:?}", coords);
-                    return Some(coords);
-                }
+            // 第一种方法：使用VCF文件名节点自身的bounds
+            if let Some(coords) = file_nodes.iter().find_map(|n| n.center()) {
+                info!("📋 从VCF文件节点解析坐标: {:?}", coords);
+                return Some(coords);
             }
-        }
 
-        // 第二种方法：查找VCF文件行前的LinearLayout容器bounds
-        for (i, line) in lines.iter().enumerate() {
-            if line.contains(filename) {
-                // 查找前面几行是否有LinearLayout的bounds
-                let start_idx = if i >= 5 { i - 5 } else { 0 };
-                for j in start_idx..i {
-                    if lines[j].contains("LinearLayout") && lines[j].contains("bounds=") {
-                        if let Some(coords) = self.parse_bounds_from_line(lines[j]) {
-                            info!("📋 从父容器LinearLayout解析VCF文件坐标: {:?}", coords);
-                            return Some(coords);
-                        }
-                    }
+            // 第二种方法：使用VCF文件节点所在的LinearLayout容器bounds
+            for node in &file_nodes {
+                let container = node
+                    .ancestors()
+                    .find(|a| a.class_name().contains("LinearLayout"));
+                if let Some(coords) = container.and_then(|c| c.center()) {
+                    info!("📋 从父容器LinearLayout解析VCF文件坐标: {:?}", coords);
+                    return Some(coords);
                 }
             }
         }
@@ -850,35 +795,6 @@ impl VcfImporter {
         Some((175, 481))
     }
 
-    /// 从XML行中解析bounds坐标
-    fn parse_bounds_from_line(&self, line: &str) -> Option<(i32, i32)> {
-        if let Some(bounds_start) = line.find("bounds=\"[") {
-            if let Some(bounds_end) = line[bounds_start..].find("]\"") {
-                let bounds_end = bounds_start + bounds_end;
-                let bounds_str = &line[bounds_start + 9..bounds_end + 1];
-
-                // 格式: [left,top][right,bottom]
-                if let Some((left_top, right_bottom)) = bounds_str.split_once("][") {
-                    if let (Some((left, top)), Some((right, bottom))) =
-                        (left_top.split_once(","), right_bottom.split_once(","))
-                    {
-                        if let (Ok(l), Ok(t), Ok(r), Ok(b)) = (
-                            left.parse::<i32>(),
-                            top.parse::<i32>(),
-                            right.parse::<i32>(),
-                            bottom.parse::<i32>(),
-                        ) {
-                            let center_x = (l + r) / 2;
-                            let center_y = (t + b) / 2;
-                            return Some((center_x, center_y));
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
-
     /// 获取文件选择器UI内容
     async fn get_file_picker_ui_dump(&self) -> Result<String> {
         let output = self.execute_adb_command(&[
@@ -912,41 +828,22 @@ impl VcfImporter {
     fn find_vcf_file_coordinates(&self, ui_content: &str, filename: &str) -> Option<(i32, i32)> {
         info!("查找VCF文件坐标: {}", filename);
 
-        // 尝试解析XML找到文件相关的节点
-        if let Some(start_pos) = ui_content.find(filename) {
-            info!("在UI中找到文件名: {}", filename);
-
-            // 查找包含文件名的节点的bounds属性
-            let before_filename = &ui_content[..start_pos];
-            let _after_filename = &ui_content[start_pos..];
-
-            // 向前查找最近的bounds属性
-            if let Some(bounds_start) = before_filename.rfind("bounds=\"[") {
-                let bounds_str = &before_filename[bounds_start + 8..];
-                if let Some(bounds_end) = bounds_str.find("]") {
-                    let bounds = &bounds_str[..bounds_end];
-                    if let Some((left_top, right_bottom)) = bounds.split_once("][") {
-                        if let (Some((left, top)), Some((right, bottom))) =
-                            (left_top.split_once(","), right_bottom.split_once(","))
-                        {
-                            if let (Ok(l), Ok(t), Ok(r), Ok(b)) = (
-                                left.parse::<i32>(),
-                                top.parse::<i32>(),
-                                right.parse::<i32>(),
-                                bottom.parse::<i32>(),
-                            ) {
-                                let center_x = (l + r) / 2;
-                                let center_y = (t + b) / 2;
-                                info!("解析到精确坐标: ({}, {})", center_x, center_y);
-                                return Some((center_x, center_y));
-                            }
-                        }
-                    }
+        // 查找显示该文件名的节点，优先点击其可点击的列表项容器
+        if let Ok(tree) = UiTree::parse(ui_content) {
+            if let Some(node) = tree.find_first(|n| n.text().contains(filename) || n.content_desc().contains(filename)) {
+                info!("在UI中找到文件名: {}", filename);
+                let target = std::iter::once(node)
+                    .chain(node.ancestors())
+                    .find(|n| n.is_clickable())
+                    .unwrap_or(node);
+                if let Some((center_x, center_y)) = target.center().or_else(|| node.center()) {
+                    info!("解析到精确坐标: ({}, {})", center_x, center_y);
+                    return Some((center_x, center_y));
                 }
-            }
 
-            // 如果解析失败，返回一个基于搜索位置的估算坐标
-            return Some((960, 400));
+                // 如果解析失败，返回一个基于搜索位置的估算坐标
+                return Some((960, 400));
+            }
         }
 
         // 如果没有找到确切的文件名，查找.vcf扩展名
@@ -980,7 +877,7 @@ impl VcfImporter {
         info!("检查是否出现权限对话框");
 
         // 等待可能的权限对话框出现
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
 
         // 获取当前UI状态
         let ui_content = self.get_current_ui_dump().await?;
@@ -989,13 +886,17 @@ impl VcfImporter {
         if self.has_permission_dialog(&ui_content) {
             info!("检测到权限对话框，正在处理");
 
-            // 点击"允许"按钮
-            // 根据XML分析，"允许"按钮位于 bounds="[1299,584][1411,668]"
-            // 中心点坐标: (1355, 626)
-            self.adb_tap(1355, 626).await?;
-
-            info!("已点击允许按钮");
-            sleep(Duration::from_secs(2)).await;
+            // 优先交给弹窗监视器按界面定位"允许"按钮
+            let watchers = get_popup_watchers();
+            let settings = watchers.settings_for(&self.device_id);
+            let fired = watchers.check_and_dismiss(&self.device_id, &settings, None).await;
+            if fired.is_empty() {
+                // 根据XML分析，"允许"按钮位于 bounds="[1299,584][1411,668]"
+                // 中心点坐标: (1355, 626)
+                self.adb_tap(1355, 626).await?;
+                info!("已点击允许按钮");
+                settle(&self.device_id, Duration::from_secs(2)).await;
+            }
 
             // 再次检查是否权限对话框已消失
             let new_ui_content = self.get_current_ui_dump().await?;
@@ -1100,7 +1001,7 @@ impl VcfImporter {
         info!("🔍 检查是否出现应用选择器对话框");
 
         // 等待可能的应用选择器对话框出现
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
 
         // 获取当前UI状态
         let ui_content = self.get_current_ui_dump().await?;
@@ -1113,18 +1014,18 @@ impl VcfImporter {
             if let Some((x, y)) = self.find_contacts_app_in_chooser(&ui_content) {
                 info!("📱 找到通讯录应用位置: ({}, {})", x, y);
                 self.adb_tap(x, y).await?;
-                sleep(Duration::from_secs(1)).await;
+                settle(&self.device_id, Duration::from_secs(2)).await;
 
                 // 点击"始终"按钮
                 if let Some((always_x, always_y)) = self.find_always_button(&ui_content) {
                     info!("✅ 点击'始终'按钮: ({}, {})", always_x, always_y);
                     self.adb_tap(always_x, always_y).await?;
-                    sleep(Duration::from_secs(2)).await;
+                    settle(&self.device_id, Duration::from_secs(2)).await;
                 } else {
                     // 使用常见的"始终"按钮坐标（基于常见Android布局）
                     warn!("未找到'始终'按钮坐标，使用默认位置");
                     self.adb_tap(300, 700).await?; // 通常在左下角
-                    sleep(Duration::from_secs(2)).await;
+                    settle(&self.device_id, Duration::from_secs(2)).await;
                 }
 
                 info!("🎉 应用选择器对话框处理完成");
@@ -1172,6 +1073,8 @@ impl VcfImporter {
 
     /// 在应用选择器中查找通讯录应用的坐标
     fn find_contacts_app_in_chooser(&self, ui_content: &str) -> Option<(i32, i32)> {
+        let tree = UiTree::parse(ui_content).ok()?;
+
         // 通讯录应用的可能标识
         let contacts_apps = vec![
             "通讯录",
@@ -1182,47 +1085,26 @@ impl VcfImporter {
         ];
 
         for app_name in &contacts_apps {
-            if let Some(bounds_start) = ui_content.find(&format!("text=\"{}\"", app_name)) {
-                // 向后查找bounds属性
-                if let Some(bounds_match) = ui_content[bounds_start..].find("bounds=\"[") {
-                    let bounds_start_index = bounds_start + bounds_match + 9;
-                    if let Some(bounds_end) = ui_content[bounds_start_index..].find("]\"") {
-                        let bounds_str = &ui_content[bounds_start_index..bounds_start_index + bounds_end];
-                        if let Some((x, y)) = self.parse_bounds_to_center(bounds_str) {
-                            info!("✅ 找到通讯录应用 '{}' 的坐标: ({}, {})", app_name, x, y);
-                            return Some((x, y));
-                        }
-                    }
-                }
+            if let Some((x, y)) = tree.find_first(|n| n.text() == *app_name).and_then(|n| n.center()) {
+                info!("✅ 找到通讯录应用 '{}' 的坐标: ({}, {})", app_name, x, y);
+                return Some((x, y));
             }
         }
 
         // 如果没找到特定应用，查找应用图标的通用模式
-        self.find_first_app_icon_in_chooser(ui_content)
+        self.find_first_app_icon_in_chooser(&tree)
     }
 
     /// 查找第一个应用图标（作为备选方案）
-    fn find_first_app_icon_in_chooser(&self, ui_content: &str) -> Option<(i32, i32)> {
+    fn find_first_app_icon_in_chooser(&self, tree: &UiTree) -> Option<(i32, i32)> {
         // 查找应用图标的通用特征
-        let icon_patterns = vec![
-            "resource-id=\"android:id/icon\"",
-            "class=\"android.widget.ImageView\"",
-        ];
+        let icon = tree
+            .find_first(|n| n.resource_id() == "android:id/icon")
+            .or_else(|| tree.find_first(|n| n.class_name() == "android.widget.ImageView"));
 
-        for pattern in &icon_patterns {
-            if let Some(start) = ui_content.find(pattern) {
-                // 向后查找bounds属性
-                if let Some(bounds_match) = ui_content[start..].find("bounds=\"[") {
-                    let bounds_start = start + bounds_match + 9;
-                    if let Some(bounds_end) = ui_content[bounds_start..].find("]\"") {
-                        let bounds_str = &ui_content[bounds_start..bounds_start + bounds_end];
-                        if let Some((x, y)) = self.parse_bounds_to_center(bounds_str) {
-                            info!("📱 找到第一个应用图标坐标: ({}, {})", x, y);
-                            return Some((x, y));
-                        }
-                    }
-                }
-            }
+        if let Some((x, y)) = icon.and_then(|n| n.center()) {
+            info!("📱 找到第一个应用图标坐标: ({}, {})", x, y);
+            return Some((x, y));
         }
 
         warn!("⚠️ 未找到任何应用图标");
@@ -1231,46 +1113,13 @@ impl VcfImporter {
 
     /// 查找"始终"按钮的坐标
     fn find_always_button(&self, ui_content: &str) -> Option<(i32, i32)> {
+        let tree = UiTree::parse(ui_content).ok()?;
         let always_texts = vec!["始终", "Always", "ALWAYS"];
 
         for text in &always_texts {
-            if let Some(start) = ui_content.find(&format!("text=\"{}\"", text)) {
-                // 向后查找bounds属性
-                if let Some(bounds_match) = ui_content[start..].find("bounds=\"[") {
-                    let bounds_start = start + bounds_match + 9;
-                    if let Some(bounds_end) = ui_content[bounds_start..].find("]\"") {
-                        let bounds_str = &ui_content[bounds_start..bounds_start + bounds_end];
-                        if let Some((x, y)) = self.parse_bounds_to_center(bounds_str) {
-                            info!("✅ 找到'{}' 按钮坐标: ({}, {})", text, x, y);
-                            return Some((x, y));
-                        }
-                    }
-                }
-            }
-        }
-
-        None
-    }
-
-    /// 解析bounds字符串到中心坐标
-    fn parse_bounds_to_center(&self, bounds_str: &str) -> Option<(i32, i32)> {
-        // bounds格式: "left,top][right,bottom"
-        let parts: Vec<&str> = bounds_str.split("][").collect();
-        if parts.len() == 2 {
-            let left_top: Vec<&str> = parts[0].split(',').collect();
-            let right_bottom: Vec<&str> = parts[1].split(',').collect();
-
-            if left_top.len() == 2 && right_bottom.len() == 2 {
-                if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
-                    left_top[0].parse::<i32>(),
-                    left_top[1].parse::<i32>(),
-                    right_bottom[0].parse::<i32>(),
-                    right_bottom[1].parse::<i32>(),
-                ) {
-                    let center_x = (left + right) / 2;
-                    let center_y = (top + bottom) / 2;
-                    return Some((center_x, center_y));
-                }
+            if let Some((x, y)) = tree.find_first(|n| n.text() == *text).and_then(|n| n.center()) {
+                info!("✅ 找到'{}' 按钮坐标: ({}, {})", text, x, y);
+                return Some((x, y));
             }
         }
 
@@ -1287,7 +1136,7 @@ impl VcfImporter {
         // 点击第一个应用位置（通常在屏幕上半部分）
         info!("📱 点击第一个应用位置（估算坐标）");
         self.adb_tap(200, 300).await?; // 左上角区域
-        sleep(Duration::from_secs(1)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
 
         // 尝试点击"始终"按钮的常见位置
         let always_positions = vec![
@@ -1299,7 +1148,7 @@ impl VcfImporter {
         for (x, y) in &always_positions {
             info!("🔘 尝试点击'始终'按钮位置: ({}, {})", x, y);
             self.adb_tap(*x, *y).await?;
-            sleep(Duration::from_secs(1)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
 
             // 检查对话框是否消失
             let new_ui = self.get_current_ui_dump().await?;
@@ -1320,11 +1169,12 @@ impl VcfImporter {
     ) -> Result<VcfVerifyResult> {
         info!("开始验证VCF导入结果");
 
-        sleep(Duration::from_secs(5)).await; // 等待系统同步
+        // 联系人写入 ContactsProvider 是后台同步，界面上没有可等待的信号，只能固定留出时间
+        sleep(Duration::from_secs(5)).await;
 
         // 启动联系人应用
         self.open_contacts_app().await?;
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
 
         let mut verified_contacts = 0;
         let mut details = Vec::new();
@@ -1421,7 +1271,7 @@ impl VcfImporter {
 
         // 点击返回按钮到首页
         self.adb_tap(112, 98).await?;
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
 
         // 或者直接启动联系人首页
         let output = self.execute_adb_command(&[
@@ -1439,7 +1289,7 @@ impl VcfImporter {
             warn!("启动联系人应用可能失败，但继续验证");
         }
 
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
         Ok(())
     }
 
@@ -1548,7 +1398,7 @@ impl VcfImporter {
 
             if output.status.success() {
                 info!("✅ 成功使用 {} 打开VCF文件", package);
-                sleep(Duration::from_secs(3)).await;
+                settle(&self.device_id, Duration::from_secs(3)).await;
                 return Ok(());
             } else {
                 let error = String::from_utf8_lossy(&output.stderr);
@@ -1614,7 +1464,7 @@ impl VcfImporter {
 
         if output.status.success() {
             info!("✅ 系统Intent执行成功");
-            sleep(Duration::from_secs(3)).await;
+            settle(&self.device_id, Duration::from_secs(3)).await;
             
             // 由于使用了系统Intent，仍可能出现应用选择器
             // 所以还是需要处理应用选择器
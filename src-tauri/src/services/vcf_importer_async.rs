@@ -7,6 +7,7 @@ use tokio::time::{sleep, timeout, Duration};
 use tracing::{error, info, warn};
 
 use crate::services::safe_adb_manager::SafeAdbManager;
+use crate::services::vcard::{write_vcards, VCard, VCardVersion};
 
 // 重用现有的数据结构
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -196,33 +197,12 @@ impl VcfImporterAsync {
     async fn generate_vcf_file(&self, contacts: Vec<Contact>, output_path: &str) -> Result<String> {
         info!("开始生成VCF文件: {}", output_path);
 
-        let mut vcf_content = String::new();
-
-        for contact in &contacts {
-            vcf_content.push_str("BEGIN:VCARD\n");
-            vcf_content.push_str("VERSION:2.1\n");
-            vcf_content.push_str(&format!("FN:{}\n", contact.name));
-            vcf_content.push_str(&format!("N:{};\n", contact.name));
-
-            if !contact.phone.is_empty() {
-                let formatted_phone = self.format_chinese_phone(&contact.phone);
-                vcf_content.push_str(&format!("TEL;CELL:{}\n", formatted_phone));
-            }
-
-            if !contact.email.is_empty() {
-                vcf_content.push_str(&format!("EMAIL:{}\n", contact.email));
-            }
-
-            if !contact.address.is_empty() {
-                vcf_content.push_str(&format!("ADR:;;{};;;;\n", contact.address));
-            }
-
-            if !contact.occupation.is_empty() {
-                vcf_content.push_str(&format!("NOTE:{}\n", contact.occupation));
-            }
-
-            vcf_content.push_str("END:VCARD\n");
-        }
+        // 转义、CHARSET、折行由 vcard 模块处理
+        let cards: Vec<VCard> = contacts
+            .iter()
+            .map(|c| VCard::from_fields(&c.name, &c.phone, &c.email, &c.address, &c.occupation))
+            .collect();
+        let vcf_content = write_vcards(&cards, VCardVersion::default());
 
         // 先检查目录是否存在
         if let Some(parent) = std::path::Path::new(output_path).parent() {
@@ -453,15 +433,4 @@ impl VcfImporterAsync {
         info!("读取到 {} 个联系人", contacts.len());
         Ok(contacts)
     }
-
-    /// 格式化中国手机号
-    fn format_chinese_phone(&self, phone: &str) -> String {
-        let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
-
-        if digits.len() == 11 && digits.starts_with('1') {
-            format!("+86 {} {} {}", &digits[0..3], &digits[3..7], &digits[7..11])
-        } else {
-            phone.to_string()
-        }
-    }
 }
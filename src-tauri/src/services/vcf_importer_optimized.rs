@@ -1,13 +1,14 @@
 use anyhow::{Context, Result};
 use std::process::Command;
 use std::time::Duration;
-use tokio::time::sleep;
 use tracing::{info, warn};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
 use super::vcf_importer::VcfImportResult;
+use super::ui_idle::settle;
+use super::ui_tree::UiTree;
 
 /// 基于Python脚本优化的VCF导入器
 pub struct VcfImporterOptimized {
@@ -69,7 +70,7 @@ impl VcfImporterOptimized {
             return Err(anyhow::anyhow!("ADB点击失败: {}", error));
         }
 
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
         Ok(())
     }
 
@@ -172,7 +173,7 @@ impl VcfImporterOptimized {
             self.adb_tap(280, 338, "下载文件夹").await?;
 
             // 获取最新UI并选择VCF文件
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(2)).await;
             let ui_content = self.get_ui_dump("after_navigation.xml").await?;
             if ui_content.contains("contacts_import.vcf") {
                 return self.select_vcf_file_from_ui(&ui_content).await;
@@ -202,63 +203,28 @@ impl VcfImporterOptimized {
 
     /// 从UI内容中精确定位VCF文件坐标 (Python算法移植)
     fn find_vcf_file_coordinates_optimized(&self, ui_content: &str) -> Option<(i32, i32)> {
-        let lines: Vec<&str> = ui_content.split('\n').collect();
+        let tree = UiTree::parse(ui_content).ok()?;
+        let file_nodes = tree.find_all(|n| n.text().contains("contacts_import.vcf"));
 
-        for line in &lines {
-            if line.contains("contacts_import.vcf") && line.contains("bounds=") {
-                if let Some(coords) = self.parse_bounds_from_line(line) {
-                    info!("📋 解析VCF文件坐标: {:?}", coords);
-                    return Some(coords);
-                }
-            }
+        if let Some(coords) = file_nodes.iter().find_map(|n| n.center()) {
+            info!("📋 解析VCF文件坐标: {:?}", coords);
+            return Some(coords);
         }
 
         // 查找包含VCF文件的父级容器
-        for (i, line) in lines.iter().enumerate() {
-            if line.contains("contacts_import.vcf") {
-                let start_idx = if i >= 5 { i - 5 } else { 0 };
-                for j in start_idx..i {
-                    if lines[j].contains("LinearLayout") && lines[j].contains("bounds=") {
-                        if let Some(coords) = self.parse_bounds_from_line(lines[j]) {
-                            info!("📋 从父容器解析VCF文件坐标: {:?}", coords);
-                            return Some(coords);
-                        }
-                    }
-                }
+        for node in &file_nodes {
+            let container = node
+                .ancestors()
+                .find(|a| a.class_name().contains("LinearLayout"));
+            if let Some(coords) = container.and_then(|c| c.center()) {
+                info!("📋 从父容器解析VCF文件坐标: {:?}", coords);
+                return Some(coords);
             }
         }
 
         None
     }
 
-    /// 从XML行中解析bounds坐标
-    fn parse_bounds_from_line(&self, line: &str) -> Option<(i32, i32)> {
-        if let Some(bounds_start) = line.find("bounds=\"[") {
-            if let Some(bounds_end) = line[bounds_start..].find("]\"") {
-                let bounds_end = bounds_start + bounds_end;
-                let bounds_str = &line[bounds_start + 9..bounds_end + 1];
-
-                if let Some((left_top, right_bottom)) = bounds_str.split_once("][") {
-                    if let (Some((left, top)), Some((right, bottom))) =
-                        (left_top.split_once(","), right_bottom.split_once(","))
-                    {
-                        if let (Ok(l), Ok(t), Ok(r), Ok(b)) = (
-                            left.parse::<i32>(),
-                            top.parse::<i32>(),
-                            right.parse::<i32>(),
-                            bottom.parse::<i32>(),
-                        ) {
-                            let center_x = (l + r) / 2;
-                            let center_y = (t + b) / 2;
-                            return Some((center_x, center_y));
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
-
     /// 运行完整的VCF导入流程 (基于Python脚本移植)
     pub async fn run_complete_vcf_import(
         &self,
@@ -362,7 +328,7 @@ impl VcfImporterOptimized {
             }
 
             let delay = if i < navigation_steps.len() - 1 { 2 } else { 3 };
-            sleep(Duration::from_secs(delay)).await;
+            settle(&self.device_id, Duration::from_secs(delay)).await;
         }
 
         // 步骤3: 导航并选择VCF文件
@@ -412,7 +378,7 @@ impl VcfImporterOptimized {
 
         // 导航到联系人首页
         self.adb_tap(112, 98, "返回按钮").await?;
-        sleep(Duration::from_secs(2)).await;
+        settle(&self.device_id, Duration::from_secs(2)).await;
 
         // 启动联系人首页
         let output = self.execute_adb_command(&[
@@ -430,7 +396,7 @@ impl VcfImporterOptimized {
             warn!("启动联系人应用可能失败，但继续验证");
         }
 
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
 
         let ui_content = self.get_ui_dump("contacts_home.xml").await?;
 
@@ -4,6 +4,13 @@ use std::process::Command;
 use tokio::time::{sleep, Duration};
 use tracing::{error, info, warn};
 use crate::utils::adb_utils::get_adb_path;
+use crate::services::navigation_bar_detector::{create_xiaohongshu_sidebar_config, NavigationBarDetector};
+use crate::services::navigation_graph::navigate_to;
+use crate::services::page_fingerprint::get_page_registry;
+use crate::services::ui_dump_cache::get_ui_dump_cache;
+use crate::services::scroll_finder::{ScrollOptions, ScrollSession, Swipe};
+use crate::services::ui_idle::settle;
+use crate::services::ui_tree::{UiNode, UiTree};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -25,6 +32,30 @@ pub struct NavigationResult {
     pub message: String,
 }
 
+/// 通讯录页面在页面指纹注册表中的名称
+pub const XHS_CONTACTS_PAGE: &str = "小红书-通讯录";
+
+/// 按导航图前往通讯录页面
+///
+/// 页面未注册或导航图无法到达时返回 None，由调用方继续走固定流程。
+pub(crate) async fn navigate_to_contacts_by_graph(device_id: &str) -> Option<NavigationResult> {
+    get_page_registry().get(XHS_CONTACTS_PAGE).await?;
+    match navigate_to(device_id, "com.xingin.xhs", XHS_CONTACTS_PAGE, Duration::from_millis(1500)).await {
+        Ok(outcome) if outcome.success => {
+            info!("🗺️ 已按导航图到达通讯录页面: {}", outcome.visited.join(" → "));
+            Some(NavigationResult { success: true, message: outcome.message })
+        }
+        Ok(outcome) => {
+            warn!("⚠️ 导航图未能到达通讯录页面，改用固定流程: {}", outcome.message);
+            None
+        }
+        Err(e) => {
+            warn!("⚠️ 导航图导航失败，改用固定流程: {}", e);
+            None
+        }
+    }
+}
+
 // 关注操作配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct XiaohongshuFollowOptions {
@@ -166,8 +197,11 @@ impl XiaohongshuAutomator {
             cmd.creation_flags(0x08000000);
         }
         
+        let cache = get_ui_dump_cache();
+        cache.note_adb_args(args);
         let output = cmd.output()
             .context(format!("执行ADB命令失败 - ADB路径: {}, 参数: {:?}", self.adb_path, args))?;
+        cache.note_adb_args(args);
         
         Ok(output)
     }
@@ -300,11 +334,16 @@ impl XiaohongshuAutomator {
                     message: error_msg,
                 });
             }
-            sleep(Duration::from_millis(3000)).await;
+            settle(&self.device_id, Duration::from_secs(5)).await;
         } else {
             info!("✅ 小红书应用已运行");
         }
 
+        // 已录制导航图时优先按图导航
+        if let Some(result) = navigate_to_contacts_by_graph(&self.device_id).await {
+            return Ok(result);
+        }
+
         // 步骤2: 检查当前页面状态并确定起始点
         info!("🏠 步骤2: 检查当前页面状态");
         let page_state = match self.recognize_current_page().await {
@@ -333,7 +372,7 @@ impl XiaohongshuAutomator {
                         message: error_msg,
                     });
                 }
-                sleep(Duration::from_millis(5000)).await;
+                settle(&self.device_id, Duration::from_secs(8)).await;
                 
                 // 启动后重新检查页面状态
                 let new_state = match self.recognize_current_page().await {
@@ -396,7 +435,7 @@ impl XiaohongshuAutomator {
                         message: error_msg,
                     });
                 }
-                sleep(Duration::from_millis(3000)).await;
+                settle(&self.device_id, Duration::from_secs(5)).await;
                 
                 // 重新检查页面状态
                 let retry_state = match self.recognize_current_page().await {
@@ -433,7 +472,7 @@ impl XiaohongshuAutomator {
                 message: error_msg,
             });
         }
-        sleep(Duration::from_millis(2000)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
         
         // 验证侧边栏是否打开并继续导航
         self.navigate_from_sidebar().await
@@ -489,7 +528,7 @@ impl XiaohongshuAutomator {
                 message: error_msg,
             });
         }
-        sleep(Duration::from_millis(2000)).await;
+        settle(&self.device_id, Duration::from_secs(3)).await;
         
         // 检查结果并继续导航
         self.navigate_from_discover_friends().await
@@ -539,7 +578,7 @@ impl XiaohongshuAutomator {
                         message: error_msg,
                     });
                 }
-                sleep(Duration::from_millis(3000)).await; // 联系人加载可能需要更长时间
+                settle(&self.device_id, Duration::from_secs(5)).await; // 联系人加载可能需要更长时间
                 
                 // 验证最终是否到达联系人页面
                 let final_check = match self.recognize_current_page().await {
@@ -676,38 +715,49 @@ impl XiaohongshuAutomator {
         }
     }
 
-    /// 解析UI元素（简化版本）
+    /// 解析UI元素
     async fn parse_ui_elements(&self, ui_dump: &str) -> Result<Vec<UIElement>> {
-        let mut elements = Vec::new();
-        
-        // 简化的XML解析 - 查找可点击元素
-        for line in ui_dump.lines() {
-            if line.contains("clickable=\"true\"") || line.contains("关注") || line.contains("发现好友") {
-                if let Some(element) = self.parse_ui_element_line(line) {
-                    elements.push(element);
-                }
+        let tree = match UiTree::parse(ui_dump) {
+            Ok(tree) => tree,
+            Err(e) => {
+                warn!("⚠️ UI dump解析失败: {}", e);
+                return Ok(Vec::new());
             }
-        }
-        
+        };
+
+        // 只保留可点击元素和关键文本元素
+        let elements: Vec<UIElement> = tree
+            .elements()
+            .filter(|n| n.is_clickable() || n.text().contains("关注") || n.text().contains("发现好友"))
+            .map(|n| self.to_ui_element(&n))
+            .collect();
+
         info!("📊 解析到 {} 个UI元素", elements.len());
         Ok(elements)
     }
 
-    /// 解析单行UI元素
-    fn parse_ui_element_line(&self, line: &str) -> Option<UIElement> {
-        // 简化的解析逻辑，实际项目中应该使用更完整的XML解析
-        if line.contains("text=") {
-            let text = line.split("text=\"").nth(1)?.split("\"").next()?.to_string();
-            Some(UIElement {
-                element_type: UIElementType::Button,
-                text,
-                bounds: (0, 0, 0, 0), // 简化处理
-                clickable: line.contains("clickable=\"true\""),
-                resource_id: None,
-                class_name: None,
-            })
-        } else {
-            None
+    /// 将UI树节点转换为UI元素
+    fn to_ui_element(&self, node: &UiNode) -> UIElement {
+        let class_name = node.class_name();
+        let element_type = match class_name.rsplit('.').next().unwrap_or("") {
+            "Button" => UIElementType::Button,
+            "TextView" => UIElementType::TextView,
+            "ImageView" => UIElementType::ImageView,
+            "EditText" => UIElementType::EditText,
+            "RecyclerView" => UIElementType::RecyclerView,
+            "LinearLayout" => UIElementType::LinearLayout,
+            "RelativeLayout" => UIElementType::RelativeLayout,
+            _ => UIElementType::Unknown,
+        };
+        let resource_id = node.resource_id();
+
+        UIElement {
+            element_type,
+            text: node.text().to_string(),
+            bounds: node_bounds(node).unwrap_or((0, 0, 0, 0)),
+            clickable: node.is_clickable(),
+            resource_id: (!resource_id.is_empty()).then(|| resource_id.to_string()),
+            class_name: (!class_name.is_empty()).then(|| class_name.to_string()),
         }
     }
 
@@ -753,38 +803,24 @@ impl XiaohongshuAutomator {
 
     /// 从UI dump中提取小红书图标的坐标
     fn extract_xiaohongshu_icon_coords(&self, ui_dump: &str) -> Option<(i32, i32, i32, i32)> {
-        // 查找包含"小红书"文本的节点
-        for line in ui_dump.lines() {
-            if line.contains("小红书") && line.contains("bounds=") {
-                // 提取bounds信息
-                if let Some(bounds_start) = line.find("bounds=\"[") {
-                    if let Some(bounds_end) = line[bounds_start..].find("]\"") {
-                        let bounds_str = &line[bounds_start + 9..bounds_start + bounds_end];
-                        
-                        // 解析坐标格式: [left,top][right,bottom]
-                        if let Some(middle) = bounds_str.find("][") {
-                            let left_top = &bounds_str[..middle];
-                            let right_bottom = &bounds_str[middle + 2..];
-                            
-                            if let (Some(comma1), Some(comma2)) = (left_top.find(','), right_bottom.find(',')) {
-                                if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
-                                    left_top[..comma1].parse::<i32>(),
-                                    left_top[comma1 + 1..].parse::<i32>(),
-                                    right_bottom[..comma2].parse::<i32>(),
-                                    right_bottom[comma2 + 1..].parse::<i32>(),
-                                ) {
-                                    info!("✓ 解析到小红书图标坐标: ({}, {}, {}, {})", left, top, right, bottom);
-                                    return Some((left, top, right, bottom));
-                                }
-                            }
-                        }
-                    }
-                }
+        let tree = UiTree::parse(ui_dump).ok()?;
+
+        // 查找文本或描述包含"小红书"的节点
+        let bounds = tree
+            .elements()
+            .filter(|n| node_mentions(n, "小红书"))
+            .find_map(|n| node_bounds(&n));
+
+        match bounds {
+            Some((left, top, right, bottom)) => {
+                info!("✓ 解析到小红书图标坐标: ({}, {}, {}, {})", left, top, right, bottom);
+                Some((left, top, right, bottom))
+            }
+            None => {
+                warn!("⚠️ 未能从UI dump中找到小红书图标坐标");
+                None
             }
         }
-        
-        warn!("⚠️ 未能从UI dump中找到小红书图标坐标");
-        None
     }
 
     /// 查找发现好友按钮坐标
@@ -848,103 +884,96 @@ impl XiaohongshuAutomator {
     /// 从UI内容中解析发现好友按钮坐标
     async fn parse_discover_friends_from_ui(&self, ui_dump: &str) -> Option<(i32, i32)> {
         info!("🔧 解析UI XML内容查找发现好友按钮...");
-        
-        // 查找包含"发现好友"文本的XML节点
-        let lines: Vec<&str> = ui_dump.lines().collect();
-        
-        // 首先尝试精确匹配"发现好友"
-        for (i, line) in lines.iter().enumerate() {
-            if line.contains("发现好友") {
-                info!("📍 找到包含'发现好友'的行 {}: {}", i, line.trim());
-                
-                // 尝试从当前行或相邻行解析bounds属性
-                for check_line in &lines[i.saturating_sub(2)..=(i + 2).min(lines.len() - 1)] {
-                    if let Some(bounds) = self.extract_bounds_from_line(check_line) {
-                        let center_x = (bounds.0 + bounds.2) / 2;
-                        let center_y = (bounds.1 + bounds.3) / 2;
-                        info!("✅ 解析到边界: {:?}, 中心点: ({}, {})", bounds, center_x, center_y);
-                        
-                        // 验证坐标合理性（避免过小或过大的坐标）
-                        if center_x > 50 && center_x < 500 && center_y > 50 && center_y < 800 {
-                            return Some((center_x, center_y));
-                        }
-                    }
+
+        let tree = match UiTree::parse(ui_dump) {
+            Ok(tree) => tree,
+            Err(e) => {
+                warn!("⚠️ UI dump解析失败: {}", e);
+                return None;
+            }
+        };
+
+        // 优先通过侧边栏检测定位抽屉里的"发现好友"入口
+        let detector = NavigationBarDetector::new(self.device_id.clone());
+        match detector.detect_in_ui_dump(ui_dump, &create_xiaohongshu_sidebar_config(), "发现好友") {
+            Ok(result) => {
+                if let Some(button) = result.target_button {
+                    let (center_x, center_y) = bounds_center(button.bounds);
+                    info!("✅ 侧边栏检测找到发现好友: {:?}, 中心点: ({}, {})", button.bounds, center_x, center_y);
+                    return Some((center_x, center_y));
                 }
+                info!("⚠️ 侧边栏检测未找到发现好友（检测到 {} 个侧边栏），改用关键字查找", result.detected_bars.len());
             }
+            Err(e) => warn!("⚠️ 侧边栏检测失败: {}", e),
         }
-        
+
+        // 其次尝试精确匹配"发现好友"
+        if let Some(coords) = self.find_sidebar_entry(&tree, &["发现好友"]) {
+            return Some(coords);
+        }
+
         // 如果没有找到"发现好友"，尝试查找"发现"和"好友"分开的情况
-        for (i, line) in lines.iter().enumerate() {
-            if line.contains("发现") && (line.contains("clickable=\"true\"") || line.contains("TextView")) {
-                info!("📍 找到包含'发现'的可点击元素行 {}: {}", i, line.trim());
-                
-                // 检查前后几行是否有"好友"
-                let context_lines = &lines[i.saturating_sub(3)..=(i + 3).min(lines.len() - 1)];
-                if context_lines.iter().any(|l| l.contains("好友")) {
-                    info!("📍 在上下文中找到'好友'，认为这是发现好友按钮");
-                    
-                    if let Some(bounds) = self.extract_bounds_from_line(line) {
-                        let center_x = (bounds.0 + bounds.2) / 2;
-                        let center_y = (bounds.1 + bounds.3) / 2;
-                        info!("✅ 解析到发现好友按钮边界: {:?}, 中心点: ({}, {})", bounds, center_x, center_y);
-                        
-                        if center_x > 50 && center_x < 500 && center_y > 50 && center_y < 800 {
-                            return Some((center_x, center_y));
-                        }
+        for node in tree.elements() {
+            let is_candidate = node_mentions(&node, "发现")
+                && (node.is_clickable() || node.class_name().contains("TextView"));
+            if !is_candidate {
+                continue;
+            }
+            info!("📍 找到包含'发现'的可点击元素: {:?}", node);
+
+            // 检查同一容器内是否有"好友"
+            let container = node.parent().unwrap_or(node);
+            let has_friends = container.descendants().iter().any(|n| node_mentions(n, "好友"));
+            if has_friends {
+                info!("📍 在上下文中找到'好友'，认为这是发现好友按钮");
+                if let Some(bounds) = node_bounds(&node) {
+                    let (center_x, center_y) = bounds_center(bounds);
+                    info!("✅ 解析到发现好友按钮边界: {:?}, 中心点: ({}, {})", bounds, center_x, center_y);
+                    if is_sidebar_position(center_x, center_y) {
+                        return Some((center_x, center_y));
                     }
                 }
             }
         }
-        
+
         // 如果没有找到"发现好友"，尝试查找相关的按钮元素
-        for line in &lines {
-            if (line.contains("clickable=\"true\"") || line.contains("android.widget.TextView")) 
-                && (line.contains("好友") || line.contains("发现")) {
-                info!("📍 找到可能的相关按钮: {}", line.trim());
-                
-                if let Some(bounds) = self.extract_bounds_from_line(line) {
-                    let center_x = (bounds.0 + bounds.2) / 2;
-                    let center_y = (bounds.1 + bounds.3) / 2;
-                    info!("✅ 解析到候选边界: {:?}, 中心点: ({}, {})", bounds, center_x, center_y);
-                    
-                    if center_x > 50 && center_x < 500 && center_y > 50 && center_y < 800 {
-                        return Some((center_x, center_y));
-                    }
+        for node in tree.elements() {
+            let is_candidate = (node.is_clickable() || node.class_name() == "android.widget.TextView")
+                && (node_mentions(&node, "好友") || node_mentions(&node, "发现"));
+            if !is_candidate {
+                continue;
+            }
+            info!("📍 找到可能的相关按钮: {:?}", node);
+
+            if let Some(bounds) = node_bounds(&node) {
+                let (center_x, center_y) = bounds_center(bounds);
+                info!("✅ 解析到候选边界: {:?}, 中心点: ({}, {})", bounds, center_x, center_y);
+                if is_sidebar_position(center_x, center_y) {
+                    return Some((center_x, center_y));
                 }
             }
         }
-        
+
         info!("❌ 未能从UI解析到发现好友按钮坐标");
         None
     }
 
-    /// 从XML行中提取bounds属性
-    fn extract_bounds_from_line(&self, line: &str) -> Option<(i32, i32, i32, i32)> {
-        // 查找bounds="[left,top][right,bottom]"格式
-        if let Some(bounds_start) = line.find("bounds=\"[") {
-            let bounds_part = &line[bounds_start + 9..];
-            if let Some(bounds_end) = bounds_part.find('"') {
-                let bounds_str = &bounds_part[..bounds_end];
-                
-                // 解析 "[left,top][right,bottom]" 格式
-                if let Some(middle) = bounds_str.find("][") {
-                    let left_top = &bounds_str[..middle];
-                    let right_bottom = &bounds_str[middle + 2..];
-                    
-                    if let (Some(comma1), Some(comma2)) = (left_top.find(','), right_bottom.find(',')) {
-                        let left_str = &left_top[..comma1];
-                        let top_str = &left_top[comma1 + 1..];
-                        let right_str = &right_bottom[..comma2];
-                        let bottom_str = &right_bottom[comma2 + 1..];
-                        
-                        if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
-                            left_str.parse::<i32>(),
-                            top_str.parse::<i32>(),
-                            right_str.parse::<i32>(),
-                            bottom_str.parse::<i32>()
-                        ) {
-                            return Some((left, top, right, bottom));
-                        }
+    /// 在侧边栏中查找包含关键字的入口：依次检查节点自身及其最近两层父容器
+    fn find_sidebar_entry(&self, tree: &UiTree, keywords: &[&str]) -> Option<(i32, i32)> {
+        for node in tree.elements() {
+            if !keywords.iter().any(|k| node_mentions(&node, k)) {
+                continue;
+            }
+            info!("📍 找到包含{:?}的节点: {:?}", keywords, node);
+
+            for candidate in std::iter::once(node).chain(node.ancestors().take(2)) {
+                if let Some(bounds) = node_bounds(&candidate) {
+                    let (center_x, center_y) = bounds_center(bounds);
+                    info!("✅ 解析到边界: {:?}, 中心点: ({}, {})", bounds, center_x, center_y);
+
+                    // 验证坐标合理性（避免过小或过大的坐标）
+                    if is_sidebar_position(center_x, center_y) {
+                        return Some((center_x, center_y));
                     }
                 }
             }
@@ -998,30 +1027,20 @@ impl XiaohongshuAutomator {
     /// 从UI内容中解析通讯录选项坐标
     async fn parse_contacts_from_ui(&self, ui_dump: &str) -> Option<(i32, i32)> {
         info!("🔧 解析UI XML内容查找通讯录选项...");
-        
-        // 查找包含"通讯录"或"联系人"文本的XML节点
-        let lines: Vec<&str> = ui_dump.lines().collect();
-        
-        for (i, line) in lines.iter().enumerate() {
-            if line.contains("通讯录") || line.contains("联系人") {
-                info!("📍 找到包含'通讯录/联系人'的行 {}: {}", i, line.trim());
-                
-                // 尝试从当前行或相邻行解析bounds属性
-                for check_line in &lines[i.saturating_sub(2)..=(i + 2).min(lines.len() - 1)] {
-                    if let Some(bounds) = self.extract_bounds_from_line(check_line) {
-                        let center_x = (bounds.0 + bounds.2) / 2;
-                        let center_y = (bounds.1 + bounds.3) / 2;
-                        info!("✅ 解析到边界: {:?}, 中心点: ({}, {})", bounds, center_x, center_y);
-                        
-                        // 验证坐标合理性
-                        if center_x > 50 && center_x < 500 && center_y > 50 && center_y < 800 {
-                            return Some((center_x, center_y));
-                        }
-                    }
-                }
+
+        let tree = match UiTree::parse(ui_dump) {
+            Ok(tree) => tree,
+            Err(e) => {
+                warn!("⚠️ UI dump解析失败: {}", e);
+                return None;
             }
+        };
+
+        // 查找包含"通讯录"或"联系人"文本的节点
+        if let Some(coords) = self.find_sidebar_entry(&tree, &["通讯录", "联系人"]) {
+            return Some(coords);
         }
-        
+
         info!("❌ 未能从UI解析到通讯录选项坐标");
         None
     }
@@ -1045,6 +1064,12 @@ impl XiaohongshuAutomator {
         let mut pages_processed = 0;
         let mut details = Vec::new();
 
+        let mut scroller = ScrollSession::new(ScrollOptions {
+            max_scrolls: max_pages as u32,
+            swipe_duration_ms: 800,
+            ..ScrollOptions::default()
+        });
+
         for page in 0..max_pages {
             info!("📄 处理第 {} 页", page + 1);
 
@@ -1092,8 +1117,8 @@ impl XiaohongshuAutomator {
                                     total_followed += 1;
                                     info!("✅ 成功关注用户 at ({}, {})", button.x, button.y);
 
-                                    // 验证关注结果
-                                    sleep(Duration::from_millis(1000)).await;
+                                    // 验证关注结果：等按钮状态刷新后再读取
+                                    settle(&self.device_id, Duration::from_secs(2)).await;
                                     let button_text_after = self.get_button_text_at(button.x, button.y).await.unwrap_or_default();
                                     let final_state_after = self.analyze_button_state(&button_text_after);
                                     
@@ -1175,20 +1200,23 @@ impl XiaohongshuAutomator {
             if page < max_pages - 1 {
                 info!("📜 检查是否可以滚动到下一页");
                 
-                // 获取滚动前的UI内容hash，用于检测是否有新内容
-                let content_before = self.get_ui_content_hash().await?;
+                // 记录滚动前的列表内容，用于检测是否有新内容
+                let ui_before = UiTree::parse(&self.get_ui_dump().await?)?;
+                let Some(swipe) = scroller.next_swipe(&ui_before, Some("关注")) else {
+                    break;
+                };
                 
-                if let Err(e) = self.scroll_down().await {
+                if let Err(e) = self.scroll_down(&swipe).await {
                     warn!("滚动失败: {}", e);
                     break;
                 }
                 
-                // 等待页面加载并检测变化
-                sleep(Duration::from_millis(2000)).await;
+                // 等待页面加载稳定后检测变化
+                settle(&self.device_id, Duration::from_secs(3)).await;
                 
-                let content_after = self.get_ui_content_hash().await?;
+                let ui_after = UiTree::parse(&self.get_ui_dump().await?)?;
                 
-                if content_before == content_after {
+                if !scroller.content_changed(&ui_after) {
                     info!("📄 页面内容未变化，可能已到达底部，停止滚动");
                     break;
                 } else {
@@ -1233,117 +1261,64 @@ impl XiaohongshuAutomator {
         
         info!("🔍 开始动态解析UI内容查找关注按钮...");
         
-        // 处理压缩的XML
-        let expanded_content = if ui_content.lines().count() <= 3 {
-            info!("⚠️ 检测到压缩的XML格式，正在展开以便按钮解析...");
-            self.expand_compressed_xml(&ui_content)
-        } else {
-            ui_content
-        };
-        
-        // 动态解析UI XML来查找关注按钮
+        // 动态解析UI树来查找关注按钮
         let mut buttons = Vec::new();
         
-        // 解析XML内容，查找包含"关注"文本的可点击元素
-        for line in expanded_content.lines() {
-            if self.is_follow_button_line(line) {
-                if let Some((x, y)) = self.extract_button_center_coords(line) {
-                    // 直接从当前行提取按钮文本和状态
-                    let text = self.extract_text_from_line(line).unwrap_or_else(|| "关注".to_string());
-                    let state = self.analyze_button_state(&text);
-                    
-                    let button = FollowButton {
-                        x,
-                        y,
-                        state: state.clone(),
-                        text: text.clone(),
-                    };
-                    
-                    info!("✓ 发现关注按钮: 位置({}, {}) 状态({:?}) 文本('{}')", x, y, state, text);
-                    buttons.push(button);
+        match UiTree::parse(&ui_content) {
+            Ok(tree) => {
+                // 查找包含"关注"文本的可点击元素
+                for node in tree.elements().filter(|n| self.is_follow_button(n)) {
+                    if let Some((x, y)) = node.center() {
+                        let text = self.button_text(&node).unwrap_or_else(|| "关注".to_string());
+                        let state = self.analyze_button_state(&text);
+                        
+                        info!("✓ 发现关注按钮: 位置({}, {}) 状态({:?}) 文本('{}')", x, y, state, text);
+                        buttons.push(FollowButton { x, y, state, text });
+                    }
                 }
             }
+            Err(e) => warn!("⚠️ UI dump解析失败: {}", e),
         }
         
         // 如果动态解析失败，使用备用的启发式方法
         if buttons.is_empty() {
             warn!("⚠️ 动态解析未找到按钮，使用启发式方法");
-            buttons = self.find_buttons_heuristic(&expanded_content).await?;
+            buttons = self.find_buttons_heuristic(&ui_content).await?;
         }
         
         info!("📊 总共找到 {} 个关注按钮", buttons.len());
         Ok(buttons)
     }
 
-    /// 判断某一行是否包含关注按钮信息
-    fn is_follow_button_line(&self, line: &str) -> bool {
+    /// 判断节点是否为关注按钮
+    fn is_follow_button(&self, node: &UiNode) -> bool {
         // 检查是否包含关注按钮的特征
-        let has_follow_text = line.contains("关注") || 
-                             line.contains("follow") || 
-                             line.contains("Follow") ||
-                             line.contains("+ 关注");
-        
-        // 检查是否是可点击的元素
-        let is_clickable = line.contains("clickable=\"true\"");
+        let has_follow_text = ["关注", "follow", "Follow"].iter().any(|k| {
+            node.text().contains(k) || node.content_desc().contains(k) || node.resource_id().contains(k)
+        });
+        if !has_follow_text {
+            return false;
+        }
         
-        // 检查是否有合理的坐标信息（不是整个屏幕）
-        let has_reasonable_bounds = line.contains("bounds=\"[") && 
-                                   !line.contains("bounds=\"[0,0][1080,1920]") && // 排除全屏元素
-                                   !line.contains("bounds=\"[0,0][1920,1080]"); // 排除横屏全屏元素
+        // 检查是否有合理的坐标信息（排除全屏元素）
+        let has_reasonable_bounds = match node_bounds(node) {
+            Some((0, 0, 1080, 1920)) | Some((0, 0, 1920, 1080)) | None => false,
+            Some(_) => true,
+        };
         
         // 检查是否是Button或TextView类型
-        let is_button_type = line.contains("class=\"android.widget.Button\"") ||
-                            line.contains("class=\"android.widget.TextView\"") ||
-                            line.contains("class=\"android.view.View\"");
-        
-        let result = has_follow_text && is_clickable && has_reasonable_bounds && is_button_type;
+        let is_button_type = matches!(
+            node.class_name(),
+            "android.widget.Button" | "android.widget.TextView" | "android.view.View"
+        );
         
-        if has_follow_text {
-            info!("🔍 检查按钮行: follow_text={}, clickable={}, reasonable_bounds={}, button_type={}, result={}",
-                  has_follow_text, is_clickable, has_reasonable_bounds, is_button_type, result);
-            if result {
-                info!("✓ 找到有效关注按钮行: {}", line.chars().take(100).collect::<String>());
-            }
-        }
+        let result = node.is_clickable() && has_reasonable_bounds && is_button_type;
+        info!("🔍 检查按钮节点: clickable={}, reasonable_bounds={}, button_type={}, result={}",
+              node.is_clickable(), has_reasonable_bounds, is_button_type, result);
         
         result
     }
 
-    /// 从UI元素行中提取按钮中心坐标
-    fn extract_button_center_coords(&self, line: &str) -> Option<(i32, i32)> {
-        // 查找bounds信息: bounds="[left,top][right,bottom]"
-        if let Some(bounds_start) = line.find("bounds=\"[") {
-            if let Some(bounds_end) = line[bounds_start..].find("]\"") {
-                let bounds_str = &line[bounds_start + 9..bounds_start + bounds_end];
-                
-                // 解析坐标格式: [left,top][right,bottom]
-                if let Some(middle) = bounds_str.find("][") {
-                    let left_top = &bounds_str[..middle];
-                    let right_bottom = &bounds_str[middle + 2..];
-                    
-                    if let (Some(comma1), Some(comma2)) = (left_top.find(','), right_bottom.find(',')) {
-                        if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
-                            left_top[..comma1].parse::<i32>(),
-                            left_top[comma1 + 1..].parse::<i32>(),
-                            right_bottom[..comma2].parse::<i32>(),
-                            right_bottom[comma2 + 1..].parse::<i32>(),
-                        ) {
-                            // 计算按钮中心坐标
-                            let center_x = (left + right) / 2;
-                            let center_y = (top + bottom) / 2;
-                            
-                            info!("📍 解析按钮坐标: bounds=[{},{},{},{}], center=({},{})", 
-                                  left, top, right, bottom, center_x, center_y);
-                            return Some((center_x, center_y));
-                        }
-                    }
-                }
-            }
-        }
-        
-        None
-    }
-
     /// 启发式方法查找按钮（备用方案）
     async fn find_buttons_heuristic(&self, ui_content: &str) -> Result<Vec<FollowButton>> {
         let mut buttons = Vec::new();
@@ -1442,56 +1417,43 @@ impl XiaohongshuAutomator {
 
     /// 获取指定位置的按钮文本（真实实现）
     async fn get_button_text_at(&self, x: i32, y: i32) -> Result<String> {
-        info!("� 正在读取坐标 ({}, {}) 处的按钮文本", x, y);
+        info!("📖 正在读取坐标 ({}, {}) 处的按钮文本", x, y);
         
         // 获取UI dump
         let ui_content = self.get_ui_dump().await?;
-        
-        // 处理压缩的XML - 将其拆分成多行以便解析
-        let expanded_content = if ui_content.lines().count() <= 3 {
-            // 如果只有少数几行，说明XML被压缩了，需要展开
-            info!("⚠️ 检测到压缩的XML格式，正在展开...");
-            self.expand_compressed_xml(&ui_content)
-        } else {
-            ui_content
-        };
-        
-        let lines: Vec<&str> = expanded_content.lines().collect();
-        info!("📄 处理后的UI Dump 共有 {} 行", lines.len());
-        
-        // 调试：先输出所有包含关注相关内容的行
-        info!("🔍 调试: 搜索包含'关注'或'follow'的所有XML行...");
-        let mut follow_related_lines = 0;
-        for (line_num, line) in lines.iter().enumerate() {
-            if line.contains("关注") || line.contains("follow") || line.contains("text=\"关注\"") {
-                follow_related_lines += 1;
-                info!("🎯 第{}行包含关注相关内容: {}", line_num + 1, 
-                    line.chars().take(200).collect::<String>());
-                
-                // 尝试解析这行的坐标
-                if let Some((left, top, right, bottom)) = self.parse_bounds_from_line(line) {
-                    let center_x = (left + right) / 2;
-                    let center_y = (top + bottom) / 2;
-                    info!("   📍 该元素坐标: 范围[{},{},{},{}] 中心({},{})", 
-                        left, top, right, bottom, center_x, center_y);
-                    
-                    // 检查是否与目标坐标匹配
-                    let distance = (((center_x - x).pow(2) + (center_y - y).pow(2)) as f64).sqrt() as i32;
-                    if distance <= 10 {
-                        info!("   ✅ 该元素与目标坐标({},{})非常接近，距离{}像素", x, y, distance);
-                    }
-                }
+        let tree = UiTree::parse(&ui_content)?;
+        info!("📄 UI树共有 {} 个节点", tree.len());
+        
+        // 调试：输出所有关注相关的元素
+        let follow_related: Vec<UiNode> = tree
+            .elements()
+            .filter(|n| node_mentions(n, "关注") || n.resource_id().contains("follow"))
+            .collect();
+        for node in &follow_related {
+            if let Some((left, top, right, bottom)) = node_bounds(node) {
+                let (center_x, center_y) = bounds_center((left, top, right, bottom));
+                info!("🎯 关注相关元素: 范围[{},{},{},{}] 中心({},{}) 文本:'{}'",
+                    left, top, right, bottom, center_x, center_y, node.text());
             }
         }
-        info!("📊 总共找到 {} 行包含关注相关内容", follow_related_lines);
+        info!("📊 总共找到 {} 个关注相关元素", follow_related.len());
         
+        // 扫描所有UI元素，查找包含目标坐标的元素（文档顺序，越靠后越接近顶层）
         let mut found_texts = Vec::new();
-        
-        // 扫描所有UI元素，查找包含目标坐标的元素
-        for (line_num, line) in lines.iter().enumerate() {
-            if let Some(text) = self.extract_text_from_coords_line(line, x, y) {
-                info!("✓ 第{}行: 在坐标({},{})处找到文本: '{}'", line_num + 1, x, y, text);
-                found_texts.push(text.clone());
+        for node in tree.elements() {
+            let Some((left, top, right, bottom)) = node_bounds(&node) else {
+                continue;
+            };
+            let (center_x, center_y) = bounds_center((left, top, right, bottom));
+            let in_bounds = x >= left && x <= right && y >= top && y <= bottom;
+            let near_center = (x - center_x).abs() <= 5 && (y - center_y).abs() <= 5;
+            
+            if in_bounds || near_center {
+                if let Some(text) = self.button_text(&node) {
+                    info!("✓ 在范围[{},{},{},{}]中心({},{})找到文本: '{}' (目标:{},{})",
+                        left, top, right, bottom, center_x, center_y, text, x, y);
+                    found_texts.push(text);
+                }
             }
         }
         
@@ -1500,29 +1462,23 @@ impl XiaohongshuAutomator {
             info!("⚠️ 在坐标({},{})处未找到任何文本，正在检查附近元素...", x, y);
             
             let tolerance = 50; // 50像素容差
-            let mut nearby_elements = Vec::new();
-            
-            for (line_num, line) in lines.iter().enumerate() {
-                if let Some((left, top, right, bottom)) = self.parse_bounds_from_line(line) {
-                    // 检查是否在附近
-                    let center_x = (left + right) / 2;
-                    let center_y = (top + bottom) / 2;
+            let mut nearby_elements: Vec<(i32, i32, i32, String)> = tree
+                .elements()
+                .filter_map(|node| {
+                    let (center_x, center_y) = node.center()?;
                     let distance_sq = (center_x - x).pow(2) + (center_y - y).pow(2);
-                    
-                    if distance_sq <= tolerance * tolerance {
-                        if let Some(text) = self.extract_text_from_line(line) {
-                            let distance = (distance_sq as f64).sqrt() as i32;
-                            nearby_elements.push((distance, line_num + 1, center_x, center_y, text));
-                        }
+                    if distance_sq > tolerance * tolerance {
+                        return None;
                     }
-                }
-            }
+                    let text = self.button_text(&node)?;
+                    Some(((distance_sq as f64).sqrt() as i32, center_x, center_y, text))
+                })
+                .collect();
             
             // 按距离排序并打印最近的几个元素
-            nearby_elements.sort_by_key(|&(distance, _, _, _, _)| distance);
-            for (distance, line_num, center_x, center_y, text) in nearby_elements.iter().take(5) {
-                info!("🔍 附近元素(第{}行): 中心({},{}) 距离{} 文本:'{}'", 
-                    line_num, center_x, center_y, distance, text);
+            nearby_elements.sort_by_key(|(distance, _, _, _)| *distance);
+            for (distance, center_x, center_y, text) in nearby_elements.iter().take(5) {
+                info!("🔍 附近元素: 中心({},{}) 距离{} 文本:'{}'", center_x, center_y, distance, text);
             }
             
             return Ok("未知".to_string());
@@ -1534,101 +1490,16 @@ impl XiaohongshuAutomator {
         Ok(result)
     }
 
-    /// 从UI元素行中解析坐标范围
-    fn parse_bounds_from_line(&self, line: &str) -> Option<(i32, i32, i32, i32)> {
-        if let Some(bounds_start) = line.find("bounds=\"[") {
-            let bounds_part = &line[bounds_start + 9..];
-            if let Some(bounds_end) = bounds_part.find("\"]") {
-                let bounds_str = &bounds_part[..bounds_end];
-                
-                // 解析格式如: 123,456][789,012
-                if let Some(right_bracket) = bounds_str.find("][") {
-                    let left_top = &bounds_str[..right_bracket];
-                    let right_bottom = &bounds_str[right_bracket + 2..];
-                    
-                    if let Some(comma1) = left_top.find(',') {
-                        if let Some(comma2) = right_bottom.find(',') {
-                            if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
-                                left_top[..comma1].parse::<i32>(),
-                                left_top[comma1 + 1..].parse::<i32>(),
-                                right_bottom[..comma2].parse::<i32>(),
-                                right_bottom[comma2 + 1..].parse::<i32>()
-                            ) {
-                                return Some((left, top, right, bottom));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
-
-    /// 从UI元素行中提取文本内容
-    fn extract_text_from_line(&self, line: &str) -> Option<String> {
-        // 尝试提取text属性
-        if let Some(text_start) = line.find("text=\"") {
-            let text_part = &line[text_start + 6..];
-            if let Some(text_end) = text_part.find("\"") {
-                let text = text_part[..text_end].to_string();
-                if !text.trim().is_empty() {
-                    return Some(text);
-                }
-            }
-        }
-        
-        // 如果没有text，尝试提取resource-id
-        if let Some(id_start) = line.find("resource-id=\"") {
-            let id_part = &line[id_start + 13..];
-            if let Some(id_end) = id_part.find("\"") {
-                let id = id_part[..id_end].to_string();
-                if id.contains("follow") || id.contains("关注") {
-                    return Some("关注".to_string());
-                }
-            }
-        }
-        
-        None
-    }
-
-    /// 从UI元素行中提取指定坐标范围内的文本
-    fn extract_text_from_coords_line(&self, line: &str, target_x: i32, target_y: i32) -> Option<String> {
-        // 检查这行是否包含坐标信息
-        if !line.contains("bounds=\"[") {
-            return None;
+    /// 读取按钮文本：优先 text 属性，否则根据 resource-id 判断是否为关注按钮
+    fn button_text(&self, node: &UiNode) -> Option<String> {
+        let text = node.text();
+        if !text.trim().is_empty() {
+            return Some(text.to_string());
         }
         
-        // 解析坐标范围
-        if let Some((left, top, right, bottom)) = self.parse_bounds_from_line(line) {
-            // 计算元素的中心点
-            let center_x = (left + right) / 2;
-            let center_y = (top + bottom) / 2;
-            
-            // 检查目标坐标是否在这个元素的范围内，或者非常接近中心点
-            let in_bounds = target_x >= left && target_x <= right && target_y >= top && target_y <= bottom;
-            let near_center = (target_x - center_x).abs() <= 5 && (target_y - center_y).abs() <= 5;
-            
-            if in_bounds || near_center {
-                // 提取文本内容
-                if let Some(text) = self.extract_text_from_line(line) {
-                    if !text.trim().is_empty() {
-                        info!("✓ 在范围[{},{},{},{}]中心({},{})找到文本: '{}' (目标:{},{})", 
-                            left, top, right, bottom, center_x, center_y, text, target_x, target_y);
-                        return Some(text);
-                    }
-                }
-                
-                // 如果没有text属性，检查resource-id或class等其他信息
-                if line.contains("关注") || line.contains("follow") {
-                    info!("✓ 在范围[{},{},{},{}]中心({},{})发现关注相关元素 (目标:{},{})", 
-                        left, top, right, bottom, center_x, center_y, target_x, target_y);
-                    return Some("关注".to_string());
-                }
-                
-                // 打印调试信息，看看这个元素是什么
-                info!("🔍 在目标坐标({},{})附近找到元素: {}", target_x, target_y, 
-                    line.chars().take(150).collect::<String>());
-            }
+        let resource_id = node.resource_id();
+        if resource_id.contains("follow") || resource_id.contains("关注") {
+            return Some("关注".to_string());
         }
         
         None
@@ -1744,21 +1615,21 @@ impl XiaohongshuAutomator {
     }
 
     /// 向下滚动页面
-    async fn scroll_down(&self) -> Result<()> {
+    async fn scroll_down(&self, swipe: &Swipe) -> Result<()> {
         info!("📜 执行向下滚动操作");
         
-        // 从屏幕中间向上滑动，距离适中以避免滑动过快
+        // 在列表容器内从下往上滑动，滑动时长较长以避免滑动过快
         let _output = self.execute_adb_command(&[
                 "-s",
                 &self.device_id,
                 "shell",
                 "input",
                 "swipe",
-                "500",
-                "700", // 起始位置 (稍微降低起始位置)
-                "500",
-                "400",  // 结束位置 (增加滚动距离)
-                "800", // 滑动时长(ms) (减少滑动时间使其更流畅)
+                &swipe.x1.to_string(),
+                &swipe.y1.to_string(),
+                &swipe.x2.to_string(),
+                &swipe.y2.to_string(),
+                &swipe.duration_ms.to_string(),
             ])
             .context("滑动页面失败")?;
 
@@ -1766,36 +1637,6 @@ impl XiaohongshuAutomator {
         Ok(())
     }
 
-    /// 获取UI内容的简化hash，用于检测页面变化
-    async fn get_ui_content_hash(&self) -> Result<u64> {
-        let ui_content = self.get_ui_dump().await?;
-        
-        // 提取关键内容用于hash计算（忽略动态变化的部分）
-        let key_content = ui_content
-            .lines()
-            .filter(|line| {
-                // 只关注包含用户信息和按钮的行
-                line.contains("关注") || 
-                line.contains("用户") || 
-                line.contains("用户名") ||
-                line.contains("nickname") ||
-                (line.contains("TextView") && line.contains("bounds"))
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-        
-        // 计算简单hash
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        
-        let mut hasher = DefaultHasher::new();
-        key_content.hash(&mut hasher);
-        let hash = hasher.finish();
-        
-        info!("📊 计算页面内容hash: {}, 关键行数: {}", hash, key_content.lines().count());
-        Ok(hash)
-    }
-
     /// 返回主页
     async fn return_to_home(&self) -> Result<()> {
         // 点击返回按钮或按Home键
@@ -1835,31 +1676,23 @@ impl XiaohongshuAutomator {
         info!("✓ 点击操作成功");
         Ok(())
     }
+}
 
-    /// 展开压缩的XML内容
-    fn expand_compressed_xml(&self, compressed_xml: &str) -> String {
-        // 在关键标签前后添加换行符，使XML更易解析
-        let mut expanded = compressed_xml.to_string();
-        
-        // 在标签开始前添加换行
-        let patterns = [
-            r"<node",
-            r"</node>",
-            r"<hierarchy",
-            r"</hierarchy>",
-        ];
-        
-        for pattern in &patterns {
-            expanded = expanded.replace(pattern, &format!("\n{}", pattern));
-        }
-        
-        // 在属性间添加空格，确保解析正确
-        expanded = expanded.replace("\" ", "\" ");
-        expanded = expanded.replace("\"><", "\">\n<");
-        
-        info!("✅ XML展开完成，从 {} 字符扩展到 {} 字符", 
-              compressed_xml.len(), expanded.len());
-        
-        expanded
-    }
-}
\ No newline at end of file
+/// 节点边界元组 (left, top, right, bottom)
+fn node_bounds(node: &UiNode) -> Option<(i32, i32, i32, i32)> {
+    node.bounds().map(|b| (b.left, b.top, b.right, b.bottom))
+}
+
+fn bounds_center(bounds: (i32, i32, i32, i32)) -> (i32, i32) {
+    ((bounds.0 + bounds.2) / 2, (bounds.1 + bounds.3) / 2)
+}
+
+/// 节点的文本或描述是否包含关键字
+fn node_mentions(node: &UiNode, keyword: &str) -> bool {
+    node.text().contains(keyword) || node.content_desc().contains(keyword)
+}
+
+/// 侧边栏入口的合理坐标范围
+fn is_sidebar_position(x: i32, y: i32) -> bool {
+    x > 50 && x < 500 && y > 50 && y < 800
+}
@@ -5,11 +5,13 @@ use tokio::time::{sleep, Duration, Instant};
 use tracing::{debug, error, info, warn};
 
 use super::adb_shell_session::AdbShellSession;
+use super::ui_idle::settle;
+use super::ui_tree::{UiNode, UiTree};
 use crate::utils::adb_utils::get_adb_path;
 
 // 重用原有的数据结构
 use super::xiaohongshu_automator::{
-    AppStatusResult, ButtonState, FollowButton, FollowDetail, NavigationResult, 
+    navigate_to_contacts_by_graph, AppStatusResult, ButtonState, FollowButton, FollowDetail, NavigationResult, 
     XiaohongshuFollowOptions, XiaohongshuFollowResult
 };
 
@@ -107,7 +109,7 @@ impl XiaohongshuLongConnectionAutomator {
         self.shell_session.start_app("com.xingin.xhs").await?;
         
         // 等待应用启动
-        sleep(Duration::from_secs(3)).await;
+        settle(&self.device_id, Duration::from_secs(5)).await;
 
         // 验证启动结果
         let current_activity = self.shell_session.get_current_activity().await?;
@@ -125,6 +127,11 @@ impl XiaohongshuLongConnectionAutomator {
         
         info!("🧭 导航到通讯录页面（长连接模式）");
 
+        // 已录制导航图时优先按图导航
+        if let Some(result) = navigate_to_contacts_by_graph(&self.device_id).await {
+            return Ok(result);
+        }
+
         // 步骤1: 确保小红书应用正在运行
         info!("📱 步骤1: 检查小红书应用状态");
         let _current_activity = self.shell_session.get_current_activity().await.unwrap_or_default();
@@ -138,7 +145,7 @@ impl XiaohongshuLongConnectionAutomator {
             info!("👤 步骤3: 点击头像打开侧边栏");
             // 使用旧版本验证的坐标 (60, 100)
             self.shell_session.tap(60, 100).await?;
-            sleep(Duration::from_secs(2)).await;
+            settle(&self.device_id, Duration::from_secs(3)).await;
             
             // 验证侧边栏是否打开
             let sidebar_ui = self.shell_session.dump_ui().await?;
@@ -170,7 +177,7 @@ impl XiaohongshuLongConnectionAutomator {
             info!("🎯 尝试发现好友坐标: ({}, {})", x, y);
             match self.shell_session.tap(x, y).await {
                 Ok(_) => {
-                    sleep(Duration::from_secs(2)).await;
+                    settle(&self.device_id, Duration::from_secs(3)).await;
                     
                     // 检查是否成功进入发现好友页面
                     let discover_ui = self.shell_session.dump_ui().await?;
@@ -209,7 +216,7 @@ impl XiaohongshuLongConnectionAutomator {
             info!("🎯 尝试通讯录坐标: ({}, {})", x, y);
             match self.shell_session.tap(x, y).await {
                 Ok(_) => {
-                    sleep(Duration::from_secs(3)).await; // 联系人加载需要更长时间
+                    settle(&self.device_id, Duration::from_secs(5)).await; // 联系人加载需要更长时间
                     
                     // 验证是否成功进入通讯录页面
                     let contacts_ui = self.shell_session.dump_ui().await?;
@@ -391,121 +398,60 @@ impl XiaohongshuLongConnectionAutomator {
 
     /// 从UI内容中查找关注按钮
     async fn find_follow_buttons_from_ui(&self, ui_content: &str) -> Result<Vec<FollowButton>> {
-        let mut buttons = Vec::new();
-        
-        // 简化的按钮查找逻辑
-        for line in ui_content.lines() {
-            if self.is_follow_button_line(line) {
-                if let Some(button) = self.parse_follow_button_from_line(line) {
-                    buttons.push(button);
-                }
-            }
-        }
+        let tree = UiTree::parse(ui_content)?;
+        let buttons: Vec<FollowButton> = tree
+            .elements()
+            .filter(|n| self.is_follow_button(n))
+            .filter_map(|n| self.parse_follow_button(&n))
+            .collect();
 
         debug!("🔍 从UI解析出 {} 个关注按钮", buttons.len());
         Ok(buttons)
     }
 
-    /// 检查是否为关注按钮行
-    fn is_follow_button_line(&self, line: &str) -> bool {
-        let has_follow_text = line.contains("关注") || line.contains("+ 关注");
-        let is_clickable = line.contains("clickable=\"true\"");
-        let is_button_type = line.contains("Button") || line.contains("TextView");
-        
-        has_follow_text && is_clickable && is_button_type
+    /// 检查节点是否为关注按钮
+    fn is_follow_button(&self, node: &UiNode) -> bool {
+        let has_follow_text = node.text().contains("关注");
+        let class_name = node.class_name();
+        let is_button_type = class_name.contains("Button") || class_name.contains("TextView");
+
+        has_follow_text && node.is_clickable() && is_button_type
     }
 
-    /// 从XML行解析关注按钮
-    fn parse_follow_button_from_line(&self, line: &str) -> Option<FollowButton> {
-        // 解析bounds属性
-        if let Some(bounds_start) = line.find("bounds=\"[") {
-            let bounds_end = line[bounds_start..].find("\"]")?;
-            let bounds_str = &line[bounds_start + 9..bounds_start + bounds_end];
-            let parts: Vec<&str> = bounds_str.split("][").collect();
-            
-            if parts.len() == 2 {
-                let left_top: Vec<&str> = parts[0].split(',').collect();
-                let right_bottom: Vec<&str> = parts[1].split(',').collect();
-                
-                if left_top.len() == 2 && right_bottom.len() == 2 {
-                    if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
-                        left_top[0].parse::<i32>(),
-                        left_top[1].parse::<i32>(),
-                        right_bottom[0].parse::<i32>(),
-                        right_bottom[1].parse::<i32>(),
-                    ) {
-                        let x = (left + right) / 2;
-                        let y = (top + bottom) / 2;
-                        
-                        // 解析按钮文本
-                        let text = if let Some(text_start) = line.find("text=\"") {
-                            let text_end = line[text_start + 6..].find("\"")?;
-                            line[text_start + 6..text_start + 6 + text_end].to_string()
-                        } else {
-                            "关注".to_string()
-                        };
-
-                        // 分析按钮状态
-                        let state = if text.contains("已关注") || text.contains("已关注") {
-                            ButtonState::AlreadyFollowed
-                        } else if text.contains("关注") {
-                            ButtonState::CanFollow
-                        } else {
-                            ButtonState::Unknown
-                        };
+    /// 从节点解析关注按钮
+    fn parse_follow_button(&self, node: &UiNode) -> Option<FollowButton> {
+        let (x, y) = node.center()?;
+        let text = node.text().to_string();
 
-                        return Some(FollowButton { x, y, state, text });
-                    }
-                }
-            }
-        }
-        None
+        // 分析按钮状态
+        let state = if text.contains("已关注") {
+            ButtonState::AlreadyFollowed
+        } else if text.contains("关注") {
+            ButtonState::CanFollow
+        } else {
+            ButtonState::Unknown
+        };
+
+        Some(FollowButton { x, y, state, text })
     }
 
     /// 查找发现按钮坐标
     fn find_discover_button(&self, ui_content: &str) -> Option<(i32, i32)> {
-        for line in ui_content.lines() {
-            if line.contains("发现") && line.contains("clickable=\"true\"") {
-                return self.extract_center_coordinates(line);
-            }
-        }
-        None
+        self.find_clickable_center(ui_content, &["发现"])
     }
 
-    /// 查找通讯录选项坐标  
+    /// 查找通讯录选项坐标
     fn find_contacts_option(&self, ui_content: &str) -> Option<(i32, i32)> {
-        for line in ui_content.lines() {
-            if (line.contains("通讯录朋友") || line.contains("通讯录")) && line.contains("clickable=\"true\"") {
-                return self.extract_center_coordinates(line);
-            }
-        }
-        None
+        self.find_clickable_center(ui_content, &["通讯录朋友", "通讯录"])
     }
 
-    /// 从XML行提取中心坐标
-    fn extract_center_coordinates(&self, line: &str) -> Option<(i32, i32)> {
-        if let Some(bounds_start) = line.find("bounds=\"[") {
-            let bounds_end = line[bounds_start..].find("\"]")?;
-            let bounds_str = &line[bounds_start + 9..bounds_start + bounds_end];
-            let parts: Vec<&str> = bounds_str.split("][").collect();
-            
-            if parts.len() == 2 {
-                let left_top: Vec<&str> = parts[0].split(',').collect();
-                let right_bottom: Vec<&str> = parts[1].split(',').collect();
-                
-                if left_top.len() == 2 && right_bottom.len() == 2 {
-                    if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
-                        left_top[0].parse::<i32>(),
-                        left_top[1].parse::<i32>(),
-                        right_bottom[0].parse::<i32>(),
-                        right_bottom[1].parse::<i32>(),
-                    ) {
-                        return Some(((left + right) / 2, (top + bottom) / 2));
-                    }
-                }
-            }
-        }
-        None
+    /// 按关键词顺序查找文本或描述匹配的可点击节点中心坐标
+    fn find_clickable_center(&self, ui_content: &str, keywords: &[&str]) -> Option<(i32, i32)> {
+        let tree = UiTree::parse(ui_content).ok()?;
+        keywords.iter().find_map(|keyword| {
+            tree.find_first(|n| n.is_clickable() && (n.text().contains(keyword) || n.content_desc().contains(keyword)))
+                .and_then(|n| n.center())
+        })
     }
 
     /// 确保连接已初始化
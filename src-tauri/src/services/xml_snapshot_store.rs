@@ -0,0 +1,761 @@
+use chrono::{TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult, Row};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+use tracing::{info, warn};
+
+use crate::screenshot_service::ScreenshotService;
+use crate::services::page_fingerprint::{current_activity, get_page_registry, PageFingerprint, DEFAULT_MATCH_THRESHOLD};
+use crate::services::ui_idle::hierarchy_hash;
+use crate::services::ui_tree::UiTree;
+use crate::xml_judgment_service::XmlJudgmentService;
+
+const SCREENSHOTS_DIR: &str = "data/xml_snapshots";
+const RETENTION_POLICY_FILE: &str = "data/xml_snapshot_retention.json";
+/// 已导入的旧缓存文件移动到 debug_xml 下的该子目录，避免每次启动重复导入
+const IMPORTED_LEGACY_SUBDIR: &str = "imported";
+
+/// FTS5 trigram 分词至少需要 3 个字符，更短的关键词退回 LIKE 扫描
+const MIN_FTS_QUERY_CHARS: usize = 3;
+
+/// 一条 XML 快照的元信息（不含 XML 内容，需要时按 ID 或文件名读取）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XmlSnapshotMeta {
+    pub id: i64,
+    /// 兼容旧缓存的文件名：ui_dump_<设备>_<YYYYmmdd_HHMMSS>.xml
+    pub file_name: String,
+    pub device_id: String,
+    pub app_package: String,
+    pub activity_name: Option<String>,
+    /// 页面指纹注册表识别出的页面名
+    pub page_name: Option<String>,
+    pub screen_width: i32,
+    pub screen_height: i32,
+    pub screenshot_path: Option<String>,
+    pub size_bytes: u64,
+    /// 首次抓取时间（Unix 秒）
+    pub captured_at: u64,
+    /// 最近一次抓到相同内容的时间（Unix 秒）
+    pub last_seen_at: u64,
+    /// 抓到相同内容的次数
+    pub seen_count: u32,
+}
+
+/// 待保存的快照
+#[derive(Debug, Clone, Default)]
+pub struct NewXmlSnapshot {
+    pub device_id: String,
+    pub xml_content: String,
+    pub activity_name: Option<String>,
+    pub fingerprint: Option<PageFingerprint>,
+    pub page_name: Option<String>,
+    pub screenshot_path: Option<String>,
+    pub captured_at: u64,
+    /// 指定文件名（导入旧缓存时使用），默认按设备和时间生成
+    pub file_name: Option<String>,
+}
+
+/// 保存结果：内容与该设备已有快照完全一致时只更新已有记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XmlSnapshotSaveResult {
+    pub snapshot: XmlSnapshotMeta,
+    pub deduplicated: bool,
+}
+
+/// 快照查询条件，未设置的条件不过滤
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XmlSnapshotQuery {
+    /// 在节点 text / content-desc 中全文搜索
+    pub text: Option<String>,
+    pub device_id: Option<String>,
+    pub app_package: Option<String>,
+    /// 起始时间（Unix 秒，含）
+    pub since: Option<u64>,
+    /// 截止时间（Unix 秒，含）
+    pub until: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+/// 快照保留策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XmlSnapshotRetentionPolicy {
+    /// 超过天数未再出现的快照将被删除
+    pub max_age_days: u64,
+    /// 快照（XML + 截图）总大小上限（MB），超出时从最旧的开始删除
+    pub max_total_mb: u64,
+}
+
+impl Default for XmlSnapshotRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: 30,
+            max_total_mb: 512,
+        }
+    }
+}
+
+/// 可检索的 XML 快照存储（SQLite + FTS5），替代 debug_xml 目录
+pub struct XmlSnapshotStore {
+    conn: Connection,
+    retention: XmlSnapshotRetentionPolicy,
+}
+
+const META_COLUMNS: &str = "s.id, s.file_name, s.device_id, s.app_package, s.activity_name, s.page_name, s.screen_width, s.screen_height, s.screenshot_path, s.size_bytes, s.captured_at, s.last_seen_at, s.seen_count";
+
+impl XmlSnapshotStore {
+    pub fn new() -> SqliteResult<Self> {
+        let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let app_data_dir = current_dir.join("data");
+
+        if let Err(e) = std::fs::create_dir_all(&app_data_dir) {
+            eprintln!("警告：无法创建数据目录 {:?}: {}", app_data_dir, e);
+        }
+
+        let mut store = Self::open(&app_data_dir.join("xml_snapshots.db"))?;
+        store.retention = std::fs::read_to_string(RETENTION_POLICY_FILE)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Ok(store)
+    }
+
+    pub fn open(db_path: &Path) -> SqliteResult<Self> {
+        Self::with_connection(Connection::open(db_path)?)
+    }
+
+    fn with_connection(conn: Connection) -> SqliteResult<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS xml_snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                file_name TEXT NOT NULL,
+                device_id TEXT NOT NULL,
+                app_package TEXT NOT NULL,
+                activity_name TEXT,
+                page_name TEXT,
+                fingerprint_json TEXT,
+                screen_width INTEGER NOT NULL,
+                screen_height INTEGER NOT NULL,
+                screenshot_path TEXT,
+                size_bytes INTEGER NOT NULL,
+                content_hash TEXT NOT NULL,
+                captured_at INTEGER NOT NULL,
+                last_seen_at INTEGER NOT NULL,
+                seen_count INTEGER NOT NULL DEFAULT 1,
+                xml_content TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_xml_snapshots_device ON xml_snapshots (device_id, last_seen_at);
+            CREATE INDEX IF NOT EXISTS idx_xml_snapshots_app ON xml_snapshots (app_package, last_seen_at);
+            CREATE INDEX IF NOT EXISTS idx_xml_snapshots_hash ON xml_snapshots (device_id, content_hash);
+            CREATE INDEX IF NOT EXISTS idx_xml_snapshots_file ON xml_snapshots (file_name);
+            CREATE VIRTUAL TABLE IF NOT EXISTS xml_snapshot_texts USING fts5(texts, tokenize = 'trigram');",
+        )?;
+        Ok(Self {
+            conn,
+            retention: XmlSnapshotRetentionPolicy::default(),
+        })
+    }
+
+    /// 保存快照；同一设备已有相同层次结构时只更新出现时间和次数
+    pub fn save(&self, snapshot: &NewXmlSnapshot) -> SqliteResult<XmlSnapshotSaveResult> {
+        let content_hash = format!("{:016x}", hierarchy_hash(&snapshot.xml_content));
+        let existing: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT id FROM xml_snapshots WHERE device_id = ?1 AND content_hash = ?2",
+                params![snapshot.device_id, content_hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        if let Some(id) = existing {
+            self.conn.execute(
+                "UPDATE xml_snapshots
+                 SET last_seen_at = MAX(last_seen_at, ?2), seen_count = seen_count + 1,
+                     screenshot_path = COALESCE(screenshot_path, ?3),
+                     activity_name = COALESCE(activity_name, ?4),
+                     page_name = COALESCE(page_name, ?5)
+                 WHERE id = ?1",
+                params![
+                    id,
+                    snapshot.captured_at as i64,
+                    snapshot.screenshot_path,
+                    snapshot.activity_name,
+                    snapshot.page_name,
+                ],
+            )?;
+            let meta = self.get(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+            return Ok(XmlSnapshotSaveResult { snapshot: meta, deduplicated: true });
+        }
+
+        let summary = summarize(&snapshot.xml_content);
+        let app_package = snapshot
+            .fingerprint
+            .as_ref()
+            .map(|f| f.package.clone())
+            .filter(|p| !p.is_empty())
+            .unwrap_or(summary.package);
+        let fingerprint_json = snapshot
+            .fingerprint
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let file_name = snapshot
+            .file_name
+            .clone()
+            .unwrap_or_else(|| legacy_file_name(&snapshot.device_id, snapshot.captured_at));
+
+        self.conn.execute(
+            "INSERT INTO xml_snapshots (file_name, device_id, app_package, activity_name, page_name, fingerprint_json,
+                 screen_width, screen_height, screenshot_path, size_bytes, content_hash, captured_at, last_seen_at, xml_content)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12, ?13)",
+            params![
+                file_name,
+                snapshot.device_id,
+                app_package,
+                snapshot.activity_name,
+                snapshot.page_name,
+                fingerprint_json,
+                summary.screen_width,
+                summary.screen_height,
+                snapshot.screenshot_path,
+                snapshot.xml_content.len() as i64,
+                content_hash,
+                snapshot.captured_at as i64,
+                snapshot.xml_content,
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.conn.execute(
+            "INSERT INTO xml_snapshot_texts (rowid, texts) VALUES (?1, ?2)",
+            params![id, summary.texts],
+        )?;
+        let meta = self.get(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        Ok(XmlSnapshotSaveResult { snapshot: meta, deduplicated: false })
+    }
+
+    pub fn get(&self, id: i64) -> SqliteResult<Option<XmlSnapshotMeta>> {
+        self.conn
+            .query_row(
+                &format!("SELECT {} FROM xml_snapshots s WHERE s.id = ?1", META_COLUMNS),
+                [id],
+                meta_from_row,
+            )
+            .optional()
+    }
+
+    /// 按条件检索快照，最近出现的在前
+    pub fn search(&self, query: &XmlSnapshotQuery) -> SqliteResult<Vec<XmlSnapshotMeta>> {
+        let text = query.text.as_deref().map(str::trim).filter(|t| !t.is_empty());
+        let text_filter = match text {
+            Some(t) if t.chars().count() >= MIN_FTS_QUERY_CHARS => "AND t.texts MATCH ?1",
+            Some(_) => "AND t.texts LIKE '%' || ?1 || '%'",
+            None => "AND ?1 IS NULL",
+        };
+        let fts_query = text.map(|t| match t.chars().count() >= MIN_FTS_QUERY_CHARS {
+            // 整体作为短语匹配，避免关键词中的引号、运算符被当作 FTS 语法
+            true => format!("\"{}\"", t.replace('"', "\"\"")),
+            false => t.to_string(),
+        });
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM xml_snapshots s JOIN xml_snapshot_texts t ON t.rowid = s.id
+             WHERE 1 = 1 {}
+               AND (?2 IS NULL OR s.device_id = ?2)
+               AND (?3 IS NULL OR s.app_package = ?3)
+               AND (?4 IS NULL OR s.last_seen_at >= ?4)
+               AND (?5 IS NULL OR s.captured_at <= ?5)
+             ORDER BY s.last_seen_at DESC, s.id DESC
+             LIMIT ?6",
+            META_COLUMNS, text_filter
+        ))?;
+        let limit = query.limit.map(|l| l as i64).unwrap_or(-1);
+        let records = stmt.query_map(
+            params![
+                fts_query,
+                query.device_id,
+                query.app_package,
+                query.since.map(|t| t as i64),
+                query.until.map(|t| t as i64),
+                limit,
+            ],
+            meta_from_row,
+        )?;
+        records.collect()
+    }
+
+    pub fn get_xml(&self, id: i64) -> SqliteResult<Option<String>> {
+        self.conn
+            .query_row("SELECT xml_content FROM xml_snapshots WHERE id = ?1", [id], |row| row.get(0))
+            .optional()
+    }
+
+    /// 同一页面（指纹相似度达到阈值）的历史快照 XML，最近出现的在前
+    pub fn similar_page_xmls(&self, fingerprint: &PageFingerprint, limit: usize) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT fingerprint_json, xml_content FROM xml_snapshots
+             WHERE app_package = ?1 AND fingerprint_json IS NOT NULL
+             ORDER BY last_seen_at DESC, id DESC",
+        )?;
+        let rows = stmt.query_map([&fingerprint.package], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut xmls = Vec::new();
+        for row in rows {
+            let (fingerprint_json, xml_content) = row?;
+            let Ok(stored) = serde_json::from_str::<PageFingerprint>(&fingerprint_json) else {
+                continue;
+            };
+            if stored.similarity(fingerprint) >= DEFAULT_MATCH_THRESHOLD {
+                xmls.push(xml_content);
+                if xmls.len() >= limit {
+                    break;
+                }
+            }
+        }
+        Ok(xmls)
+    }
+
+    /// 按旧缓存文件名查找（同名取最新的一条）
+    pub fn find_by_file_name(&self, file_name: &str) -> SqliteResult<Option<XmlSnapshotMeta>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM xml_snapshots s WHERE s.file_name = ?1 ORDER BY s.id DESC LIMIT 1",
+                    META_COLUMNS
+                ),
+                [file_name],
+                meta_from_row,
+            )
+            .optional()
+    }
+
+    /// 删除快照，返回截图路径以便清理文件
+    pub fn delete(&self, id: i64) -> SqliteResult<Option<String>> {
+        let screenshot: Option<Option<String>> = self
+            .conn
+            .query_row("SELECT screenshot_path FROM xml_snapshots WHERE id = ?1", [id], |row| row.get(0))
+            .optional()?;
+        self.conn.execute("DELETE FROM xml_snapshots WHERE id = ?1", [id])?;
+        self.conn.execute("DELETE FROM xml_snapshot_texts WHERE rowid = ?1", [id])?;
+        Ok(screenshot.flatten())
+    }
+
+    pub fn retention_policy(&self) -> XmlSnapshotRetentionPolicy {
+        self.retention.clone()
+    }
+
+    pub fn set_retention_policy(&mut self, policy: XmlSnapshotRetentionPolicy) {
+        self.retention = policy;
+    }
+
+    /// 设置保留策略并保存到 data/xml_snapshot_retention.json，重启后仍然生效
+    pub fn save_retention_policy(&mut self, policy: XmlSnapshotRetentionPolicy) -> Result<(), String> {
+        std::fs::create_dir_all("data").map_err(|e| format!("创建数据目录失败: {}", e))?;
+        let content = serde_json::to_string_pretty(&policy).map_err(|e| e.to_string())?;
+        std::fs::write(RETENTION_POLICY_FILE, content).map_err(|e| format!("保存XML快照保留策略失败: {}", e))?;
+        self.set_retention_policy(policy);
+        Ok(())
+    }
+
+    /// 按保留策略删除快照：先按最近出现时间过期，再按总大小从最旧的开始淘汰
+    ///
+    /// 返回删除的快照数量和需要清理的截图路径
+    pub fn apply_retention(&self, now_secs: u64) -> SqliteResult<(usize, Vec<String>)> {
+        let max_age_secs = self.retention.max_age_days * 24 * 3600;
+        let max_total_bytes = self.retention.max_total_mb * 1024 * 1024;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, size_bytes, last_seen_at, screenshot_path FROM xml_snapshots ORDER BY last_seen_at DESC, id DESC",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)? as u64,
+                    row.get::<_, i64>(2)? as u64,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+        drop(stmt);
+
+        let mut kept_bytes = 0u64;
+        let mut removed = 0;
+        let mut screenshots = Vec::new();
+        for (id, size_bytes, last_seen_at, screenshot_path) in rows {
+            let size_bytes = size_bytes + screenshot_path.as_deref().map(file_size).unwrap_or(0);
+            let too_old = now_secs.saturating_sub(last_seen_at) > max_age_secs;
+            if too_old || kept_bytes + size_bytes > max_total_bytes {
+                screenshots.extend(self.delete(id)?);
+                removed += 1;
+            } else {
+                kept_bytes += size_bytes;
+            }
+        }
+        Ok((removed, screenshots))
+    }
+
+    /// 导入旧 debug_xml 目录中的 ui_dump_*.xml，返回导入数量
+    ///
+    /// 导入后（或已存在同名记录时）把文件移到 `imported/` 子目录，
+    /// 这样被保留策略清理掉的快照不会在下次启动时重新导入。
+    pub fn import_legacy_dir(&self, dir: &Path) -> usize {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return 0;
+        };
+        let imported_dir = dir.join(IMPORTED_LEGACY_SUBDIR);
+        let mut imported = 0;
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some((device_id, captured_at)) = parse_legacy_file_name(&file_name) else {
+                continue;
+            };
+            if matches!(self.find_by_file_name(&file_name), Ok(Some(_))) {
+                archive_legacy_file(&entry.path(), &imported_dir);
+                continue;
+            }
+            let Ok(xml_content) = std::fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            let fingerprint = UiTree::parse(&xml_content)
+                .ok()
+                .map(|tree| PageFingerprint::from_tree(&tree, None));
+            let snapshot = NewXmlSnapshot {
+                device_id,
+                xml_content,
+                fingerprint,
+                captured_at,
+                file_name: Some(file_name.clone()),
+                ..Default::default()
+            };
+            match self.save(&snapshot) {
+                Ok(_) => {
+                    imported += 1;
+                    archive_legacy_file(&entry.path(), &imported_dir);
+                }
+                Err(e) => warn!("⚠️ 导入旧XML缓存失败 {}: {}", file_name, e),
+            }
+        }
+        imported
+    }
+}
+
+fn meta_from_row(row: &Row) -> SqliteResult<XmlSnapshotMeta> {
+    Ok(XmlSnapshotMeta {
+        id: row.get(0)?,
+        file_name: row.get(1)?,
+        device_id: row.get(2)?,
+        app_package: row.get(3)?,
+        activity_name: row.get(4)?,
+        page_name: row.get(5)?,
+        screen_width: row.get(6)?,
+        screen_height: row.get(7)?,
+        screenshot_path: row.get(8)?,
+        size_bytes: row.get::<_, i64>(9)? as u64,
+        captured_at: row.get::<_, i64>(10)? as u64,
+        last_seen_at: row.get::<_, i64>(11)? as u64,
+        seen_count: row.get(12)?,
+    })
+}
+
+fn file_size(path: &str) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// 从 XML 中提取的索引信息
+struct XmlSummary {
+    package: String,
+    screen_width: i32,
+    screen_height: i32,
+    /// 所有节点的 text / content-desc，每行一条
+    texts: String,
+}
+
+fn summarize(xml: &str) -> XmlSummary {
+    let Ok(tree) = UiTree::parse(xml) else {
+        return XmlSummary { package: String::new(), screen_width: 0, screen_height: 0, texts: String::new() };
+    };
+    let package = tree
+        .elements()
+        .map(|n| n.package())
+        .find(|p| !p.is_empty())
+        .unwrap_or_default()
+        .to_string();
+    let (screen_width, screen_height) = tree
+        .elements()
+        .find_map(|n| n.bounds())
+        .map(|b| (b.right, b.bottom))
+        .unwrap_or((0, 0));
+    let texts = tree
+        .elements()
+        .flat_map(|n| [n.text(), n.content_desc()])
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    XmlSummary { package, screen_width, screen_height, texts }
+}
+
+/// 旧缓存的文件名格式，前端按此格式解析设备和时间
+fn legacy_file_name(device_id: &str, captured_at: u64) -> String {
+    let timestamp = Utc
+        .timestamp_opt(captured_at as i64, 0)
+        .single()
+        .unwrap_or_default()
+        .format("%Y%m%d_%H%M%S");
+    format!("ui_dump_{}_{}.xml", device_id, timestamp)
+}
+
+fn parse_legacy_file_name(file_name: &str) -> Option<(String, u64)> {
+    let stem = file_name.strip_prefix("ui_dump_")?.strip_suffix(".xml")?;
+    let (device_id, timestamp) = stem.split_at(stem.len().checked_sub(15)?);
+    let device_id = device_id.strip_suffix('_')?;
+    let captured_at = chrono::NaiveDateTime::parse_from_str(timestamp, "%Y%m%d_%H%M%S").ok()?;
+    Some((device_id.to_string(), captured_at.and_utc().timestamp() as u64))
+}
+
+/// 把已导入的旧缓存文件移到归档目录
+fn archive_legacy_file(path: &Path, imported_dir: &Path) {
+    let Some(file_name) = path.file_name() else {
+        return;
+    };
+    let result = std::fs::create_dir_all(imported_dir).and_then(|_| std::fs::rename(path, imported_dir.join(file_name)));
+    if let Err(e) = result {
+        warn!("⚠️ 归档旧XML缓存失败 {:?}: {}", path, e);
+    }
+}
+
+/// 旧版 debug_xml 目录（项目根目录下）
+pub fn legacy_debug_xml_dir() -> PathBuf {
+    std::env::current_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .parent()
+        .unwrap_or_else(|| Path::new(".."))
+        .join("debug_xml")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 补全快照的 Activity、页面指纹和可选截图（在锁外执行的设备操作）
+pub async fn describe_snapshot(device_id: &str, xml_content: String, with_screenshot: bool) -> NewXmlSnapshot {
+    let activity_name = current_activity(device_id).await;
+    let fingerprint = UiTree::parse(&xml_content)
+        .ok()
+        .map(|tree| PageFingerprint::from_tree(&tree, activity_name.clone()));
+    let page_name = match &fingerprint {
+        Some(fp) => get_page_registry().identify(fp).await.map(|m| m.name),
+        None => None,
+    };
+    let screenshot_path = if with_screenshot {
+        ScreenshotService::capture_to_dir(device_id, Path::new(SCREENSHOTS_DIR)).await.screenshot_path
+    } else {
+        None
+    };
+    NewXmlSnapshot {
+        device_id: device_id.to_string(),
+        xml_content,
+        activity_name,
+        fingerprint,
+        page_name,
+        screenshot_path,
+        captured_at: now_secs(),
+        file_name: None,
+    }
+}
+
+/// 保存快照并执行保留策略；去重时清理多余的截图
+pub fn store_snapshot(store: &XmlSnapshotStore, snapshot: &NewXmlSnapshot) -> Result<XmlSnapshotSaveResult, String> {
+    let saved = store.save(snapshot).map_err(|e| e.to_string())?;
+    if let Some(path) = &snapshot.screenshot_path {
+        if saved.snapshot.screenshot_path.as_ref() != Some(path) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    match store.apply_retention(now_secs()) {
+        Ok((_, screenshots)) => {
+            for path in &screenshots {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        Err(e) => warn!("⚠️ 清理XML快照失败: {}", e),
+    }
+    if saved.deduplicated {
+        info!("♻️ XML快照与已有记录一致: #{} (第{}次)", saved.snapshot.id, saved.snapshot.seen_count);
+    } else {
+        info!("💾 已保存XML快照: #{} {}", saved.snapshot.id, saved.snapshot.file_name);
+    }
+    Ok(saved)
+}
+
+/// 抓取当前界面并保存为快照
+#[tauri::command]
+pub async fn capture_xml_snapshot(
+    device_id: String,
+    with_screenshot: Option<bool>,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<XmlSnapshotSaveResult, String> {
+    let xml_content = XmlJudgmentService::get_ui_xml(&device_id).await?;
+    let snapshot = describe_snapshot(&device_id, xml_content, with_screenshot.unwrap_or(false)).await;
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store_snapshot(&store, &snapshot)
+}
+
+/// 检索 XML 快照（节点文本全文搜索 + 设备、应用、时间过滤）
+#[tauri::command]
+pub async fn search_xml_snapshots(
+    query: XmlSnapshotQuery,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<Vec<XmlSnapshotMeta>, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store.search(&query).map_err(|e| e.to_string())
+}
+
+/// 读取快照的 XML 内容
+#[tauri::command]
+pub async fn get_xml_snapshot_content(
+    id: i64,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<String, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    store.get_xml(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("XML快照不存在: {}", id))
+}
+
+/// 删除快照及其截图
+#[tauri::command]
+pub async fn delete_xml_snapshot(
+    id: i64,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<(), String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    if let Some(path) = store.delete(id).map_err(|e| e.to_string())? {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+/// 获取 XML 快照保留策略
+#[tauri::command]
+pub async fn get_xml_snapshot_retention_policy(
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<XmlSnapshotRetentionPolicy, String> {
+    let store = store.lock().map_err(|e| e.to_string())?;
+    Ok(store.retention_policy())
+}
+
+/// 设置 XML 快照保留策略并立即清理，返回删除的快照数量
+#[tauri::command]
+pub async fn set_xml_snapshot_retention_policy(
+    policy: XmlSnapshotRetentionPolicy,
+    store: State<'_, Mutex<XmlSnapshotStore>>,
+) -> Result<usize, String> {
+    let mut store = store.lock().map_err(|e| e.to_string())?;
+    store.save_retention_policy(policy)?;
+    let (removed, screenshots) = store.apply_retention(now_secs()).map_err(|e| e.to_string())?;
+    for path in &screenshots {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(texts: &[&str]) -> String {
+        let nodes: String = texts
+            .iter()
+            .map(|t| format!(r#"<node text="{}" package="com.xingin.xhs" bounds="[0,0][100,100]" />"#, t))
+            .collect();
+        format!(
+            r#"<?xml version='1.0' ?><hierarchy rotation="0"><node package="com.xingin.xhs" bounds="[0,0][1080,2340]">{}</node></hierarchy>"#,
+            nodes
+        )
+    }
+
+    fn snapshot(device_id: &str, xml_content: String, captured_at: u64) -> NewXmlSnapshot {
+        NewXmlSnapshot { device_id: device_id.to_string(), xml_content, captured_at, ..Default::default() }
+    }
+
+    #[test]
+    fn test_save_dedup_and_full_text_search() {
+        let store = XmlSnapshotStore::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let first = store.save(&snapshot("dev1", page(&["通讯录好友", "关注"]), 100)).unwrap();
+        assert!(!first.deduplicated);
+        assert_eq!((first.snapshot.screen_width, first.snapshot.screen_height), (1080, 2340));
+        assert_eq!(first.snapshot.app_package, "com.xingin.xhs");
+        assert_eq!(first.snapshot.file_name, "ui_dump_dev1_19700101_000140.xml");
+
+        // 相同内容只更新出现次数；其他设备单独保存
+        let again = store.save(&snapshot("dev1", page(&["通讯录好友", "关注"]), 200)).unwrap();
+        assert!(again.deduplicated);
+        assert_eq!((again.snapshot.id, again.snapshot.seen_count, again.snapshot.last_seen_at), (first.snapshot.id, 2, 200));
+        store.save(&snapshot("dev2", page(&["通讯录好友", "关注"]), 300)).unwrap();
+        store.save(&snapshot("dev1", page(&["发现", "消息"]), 400)).unwrap();
+
+        let hits = store.search(&XmlSnapshotQuery { text: Some("通讯录".into()), ..Default::default() }).unwrap();
+        assert_eq!(hits.iter().map(|m| m.device_id.as_str()).collect::<Vec<_>>(), vec!["dev2", "dev1"]);
+        let short = store
+            .search(&XmlSnapshotQuery { text: Some("关注".into()), device_id: Some("dev1".into()), ..Default::default() })
+            .unwrap();
+        assert_eq!(short.len(), 1);
+        let recent = store.search(&XmlSnapshotQuery { since: Some(350), ..Default::default() }).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert!(store.get_xml(recent[0].id).unwrap().unwrap().contains("消息"));
+    }
+
+    #[test]
+    fn test_retention_and_legacy_file_names() {
+        let mut store = XmlSnapshotStore::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        for (i, at) in [10u64, 5 * 86400, 6 * 86400].into_iter().enumerate() {
+            store.save(&snapshot("dev1", page(&[&format!("页面{}", i)]), at)).unwrap();
+        }
+        let size = store.search(&XmlSnapshotQuery::default()).unwrap()[0].size_bytes;
+        store.set_retention_policy(XmlSnapshotRetentionPolicy { max_age_days: 3, max_total_mb: 1 });
+        assert_eq!(store.apply_retention(6 * 86400).unwrap().0, 1);
+        assert_eq!(store.search(&XmlSnapshotQuery::default()).unwrap().len(), 2);
+        assert!(size < 1024 * 1024);
+
+        store.set_retention_policy(XmlSnapshotRetentionPolicy { max_age_days: 3, max_total_mb: 0 });
+        store.apply_retention(6 * 86400).unwrap();
+        assert!(store.search(&XmlSnapshotQuery::default()).unwrap().is_empty());
+        assert!(store.search(&XmlSnapshotQuery { text: Some("页面1".into()), ..Default::default() }).unwrap().is_empty());
+
+        assert_eq!(
+            parse_legacy_file_name("ui_dump_emulator-5554_20250101_080000.xml"),
+            Some(("emulator-5554".to_string(), 1735718400))
+        );
+        assert_eq!(legacy_file_name("emulator-5554", 1735718400), "ui_dump_emulator-5554_20250101_080000.xml");
+        assert_eq!(parse_legacy_file_name("ui_dump_bad.xml"), None);
+    }
+
+    #[test]
+    fn test_legacy_import_archives_files() {
+        let dir = std::env::temp_dir().join(format!("xml_snapshot_legacy_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let file_name = "ui_dump_dev1_20250101_080000.xml";
+        std::fs::write(dir.join(file_name), page(&["通讯录好友"])).unwrap();
+
+        let store = XmlSnapshotStore::with_connection(Connection::open_in_memory().unwrap()).unwrap();
+        assert_eq!(store.import_legacy_dir(&dir), 1);
+        assert!(!dir.join(file_name).exists());
+        assert!(dir.join(IMPORTED_LEGACY_SUBDIR).join(file_name).exists());
+
+        // 被保留策略删除后不会再次导入
+        let id = store.find_by_file_name(file_name).unwrap().unwrap().id;
+        store.delete(id).unwrap();
+        assert_eq!(store.import_legacy_dir(&dir), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
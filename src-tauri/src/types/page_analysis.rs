@@ -228,6 +228,35 @@ pub struct SelectedElementConfig {
     pub retry_count: u32,
     /// 操作描述
     pub description: String,
+    /// 候选定位器（按得分排序的回退链），执行时依次尝试；element_id 在两次 dump 之间不稳定
+    #[serde(default)]
+    pub locators: Vec<ElementLocator>,
+}
+
+/// 元素定位策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LocatorStrategy {
+    ResourceId { resource_id: String },
+    Text { text: String },
+    ContentDesc { content_desc: String },
+    XPath { xpath: String },
+    /// 相对锚点元素（文本唯一的邻近元素）中心的偏移
+    RelativeToAnchor { anchor_text: String, dx: i32, dy: i32, class_name: String },
+    /// 按屏幕尺寸归一化的中心位置（0~1）
+    NormalizedPosition { x: f64, y: f64 },
+}
+
+/// 带评分的元素定位器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElementLocator {
+    pub strategy: LocatorStrategy,
+    /// 当前页面中的唯一性（0~1，唯一命中目标为 1）
+    pub uniqueness: f64,
+    /// 在同一页面历史快照中的稳定性（0~1，没有历史时取策略的经验值）
+    pub stability: f64,
+    /// 综合得分，回退链按此排序
+    pub score: f64,
 }
 
 /// 页面分析配置
@@ -71,5 +71,41 @@ pub fn execute_command_hidden(command: &str, args: &[&str]) -> Result<Output> {
 #[allow(dead_code)]
 pub fn execute_adb_command(args: &[&str]) -> Result<Output> {
     let adb_path = get_adb_path();
-    execute_command_hidden(&adb_path, args)
-}
\ No newline at end of file
+    // 输入类命令执行前后都使 UI dump 缓存失效（点击生效前后都可能被 dump）
+    let cache = crate::services::ui_dump_cache::get_ui_dump_cache();
+    cache.note_adb_args(args);
+    let output = execute_command_hidden(&adb_path, args);
+    cache.note_adb_args(args);
+    output
+}
+
+/// 异步执行单条 adb shell 命令（带超时），成功返回标准输出，失败返回标准错误
+pub async fn adb_shell(device_id: &str, command: &str) -> std::result::Result<String, String> {
+    let mut cmd = tokio::process::Command::new(get_adb_path());
+    cmd.args(["-s", device_id, "shell", command]);
+
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = tokio::time::timeout(std::time::Duration::from_secs(10), cmd.output())
+        .await
+        .map_err(|_| format!("命令超时: {}", command))?
+        .map_err(|e| format!("执行命令失败: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+/// 执行 adb shell 命令，输入类命令执行前后都使 UI dump 缓存失效
+pub async fn run_shell(device_id: &str, command: &str) -> std::result::Result<String, String> {
+    let cache = crate::services::ui_dump_cache::get_ui_dump_cache();
+    cache.note_shell_command(Some(device_id), command);
+    let result = adb_shell(device_id, command).await;
+    cache.note_shell_command(Some(device_id), command);
+    result
+}
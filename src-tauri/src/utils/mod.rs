@@ -1 +1,2 @@
-pub mod adb_utils;
\ No newline at end of file
+pub mod adb_utils;
+pub mod hash;
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use regex::Regex;
 use std::process::Output;
+use crate::services::ui_dump_cache::get_ui_dump_cache;
+use crate::services::ui_tree::{match_nodes, MatchStrategy, UiDiff, UiNode, UiTree};
 use crate::utils::adb_utils::execute_adb_command;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -48,6 +49,20 @@ async fn execute_adb_with_result(args: &[&str]) -> Result<Output, String> {
 impl XmlJudgmentService {
     /// 获取设备当前UI的XML结构
     pub async fn get_ui_xml(device_id: &str) -> Result<String, String> {
+        get_ui_dump_cache()
+            .get_or_dump(device_id, || Self::dump_ui_xml(device_id))
+            .await
+    }
+
+    /// 跳过缓存重新获取UI并写回缓存（轮询等待界面变化时使用）
+    pub async fn refresh_ui_xml(device_id: &str) -> Result<String, String> {
+        get_ui_dump_cache()
+            .refresh(device_id, || Self::dump_ui_xml(device_id))
+            .await
+    }
+
+    /// 实际执行 uiautomator dump 并读取结果（不经过缓存）
+    async fn dump_ui_xml(device_id: &str) -> Result<String, String> {
         // 先dump UI hierarchy
         match execute_adb_with_result(&["-s", device_id, "shell", "uiautomator", "dump", "/sdcard/ui_dump.xml"]).await {
             Ok(dump_result) => {
@@ -88,134 +103,54 @@ impl XmlJudgmentService {
     /// 解析XML字符串为结构化数据
     #[allow(dead_code)]
     pub fn parse_xml(xml_content: &str) -> Result<XmlElement, String> {
-        // 简化的XML解析实现
-        // 在实际项目中建议使用 quick-xml 或其他专业XML解析库
-        
-        let xml_content = xml_content.trim();
-        if xml_content.is_empty() {
-            return Err("XML内容为空".to_string());
-        }
-
-        // 提取根节点
-        if let Some(start) = xml_content.find('<') {
-            if let Some(end) = xml_content.rfind('>') {
-                let root_xml = &xml_content[start..=end];
-                return Self::parse_element(root_xml);
-            }
-        }
-
-        Err("无效的XML格式".to_string())
-    }
-
-    /// 解析单个XML元素
-    fn parse_element(element_str: &str) -> Result<XmlElement, String> {
-        // 简化的元素解析
-        let tag_regex = Regex::new(r"<(\w+)([^>]*)>").map_err(|e| e.to_string())?;
-        
-        if let Some(captures) = tag_regex.captures(element_str) {
-            let tag = captures.get(1).unwrap().as_str().to_string();
-            let attrs_str = captures.get(2).unwrap().as_str();
-            
-            let attributes = Self::parse_attributes(attrs_str);
-            let bounds = Self::extract_bounds(&attributes);
-            
-            // 提取文本内容
-            let text_regex = Regex::new(&format!(r"<{}[^>]*>(.*?)</{}>", tag, tag)).map_err(|e| e.to_string())?;
-            let text = text_regex.captures(element_str)
-                .and_then(|cap| cap.get(1))
-                .map(|m| m.as_str().trim().to_string())
-                .filter(|s| !s.is_empty());
-
-            return Ok(XmlElement {
-                tag,
-                attributes,
-                text,
-                children: vec![], // 简化实现，不递归解析子元素
-                bounds,
-            });
-        }
-
-        Err("无法解析XML元素".to_string())
+        let tree = UiTree::parse(xml_content).map_err(|e| e.to_string())?;
+        let root = tree.root().ok_or("XML内容为空")?;
+        Ok(Self::to_xml_element(&root, true))
     }
 
-    /// 解析XML属性
-    fn parse_attributes(attrs_str: &str) -> HashMap<String, String> {
-        let mut attributes = HashMap::new();
-        let attr_regex = Regex::new(r#"(\w+)="([^"]*)""#).unwrap();
-        
-        for captures in attr_regex.captures_iter(attrs_str) {
-            if let (Some(key), Some(value)) = (captures.get(1), captures.get(2)) {
-                attributes.insert(key.as_str().to_string(), value.as_str().to_string());
-            }
-        }
-        
-        attributes
-    }
+    /// 将统一模型中的节点转换为 XmlElement
+    fn to_xml_element(node: &UiNode, recursive: bool) -> XmlElement {
+        let attributes: HashMap<String, String> = node.attributes().iter().cloned().collect();
+        let children = if recursive {
+            node.children().map(|child| Self::to_xml_element(&child, true)).collect()
+        } else {
+            vec![]
+        };
 
-    /// 提取bounds坐标
-    fn extract_bounds(attributes: &HashMap<String, String>) -> Option<(i32, i32, i32, i32)> {
-        if let Some(bounds_str) = attributes.get("bounds") {
-            // bounds格式: "[left,top][right,bottom]"
-            let bounds_regex = Regex::new(r"\[(\d+),(\d+)\]\[(\d+),(\d+)\]").unwrap();
-            if let Some(captures) = bounds_regex.captures(bounds_str) {
-                let left = captures.get(1)?.as_str().parse().ok()?;
-                let top = captures.get(2)?.as_str().parse().ok()?;
-                let right = captures.get(3)?.as_str().parse().ok()?;
-                let bottom = captures.get(4)?.as_str().parse().ok()?;
-                return Some((left, top, right, bottom));
-            }
+        XmlElement {
+            tag: node.tag().to_string(),
+            attributes,
+            text: Some(node.text().to_string()).filter(|t| !t.is_empty()),
+            children,
+            bounds: node.bounds().map(|b| (b.left, b.top, b.right, b.bottom)),
         }
-        None
     }
 
     /// 查找符合条件的UI元素
     pub async fn find_elements(device_id: &str, condition: &XmlCondition) -> Result<XmlJudgmentResult, String> {
         let xml_content = Self::get_ui_xml(device_id).await?;
         
-        // 简化的元素查找实现
-        let mut matched_elements = Vec::new();
-        let mut matched = false;
-
-        match condition.condition_type.as_str() {
-            "resource_id" => {
-                if xml_content.contains(&format!("resource-id=\"{}\"", condition.selector)) {
-                    matched = true;
-                    // 这里应该解析出具体的元素，简化实现
-                    if let Ok(element) = Self::parse_element(&xml_content) {
-                        matched_elements.push(element);
-                    }
-                }
-            }
-            "text" => {
-                if xml_content.contains(&format!("text=\"{}\"", condition.selector)) {
-                    matched = true;
-                    if let Ok(element) = Self::parse_element(&xml_content) {
-                        matched_elements.push(element);
-                    }
-                }
-            }
+        let tree = UiTree::parse(&xml_content).map_err(|e| e.to_string())?;
+
+        let nodes = match condition.condition_type.as_str() {
+            "resource_id" => tree.find_all(|n| n.resource_id() == condition.selector),
+            "text" => tree.find_all(|n| n.text() == condition.selector),
             "text_contains" => {
-                if let Some(value) = &condition.value {
-                    if xml_content.contains(value) {
-                        matched = true;
-                        if let Ok(element) = Self::parse_element(&xml_content) {
-                            matched_elements.push(element);
-                        }
-                    }
-                }
-            }
-            "class" => {
-                if xml_content.contains(&format!("class=\"{}\"", condition.selector)) {
-                    matched = true;
-                    if let Ok(element) = Self::parse_element(&xml_content) {
-                        matched_elements.push(element);
-                    }
-                }
+                let value = condition.value.as_deref().unwrap_or(&condition.selector);
+                tree.find_all(|n| n.text().contains(value) || n.content_desc().contains(value))
             }
+            "class" => tree.find_all(|n| n.class_name() == condition.selector),
+            "xpath" => tree.select(&condition.selector).map_err(|e| e.to_string())?,
             _ => {
                 return Err(format!("不支持的条件类型: {}", condition.condition_type));
             }
-        }
+        };
+
+        let matched_elements: Vec<XmlElement> = nodes
+            .iter()
+            .map(|node| Self::to_xml_element(node, false))
+            .collect();
+        let matched = !matched_elements.is_empty();
 
         Ok(XmlJudgmentResult {
             success: true,
@@ -244,6 +179,8 @@ impl XmlJudgmentService {
                 });
             }
 
+            // 每轮先刷新缓存，之后的查找读取的是本轮的新 dump
+            Self::refresh_ui_xml(device_id).await?;
             let result = Self::find_elements(device_id, condition).await?;
             if result.matched {
                 return Ok(result);
@@ -333,6 +270,15 @@ pub struct MatchPreviewDTO {
     pub xpath: Option<String>,
 }
 
+/// 单个候选匹配（按得分排序返回全部候选）
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MatchCandidateDTO {
+    pub index: usize,               // 在全部节点中的序号
+    pub score: f64,
+    pub preview: MatchPreviewDTO,
+}
+
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct MatchResultDTO {
@@ -341,6 +287,20 @@ pub struct MatchResultDTO {
     pub total: Option<usize>,
     pub matchedIndex: Option<usize>,
     pub preview: Option<MatchPreviewDTO>,
+    #[serde(default)]
+    pub matches: Vec<MatchCandidateDTO>,
+}
+
+fn to_match_preview(node: &UiNode, xpath: String) -> MatchPreviewDTO {
+    let get_attr = |name: &str| node.attr(name).map(|v| v.to_string());
+    MatchPreviewDTO {
+        text: get_attr("text"),
+        resource_id: get_attr("resource-id"),
+        class_name: get_attr("class"),
+        package: get_attr("package"),
+        bounds: get_attr("bounds"),
+        xpath: Some(xpath),
+    }
 }
 
 #[command]
@@ -352,60 +312,57 @@ pub async fn match_element_by_criteria(
     // 读取当前XML
     let xml = XmlJudgmentService::get_ui_xml(&deviceId).await?;
 
-    // 简单行级匹配：按 <node ...> 行过滤
-    let lines: Vec<&str> = xml.lines().filter(|l| l.contains("<node")).collect();
+    let tree = UiTree::parse(&xml).map_err(|e| e.to_string())?;
+    let nodes: Vec<UiNode> = tree.elements().collect();
 
-    if lines.is_empty() {
-        return Ok(MatchResultDTO { ok: false, message: "未解析到任何节点".into(), total: Some(0), matchedIndex: None, preview: None });
+    if nodes.is_empty() {
+        return Ok(MatchResultDTO { ok: false, message: "未解析到任何节点".into(), total: Some(0), matchedIndex: None, preview: None, matches: Vec::new() });
     }
 
-    // 根据选择字段匹配；对 positionless/relaxed/strict/standard 策略忽略位置字段
-    let ignore_bounds = criteria.strategy == "positionless" || criteria.strategy == "relaxed" || criteria.strategy == "strict" || criteria.strategy == "standard";
-
-    let mut matched_idx: Option<usize> = None;
-    for (idx, line) in lines.iter().enumerate() {
-        let mut ok = true;
-        for f in &criteria.fields {
-            if *f == "bounds" && ignore_bounds { continue; }
-            if let Some(v) = criteria.values.get(f) {
-                // 宽松匹配：text/content-desc 使用包含，其他使用等值（字符串出现）
-                if f == "text" || f == "content-desc" {
-                    if !line.contains(&format!("{}=\"{}", f, v)) && !line.contains(v) {
-                        ok = false; break;
-                    }
-                } else if f == "resource-id" {
-                    if !line.contains(&format!("resource-id=\"{}\"", v)) && !line.contains(&format!("resource-id=\".*/{}\"", v)) {
-                        if !line.contains(v) { ok = false; break; }
-                    }
-                } else {
-                    if !line.contains(&format!("{}=\"{}\"", f, v)) { ok = false; break; }
-                }
-            }
+    let strategy = MatchStrategy::parse(&criteria.strategy).map_err(|e| e.to_string())?;
+    let matches = match_nodes(&tree, strategy, &criteria.fields, &criteria.values).map_err(|e| e.to_string())?;
+
+    let candidates: Vec<MatchCandidateDTO> = matches
+        .into_iter()
+        .filter_map(|m| {
+            let index = nodes.iter().position(|n| *n == m.node)?;
+            Some(MatchCandidateDTO { index, score: m.score, preview: to_match_preview(&m.node, m.xpath) })
+        })
+        .collect();
+
+    match candidates.first() {
+        Some(best) => {
+            let message = if candidates.len() > 1 {
+                format!("已匹配（共 {} 个候选，按得分排序）", candidates.len())
+            } else {
+                "已匹配".to_string()
+            };
+            Ok(MatchResultDTO {
+                ok: true,
+                message,
+                total: Some(nodes.len()),
+                matchedIndex: Some(best.index),
+                preview: Some(best.preview.clone()),
+                matches: candidates,
+            })
         }
-        if ok { matched_idx = Some(idx); break; }
+        None => Ok(MatchResultDTO { ok: false, message: "未找到匹配元素".into(), total: Some(nodes.len()), matchedIndex: None, preview: None, matches: Vec::new() }),
     }
+}
 
-    if let Some(i) = matched_idx {
-        // 构造预览
-        let line = lines[i];
-        let get_attr = |name: &str| -> Option<String> {
-            let pat = format!("{}=\"", name);
-            if let Some(s) = line.find(&pat) {
-                let start = s + pat.len();
-                if let Some(e) = line[start..].find('"') { return Some(line[start..start+e].to_string()); }
-            }
-            None
-        };
-        let preview = MatchPreviewDTO {
-            text: get_attr("text"),
-            resource_id: get_attr("resource-id"),
-            class_name: get_attr("class"),
-            package: get_attr("package"),
-            bounds: get_attr("bounds"),
-            xpath: None,
-        };
-        Ok(MatchResultDTO { ok: true, message: "已匹配".into(), total: Some(lines.len()), matchedIndex: Some(i), preview: Some(preview) })
-    } else {
-        Ok(MatchResultDTO { ok: false, message: "未找到匹配元素".into(), total: Some(lines.len()), matchedIndex: None, preview: None })
-    }
-}
\ No newline at end of file
+// ====== 新增：两份UI层次结构的结构化差异 ======
+
+/// 比较两份UI dump；未提供 after_xml 时读取设备当前UI作为比较对象
+#[command]
+pub async fn diff_ui_hierarchies(
+    before_xml: String,
+    after_xml: Option<String>,
+    device_id: Option<String>,
+) -> Result<UiDiff, String> {
+    let after_xml = match (after_xml, device_id) {
+        (Some(xml), _) => xml,
+        (None, Some(device_id)) => XmlJudgmentService::get_ui_xml(&device_id).await?,
+        (None, None) => return Err("需要提供 after_xml 或 device_id".to_string()),
+    };
+    UiDiff::between(&before_xml, &after_xml).map_err(|e| e.to_string())
+}
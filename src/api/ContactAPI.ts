@@ -10,6 +10,8 @@ import {
   EnhancedImportAndFollowResult,
   ImportAndFollowResult,
   NavigationResult,
+  VCard,
+  VCardVersion,
   VcfImportResult,
   VcfVerifyResult,
   XiaohongshuFollowOptions,
@@ -373,15 +375,39 @@ export class AdbAPI {
   }
 
   /**
-   * 生成VCF文件从联系人列表
+   * 生成VCF文件从联系人列表（version 不填时写 vCard 2.1）
    */
   static async generateVcfFile(
     contacts: Contact[],
-    outputPath: string
+    outputPath: string,
+    version?: VCardVersion
   ): Promise<string> {
     return await invoke<string>("generate_vcf_file", {
       contacts,
       outputPath,
+      version,
+    });
+  }
+
+  /**
+   * 解析VCF文件（支持 vCard 2.1/3.0/4.0）
+   */
+  static async parseVcfFile(filePath: string): Promise<VCard[]> {
+    return await invoke<VCard[]>("parse_vcf_file", { filePath });
+  }
+
+  /**
+   * 将VCF文件转换为指定版本，返回联系人数量
+   */
+  static async convertVcfFile(
+    inputPath: string,
+    outputPath: string,
+    version: VCardVersion
+  ): Promise<number> {
+    return await invoke<number>("convert_vcf_file", {
+      inputPath,
+      outputPath,
+      version,
     });
   }
 
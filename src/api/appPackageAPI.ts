@@ -0,0 +1,74 @@
+/**
+ * 应用包管理API
+ * 批量安装/升级APK（按 versionName 比较，已是目标版本的设备跳过）、卸载、清除数据、
+ * 预授予运行时权限、禁止应用自更新，结果按设备逐台返回
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface InstallOptions {
+  allow_downgrade?: boolean;
+  force?: boolean;
+  grant_permissions?: string[];
+  block_self_update?: boolean;
+  /** 本机没有 aapt 无法读取 APK 时需要指定 */
+  apk_version?: string;
+  package_name?: string;
+}
+
+export interface PackageOpResult {
+  device_id: string;
+  package_name: string;
+  action: string;
+  success: boolean;
+  message: string;
+  previous_version?: string;
+  current_version?: string;
+}
+
+export class AppPackageAPI {
+  /**
+   * 批量安装或升级APK
+   */
+  static async install(deviceIds: string[], apkPath: string, options?: InstallOptions): Promise<PackageOpResult[]> {
+    return await invoke<PackageOpResult[]>('install_apk_on_devices', { deviceIds, apkPath, options });
+  }
+
+  /**
+   * 批量卸载应用
+   */
+  static async uninstall(deviceIds: string[], packageName: string, keepData = false): Promise<PackageOpResult[]> {
+    return await invoke<PackageOpResult[]>('uninstall_app_on_devices', { deviceIds, packageName, keepData });
+  }
+
+  /**
+   * 批量清除应用数据
+   */
+  static async clearData(deviceIds: string[], packageName: string): Promise<PackageOpResult[]> {
+    return await invoke<PackageOpResult[]>('clear_app_data_on_devices', { deviceIds, packageName });
+  }
+
+  /**
+   * 批量预授予运行时权限
+   */
+  static async grantPermissions(deviceIds: string[], packageName: string, permissions: string[]): Promise<PackageOpResult[]> {
+    return await invoke<PackageOpResult[]>('grant_app_permissions_on_devices', { deviceIds, packageName, permissions });
+  }
+
+  /**
+   * 批量禁止应用自行安装更新包
+   * 仅撤销安装未知应用的权限：应用内的更新提示仍会出现（由弹窗监视器 app_update 关闭），应用商店更新不受影响
+   */
+  static async blockSelfUpdate(deviceIds: string[], packageName: string): Promise<PackageOpResult[]> {
+    return await invoke<PackageOpResult[]>('block_app_self_update_on_devices', { deviceIds, packageName });
+  }
+
+  /**
+   * 查询各设备已安装版本
+   */
+  static async getVersions(deviceIds: string[], packageName: string): Promise<PackageOpResult[]> {
+    return await invoke<PackageOpResult[]>('get_app_versions_on_devices', { deviceIds, packageName });
+  }
+}
+
+export default AppPackageAPI;
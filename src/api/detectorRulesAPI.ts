@@ -0,0 +1,19 @@
+/**
+ * 应用检测规则API
+ * 规则文件放在 data/detector_rules/（TOML 或 JSON，一个文件对应一个应用），
+ * 描述启动画面、广告、权限、登录、首页、网络错误等状态的匹配条件。
+ * 创建检测器时会自动重载改动过的规则文件，这里提供手动重载入口
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export class DetectorRulesAPI {
+  /**
+   * 重新加载规则文件，返回当前已配置规则的应用包名
+   */
+  static async reload(): Promise<string[]> {
+    return await invoke<string[]>('reload_detector_rules');
+  }
+}
+
+export default DetectorRulesAPI;
@@ -0,0 +1,44 @@
+/**
+ * 元素定位器API
+ * 为页面分析中选中的元素生成多种候选定位器（resource-id、文本、content-desc、XPath、
+ * 相对锚点、归一化位置），按当前页面唯一性和同一页面历史快照中的稳定性打分排序。
+ * 排序后的数组保存到步骤参数 `locators`（或 SelectedElementConfig.locators），执行时依次尝试
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type LocatorStrategy =
+  | { type: 'resource_id'; resource_id: string }
+  | { type: 'text'; text: string }
+  | { type: 'content_desc'; content_desc: string }
+  | { type: 'x_path'; xpath: string }
+  | { type: 'relative_to_anchor'; anchor_text: string; dx: number; dy: number; class_name: string }
+  | { type: 'normalized_position'; x: number; y: number };
+
+export interface ElementLocator {
+  strategy: LocatorStrategy;
+  /** 当前页面中的唯一性（0~1） */
+  uniqueness: number;
+  /** 历史快照中的稳定性（0~1） */
+  stability: number;
+  score: number;
+}
+
+export interface LocatorBounds {
+  left: number;
+  top: number;
+  right: number;
+  bottom: number;
+}
+
+export class ElementLocatorAPI {
+  /**
+   * 按元素 bounds 生成排序后的定位器回退链
+   * @param xmlContent 选取元素时使用的页面 XML，不传则使用设备当前界面
+   */
+  static async generate(deviceId: string, bounds: LocatorBounds, xmlContent?: string): Promise<ElementLocator[]> {
+    return await invoke<ElementLocator[]>('generate_element_locators', { deviceId, bounds, xmlContent });
+  }
+}
+
+export default ElementLocatorAPI;
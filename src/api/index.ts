@@ -9,6 +9,18 @@ export * from './deviceAPI';
 export { default as DeviceAPI } from './deviceAPI';
 export * from './taskAPI';
 export { BalanceAPI, StatisticsAPI, default as TaskAPI } from './taskAPI';
+export * from './pageFingerprintAPI';
+export * from './navigationGraphAPI';
+export * from './templateMatchAPI';
+export * from './uiIdleAPI';
+export * from './pageAnalysisHistoryAPI';
+export * from './xmlSnapshotAPI';
+export * from './screenshotAnnotationAPI';
+export * from './elementLocatorAPI';
+export * from './detectorRulesAPI';
+export * from './popupWatcherAPI';
+export * from './appPackageAPI';
+export * from './launchBenchmarkAPI';
 
 // 页面分析API
 // 删除已不存在的 pageAnalysisAPI 模块导出
@@ -0,0 +1,84 @@
+/**
+ * 应用启动基准测试API
+ * 冷启动（force-stop 后启动）与热启动（退到后台后启动）各跑 N 次，
+ * 记录 am start -W 的 TotalTime/WaitTime 和检测器判定的可用时间，结果按机型保存
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type LaunchMode = 'cold' | 'warm';
+
+export interface AmStartTiming {
+  status?: string;
+  launch_state?: string;
+  total_time_ms?: number;
+  wait_time_ms?: number;
+}
+
+export interface LaunchSample {
+  mode: LaunchMode;
+  iteration: number;
+  am_timing: AmStartTiming;
+  ready_time_ms?: number;
+  ready: boolean;
+  final_state: string;
+}
+
+export interface TimeSummary {
+  count: number;
+  min_ms: number;
+  max_ms: number;
+  mean_ms: number;
+  median_ms: number;
+  p90_ms: number;
+}
+
+export interface LaunchModeStats {
+  mode: LaunchMode;
+  detection_stats: {
+    total_detections: number;
+    successful_detections: number;
+    average_detection_time: { secs: number; nanos: number };
+    fastest_detection: { secs: number; nanos: number };
+    slowest_detection: { secs: number; nanos: number };
+    state_counts: Record<string, number>;
+  };
+  total_time: TimeSummary;
+  wait_time: TimeSummary;
+  ready_time: TimeSummary;
+  suggested_ready_timeout_ms?: number;
+}
+
+export interface LaunchBenchmarkReport {
+  device_id: string;
+  device_model: string;
+  manufacturer: string;
+  package_name: string;
+  iterations: number;
+  started_at: number;
+  samples: LaunchSample[];
+  stats: LaunchModeStats[];
+}
+
+export interface DeviceModelBenchmarks {
+  device_model: string;
+  reports: LaunchBenchmarkReport[];
+}
+
+export class LaunchBenchmarkAPI {
+  /**
+   * 运行启动基准测试
+   */
+  static async run(deviceId: string, packageName: string, iterations = 5, modes?: LaunchMode[]): Promise<LaunchBenchmarkReport> {
+    return await invoke<LaunchBenchmarkReport>('benchmark_app_launch', { deviceId, packageName, iterations, modes });
+  }
+
+  /**
+   * 获取已保存的结果（不指定型号时返回全部）
+   */
+  static async list(deviceModel?: string): Promise<DeviceModelBenchmarks[]> {
+    return await invoke<DeviceModelBenchmarks[]>('get_launch_benchmarks', { deviceModel });
+  }
+}
+
+export default LaunchBenchmarkAPI;
@@ -0,0 +1,82 @@
+/**
+ * 应用导航图API
+ * 节点为已保存的页面指纹，边为页面之间的点击/返回动作；可探索构建，并按图自动导航
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type NavAction =
+  | { type: 'tap'; xpath?: string | null; text?: string | null; x: number; y: number }
+  | { type: 'back' };
+
+export interface NavEdge {
+  from: string;
+  to: string;
+  action: NavAction;
+  success_count: number;
+  failure_count: number;
+  updated_at: string;
+}
+
+export interface NavigationGraph {
+  package: string;
+  pages: string[];
+  edges: NavEdge[];
+}
+
+export interface ExplorationOptions {
+  max_depth?: number;
+  max_actions?: number;
+  max_elements_per_page?: number;
+  settle_ms?: number;
+  /** 含有这些关键字的元素不会被点击（默认包含删除、退出、支付等） */
+  deny_list?: string[];
+}
+
+export interface ExplorationReport {
+  package: string;
+  pages_discovered: string[];
+  edges_added: number;
+  actions_taken: number;
+  logs: string[];
+}
+
+export interface NavigationOutcome {
+  success: boolean;
+  target: string;
+  visited: string[];
+  message: string;
+  logs: string[];
+}
+
+export class NavigationGraphAPI {
+  /**
+   * 获取应用的导航图
+   */
+  static async getGraph(packageName: string): Promise<NavigationGraph> {
+    return await invoke<NavigationGraph>('get_navigation_graph', { package: packageName });
+  }
+
+  /**
+   * 清空应用的导航图
+   */
+  static async clearGraph(packageName: string): Promise<void> {
+    await invoke('clear_navigation_graph', { package: packageName });
+  }
+
+  /**
+   * 从设备当前页面开始探索应用
+   */
+  static async explore(deviceId: string, packageName: string, options?: ExplorationOptions): Promise<ExplorationReport> {
+    return await invoke<ExplorationReport>('explore_app_navigation', { deviceId, package: packageName, options });
+  }
+
+  /**
+   * 按导航图从当前页面导航到目标页面
+   */
+  static async navigateTo(deviceId: string, packageName: string, targetPage: string, settleMs?: number): Promise<NavigationOutcome> {
+    return await invoke<NavigationOutcome>('navigate_to_page', { deviceId, package: packageName, targetPage, settleMs });
+  }
+}
+
+export default NavigationGraphAPI;
@@ -0,0 +1,65 @@
+/**
+ * 页面分析历史API
+ * analyze_current_page 的每次结果都会连同原始XML、截图存入历史，
+ * 可按设备/应用/时间查询、用当前分析器重新分析，或比较两次记录（如应用更新前后）
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface PageAnalysisRecord {
+  id: number;
+  device_id: string;
+  app_package: string;
+  activity_name: string;
+  screenshot_path: string | null;
+  /** 分析时间（Unix 秒） */
+  analyzed_at: number;
+  /** 分析结果（PageAnalysisResult） */
+  result: any;
+}
+
+export interface PageAnalysisHistoryQuery {
+  deviceId?: string;
+  appPackage?: string;
+  /** 起始时间（Unix 秒） */
+  since?: number;
+  /** 截止时间（Unix 秒） */
+  until?: number;
+  limit?: number;
+}
+
+export class PageAnalysisHistoryAPI {
+  /**
+   * 查询历史记录，最新的在前
+   */
+  static async list(query: PageAnalysisHistoryQuery = {}): Promise<PageAnalysisRecord[]> {
+    return await invoke<PageAnalysisRecord[]>('get_page_analysis_history', { ...query });
+  }
+
+  /**
+   * 获取记录保存的原始XML
+   */
+  static async getXml(id: number): Promise<string> {
+    return await invoke<string>('get_page_analysis_xml', { id });
+  }
+
+  /**
+   * 用当前分析器重新分析历史快照
+   */
+  static async reanalyze(id: number, config?: Record<string, unknown>): Promise<any> {
+    return await invoke('reanalyze_page_analysis', { id, config });
+  }
+
+  /**
+   * 比较两条记录的页面结构差异
+   */
+  static async compare(beforeId: number, afterId: number): Promise<any> {
+    return await invoke('compare_page_analyses', { beforeId, afterId });
+  }
+
+  static async remove(id: number): Promise<void> {
+    await invoke('delete_page_analysis', { id });
+  }
+}
+
+export default PageAnalysisHistoryAPI;
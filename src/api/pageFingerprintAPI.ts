@@ -0,0 +1,66 @@
+/**
+ * 页面指纹API
+ * 保存“当前页面”为命名页面，并在 WaitForPageState / RecognizePage 步骤中按名称复用
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface PageFingerprint {
+  package: string;
+  activity?: string | null;
+  resource_ids: string[];
+  landmark_texts: string[];
+}
+
+export interface PageSignature {
+  name: string;
+  fingerprint: PageFingerprint;
+  threshold: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface PageMatch {
+  name: string;
+  score: number;
+  matched: boolean;
+}
+
+export class PageFingerprintAPI {
+  /**
+   * 将设备当前页面保存为命名页面（同名覆盖）
+   */
+  static async saveCurrentPage(deviceId: string, name: string): Promise<PageSignature> {
+    return await invoke<PageSignature>('save_current_page_fingerprint', { deviceId, name });
+  }
+
+  /**
+   * 列出已保存的页面
+   */
+  static async listPages(): Promise<PageSignature[]> {
+    return await invoke<PageSignature[]>('list_page_fingerprints');
+  }
+
+  /**
+   * 删除已保存的页面
+   */
+  static async deletePage(name: string): Promise<boolean> {
+    return await invoke<boolean>('delete_page_fingerprint', { name });
+  }
+
+  /**
+   * 识别设备当前页面，返回按相似度排序的匹配结果
+   */
+  static async recognizeCurrentPage(deviceId: string): Promise<PageMatch[]> {
+    return await invoke<PageMatch[]>('recognize_page_by_fingerprint', { deviceId });
+  }
+
+  /**
+   * 按指纹识别给定 XML 对应的已注册页面，未命中时返回 null
+   */
+  static async identifyRegisteredPage(xmlContent: string): Promise<PageMatch | null> {
+    return await invoke<PageMatch | null>('identify_registered_page', { xmlContent });
+  }
+}
+
+export default PageFingerprintAPI;
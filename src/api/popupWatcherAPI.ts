@@ -0,0 +1,78 @@
+/**
+ * 弹窗监视器API
+ * 智能脚本执行时会在步骤之间自动关闭权限、更新、评分、青少年模式、开屏广告等弹窗，
+ * 脚本可通过 config.popup_watchers 禁用内置监视器或追加自定义监视器
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type DismissAction =
+  | { type: 'tap_text'; texts: string[] }
+  | { type: 'tap_resource_id'; resource_id: string }
+  | { type: 'back' };
+
+export interface PopupSignature {
+  text_any: string[];
+  resource_id_any: string[];
+  package?: string;
+}
+
+export interface PopupWatcher {
+  id: string;
+  name: string;
+  signature: PopupSignature;
+  action: DismissAction;
+}
+
+export interface PopupWatcherSettings {
+  enabled: boolean;
+  disabled: string[];
+  custom: PopupWatcher[];
+  max_dismissals: number;
+}
+
+export interface PopupWatcherStats {
+  watcher_id: string;
+  fire_count: number;
+  last_fired_at?: number;
+}
+
+export interface PopupFiring {
+  watcher_id: string;
+  watcher_name: string;
+  step_id?: string;
+  action: string;
+  fired_at: number;
+}
+
+export class PopupWatcherAPI {
+  /**
+   * 获取内置弹窗监视器
+   */
+  static async list(): Promise<PopupWatcher[]> {
+    return await invoke<PopupWatcher[]>('list_popup_watchers');
+  }
+
+  /**
+   * 获取各监视器的触发统计
+   */
+  static async getStats(): Promise<PopupWatcherStats[]> {
+    return await invoke<PopupWatcherStats[]>('get_popup_watcher_stats');
+  }
+
+  /**
+   * 清空触发统计
+   */
+  static async resetStats(): Promise<void> {
+    await invoke('reset_popup_watcher_stats');
+  }
+
+  /**
+   * 立即检查设备界面并关闭弹窗
+   */
+  static async dismiss(deviceId: string, settings?: PopupWatcherSettings): Promise<PopupFiring[]> {
+    return await invoke<PopupFiring[]>('dismiss_popups', { deviceId, settings });
+  }
+}
+
+export default PopupWatcherAPI;
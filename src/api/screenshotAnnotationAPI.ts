@@ -0,0 +1,39 @@
+/**
+ * 截图标注API
+ * 在截图上绘制元素框、ID和类型，并突出显示匹配/点击的元素。
+ * 智能点击/批量匹配/元素查找步骤失败时，执行器会自动保存标注截图，
+ * 路径见步骤结果 extracted_data.evidence_screenshot
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface AnnotationBounds {
+  left: number;
+  top: number;
+  right: number;
+  bottom: number;
+}
+
+export interface AnnotateScreenshotOptions {
+  /** 按UI XML绘制元素框 */
+  xmlContent?: string;
+  /** 按页面分析结果（PageAnalysisResult）绘制元素框 */
+  analysis?: unknown;
+  /** 突出显示的元素，未指定时使用点击坐标命中的元素 */
+  highlight?: AnnotationBounds;
+  tapX?: number;
+  tapY?: number;
+  /** 输出路径，默认在原图旁生成 *.annotated.png */
+  outputPath?: string;
+}
+
+export class ScreenshotAnnotationAPI {
+  /**
+   * 生成标注截图，返回标注图路径
+   */
+  static async annotate(screenshotPath: string, options: AnnotateScreenshotOptions = {}): Promise<string> {
+    return await invoke<string>('annotate_screenshot', { screenshotPath, ...options });
+  }
+}
+
+export default ScreenshotAnnotationAPI;
@@ -0,0 +1,54 @@
+/**
+ * 截图模板匹配API
+ * 用于没有文本/描述的图片按钮：从截图裁剪模板，在 SmartFindElement 步骤中以
+ * { strategy: 'template', template_path, template_screen_width } 参数定位
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface TemplateBounds {
+  left: number;
+  top: number;
+  right: number;
+  bottom: number;
+}
+
+export interface TemplateMatchOptions {
+  /** 最低置信度（0~1），默认 0.8 */
+  threshold?: number;
+  /** 相对缩放比例，默认 [0.8, 0.9, 1.0, 1.1, 1.25] */
+  scales?: number[];
+  /** 截取模板时的屏幕宽度，用于跨分辨率换算 */
+  template_screen_width?: number | null;
+}
+
+export interface TemplateMatch {
+  bounds: TemplateBounds;
+  confidence: number;
+  scale: number;
+}
+
+export interface TemplateInfo {
+  path: string;
+  width: number;
+  height: number;
+  screen_width: number;
+}
+
+export class TemplateMatchAPI {
+  /**
+   * 在设备当前截图中查找模板，未找到时返回 null
+   */
+  static async findOnScreen(deviceId: string, templatePath: string, options?: TemplateMatchOptions): Promise<TemplateMatch | null> {
+    return await invoke<TemplateMatch | null>('find_template_on_screen', { deviceId, templatePath, options });
+  }
+
+  /**
+   * 从截图裁剪模板并保存
+   */
+  static async saveFromScreenshot(screenshotPath: string, bounds: TemplateBounds, name: string): Promise<TemplateInfo> {
+    return await invoke<TemplateInfo>('save_template_from_screenshot', { screenshotPath, bounds, name });
+  }
+}
+
+export default TemplateMatchAPI;
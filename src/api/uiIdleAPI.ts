@@ -0,0 +1,26 @@
+/**
+ * 等待界面稳定API
+ * 轮询UI层次结构，连续多次dump一致即视为稳定，替代固定等待时间
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface UiIdleResult {
+  /** 最后一次（稳定时即稳定的）UI XML */
+  xml: string;
+  stable: boolean;
+  condition_met: boolean;
+  dumps: number;
+  elapsed_ms: number;
+}
+
+export class UiIdleAPI {
+  /**
+   * 等待设备界面稳定，超时时返回最后一次dump（stable 为 false）
+   */
+  static async waitForIdle(deviceId: string, timeoutMs?: number, stableDumps?: number): Promise<UiIdleResult> {
+    return await invoke<UiIdleResult>('wait_for_ui_idle', { deviceId, timeoutMs, stableDumps });
+  }
+}
+
+export default UiIdleAPI;
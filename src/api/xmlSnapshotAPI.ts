@@ -0,0 +1,87 @@
+/**
+ * XML快照存储API
+ * 每次抓取的UI dump按设备/应用/Activity/页面指纹存入SQLite，相同内容自动去重，
+ * 支持按节点文本全文检索，并按保留策略（天数、总大小）自动清理
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface XmlSnapshotMeta {
+  id: number;
+  /** 兼容旧缓存的文件名：ui_dump_<设备>_<YYYYmmdd_HHMMSS>.xml */
+  file_name: string;
+  device_id: string;
+  app_package: string;
+  activity_name: string | null;
+  /** 页面指纹识别出的页面名 */
+  page_name: string | null;
+  screen_width: number;
+  screen_height: number;
+  screenshot_path: string | null;
+  size_bytes: number;
+  /** 首次抓取时间（Unix 秒） */
+  captured_at: number;
+  /** 最近一次抓到相同内容的时间（Unix 秒） */
+  last_seen_at: number;
+  seen_count: number;
+}
+
+export interface XmlSnapshotSaveResult {
+  snapshot: XmlSnapshotMeta;
+  /** 与已有快照内容一致，仅更新了出现次数 */
+  deduplicated: boolean;
+}
+
+export interface XmlSnapshotQuery {
+  /** 在节点 text / content-desc 中全文搜索 */
+  text?: string;
+  device_id?: string;
+  app_package?: string;
+  /** 起始时间（Unix 秒） */
+  since?: number;
+  /** 截止时间（Unix 秒） */
+  until?: number;
+  limit?: number;
+}
+
+export interface XmlSnapshotRetentionPolicy {
+  max_age_days: number;
+  max_total_mb: number;
+}
+
+export class XmlSnapshotAPI {
+  /**
+   * 抓取当前界面并保存为快照
+   */
+  static async capture(deviceId: string, withScreenshot = false): Promise<XmlSnapshotSaveResult> {
+    return await invoke<XmlSnapshotSaveResult>('capture_xml_snapshot', { deviceId, withScreenshot });
+  }
+
+  /**
+   * 检索快照，最近出现的在前
+   */
+  static async search(query: XmlSnapshotQuery = {}): Promise<XmlSnapshotMeta[]> {
+    return await invoke<XmlSnapshotMeta[]>('search_xml_snapshots', { query });
+  }
+
+  static async getContent(id: number): Promise<string> {
+    return await invoke<string>('get_xml_snapshot_content', { id });
+  }
+
+  static async remove(id: number): Promise<void> {
+    await invoke('delete_xml_snapshot', { id });
+  }
+
+  static async getRetentionPolicy(): Promise<XmlSnapshotRetentionPolicy> {
+    return await invoke<XmlSnapshotRetentionPolicy>('get_xml_snapshot_retention_policy');
+  }
+
+  /**
+   * 设置保留策略并立即清理，返回删除的快照数量
+   */
+  static async setRetentionPolicy(policy: XmlSnapshotRetentionPolicy): Promise<number> {
+    return await invoke<number>('set_xml_snapshot_retention_policy', { policy });
+  }
+}
+
+export default XmlSnapshotAPI;
@@ -118,7 +118,7 @@ export const NodeDetailPanel: React.FC<NodeDetailPanelProps> = ({ node, onMatche
       });
       if (res.ok) {
         // 简单提示；后续可联动预览高亮（需要回传 bounds/xpath 并在上层转换为 UiNode）
-        alert(`匹配成功：在 ${res.total ?? 0} 个中命中第 ${res.matchedIndex != null ? res.matchedIndex + 1 : 1} 个\n提示：${res.message}`);
+        alert(`匹配成功：在 ${res.total ?? 0} 个中命中第 ${res.matchedIndex != null ? res.matchedIndex + 1 : 1} 个（候选 ${res.matches?.length ?? 1} 个）\nXPath：${res.preview?.xpath ?? '-'}\n提示：${res.message}`);
         // 通知上层：传递用于定位的关键信息（xpath/bounds）
         if (onMatched) {
           onMatched({
@@ -126,7 +126,8 @@ export const NodeDetailPanel: React.FC<NodeDetailPanelProps> = ({ node, onMatche
             message: res.message,
             total: res.total,
             matchedIndex: res.matchedIndex,
-            preview: res.preview
+            preview: res.preview,
+            matches: res.matches
           });
         }
       } else {
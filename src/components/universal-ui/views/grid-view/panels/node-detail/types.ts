@@ -14,4 +14,5 @@ export interface MatchResultSummary {
   matchedIndex?: number;
   total?: number;
   preview?: { text?: string; resource_id?: string; class_name?: string; xpath?: string; bounds?: string; package?: string };
+  matches?: Array<{ index: number; score: number; preview: NonNullable<MatchResultSummary['preview']> }>;
 }
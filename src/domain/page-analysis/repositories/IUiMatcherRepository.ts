@@ -19,12 +19,20 @@ export interface MatchPreview {
   xpath?: string;
 }
 
+export interface MatchCandidate {
+  index: number;
+  score: number;
+  preview: MatchPreview;
+}
+
 export interface MatchResultDTO {
   ok: boolean;
   message: string;
   total?: number;
   matchedIndex?: number;
   preview?: MatchPreview;
+  /** 全部匹配候选（按得分降序），preview.xpath 可唯一定位对应节点 */
+  matches?: MatchCandidate[];
 }
 
 export interface IUiMatcherRepository {
@@ -82,6 +82,19 @@ export interface SmartFindElementStepParams extends BaseStepParams {
   element_description: string;
   find_multiple?: boolean;
   return_coordinates?: boolean;
+  /** 当前屏未找到时滚动容器继续查找（true 使用默认参数） */
+  scroll?: boolean | ScrollFindOptions;
+}
+
+/**
+ * 滚动查找参数
+ */
+export interface ScrollFindOptions {
+  direction?: 'up' | 'down' | 'left' | 'right';
+  max_scrolls?: number;
+  scroll_back?: boolean;
+  swipe_duration_ms?: number;
+  settle_timeout_ms?: number;
 }
 
 /**
@@ -184,6 +197,8 @@ export interface ScriptConfig {
   // 功能开关
   page_recognition_enabled: boolean;
   screenshot_on_error: boolean;
+  record_screen?: boolean;  // 执行期间录制设备屏幕
+  record_navigation?: boolean;  // 点击步骤的页面跳转记录到应用导航图
   
   // 高级设置
   parallel_execution?: boolean;
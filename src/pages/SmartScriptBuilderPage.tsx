@@ -93,6 +93,7 @@ import {
   EnhancedElementInfoService,
 } from "../modules/enhanced-element-info";
 import XmlCacheManager from "../services/XmlCacheManager";
+import ElementLocatorAPI from "../api/elementLocatorAPI";
 // 🧪 XML数据质量校验
 import { XmlDataValidator } from "../modules/distributed-script-quality/XmlDataValidator";
 // 🆕 自包含脚本支持
@@ -2544,6 +2545,10 @@ const SmartScriptBuilderPage: React.FC = () => {
           <Form.Item name="xmlSnapshot" hidden>
             <Input />
           </Form.Item>
+          {/* 🆕 定位器回退链（数组），选择元素时自动生成 */}
+          <Form.Item name="locators" hidden>
+            <Input />
+          </Form.Item>
         </Form>
       </Modal>
 
@@ -2885,6 +2890,32 @@ const SmartScriptBuilderPage: React.FC = () => {
 
             console.log("💾 已保存基础步骤参数:", basicParams);
 
+            // 🆕 生成排序后的定位器回退链，保存到步骤参数 locators（执行时依次尝试）
+            const locatorDeviceId =
+              (element as any).deviceId || currentDeviceInfo.deviceId || currentDeviceId;
+            const targetStepId = editingStepForParams?.id;
+            if (element.bounds && locatorDeviceId) {
+              ElementLocatorAPI.generate(
+                locatorDeviceId,
+                element.bounds,
+                currentXmlContent || (element as any).xmlContent
+              )
+                .then((locators) => {
+                  form.setFieldValue("locators", locators);
+                  if (targetStepId) {
+                    setSteps((prev) =>
+                      prev.map((s) =>
+                        s.id === targetStepId
+                          ? { ...s, parameters: { ...s.parameters, locators } }
+                          : s
+                      )
+                    );
+                  }
+                  console.log("🧭 已生成定位器回退链:", locators.length);
+                })
+                .catch((e) => console.warn("生成定位器回退链失败:", e));
+            }
+
             // 关闭页面分析器并重置状态
             setShowPageAnalyzer(false);
             setIsQuickAnalyzer(false);
@@ -159,6 +159,43 @@ export interface ContactVerification {
   method: "phone" | "email" | "ui_structure";
 }
 
+// vCard 版本与解析结果
+export type VCardVersion = "2.1" | "3.0" | "4.0";
+
+export interface VCardTypedValue {
+  value: string;
+  types: string[];
+}
+
+export interface VCardAddress {
+  types: string[];
+  po_box: string;
+  extended: string;
+  street: string;
+  locality: string;
+  region: string;
+  postal_code: string;
+  country: string;
+}
+
+export interface VCard {
+  version: VCardVersion;
+  formatted_name: string;
+  name: {
+    family: string;
+    given: string;
+    additional: string;
+    prefix: string;
+    suffix: string;
+  };
+  phones: VCardTypedValue[];
+  emails: VCardTypedValue[];
+  org: string[];
+  title?: string;
+  addresses: VCardAddress[];
+  notes: string[];
+}
+
 // 小红书自动关注选项
 export interface XiaohongshuFollowOptions {
   maxPages?: number; // 最大滚动页数
@@ -134,6 +134,10 @@ export enum SmartActionType {
   LAUNCH_APP = 'launch_app',
   CLOSE_APP = 'close_app',
   SWITCH_APP = 'switch_app',
+  APP_INSTALL = 'app_install',
+  APP_UNINSTALL = 'app_uninstall',
+  APP_CLEAR_DATA = 'app_clear_data',
+  APP_GRANT_PERMISSIONS = 'app_grant_permissions',
   
   // 通讯录自动化操作 - 新增
   CONTACT_IMPORT_WORKFLOW = 'contact_import_workflow',
@@ -41,4 +41,6 @@ export interface SmartExecutionResult {
   final_page_state?: string;
   extracted_data: Record<string, any>;
   message: string;
+  logcat_path?: string;   // 执行期间的 logcat 日志文件
+  recordings?: string[];  // 执行录屏分段文件
 }
\ No newline at end of file